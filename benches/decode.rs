@@ -0,0 +1,94 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Compares decoding of small records into a fresh value with [`MemoryLayout::decode_into`],
+//! which reuses the allocations of the previously decoded value.
+//!
+//! Run with `cargo bench --bench decode`. The benchmark uses only `std`, so it reports a mean
+//! time per decoded record instead of the statistics of a dedicated benchmarking framework.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use amplify::confinement::{TinyOrdMap, TinyString, TinyVec};
+use strict_encoding::{StrictDecode, StrictDumb, StrictEncode, StrictSerialize, StrictType};
+use strict_types::layout::MemoryLayout;
+use strict_types::{LibBuilder, StrictVal, SystemBuilder};
+
+const ITERATIONS: u32 = 200_000;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = "Bench")]
+struct Record {
+    id: u64,
+    note: Option<u16>,
+    name: TinyString,
+    items: TinyVec<u32>,
+    map: TinyOrdMap<u8, u64>,
+}
+
+impl StrictSerialize for Record {}
+
+fn layout() -> MemoryLayout {
+    let lib = LibBuilder::with("Bench", None).transpile::<Record>().compile().unwrap();
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    sys.type_tree("Bench.Record").unwrap().to_layout()
+}
+
+fn records() -> Vec<Vec<u8>> {
+    (0..16u8)
+        .map(|n| Record {
+            id: n as u64 * 1000,
+            note: if n % 2 == 0 { Some(n as u16) } else { None },
+            name: TinyString::try_from(format!("record {n}")).unwrap(),
+            items: TinyVec::try_from_iter(0..n as u32).unwrap(),
+            map: TinyOrdMap::try_from_iter((0..n).map(|i| (i, i as u64))).unwrap(),
+        })
+        .map(|record| record.to_strict_serialized::<0xFFFF>().unwrap().release())
+        .collect()
+}
+
+fn measure(name: &str, mut f: impl FnMut(&[u8])) -> Duration {
+    let data = records();
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(black_box(&data[i as usize % data.len()]));
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:<12} {:>8} ns/record", elapsed.as_nanos());
+    elapsed
+}
+
+fn main() {
+    let layout = layout();
+    let fresh = measure("decode", |data| {
+        black_box(layout.decode(data).unwrap());
+    });
+    let mut out = StrictVal::Unit;
+    let reused = measure("decode_into", |data| {
+        layout.decode_into(data, &mut out).unwrap();
+        black_box(&out);
+    });
+    println!("speedup      {:>8.2}x", fresh.as_secs_f64() / reused.as_secs_f64());
+}
@@ -34,6 +34,7 @@ use strict_encoding::{Sizing, TypeName, Variant, STRICT_TYPES_LIB};
 use crate::ast::ty::{Field, UnionVariants, UnnamedFields};
 use crate::ast::{EnumVariants, NamedFields, PrimitiveRef};
 use crate::typelib::LibSubref;
+use crate::util::{parse_baid64_id, IdError};
 use crate::{Cls, CommitConsume, TranspileRef, Ty, TypeRef};
 
 /// Semantic type id, which commits to the type memory layout, name and field/variant names.
@@ -72,6 +73,21 @@ impl Display for SemId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.fmt_baid64(f) }
 }
 
+impl SemId {
+    /// Returns mnemonic suffix of the identifier (the words following `#` in its string form).
+    pub fn mnemonic(&self) -> String { self.to_baid64_mnemonic() }
+
+    /// Returns raw digest bytes the identifier is made of.
+    pub fn checksum_bytes(&self) -> [u8; 32] { self.to_byte_array() }
+
+    /// Parses the identifier string, reporting [`IdError::MnemonicMismatch`] if the mnemonic
+    /// suffix does not match the digest.
+    ///
+    /// Unlike [`FromStr`], which has to report [`Baid64ParseError`] for compatibility with ASCII
+    /// armoring, this distinguishes mnemonic mismatches from other parse errors.
+    pub fn from_str_checked(s: &str) -> Result<Self, IdError> { parse_baid64_id(s) }
+}
+
 pub const SEM_ID_TAG: [u8; 32] = *b"urn:ubideco:strict-types:typ:v01";
 
 impl SemId {
@@ -269,4 +285,20 @@ mod test {
     fn byte() {
         assert!(Ty::<SemId>::Primitive(Primitive::BYTE).is_byte());
     }
+
+    #[test]
+    fn mnemonic() {
+        let id = SemId::unit();
+        let s = id.to_string();
+        let (body, words) = s.split_once('#').unwrap();
+        assert_eq!(id.mnemonic(), words);
+        assert_eq!(id.checksum_bytes(), id.to_byte_array());
+        assert_eq!(SemId::from_str(&s).unwrap(), id);
+        assert_eq!(SemId::from_str_checked(&s).unwrap(), id);
+        assert_eq!(SemId::from_str_checked(body).unwrap(), id);
+
+        let other = SemId::from_byte_array([0xAB; 32]);
+        let forged = format!("{body}#{}", other.mnemonic());
+        assert!(matches!(SemId::from_str_checked(&forged), Err(IdError::MnemonicMismatch { .. })));
+    }
 }
@@ -105,6 +105,11 @@ impl<Ref: TypeRef> Ty<Ref> {
     }
 }
 
+// TODO: Consider a bit set type with named flags (like `BitFlags<u8>`), validated for reserved
+//       bits on decoding. Each type class is a part of the semantic id commitment and of the
+//       `StrictTypes` library, so adding one changes the ids of this library and requires a
+//       `StrictType` derive support in `rgb-strict-encoding`; until then flags are expressed as
+//       plain integers or structures of `Bool`s.
 #[derive(Clone, PartialEq, Eq, Debug, From)]
 #[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
 #[strict_type(lib = STRICT_TYPES_LIB, tags = custom)]
@@ -290,6 +295,7 @@ where Ref: Display
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = STRICT_TYPES_LIB, tags = custom)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ItemCase {
     #[strict_type(tag = 0)]
     UnnamedField(u8),
@@ -330,7 +336,7 @@ impl<Ref: TypeRef> Ty<Ref> {
             Ty::Struct(fields) => {
                 fields.get(pos as usize).map(|field| ItemCase::NamedField(pos, field.name.clone()))
             }
-            Ty::Tuple(fields) if fields.len_u8() == pos => Some(ItemCase::UnnamedField(pos)),
+            Ty::Tuple(fields) if pos < fields.len_u8() => Some(ItemCase::UnnamedField(pos)),
             Ty::Array(_, _) if pos == 0 => Some(ItemCase::ArrayItem),
             Ty::List(_, _) if pos == 0 => Some(ItemCase::ListItem),
             Ty::Set(_, _) if pos == 0 => Some(ItemCase::SetItem),
@@ -0,0 +1,299 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use encoding::{Primitive, VariantName};
+
+use super::{FieldPath, MemoryLayout};
+use crate::ast::ItemCase;
+use crate::typesys::NestedCase;
+use crate::Ty;
+
+/// Column of a columnar schema produced by [`MemoryLayout::columnar_schema`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Column {
+    /// Path to the field stored in the column. Columns of list items and map keys and values
+    /// have paths relative to the collection.
+    pub name: FieldPath,
+    /// Type of the column data.
+    pub ty: ColumnType,
+    /// Whether the column may have no value, which happens for optional fields, fields of
+    /// optional types and fields of union variants.
+    pub nullable: bool,
+}
+
+/// Type of the data stored in a [`Column`], matching the Arrow data types.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ColumnType {
+    /// Integer or float number of the given width.
+    Number(Primitive),
+    /// Dictionary-encoded enumeration, with the variant names ordered by their tags. Unions
+    /// store their variant in such a column.
+    Enum(Vec<VariantName>),
+    /// Binary data of a fixed length.
+    FixedBytes(u16),
+    /// Binary data of a variable length.
+    Bytes,
+    /// UTF-8 string, used for ASCII, restricted and unicode strings and unicode characters.
+    String,
+    /// List of a fixed length, used for arrays which are not exploded into separate columns.
+    FixedList(Box<Column>, u16),
+    /// List of a variable length, used for both lists and sets.
+    List(Box<Column>),
+    /// Map with its keys and values.
+    Map(Box<Column>, Box<Column>),
+    /// Structure with several fields, used for items of collections.
+    Struct(Vec<Column>),
+}
+
+impl MemoryLayout {
+    /// Flattens the layout into a list of columns for exporting data into columnar storage.
+    ///
+    /// Fields of nested structures, tuples and union variants become columns named after their
+    /// dot-separated paths; a union also gets a column with the path of the union itself,
+    /// storing its variant. Fixed-size arrays become a separate column per array item if
+    /// `explode_arrays` is set, and fixed-size list columns otherwise. Variable-length
+    /// collections become list and map columns. Unit fields and variants store no data and get
+    /// no columns.
+    ///
+    /// The columns follow the order of the fields in the type definitions, thus the schema
+    /// changes only when the type does.
+    ///
+    /// Returns `None` for recursive types, which can't be represented by a fixed set of columns.
+    pub fn columnar_schema(&self, explode_arrays: bool) -> Option<Vec<Column>> {
+        let mut columns = vec![];
+        if !self.items.is_empty() {
+            self.flatten(0, FieldPath::default(), false, explode_arrays, &mut columns)?;
+        }
+        Some(columns)
+    }
+
+    /// Adds the columns of the item at `pos` and its subtree to `columns`.
+    fn flatten(
+        &self,
+        pos: usize,
+        path: FieldPath,
+        nullable: bool,
+        explode: bool,
+        columns: &mut Vec<Column>,
+    ) -> Option<()> {
+        let info = &self.items[pos];
+        let nullable = nullable || info.nested.contains(&NestedCase::Option);
+        let mut push = |ty| {
+            columns.push(Column {
+                name: path.clone(),
+                ty,
+                nullable,
+            })
+        };
+        let first_child = pos + 1;
+        match (&info.ty, info.nested.last()) {
+            _ if info.is_recursive() => return None,
+            (_, Some(NestedCase::RStr(..) | NestedCase::AsciiStr(_) | NestedCase::UniStr)) => {
+                push(ColumnType::String)
+            }
+            (_, Some(NestedCase::ByteStr)) => push(ColumnType::Bytes),
+            (Ty::Primitive(Primitive::UNIT), _) => {}
+            (Ty::Primitive(prim), _) => push(ColumnType::Number(*prim)),
+            (Ty::UnicodeChar, _) => push(ColumnType::String),
+            (Ty::Enum(variants), _) => {
+                let mut variants = variants.iter().collect::<Vec<_>>();
+                variants.sort_by_key(|variant| variant.tag);
+                push(ColumnType::Enum(variants.into_iter().map(|v| v.name.clone()).collect()));
+            }
+            (Ty::Union(_), _) => {
+                let mut names = vec![];
+                let mut variants = vec![];
+                let mut child = first_child;
+                for _ in 0..info.children_count() {
+                    let Some(ItemCase::UnionVariant(tag, name)) = &self.items[child].item else {
+                        unreachable!("union items are always variants")
+                    };
+                    names.push((*tag, name.clone()));
+                    variants.push((child, name.clone()));
+                    child = self.subtree_end(child);
+                }
+                names.sort_by_key(|(tag, _)| *tag);
+                push(ColumnType::Enum(names.into_iter().map(|(_, name)| name).collect()));
+                for (child, name) in variants {
+                    self.flatten(child, path.with(name), true, explode, columns)?;
+                }
+            }
+            (Ty::Tuple(_) | Ty::Struct(_), _) => {
+                let mut child = first_child;
+                for index in 0..info.children_count() {
+                    // items of optional fields are named after the option variant, not the field
+                    let path = match &info.ty {
+                        Ty::Struct(fields) => path.with(&fields[index].name),
+                        _ => path.with(index),
+                    };
+                    self.flatten(child, path, nullable, explode, columns)?;
+                    child = self.subtree_end(child);
+                }
+            }
+            (Ty::Array(_, len), _) if info.ty.is_byte_array() => push(ColumnType::FixedBytes(*len)),
+            (Ty::Array(_, len), _) if explode => {
+                for index in 0..*len {
+                    self.flatten(first_child, path.with(index), nullable, explode, columns)?;
+                }
+            }
+            (Ty::Array(_, len), _) => {
+                let item = self.column(first_child, "item", explode)?;
+                push(ColumnType::FixedList(Box::new(item), *len))
+            }
+            (Ty::List(..) | Ty::Set(..), _) => {
+                let item = self.column(first_child, "item", explode)?;
+                push(ColumnType::List(Box::new(item)))
+            }
+            (Ty::Map(..), _) => {
+                let key = self.column(first_child, "key", explode)?;
+                let value = self.column(self.subtree_end(first_child), "value", explode)?;
+                push(ColumnType::Map(Box::new(key), Box::new(value)))
+            }
+        }
+        Some(())
+    }
+
+    /// Constructs a single column for the item at `pos`, which is an element of a collection,
+    /// wrapping multiple columns of the item into a structure.
+    fn column(&self, pos: usize, name: &str, explode: bool) -> Option<Column> {
+        let mut columns = vec![];
+        self.flatten(pos, FieldPath::default(), false, explode, &mut columns)?;
+        let name = FieldPath::from(vec![name.to_owned()]);
+        match columns.as_slice() {
+            [column] if column.name.is_empty() => Some(Column {
+                name,
+                ty: column.ty.clone(),
+                nullable: column.nullable,
+            }),
+            _ => Some(Column {
+                name,
+                ty: ColumnType::Struct(columns),
+                nullable: false,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyOrdMap, TinyString, TinyVec};
+
+    use super::*;
+    use crate::typesys::SystemBuilder;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Amount(u64);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = order, dumb = Shape::Empty)]
+    pub enum Shape {
+        Empty,
+        Pair(u8, i16),
+        Named { width: u16 },
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub amount: Amount,
+        pub note: Option<u16>,
+        pub comment: TinyString,
+        pub bytes: [u8; 4],
+        pub pair: [u16; 2],
+        pub shape: Shape,
+        pub items: TinyVec<Shape>,
+        pub map: TinyOrdMap<u8, Amount>,
+    }
+
+    #[test]
+    fn columnar_schema() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Record").unwrap().to_layout();
+        let names = |columns: &[Column]| {
+            columns.iter().map(|column| column.name.to_string()).collect::<Vec<_>>()
+        };
+        let column = |name: &str, ty, nullable| Column {
+            name: FieldPath::from(name.split('.').map(ToOwned::to_owned).collect::<Vec<_>>()),
+            ty,
+            nullable,
+        };
+
+        let exploded = layout.columnar_schema(true).unwrap();
+        assert_eq!(exploded, layout.columnar_schema(true).unwrap());
+        assert_eq!(names(&exploded), [
+            "amount",
+            "note",
+            "comment",
+            "bytes",
+            "pair.0",
+            "pair.1",
+            "shape",
+            "shape.pair.0",
+            "shape.pair.1",
+            "shape.named.width",
+            "items",
+            "map"
+        ]);
+        assert_eq!(exploded[0], column("amount", ColumnType::Number(Primitive::U64), false));
+        assert_eq!(exploded[1], column("note", ColumnType::Number(Primitive::U16), true));
+        assert_eq!(exploded[2].ty, ColumnType::String);
+        assert_eq!(exploded[3].ty, ColumnType::FixedBytes(4));
+        assert_eq!(
+            exploded[6].ty,
+            ColumnType::Enum(vec![vname!("empty"), vname!("pair"), vname!("named")])
+        );
+        assert_eq!(exploded[8], column("shape.pair.1", ColumnType::Number(Primitive::I16), true));
+        let ColumnType::List(item) = &exploded[10].ty else {
+            panic!("not a list")
+        };
+        let ColumnType::Struct(fields) = &item.ty else {
+            panic!("not a struct")
+        };
+        assert_eq!(names(fields), ["", "pair.0", "pair.1", "named.width"]);
+        assert_eq!(
+            exploded[11].ty,
+            ColumnType::Map(
+                Box::new(column("key", ColumnType::Number(Primitive::U8), false)),
+                Box::new(column("value", ColumnType::Number(Primitive::U64), false))
+            )
+        );
+
+        let columns = layout.columnar_schema(false).unwrap();
+        assert_eq!(columns.len(), exploded.len() - 1);
+        assert_eq!(
+            columns[4].ty,
+            ColumnType::FixedList(
+                Box::new(column("item", ColumnType::Number(Primitive::U16), false)),
+                2
+            )
+        );
+    }
+}
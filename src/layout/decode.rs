@@ -0,0 +1,855 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::mem;
+
+use amplify::num::{i1024, u1024};
+use encoding::{FieldName, NumCls, Primitive, Sizing};
+use indexmap::IndexMap;
+use strict_encoding::VariantName;
+
+use super::memory::len_prefix;
+use super::{MemoryLayout, ValidationError};
+use crate::ast::NamedFields;
+use crate::typesys::{NestedCase, TypeInfo};
+use crate::value::{Blob, EnumTag, StrictNum};
+use crate::{SemId, StrictVal, Ty};
+
+/// Limits on the resources used for decoding a value, independent of the bounds on the
+/// collection sizes defined by the type.
+///
+/// The limits are checked before allocating memory for a string, byte string or collection, thus
+/// a malicious length prefix can't make the decoder allocate more than `max_bytes`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct DecodeLimits {
+    /// Maximal number of items in all arrays (except byte arrays), lists, sets and maps of the
+    /// value; each map entry counts as a single item.
+    pub max_elements: u64,
+    /// Maximal size of memory allocated for the value: the length of all its strings and byte
+    /// strings plus the size of a [`StrictVal`] for each collection item.
+    pub max_bytes: usize,
+    /// Maximal depth of nesting of the decoded layout items, with the root at depth zero.
+    pub max_depth: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self { DecodeLimits::unlimited() }
+}
+
+impl DecodeLimits {
+    /// No limits besides the ones defined by the type.
+    pub const fn unlimited() -> Self {
+        DecodeLimits {
+            max_elements: u64::MAX,
+            max_bytes: usize::MAX,
+            max_depth: usize::MAX,
+        }
+    }
+}
+
+/// Kind of a [`DecodeLimits`] limit.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+pub enum DecodeLimit {
+    /// [`DecodeLimits::max_elements`].
+    #[display("number of collection items")]
+    Elements,
+    /// [`DecodeLimits::max_bytes`].
+    #[display("allocated memory size")]
+    Bytes,
+    /// [`DecodeLimits::max_depth`].
+    #[display("nesting depth")]
+    Depth,
+}
+
+impl MemoryLayout {
+    /// Decodes `bytes` holding a strict encoding of the layout root type into a strict value,
+    /// which has the same form as the one produced by
+    /// [`crate::TypeSystem::strict_deserialize_type`].
+    ///
+    /// The data are checked in the same way as with [`MemoryLayout::validate_reader`], and must
+    /// contain no other data after the encoded value.
+    pub fn decode(&self, bytes: &[u8]) -> Result<StrictVal, ValidationError> {
+        let mut val = StrictVal::Unit;
+        self.decode_into(bytes, &mut val)?;
+        Ok(val)
+    }
+
+    /// Decodes `bytes` like [`MemoryLayout::decode`] into the existing value `out`, reusing its
+    /// allocations.
+    ///
+    /// Parts of `out` which already have the form of the decoded value are updated in place:
+    /// strings, byte strings and collections are cleared and refilled, keeping their allocated
+    /// capacity, while the nested values are decoded into the existing ones. Thus decoding many
+    /// values of the same type into the same `out` allocates memory only when a value needs more
+    /// of it than the previous ones. On failure, `out` is left partially updated.
+    pub fn decode_into(&self, bytes: &[u8], out: &mut StrictVal) -> Result<(), ValidationError> {
+        self.decode_into_with_limits(bytes, out, DecodeLimits::unlimited())
+    }
+
+    /// Decodes `bytes` like [`MemoryLayout::decode`], failing with
+    /// [`ValidationError::LimitExceeded`] as soon as decoding would exceed any of the `limits`.
+    ///
+    /// Use this for the data coming from untrusted sources, since the collection bounds of the
+    /// type may allow values much larger than the application expects.
+    pub fn decode_with_limits(
+        &self,
+        bytes: &[u8],
+        limits: DecodeLimits,
+    ) -> Result<StrictVal, ValidationError> {
+        let mut val = StrictVal::Unit;
+        self.decode_into_with_limits(bytes, &mut val, limits)?;
+        Ok(val)
+    }
+
+    /// Decodes a value of the layout root type from the beginning of `bytes` like
+    /// [`MemoryLayout::decode`], but ignoring any data after the encoded value. Returns the value
+    /// together with the number of bytes it takes.
+    ///
+    /// This allows readers to accept data from writers using a newer version of the type, where
+    /// fields were appended to the end of the root structure: the fields known to the reader are
+    /// decoded, and the remaining bytes can be checked for being present. Only the root type can
+    /// grow this way, since fields appended to a nested type shift the data which follow it.
+    pub fn decode_prefix(&self, bytes: &[u8]) -> Result<(StrictVal, usize), ValidationError> {
+        let mut val = StrictVal::Unit;
+        let len = self.decode_prefix_into(bytes, &mut val, DecodeLimits::unlimited())?;
+        Ok((val, len))
+    }
+
+    fn decode_into_with_limits(
+        &self,
+        bytes: &[u8],
+        out: &mut StrictVal,
+        limits: DecodeLimits,
+    ) -> Result<(), ValidationError> {
+        let len = self.decode_prefix_into(bytes, out, limits)?;
+        if len < bytes.len() {
+            return Err(ValidationError::TrailingData {
+                offset: len,
+                remaining: bytes.len() - len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Decodes the value from the beginning of `bytes`, returning the length of its encoding.
+    fn decode_prefix_into(
+        &self,
+        bytes: &[u8],
+        out: &mut StrictVal,
+        limits: DecodeLimits,
+    ) -> Result<usize, ValidationError> {
+        let mut decoder = Decoder {
+            bytes,
+            offset: 0,
+            limits,
+            elements: 0,
+            allocated: 0,
+        };
+        if !self.items.is_empty() {
+            self.decode_item(0, &mut decoder, out).map_err(|err| err.with_len(bytes.len()))?;
+        }
+        Ok(decoder.offset)
+    }
+
+    /// Decodes data for the item at `pos` position, returning position of the next item not
+    /// belonging to the item subtree.
+    fn decode_item(
+        &self,
+        pos: usize,
+        d: &mut Decoder,
+        mut out: &mut StrictVal,
+    ) -> Result<usize, ValidationError> {
+        let info = &self.items[pos];
+        if info.depth as usize > d.limits.max_depth {
+            return Err(d.exceeded(info, DecodeLimit::Depth));
+        }
+        for nested in &info.nested {
+            match nested {
+                NestedCase::NewType(_) => {
+                    out = reuse_tuple(out, 1).first_mut().expect("single field");
+                }
+                NestedCase::Option => match d.read_u8(info)? {
+                    0 => {
+                        *reuse_union(out, vname!("none")) = StrictVal::Unit;
+                        return Ok(self.subtree_end(pos));
+                    }
+                    1 => out = reuse_union(out, vname!("some")),
+                    tag => {
+                        return Err(ValidationError::UnknownTag {
+                            offset: d.offset - 1,
+                            remaining: 0,
+                            info: info.clone(),
+                            tag,
+                        });
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        let first_child = pos + 1;
+        match (&info.ty, info.nested.last()) {
+            _ if info.is_recursive() => {
+                return Err(ValidationError::Recursive {
+                    offset: d.offset,
+                    remaining: 0,
+                    info: info.clone(),
+                });
+            }
+            (_, Some(NestedCase::RStr(_, _, sizing))) => {
+                d.read_str(info, *sizing, out, |s| s.is_ascii())?
+            }
+            (Ty::List(_, sizing), Some(NestedCase::AsciiStr(_))) => {
+                d.read_str(info, *sizing, out, |s| s.is_ascii())?
+            }
+            (Ty::List(_, sizing), Some(NestedCase::UniStr)) => {
+                d.read_str(info, *sizing, out, |s| std::str::from_utf8(s).is_ok())?
+            }
+            (Ty::List(_, sizing), Some(NestedCase::ByteStr)) => {
+                let len = d.read_len(info, *sizing)?;
+                d.reserve(info, 0, len)?;
+                let bytes = d.read_bytes(info, len as usize)?;
+                reuse_bytes(out).extend_from_slice(bytes);
+            }
+            (Ty::Primitive(prim), _) => d.read_number(info, *prim, out)?,
+            (Ty::UnicodeChar, _) => {
+                let offset = d.offset;
+                let first = d.read_u8(info)?;
+                let len = match first.leading_ones() {
+                    0 => 1,
+                    n @ 2..=4 => n as usize,
+                    _ => 0,
+                };
+                d.offset = offset;
+                let bytes = d.read_bytes(info, len.max(1))?;
+                match std::str::from_utf8(bytes) {
+                    Ok(s) if len > 0 => reuse_string(out).push_str(s),
+                    _ => {
+                        return Err(ValidationError::InvalidString {
+                            offset,
+                            remaining: 0,
+                            info: info.clone(),
+                        });
+                    }
+                }
+            }
+            (Ty::Enum(variants), _) => {
+                let tag = d.read_u8(info)?;
+                let Some(name) = variants.name_by_tag(tag) else {
+                    return Err(ValidationError::UnknownTag {
+                        offset: d.offset - 1,
+                        remaining: 0,
+                        info: info.clone(),
+                        tag,
+                    });
+                };
+                *out = StrictVal::enumer(name.clone());
+            }
+            (Ty::Union(variants), _) => {
+                let tag = d.read_u8(info)?;
+                let Some(index) = variants.keys().position(|variant| variant.tag == tag) else {
+                    return Err(ValidationError::UnknownTag {
+                        offset: d.offset - 1,
+                        remaining: 0,
+                        info: info.clone(),
+                        tag,
+                    });
+                };
+                let name = variants.name_by_tag(tag).expect("variant is present").clone();
+                let mut child = first_child;
+                for _ in 0..index {
+                    child = self.subtree_end(child);
+                }
+                self.decode_item(child, d, reuse_union(out, name))?;
+            }
+            (Ty::Tuple(_), _) => {
+                let mut child = first_child;
+                for field in reuse_tuple(out, info.children_count()) {
+                    child = self.decode_item(child, d, field)?;
+                }
+            }
+            (Ty::Struct(fields), _) => {
+                let mut child = first_child;
+                for (_, field) in reuse_struct(out, fields) {
+                    child = self.decode_item(child, d, field)?;
+                }
+            }
+            (Ty::Array(_, len), _) if info.ty.is_byte_array() => {
+                d.reserve(info, 0, *len as u64)?;
+                let bytes = d.read_bytes(info, *len as usize)?;
+                reuse_bytes(out).extend_from_slice(bytes);
+            }
+            (Ty::Array(_, len), _) => {
+                d.reserve_items(info, *len as u64, 1)?;
+                let len = *len as usize;
+                let items = reuse_list(out, len);
+                for index in 0..len {
+                    self.decode_item(first_child, d, slot(items, index, || StrictVal::Unit))?;
+                }
+            }
+            (Ty::List(_, sizing), _) => {
+                let len = d.read_len(info, *sizing)?;
+                d.reserve_items(info, len, 1)?;
+                let items = reuse_list(out, len as usize);
+                for index in 0..len as usize {
+                    self.decode_item(first_child, d, slot(items, index, || StrictVal::Unit))?;
+                }
+            }
+            (Ty::Set(_, sizing), _) => {
+                let len = d.read_len(info, *sizing)?;
+                d.reserve_items(info, len, 1)?;
+                let items = reuse_set(out, len as usize);
+                let mut last = None;
+                for index in 0..len as usize {
+                    let start = d.offset;
+                    self.decode_item(first_child, d, slot(items, index, || StrictVal::Unit))?;
+                    let encoded = &d.bytes[start..d.offset];
+                    self.check_order(first_child, start, index as u64, last, encoded)?;
+                    last = Some(encoded);
+                }
+            }
+            (Ty::Map(_, _, sizing), _) => {
+                let len = d.read_len(info, *sizing)?;
+                d.reserve_items(info, len, 2)?;
+                let items = reuse_map(out, len as usize);
+                let mut last = None;
+                for index in 0..len as usize {
+                    let (key, item) = slot(items, index, || (StrictVal::Unit, StrictVal::Unit));
+                    let start = d.offset;
+                    let value = self.decode_item(first_child, d, key)?;
+                    let encoded = &d.bytes[start..d.offset];
+                    self.check_order(first_child, start, index as u64, last, encoded)?;
+                    last = Some(encoded);
+                    self.decode_item(value, d, item)?;
+                }
+            }
+        }
+        Ok(self.subtree_end(pos))
+    }
+}
+
+/// Reader of the decoded data, which doesn't copy them.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    limits: DecodeLimits,
+    /// Number of collection items decoded so far.
+    elements: u64,
+    /// Size of memory allocated for the decoded value so far.
+    allocated: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn exceeded(&self, info: &TypeInfo, limit: DecodeLimit) -> ValidationError {
+        ValidationError::LimitExceeded {
+            offset: self.offset,
+            remaining: 0,
+            info: info.clone(),
+            limit,
+        }
+    }
+
+    /// Accounts for `elements` collection items and `bytes` of memory to be allocated.
+    fn reserve(
+        &mut self,
+        info: &TypeInfo,
+        elements: u64,
+        bytes: u64,
+    ) -> Result<(), ValidationError> {
+        self.elements = self.elements.saturating_add(elements);
+        if self.elements > self.limits.max_elements {
+            return Err(self.exceeded(info, DecodeLimit::Elements));
+        }
+        let bytes = usize::try_from(bytes).unwrap_or(usize::MAX);
+        self.allocated = self.allocated.saturating_add(bytes);
+        if self.allocated > self.limits.max_bytes {
+            return Err(self.exceeded(info, DecodeLimit::Bytes));
+        }
+        Ok(())
+    }
+
+    /// Accounts for `len` collection items, each made of `values` strict values.
+    fn reserve_items(
+        &mut self,
+        info: &TypeInfo,
+        len: u64,
+        values: u64,
+    ) -> Result<(), ValidationError> {
+        let size = mem::size_of::<StrictVal>() as u64;
+        self.reserve(info, len, len.saturating_mul(values).saturating_mul(size))
+    }
+
+    fn read_bytes(&mut self, info: &TypeInfo, len: usize) -> Result<&'a [u8], ValidationError> {
+        let bytes = self.bytes;
+        let Some(data) = bytes.get(self.offset..).and_then(|rest| rest.get(..len)) else {
+            self.offset = bytes.len();
+            return Err(ValidationError::UnexpectedEnd {
+                offset: self.offset,
+                remaining: 0,
+                info: info.clone(),
+            });
+        };
+        self.offset += len;
+        Ok(data)
+    }
+
+    fn read_u8(&mut self, info: &TypeInfo) -> Result<u8, ValidationError> {
+        Ok(self.read_bytes(info, 1)?[0])
+    }
+
+    fn read_len(&mut self, info: &TypeInfo, sizing: Sizing) -> Result<u64, ValidationError> {
+        let offset = self.offset;
+        let bytes = self.read_bytes(info, len_prefix(sizing))?;
+        let mut le = [0u8; 8];
+        le[..bytes.len()].copy_from_slice(bytes);
+        let len = u64::from_le_bytes(le);
+        if len < sizing.min || len > sizing.max {
+            return Err(ValidationError::InvalidLength {
+                offset,
+                remaining: 0,
+                info: info.clone(),
+                len,
+            });
+        }
+        Ok(len)
+    }
+
+    fn read_str(
+        &mut self,
+        info: &TypeInfo,
+        sizing: Sizing,
+        out: &mut StrictVal,
+        check: impl FnOnce(&[u8]) -> bool,
+    ) -> Result<(), ValidationError> {
+        let len = self.read_len(info, sizing)?;
+        self.reserve(info, 0, len)?;
+        let offset = self.offset;
+        let bytes = self.read_bytes(info, len as usize)?;
+        match std::str::from_utf8(bytes) {
+            Ok(s) if check(bytes) => {
+                reuse_string(out).push_str(s);
+                Ok(())
+            }
+            _ => Err(ValidationError::InvalidString {
+                offset,
+                remaining: 0,
+                info: info.clone(),
+            }),
+        }
+    }
+
+    fn read_number(
+        &mut self,
+        info: &TypeInfo,
+        prim: Primitive,
+        out: &mut StrictVal,
+    ) -> Result<(), ValidationError> {
+        if prim == Primitive::UNIT {
+            *out = StrictVal::Unit;
+            return Ok(());
+        }
+        let offset = self.offset;
+        let size = prim.byte_size() as usize;
+        let bytes = self.read_bytes(info, size)?;
+        let negative = bytes.last().is_some_and(|byte| byte & 0x80 != 0);
+        let num = match prim.info().ty {
+            _ if prim == Primitive::BYTE => StrictNum::Uint(bytes[0] as u64),
+            NumCls::Unsigned | NumCls::NonZero if size <= 8 => {
+                let mut le = [0u8; 8];
+                le[..size].copy_from_slice(bytes);
+                StrictNum::Uint(u64::from_le_bytes(le))
+            }
+            NumCls::Unsigned | NumCls::NonZero if size <= 128 => {
+                let mut le = [0u8; 128];
+                le[..size].copy_from_slice(bytes);
+                StrictNum::BigUint(u1024::from_le_bytes(le))
+            }
+            NumCls::Signed if size <= 8 => {
+                let mut le = [if negative { 0xFF } else { 0 }; 8];
+                le[..size].copy_from_slice(bytes);
+                StrictNum::Int(i64::from_le_bytes(le))
+            }
+            NumCls::Signed if size <= 128 => {
+                let mut le = [if negative { 0xFF } else { 0 }; 128];
+                le[..size].copy_from_slice(bytes);
+                StrictNum::BigInt(i1024::from_le_bytes(le))
+            }
+            _ => {
+                return Err(ValidationError::Unsupported {
+                    offset,
+                    remaining: 0,
+                    info: info.clone(),
+                });
+            }
+        };
+        *out = StrictVal::Number(num);
+        Ok(())
+    }
+}
+
+fn reuse_string(out: &mut StrictVal) -> &mut String {
+    if !matches!(out, StrictVal::String(_)) {
+        *out = StrictVal::String(s!(""));
+    }
+    let StrictVal::String(s) = out else {
+        unreachable!()
+    };
+    s.clear();
+    s
+}
+
+fn reuse_bytes(out: &mut StrictVal) -> &mut Vec<u8> {
+    if !matches!(out, StrictVal::Bytes(_)) {
+        *out = StrictVal::Bytes(Blob(vec![]));
+    }
+    let StrictVal::Bytes(Blob(bytes)) = out else {
+        unreachable!()
+    };
+    bytes.clear();
+    bytes
+}
+
+fn reuse_union(out: &mut StrictVal, name: VariantName) -> &mut StrictVal {
+    match out {
+        StrictVal::Union(EnumTag::Name(tag), _) if *tag == name => {}
+        StrictVal::Union(tag, _) => *tag = EnumTag::Name(name),
+        _ => *out = StrictVal::union(name, StrictVal::Unit),
+    }
+    let StrictVal::Union(_, inner) = out else {
+        unreachable!()
+    };
+    inner
+}
+
+/// Resizes the vector to `len` items, keeping the existing ones.
+fn resize(items: &mut Vec<StrictVal>, len: usize) -> &mut [StrictVal] {
+    items.truncate(len);
+    items.resize(len, StrictVal::Unit);
+    items
+}
+
+fn reuse_tuple(out: &mut StrictVal, len: usize) -> &mut [StrictVal] {
+    if !matches!(out, StrictVal::Tuple(_)) {
+        *out = StrictVal::Tuple(Vec::with_capacity(len));
+    }
+    let StrictVal::Tuple(items) = out else {
+        unreachable!()
+    };
+    resize(items, len)
+}
+
+/// Prepares a list for decoding `len` items, keeping up to `len` existing ones.
+///
+/// The list is not grown in advance, since `len` comes from the untrusted input: each item is
+/// added with [`slot`] only once the decoder gets to it.
+fn reuse_list(out: &mut StrictVal, len: usize) -> &mut Vec<StrictVal> {
+    if !matches!(out, StrictVal::List(_)) {
+        *out = StrictVal::List(Vec::new());
+    }
+    let StrictVal::List(items) = out else {
+        unreachable!()
+    };
+    items.truncate(len);
+    items
+}
+
+/// Prepares a set for decoding `len` items; see [`reuse_list`].
+fn reuse_set(out: &mut StrictVal, len: usize) -> &mut Vec<StrictVal> {
+    if !matches!(out, StrictVal::Set(_)) {
+        *out = StrictVal::Set(Vec::new());
+    }
+    let StrictVal::Set(items) = out else {
+        unreachable!()
+    };
+    items.truncate(len);
+    items
+}
+
+/// Prepares a map for decoding `len` entries; see [`reuse_list`].
+fn reuse_map(out: &mut StrictVal, len: usize) -> &mut Vec<(StrictVal, StrictVal)> {
+    if !matches!(out, StrictVal::Map(_)) {
+        *out = StrictVal::Map(Vec::new());
+    }
+    let StrictVal::Map(items) = out else {
+        unreachable!()
+    };
+    items.truncate(len);
+    items
+}
+
+/// Returns the item at `index`, appending a new one if the vector doesn't have it yet.
+fn slot<T>(items: &mut Vec<T>, index: usize, new: impl FnOnce() -> T) -> &mut T {
+    if index == items.len() {
+        items.push(new());
+    }
+    &mut items[index]
+}
+
+/// Prepares structure with the `fields`, keeping the existing field values if the structure
+/// already has the same fields.
+fn reuse_struct<'a>(
+    out: &'a mut StrictVal,
+    fields: &NamedFields<SemId>,
+) -> indexmap::map::IterMut<'a, FieldName, StrictVal> {
+    let same = matches!(out, StrictVal::Struct(items)
+        if items.len() == fields.len() && items.keys().zip(fields).all(|(a, b)| *a == b.name));
+    if !same {
+        let items = fields.iter().map(|field| (field.name.clone(), StrictVal::Unit));
+        *out = StrictVal::Struct(items.collect::<IndexMap<_, _>>());
+    }
+    let StrictVal::Struct(items) = out else {
+        unreachable!()
+    };
+    items.iter_mut()
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{LargeVec, SmallVec, TinyOrdMap, TinyOrdSet, TinyString, TinyVec};
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::typesys::SystemBuilder;
+    use crate::value::test_helpers::{test_system, Nominal};
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Amount(u64);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = order, dumb = Shape::Empty)]
+    pub enum Shape {
+        Empty,
+        Pair(u8, i16),
+        Named { width: u16 },
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub amount: Amount,
+        pub delta: i32,
+        pub note: Option<u16>,
+        pub comment: TinyString,
+        pub bytes: [u8; 4],
+        pub items: TinyVec<Shape>,
+        pub set: TinyOrdSet<u8>,
+        pub map: TinyOrdMap<u8, Amount>,
+    }
+
+    impl StrictSerialize for Record {}
+
+    fn record(len: u8) -> Record {
+        Record {
+            amount: Amount(1 << 40),
+            delta: -5,
+            note: if len % 2 == 0 { Some(len as u16) } else { None },
+            comment: TinyString::try_from("x".repeat(len as usize)).unwrap(),
+            bytes: [len; 4],
+            items: TinyVec::try_from(vec![
+                Shape::Pair(len, -(len as i16)),
+                Shape::Named { width: len as u16 },
+                Shape::Empty,
+            ])
+            .unwrap(),
+            set: TinyOrdSet::try_from_iter(0..len).unwrap(),
+            map: TinyOrdMap::try_from_iter((0..len).map(|i| (i, Amount(i as u64)))).unwrap(),
+        }
+    }
+
+    #[test]
+    fn decode() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Record").unwrap().to_layout();
+
+        let mut out = StrictVal::Unit;
+        for len in [4u8, 3, 6] {
+            let data = record(len).to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+            let expected = sys.strict_deserialize_type("Test.Record", &data).unwrap().unbox();
+            assert_eq!(layout.decode(&data).unwrap(), expected);
+            layout.decode_into(&data, &mut out).unwrap();
+            assert_eq!(out, expected);
+        }
+
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        let data = Nominal::with("TICK", "Some name", 2)
+            .to_strict_serialized::<{ u16::MAX as usize }>()
+            .unwrap();
+        let expected = sys.strict_deserialize_type("TestLib.Nominal", &data).unwrap().unbox();
+        assert_eq!(layout.decode(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn decode_into_reuse() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Record").unwrap().to_layout();
+
+        let mut out = StrictVal::Unit;
+        let data = record(8).to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        layout.decode_into(&data, &mut out).unwrap();
+        let comment = out.unwrap_struct("comment").unwrap_string().len();
+        let StrictVal::Struct(fields) = &out else {
+            panic!()
+        };
+        let (StrictVal::String(s), StrictVal::Set(set)) =
+            (&fields[&fname!("comment")], &fields[&fname!("set")])
+        else {
+            panic!()
+        };
+        let (comment_ptr, set_ptr) = (s.as_ptr(), set.as_ptr());
+        assert_eq!(comment, 8);
+
+        let data = record(6).to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        layout.decode_into(&data, &mut out).unwrap();
+        let StrictVal::Struct(fields) = &out else {
+            panic!()
+        };
+        let (StrictVal::String(s), StrictVal::Set(set)) =
+            (&fields[&fname!("comment")], &fields[&fname!("set")])
+        else {
+            panic!()
+        };
+        assert_eq!(s.as_str(), "xxxxxx");
+        assert_eq!(set.len(), 6);
+        assert_eq!((s.as_ptr(), set.as_ptr()), (comment_ptr, set_ptr));
+
+        let err = layout.decode_into(&data[..data.len() - 1], &mut out).unwrap_err();
+        assert!(matches!(err, ValidationError::UnexpectedEnd { remaining: 0, .. }));
+        let mut trailing = data.release();
+        trailing.push(0);
+        let err = layout.decode(&trailing).unwrap_err();
+        assert!(matches!(err, ValidationError::TrailingData { remaining: 1, .. }));
+    }
+
+    #[test]
+    fn decode_prefix() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Record").unwrap().to_layout();
+        let data = record(4).to_strict_serialized::<{ u16::MAX as usize }>().unwrap().release();
+        let expected = layout.decode(&data).unwrap();
+        assert_eq!(layout.decode_prefix(&data).unwrap(), (expected.clone(), data.len()));
+
+        // fields appended by a newer version of the type
+        let mut newer = data.clone();
+        newer.extend([1, 2, 3]);
+        assert_eq!(layout.decode_prefix(&newer).unwrap(), (expected, data.len()));
+
+        let err = layout.decode_prefix(&data[..data.len() - 1]).unwrap_err();
+        assert!(matches!(err, ValidationError::UnexpectedEnd { .. }));
+    }
+
+    #[test]
+    fn decode_with_limits() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Record").unwrap().to_layout();
+        let data = record(8).to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let expected = layout.decode(&data).unwrap();
+
+        // 3 shapes, 8 set items and 8 map entries
+        let elements = 19;
+        let bytes = 8 + 4 + (3 + 8 + 16) * mem::size_of::<StrictVal>();
+        let depth = layout.items.iter().map(|info| info.depth as usize).max().unwrap();
+        let exact = DecodeLimits {
+            max_elements: elements,
+            max_bytes: bytes,
+            max_depth: depth,
+        };
+        assert_eq!(layout.decode_with_limits(&data, exact).unwrap(), expected);
+        assert_eq!(layout.decode_with_limits(&data, DecodeLimits::default()).unwrap(), expected);
+
+        for (limits, kind) in [
+            (
+                DecodeLimits {
+                    max_elements: elements - 1,
+                    ..exact
+                },
+                DecodeLimit::Elements,
+            ),
+            (
+                DecodeLimits {
+                    max_bytes: bytes - 1,
+                    ..exact
+                },
+                DecodeLimit::Bytes,
+            ),
+            (
+                DecodeLimits {
+                    max_depth: depth - 1,
+                    ..exact
+                },
+                DecodeLimit::Depth,
+            ),
+        ] {
+            let err = layout.decode_with_limits(&data, limits).unwrap_err();
+            assert!(matches!(err, ValidationError::LimitExceeded { limit, .. } if limit == kind));
+        }
+
+        // length prefix of a large list without the list data
+        let lib = LibBuilder::with("Test", None)
+            .transpile_named::<SmallVec<u64>>(tn!("Large"))
+            .compile()
+            .unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Large").unwrap().to_layout();
+        let limits = DecodeLimits {
+            max_bytes: 0x1000,
+            ..DecodeLimits::unlimited()
+        };
+        let err = layout.decode_with_limits(&[0xFF, 0xFF], limits).unwrap_err();
+        assert_eq!(err.offset(), 2);
+        assert!(matches!(err, ValidationError::LimitExceeded {
+            limit: DecodeLimit::Bytes,
+            ..
+        }));
+        assert!(matches!(layout.decode(&[0xFF, 0xFF]), Err(ValidationError::UnexpectedEnd { .. })));
+    }
+
+    #[test]
+    fn decode_huge_prefix() {
+        let lib = LibBuilder::with("Test", None)
+            .transpile_named::<LargeVec<u64>>(tn!("Huge"))
+            .compile()
+            .unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Huge").unwrap().to_layout();
+        let data = [0xFF, 0xFF, 0xFF, 0x7F];
+
+        let err = layout.decode(&data).unwrap_err();
+        assert_eq!(err.offset(), 4);
+        assert!(matches!(err, ValidationError::UnexpectedEnd { .. }));
+        let mut out = StrictVal::Unit;
+        assert!(layout.decode_into(&data, &mut out).is_err());
+        assert!(layout.decode_prefix(&data).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use super::{FieldPath, MemoryLayout, ValidationError};
+use crate::StrictVal;
+
+/// Leaf value which differs between two encodings of the same type.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FieldDiff {
+    /// Path to the value, using field names, tuple field positions, collection item indexes and
+    /// variant names of the unions. Newtypes don't add their field to the path.
+    pub path: FieldPath,
+    /// Value decoded from the first encoding.
+    pub a: StrictVal,
+    /// Value decoded from the second encoding.
+    pub b: StrictVal,
+}
+
+/// Errors comparing two encodings of a type.
+#[derive(Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum DiffBytesError {
+    /// the first encoding is invalid: {0}
+    First(ValidationError),
+
+    /// the second encoding is invalid: {0}
+    Second(ValidationError),
+}
+
+impl MemoryLayout {
+    /// Decodes two encodings of the layout root type and reports the values which differ between
+    /// them. Encodings of equal values produce no differences.
+    ///
+    /// Values are compared down to primitives, strings, byte strings and enums. Where the
+    /// structure of the values diverges, the whole diverging values are reported: unions with
+    /// different variants, lists and arrays of different length, and sets and maps which are not
+    /// equal, unless the maps have the same keys, in which case differing values are reported.
+    pub fn diff_bytes(&self, a: &[u8], b: &[u8]) -> Result<Vec<FieldDiff>, DiffBytesError> {
+        let a = self.decode(a).map_err(DiffBytesError::First)?;
+        let b = self.decode(b).map_err(DiffBytesError::Second)?;
+        let mut diffs = vec![];
+        diff_vals(FieldPath::default(), &a, &b, &mut diffs);
+        Ok(diffs)
+    }
+}
+
+fn diff_vals(path: FieldPath, a: &StrictVal, b: &StrictVal, diffs: &mut Vec<FieldDiff>) {
+    if a == b {
+        return;
+    }
+    match (a, b) {
+        (StrictVal::Tuple(a), StrictVal::Tuple(b)) if a.len() == 1 && b.len() == 1 => {
+            diff_vals(path, &a[0], &b[0], diffs)
+        }
+        (StrictVal::Tuple(a), StrictVal::Tuple(b)) | (StrictVal::List(a), StrictVal::List(b))
+            if a.len() == b.len() =>
+        {
+            for (no, (a, b)) in a.iter().zip(b).enumerate() {
+                diff_vals(path.with(no), a, b, diffs);
+            }
+        }
+        (StrictVal::Struct(a), StrictVal::Struct(b))
+            if a.len() == b.len() && a.keys().zip(b.keys()).all(|(a, b)| a == b) =>
+        {
+            for ((name, a), b) in a.iter().zip(b.values()) {
+                diff_vals(path.with(name), a, b, diffs);
+            }
+        }
+        (StrictVal::Union(tag_a, a), StrictVal::Union(tag_b, b)) if tag_a == tag_b => {
+            diff_vals(path.with(tag_a), a, b, diffs)
+        }
+        (StrictVal::Map(a), StrictVal::Map(b))
+            if a.len() == b.len() && a.iter().zip(b).all(|((a, _), (b, _))| a == b) =>
+        {
+            for (no, ((_, a), (_, b))) in a.iter().zip(b).enumerate() {
+                diff_vals(path.with(no), a, b, diffs);
+            }
+        }
+        _ => diffs.push(FieldDiff {
+            path,
+            a: a.clone(),
+            b: b.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyOrdMap, TinyVec};
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::typesys::SystemBuilder;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Amount(u64);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        id: u16,
+        amount: Amount,
+        counter: Option<u32>,
+        items: TinyVec<u16>,
+        limits: TinyOrdMap<u8, u16>,
+    }
+    impl StrictSerialize for Record {}
+
+    fn path(segments: &[&str]) -> FieldPath {
+        FieldPath::from(segments.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn diff_bytes() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("TestLib.Record").unwrap().to_layout();
+
+        let record = Record {
+            id: 1,
+            amount: Amount(100),
+            counter: Some(5),
+            items: tiny_vec![1, 2, 3],
+            limits: tiny_bmap! { 1 => 10, 2 => 20 },
+        };
+        let a = record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        assert_eq!(layout.diff_bytes(&a, &a).unwrap(), vec![]);
+
+        let other = Record {
+            amount: Amount(200),
+            counter: Some(6),
+            items: tiny_vec![1, 4, 3],
+            limits: tiny_bmap! { 1 => 10, 2 => 30 },
+            ..record.clone()
+        };
+        let b = other.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let diffs = layout.diff_bytes(&a, &b).unwrap();
+        let paths = diffs.iter().map(|diff| diff.path.to_string()).collect::<Vec<_>>();
+        assert_eq!(paths, ["amount", "counter.some", "items.1", "limits.1"]);
+        assert_eq!(diffs[0].a, StrictVal::num(100u64));
+        assert_eq!(diffs[0].b, StrictVal::num(200u64));
+
+        let diverged = Record {
+            counter: None,
+            items: tiny_vec![1],
+            ..record.clone()
+        };
+        let b = diverged.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let diffs = layout.diff_bytes(&a, &b).unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].path, path(&["counter"]));
+        assert_eq!(diffs[0].b, StrictVal::none());
+        assert_eq!(diffs[1].path, path(&["items"]));
+
+        assert!(matches!(layout.diff_bytes(&a, &b[..4]), Err(DiffBytesError::Second(_))));
+        assert!(matches!(layout.diff_bytes(&[], &b), Err(DiffBytesError::First(_))));
+    }
+}
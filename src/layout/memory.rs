@@ -22,17 +22,48 @@
 // the License.
 
 use amplify::confinement::LargeVec;
+use encoding::{DecodeError, ReadStruct, Sizing, StrictDecode, TypedRead};
 use strict_encoding::STRICT_TYPES_LIB;
 
-use crate::typesys::{TypeInfo, TypeTree};
+use crate::typesys::{NestedCase, TypeInfo, TypeTree};
 
+/// Layout of the type tree items in the order of their strict encoding.
+///
+/// Layouts decoded from their strict encoding or deserialized with serde are checked for
+/// consistency, such that each item is followed by all of its nested items.
 #[derive(Clone, Eq, PartialEq, Debug)]
-#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[derive(StrictType, StrictDumb, StrictEncode)]
 #[strict_type(lib = STRICT_TYPES_LIB)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "LayoutItems"))]
 pub struct MemoryLayout {
+    pub(super) items: LargeVec<TypeInfo>,
+}
+
+impl StrictDecode for MemoryLayout {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_struct(|reader| {
+            let items = reader.read_field(fname!("items"))?;
+            MemoryLayout::restore(items)
+                .map_err(|err| DecodeError::DataIntegrityError(err.to_string()))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct LayoutItems {
     items: LargeVec<TypeInfo>,
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<LayoutItems> for MemoryLayout {
+    type Error = LayoutError;
+
+    fn try_from(layout: LayoutItems) -> Result<Self, Self::Error> { Self::restore(layout.items) }
+}
+
+/// Conversion of trusted type trees; use [`MemoryLayout::try_from_tree`] for untrusted ones, since
+/// the conversion panics if the layout exceeds `u32::MAX` items.
 impl From<TypeTree<'_>> for MemoryLayout {
     fn from(tree: TypeTree) -> Self {
         let mut layout = MemoryLayout::new();
@@ -41,6 +72,10 @@ impl From<TypeTree<'_>> for MemoryLayout {
     }
 }
 
+// TODO: Provide a `rayon`-gated parallel construction. Subtrees can't be laid out independently
+//       as is: `TypeInfo::recursive` keeps absolute item positions and the recursion detection
+//       requires the full path of ancestors, thus the partial layouts must be re-based when
+//       concatenated.
 impl<'a> From<&'a TypeTree<'_>> for MemoryLayout {
     fn from(tree: &'a TypeTree) -> Self {
         let mut layout = MemoryLayout::new();
@@ -49,6 +84,176 @@ impl<'a> From<&'a TypeTree<'_>> for MemoryLayout {
     }
 }
 
+/// Errors constructing memory layout from an untrusted type tree.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum LayoutError {
+    /// type layout exceeds the limit of {0} items.
+    TooLarge(usize),
+
+    /// type layout exceeds the nesting depth limit of {0}.
+    TooDeep(usize),
+
+    /// type layout is inconsistent at item {0}, which doesn't belong to the root type or lacks
+    /// nested items.
+    Inconsistent(usize),
+}
+
 impl MemoryLayout {
     fn new() -> Self { Self { items: empty!() } }
+
+    /// Constructs memory layout of a type tree, failing if the layout has more than `max_items`
+    /// items.
+    ///
+    /// Unlike the [`From`] conversion, which panics if the layout doesn't fit into the memory,
+    /// this method is safe to use on type trees coming from untrusted type systems: a small type
+    /// system may define layouts growing exponentially with the nesting depth, thus the
+    /// construction stops as soon as the limit is exceeded. The limit can't be larger than
+    /// `u32::MAX`.
+    pub fn try_from_tree(tree: &TypeTree, max_items: usize) -> Result<Self, LayoutError> {
+        let max_items = max_items.min(u32::MAX as usize);
+        let mut layout = MemoryLayout::new();
+        for info in tree {
+            if layout.items.len() >= max_items {
+                return Err(LayoutError::TooLarge(max_items));
+            }
+            layout.items.push(info).map_err(|_| LayoutError::TooLarge(max_items))?;
+        }
+        Ok(layout)
+    }
+
+    /// Constructs memory layout of a type tree, failing if it nests items deeper than `limit`;
+    /// see [`TypeTree::max_depth`] for how the depth is counted.
+    ///
+    /// Validation of data against a layout recurses into the nested items, thus limiting the
+    /// depth of layouts for types coming from untrusted type systems bounds the stack usage.
+    /// Since the depth alone doesn't bound the number of items, combine the check with
+    /// [`MemoryLayout::try_from_tree`] for such type systems.
+    pub fn with_max_depth(tree: &TypeTree, limit: usize) -> Result<Self, LayoutError> {
+        let mut layout = MemoryLayout::new();
+        for info in tree {
+            if info.depth as usize > limit {
+                return Err(LayoutError::TooDeep(limit));
+            }
+            layout.items.push(info).map_err(|_| LayoutError::TooLarge(u32::MAX as usize))?;
+        }
+        Ok(layout)
+    }
+
+    /// Checks the consistency of layout items coming from a serialized layout and restores the
+    /// back-references of recursive types, which are not kept by the strict encoding.
+    ///
+    /// An item of a type which is already being expanded by one of its ancestors is a
+    /// back-reference, in the same way as [`TypeTree`] emits them.
+    fn restore(mut items: LargeVec<TypeInfo>) -> Result<Self, LayoutError> {
+        // positions of the items being expanded, with the number of their nested items left
+        let mut ancestors: Vec<(usize, usize)> = vec![];
+        for pos in 0..items.len() {
+            while ancestors.last().is_some_and(|(_, left)| *left == 0) {
+                ancestors.pop();
+            }
+            match ancestors.last_mut() {
+                Some((_, left)) => *left -= 1,
+                None if pos > 0 => return Err(LayoutError::Inconsistent(pos)),
+                None => {}
+            }
+            let info = &items[pos];
+            match info.recursive {
+                Some(start) if start as usize >= pos => return Err(LayoutError::Inconsistent(pos)),
+                Some(_) => continue,
+                None if info.children_count() == 0 => continue,
+                None => {}
+            }
+            let start = ancestors.iter().map(|(start, _)| *start).find(|start| {
+                let ancestor = &items[*start];
+                let newtype = NestedCase::NewType(info.fqn.clone());
+                (ancestor.ty == info.ty && ancestor.fqn == info.fqn)
+                    || (info.ty.is_newtype() && ancestor.nested.contains(&newtype))
+            });
+            match start {
+                Some(start) => items[pos].recursive = Some(start as u32),
+                None => ancestors.push((pos, info.children_count())),
+            }
+        }
+        if ancestors.iter().any(|(_, left)| *left > 0) {
+            return Err(LayoutError::Inconsistent(items.len()));
+        }
+        Ok(Self { items })
+    }
+
+    /// Returns position of the first item following the subtree of the item at `pos`.
+    pub(super) fn subtree_end(&self, pos: usize) -> usize {
+        let mut next = pos + 1;
+        for _ in 0..self.items[pos].children_count() {
+            next = self.subtree_end(next);
+        }
+        next
+    }
+}
+
+/// Collections are prefixed with their length, which is encoded using the minimal number of
+/// bytes able to hold the maximal collection size.
+pub(crate) fn len_prefix(sizing: Sizing) -> usize {
+    match sizing.max {
+        max if max <= u8::MAX as u64 => 1,
+        max if max <= u16::MAX as u64 => 2,
+        max if max <= 0xFF_FFFF => 3,
+        max if max <= u32::MAX as u64 => 4,
+        _ => 8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use encoding::{StrictDeserialize, StrictSerialize};
+
+    use super::*;
+    use crate::value::test_helpers::test_system;
+
+    impl StrictSerialize for MemoryLayout {}
+    impl StrictDeserialize for MemoryLayout {}
+
+    #[test]
+    fn strict_roundtrip() {
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        let data = layout.to_strict_serialized::<{ u32::MAX as usize }>().unwrap();
+        assert_eq!(MemoryLayout::from_strict_serialized(data).unwrap(), layout);
+    }
+
+    #[test]
+    fn inconsistent() {
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        let mut items = layout.items.clone();
+        let last = items.pop().unwrap();
+        assert_eq!(
+            MemoryLayout::restore(items.clone()),
+            Err(LayoutError::Inconsistent(layout.items.len() - 1))
+        );
+        items.push(last.clone()).unwrap();
+        items.push(last).unwrap();
+        assert_eq!(
+            MemoryLayout::restore(items.clone()),
+            Err(LayoutError::Inconsistent(layout.items.len()))
+        );
+
+        let data = MemoryLayout { items }.to_strict_serialized::<{ u32::MAX as usize }>().unwrap();
+        assert!(MemoryLayout::from_strict_serialized(data).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip() {
+        let sys = test_system();
+        let layout = sys.type_tree("StrictTypes.TypeLib").unwrap().to_layout();
+        let json = serde_json::to_string(&layout).unwrap();
+        let decoded: MemoryLayout = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, layout);
+
+        let mut truncated = layout.clone();
+        truncated.items.pop();
+        let json = serde_json::to_string(&truncated).unwrap();
+        assert!(serde_json::from_str::<MemoryLayout>(&json).is_err());
+    }
 }
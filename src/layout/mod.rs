@@ -22,5 +22,22 @@
 // the License.
 
 mod memory;
+mod columns;
+mod decode;
+mod diff;
+mod offsets;
+mod order;
+mod patch;
+mod sanity;
+mod size;
+mod validate;
 
-pub use memory::MemoryLayout;
+pub use columns::{Column, ColumnType};
+pub use decode::{DecodeLimit, DecodeLimits};
+pub use diff::{DiffBytesError, FieldDiff};
+pub(crate) use memory::len_prefix;
+pub use memory::{LayoutError, MemoryLayout};
+pub use offsets::FieldPath;
+pub use patch::PatchError;
+pub use sanity::{EndianSuspect, SanityReport};
+pub use validate::{DecodeTrace, TraceStep, ValidationError};
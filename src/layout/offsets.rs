@@ -0,0 +1,146 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+use super::MemoryLayout;
+use crate::ast::ItemCase;
+use crate::typesys::NestedCase;
+use crate::Ty;
+
+/// Path to a field inside a type, consisting of field names, tuple field positions and array
+/// item indexes. Displayed as a dot-separated string, like `header.version` or `inputs.0.txid`.
+#[derive(Wrapper, WrapperMut, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+pub struct FieldPath(Vec<String>);
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str(&self.0.join(".")) }
+}
+
+impl FieldPath {
+    pub(crate) fn with(&self, segment: impl ToString) -> Self {
+        let mut path = self.clone();
+        path.0.push(segment.to_string());
+        path
+    }
+}
+
+impl MemoryLayout {
+    /// Computes byte offset and length of each leaf field of a type which has a fixed-size
+    /// encoding.
+    ///
+    /// Leaves are primitives, enums and byte arrays; elements of other arrays are addressed by
+    /// their index. The root type itself, if it is a leaf, has an empty path.
+    ///
+    /// Returns `None` if the type contains any variable-length element: collections, unions
+    /// (including optional types), unicode characters or recursive types.
+    pub fn field_offsets(&self) -> Option<BTreeMap<FieldPath, (usize, usize)>> {
+        let offsets = self.leaf_offsets()?;
+        Some(offsets.into_iter().map(|(path, (_, offset, len))| (path, (offset, len))).collect())
+    }
+
+    /// Computes layout item position, byte offset and length of each leaf field, in the same way
+    /// as [`MemoryLayout::field_offsets`].
+    pub(super) fn leaf_offsets(&self) -> Option<BTreeMap<FieldPath, (usize, usize, usize)>> {
+        let mut offsets = BTreeMap::new();
+        if !self.items.is_empty() {
+            self.item_offsets(0, FieldPath::default(), 0, &mut offsets)?;
+        }
+        Some(offsets)
+    }
+
+    /// Collects offsets of the leaves in the subtree of the item at `pos`, returning the encoded
+    /// size of the item.
+    fn item_offsets(
+        &self,
+        pos: usize,
+        path: FieldPath,
+        offset: usize,
+        offsets: &mut BTreeMap<FieldPath, (usize, usize, usize)>,
+    ) -> Option<usize> {
+        let info = &self.items[pos];
+        if info.nested.iter().any(|nested| !matches!(nested, NestedCase::NewType(_))) {
+            // options, strings and recursive references have no fixed size
+            return None;
+        }
+        let len = match &info.ty {
+            Ty::Primitive(prim) => prim.byte_size() as usize,
+            Ty::Enum(_) => 1,
+            Ty::Array(_, len) if info.ty.is_byte_array() => *len as usize,
+            Ty::Array(_, len) => {
+                let mut size = 0;
+                for index in 0..*len {
+                    size += self.item_offsets(pos + 1, path.with(index), offset + size, offsets)?;
+                }
+                return Some(size);
+            }
+            Ty::Tuple(_) | Ty::Struct(_) => {
+                let mut size = 0;
+                let mut next = pos + 1;
+                for _ in 0..info.children_count() {
+                    let child = &self.items[next];
+                    let path = match &child.item {
+                        Some(ItemCase::NamedField(_, name)) => path.with(name),
+                        Some(ItemCase::UnnamedField(index)) => path.with(index),
+                        _ => unreachable!("tuple and struct items are always fields"),
+                    };
+                    size += self.item_offsets(next, path, offset + size, offsets)?;
+                    next = self.subtree_end(next);
+                }
+                return Some(size);
+            }
+            Ty::UnicodeChar | Ty::Union(_) | Ty::List(..) | Ty::Set(..) | Ty::Map(..) => {
+                return None
+            }
+        };
+        offsets.insert(path, (pos, offset, len));
+        Some(len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value::test_helpers::test_system;
+
+    #[test]
+    fn field_offsets() {
+        let sys = test_system();
+
+        let layout = sys.type_tree("StrictTypes.Sizing").unwrap().to_layout();
+        let offsets = layout.field_offsets().unwrap();
+        assert_eq!(offsets.len(), 2);
+        assert_eq!(offsets[&FieldPath::from(vec![s!("min")])], (0, 8));
+        assert_eq!(offsets[&FieldPath::from(vec![s!("max")])], (8, 8));
+
+        let layout = sys.type_tree("StrictTypes.SemId").unwrap().to_layout();
+        let offsets = layout.field_offsets().unwrap();
+        assert_eq!(offsets[&FieldPath::default()], (0, 32));
+
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        assert_eq!(layout.field_offsets(), None);
+    }
+}
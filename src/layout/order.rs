@@ -0,0 +1,176 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Comparison of strict-encoded values following the order of the values of the original types.
+
+use std::cmp::Ordering;
+
+use encoding::{NumCls, Sizing};
+
+use super::memory::len_prefix;
+use super::MemoryLayout;
+use crate::typesys::NestedCase;
+use crate::Ty;
+
+impl MemoryLayout {
+    /// Compares two valid strict encodings of the layout root type in the order which strict
+    /// encoding requires for set items and map keys.
+    #[cfg(feature = "value-gen")]
+    pub(crate) fn cmp_values(&self, mut a: &[u8], mut b: &[u8]) -> Ordering {
+        self.cmp_encoded(0, &mut a, &mut b)
+    }
+
+    /// Compares two strict-encoded values of the type at `pos` in the same way as the original
+    /// types are ordered (for types deriving `Ord`), consuming the compared data from both
+    /// slices. The consumption stops at the first data which differ.
+    ///
+    /// Both values must be already validated against the layout.
+    pub(super) fn cmp_encoded(&self, pos: usize, a: &mut &[u8], b: &mut &[u8]) -> Ordering {
+        let info = &self.items[pos];
+        for nested in &info.nested {
+            if *nested != NestedCase::Option {
+                continue;
+            }
+            let (tag_a, tag_b) = (take(a, 1)[0], take(b, 1)[0]);
+            if tag_a == 0 || tag_b == 0 {
+                return tag_a.cmp(&tag_b);
+            }
+        }
+
+        let first_child = pos + 1;
+        match (&info.ty, info.nested.last()) {
+            // no knowledge of the recursive type structure is available, so we fall back to the
+            // comparison of the remaining data
+            _ if info.is_recursive() => (*a).cmp(*b),
+            (_, Some(NestedCase::RStr(_, _, sizing))) => cmp_str(*sizing, a, b),
+            (Ty::List(_, sizing), Some(NestedCase::AsciiStr(_) | NestedCase::UniStr)) => {
+                cmp_str(*sizing, a, b)
+            }
+            (Ty::List(_, sizing), Some(NestedCase::ByteStr)) => cmp_str(*sizing, a, b),
+            (Ty::Primitive(prim), _) => {
+                let len = prim.byte_size() as usize;
+                let (mut a, mut b) = (take(a, len).to_vec(), take(b, len).to_vec());
+                // numbers are little-endian
+                a.reverse();
+                b.reverse();
+                if prim.info().ty == NumCls::Signed && len > 0 {
+                    a[0] ^= 0x80;
+                    b[0] ^= 0x80;
+                }
+                a.cmp(&b)
+            }
+            (Ty::UnicodeChar, _) => {
+                // UTF-8 preserves the order of the code points
+                let len_a = a[0].leading_ones().max(1) as usize;
+                let len_b = b[0].leading_ones().max(1) as usize;
+                take(a, len_a).cmp(take(b, len_b))
+            }
+            (Ty::Enum(_), _) => take(a, 1).cmp(take(b, 1)),
+            (Ty::Union(variants), _) => {
+                let (tag_a, tag_b) = (take(a, 1)[0], take(b, 1)[0]);
+                if tag_a != tag_b {
+                    return tag_a.cmp(&tag_b);
+                }
+                let index = variants
+                    .keys()
+                    .position(|variant| variant.tag == tag_a)
+                    .expect("validated data");
+                let mut child = first_child;
+                for _ in 0..index {
+                    child = self.subtree_end(child);
+                }
+                self.cmp_encoded(child, a, b)
+            }
+            (Ty::Tuple(_) | Ty::Struct(_), _) => {
+                let mut child = first_child;
+                for _ in 0..info.children_count() {
+                    let ord = self.cmp_encoded(child, a, b);
+                    if ord.is_ne() {
+                        return ord;
+                    }
+                    child = self.subtree_end(child);
+                }
+                Ordering::Equal
+            }
+            (Ty::Array(_, len), _) if info.ty.is_byte_array() => {
+                take(a, *len as usize).cmp(take(b, *len as usize))
+            }
+            (Ty::Array(_, len), _) => self.cmp_seq(first_child, *len as u64, *len as u64, a, b),
+            (Ty::List(_, sizing) | Ty::Set(_, sizing), _) => {
+                let (len_a, len_b) = (read_len(*sizing, a), read_len(*sizing, b));
+                self.cmp_seq(first_child, len_a, len_b, a, b)
+            }
+            (Ty::Map(_, _, sizing), _) => {
+                let (len_a, len_b) = (read_len(*sizing, a), read_len(*sizing, b));
+                let value = self.subtree_end(first_child);
+                for _ in 0..len_a.min(len_b) {
+                    let ord = self.cmp_encoded(first_child, a, b);
+                    if ord.is_ne() {
+                        return ord;
+                    }
+                    let ord = self.cmp_encoded(value, a, b);
+                    if ord.is_ne() {
+                        return ord;
+                    }
+                }
+                len_a.cmp(&len_b)
+            }
+        }
+    }
+
+    /// Compares sequences of items lexicographically, like slices are compared.
+    fn cmp_seq(
+        &self,
+        pos: usize,
+        len_a: u64,
+        len_b: u64,
+        a: &mut &[u8],
+        b: &mut &[u8],
+    ) -> Ordering {
+        for _ in 0..len_a.min(len_b) {
+            let ord = self.cmp_encoded(pos, a, b);
+            if ord.is_ne() {
+                return ord;
+            }
+        }
+        len_a.cmp(&len_b)
+    }
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> &'a [u8] {
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    head
+}
+
+fn read_len(sizing: Sizing, data: &mut &[u8]) -> u64 {
+    let mut le = [0u8; 8];
+    let prefix = take(data, len_prefix(sizing));
+    le[..prefix.len()].copy_from_slice(prefix);
+    u64::from_le_bytes(le)
+}
+
+fn cmp_str(sizing: Sizing, a: &mut &[u8], b: &mut &[u8]) -> Ordering {
+    let (len_a, len_b) = (read_len(sizing, a), read_len(sizing, b));
+    take(a, len_a as usize).cmp(take(b, len_b as usize))
+}
@@ -0,0 +1,213 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use encoding::{NumCls, Primitive};
+
+use super::{FieldPath, MemoryLayout};
+use crate::typesys::NestedCase;
+use crate::value::{EnumTag, StrictNum};
+use crate::{StrictVal, Ty};
+
+/// Errors patching a field of an encoded value.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum PatchError {
+    /// the type contains variable-length elements, thus field offsets can't be computed.
+    VariableLayout,
+
+    /// type has no field `{0}`, or the field is not a primitive, enum or byte array.
+    UnknownField(FieldPath),
+
+    /// value doesn't match type of the field `{0}`.
+    Mismatch(FieldPath),
+
+    /// the buffer of {len} bytes is too short to hold the field ending at byte {end}.
+    ShortBuffer { len: usize, end: usize },
+}
+
+impl MemoryLayout {
+    /// Overwrites the bytes of a single field inside `bytes` holding a strict encoding of the
+    /// layout root type with the encoding of `new_value`, leaving the rest of the data untouched.
+    ///
+    /// The type must have a fixed-size encoding, and the `path` must address one of the leaf
+    /// fields returned by [`MemoryLayout::field_offsets`]. The value takes the same form as the one
+    /// produced by [`MemoryLayout::decode`]: a number, an enum variant name or tag, or bytes of a
+    /// byte array; it may be wrapped into single-field tuples of the newtypes wrapping the field.
+    pub fn patch(
+        &self,
+        bytes: &mut [u8],
+        path: &FieldPath,
+        new_value: &StrictVal,
+    ) -> Result<(), PatchError> {
+        let offsets = self.leaf_offsets().ok_or(PatchError::VariableLayout)?;
+        let (pos, offset, len) =
+            *offsets.get(path).ok_or_else(|| PatchError::UnknownField(path.clone()))?;
+        let info = &self.items[pos];
+
+        let mut val = new_value;
+        for nested in &info.nested {
+            debug_assert!(matches!(nested, NestedCase::NewType(_)));
+            if let StrictVal::Tuple(fields) = val {
+                if let [inner] = fields.as_slice() {
+                    val = inner;
+                }
+            }
+        }
+
+        let encoded = match (&info.ty, val) {
+            (Ty::Primitive(prim), StrictVal::Unit) if *prim == Primitive::UNIT => Some(vec![]),
+            (Ty::Primitive(prim), StrictVal::Number(num)) => number_bytes(num, *prim),
+            (Ty::Enum(variants), StrictVal::Enum(EnumTag::Name(name))) => {
+                variants.tag_by_name(name).map(|tag| vec![tag])
+            }
+            (Ty::Enum(variants), StrictVal::Enum(EnumTag::Ord(tag))) if variants.has_tag(*tag) => {
+                Some(vec![*tag])
+            }
+            (Ty::Array(_, _), StrictVal::Bytes(blob)) if blob.len() == len => Some(blob.to_vec()),
+            _ => None,
+        }
+        .ok_or_else(|| PatchError::Mismatch(path.clone()))?;
+        debug_assert_eq!(encoded.len(), len);
+
+        let end = offset + len;
+        if bytes.len() < end {
+            return Err(PatchError::ShortBuffer {
+                len: bytes.len(),
+                end,
+            });
+        }
+        bytes[offset..end].copy_from_slice(&encoded);
+        Ok(())
+    }
+}
+
+/// Encodes a number as a primitive, returning `None` if the number doesn't fit it.
+fn number_bytes(num: &StrictNum, prim: Primitive) -> Option<Vec<u8>> {
+    let size = prim.byte_size() as usize;
+    let signed = match prim.info().ty {
+        _ if prim == Primitive::BYTE => false,
+        NumCls::Unsigned | NumCls::NonZero => false,
+        NumCls::Signed => true,
+        NumCls::Float => return None,
+    };
+    let mut le = match num {
+        StrictNum::Uint(n) => n.to_le_bytes().to_vec(),
+        StrictNum::BigUint(n) => n.to_le_bytes().to_vec(),
+        StrictNum::Int(n) => n.to_le_bytes().to_vec(),
+        StrictNum::BigInt(n) => n.to_le_bytes().to_vec(),
+    };
+    let negative = matches!(num, StrictNum::Int(_) | StrictNum::BigInt(_))
+        && le.last().is_some_and(|byte| byte & 0x80 != 0);
+    if negative && !signed {
+        return None;
+    }
+    let ext = if negative { 0xFF } else { 0 };
+    if le.len() < size {
+        le.resize(size, ext);
+    }
+    if le[size..].iter().any(|byte| *byte != ext) {
+        return None;
+    }
+    le.truncate(size);
+    if signed && le.last().is_some_and(|byte| (byte & 0x80 != 0) != negative) {
+        return None;
+    }
+    if prim.info().ty == NumCls::NonZero && le.iter().all(|byte| *byte == 0) {
+        return None;
+    }
+    Some(le)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value::test_helpers::test_system;
+
+    #[test]
+    fn patch() {
+        let sys = test_system();
+
+        let layout = sys.type_tree("StrictTypes.Sizing").unwrap().to_layout();
+        let mut bytes = [0u8; 16];
+        let max = FieldPath::from(vec![s!("max")]);
+        layout.patch(&mut bytes, &max, &StrictVal::num(0x0102u64)).unwrap();
+        assert_eq!(bytes[8..10], [0x02, 0x01]);
+        assert_eq!(
+            layout.decode(&bytes).unwrap(),
+            StrictVal::struc([("min", StrictVal::num(0u64)), ("max", StrictVal::num(0x0102u64))])
+        );
+
+        assert_eq!(
+            layout.patch(&mut bytes, &max, &StrictVal::num(-1i64)),
+            Err(PatchError::Mismatch(max.clone()))
+        );
+        assert_eq!(
+            layout.patch(&mut bytes, &max, &StrictVal::str("1")),
+            Err(PatchError::Mismatch(max.clone()))
+        );
+        let absent = FieldPath::from(vec![s!("len")]);
+        assert_eq!(
+            layout.patch(&mut bytes, &absent, &StrictVal::num(1u64)),
+            Err(PatchError::UnknownField(absent))
+        );
+        assert_eq!(
+            layout.patch(&mut bytes[..8], &max, &StrictVal::num(1u64)),
+            Err(PatchError::ShortBuffer { len: 8, end: 16 })
+        );
+
+        let layout = sys.type_tree("TestLib.Precision").unwrap().to_layout();
+        let mut bytes = [0u8];
+        let root = FieldPath::default();
+        layout.patch(&mut bytes, &root, &StrictVal::enumer("twoDecimals")).unwrap();
+        assert_eq!(bytes, [2]);
+        layout.patch(&mut bytes, &root, &StrictVal::enumer(1u8)).unwrap();
+        assert_eq!(bytes, [1]);
+        assert_eq!(
+            layout.patch(&mut bytes, &root, &StrictVal::enumer(3u8)),
+            Err(PatchError::Mismatch(root))
+        );
+
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        assert_eq!(
+            layout.patch(&mut [], &FieldPath::default(), &StrictVal::Unit),
+            Err(PatchError::VariableLayout)
+        );
+    }
+
+    #[test]
+    fn number_range() {
+        assert_eq!(number_bytes(&StrictNum::Uint(0xFF), Primitive::U8), Some(vec![0xFF]));
+        assert_eq!(number_bytes(&StrictNum::Uint(0x100), Primitive::U8), None);
+        assert_eq!(number_bytes(&StrictNum::Int(-1), Primitive::I16), Some(vec![0xFF, 0xFF]));
+        assert_eq!(number_bytes(&StrictNum::Int(-129), Primitive::I8), None);
+        assert_eq!(number_bytes(&StrictNum::Uint(0x80), Primitive::I8), None);
+        assert_eq!(
+            number_bytes(&StrictNum::Uint(1), Primitive::U128),
+            Some({
+                let mut le = vec![0; 16];
+                le[0] = 1;
+                le
+            })
+        );
+    }
+}
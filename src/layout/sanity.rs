@@ -0,0 +1,183 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use encoding::{NumCls, Primitive};
+
+use super::{FieldPath, MemoryLayout, ValidationError};
+use crate::Ty;
+
+/// Integer field whose value looks like a small number encoded in the big-endian byte order.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EndianSuspect {
+    /// Path to the field from the layout root.
+    pub path: FieldPath,
+    /// Byte offset of the field data.
+    pub offset: usize,
+    /// Value of the field as it is decoded, in the little-endian byte order.
+    pub value: u128,
+    /// Value of the field if its bytes are read in the big-endian byte order.
+    pub swapped: u128,
+}
+
+/// Result of the [`MemoryLayout::sanity_check`] heuristic.
+#[derive(Debug)]
+pub struct SanityReport {
+    /// Number of multi-byte integer fields which were inspected.
+    pub inspected: usize,
+    /// Inspected fields which look byte-swapped.
+    pub suspects: Vec<EndianSuspect>,
+    /// Error if the data are not a valid encoding of the layout type; the fields following the
+    /// failure are not inspected.
+    pub error: Option<ValidationError>,
+}
+
+impl SanityReport {
+    /// Confidence, from 0 to 1, that the data were encoded in the big-endian byte order, which is
+    /// the share of the inspected integer fields which look byte-swapped.
+    pub fn confidence(&self) -> f64 {
+        if self.inspected == 0 {
+            return 0.0;
+        }
+        self.suspects.len() as f64 / self.inspected as f64
+    }
+
+    /// Detects whether most of the inspected integer fields look byte-swapped.
+    pub fn is_suspicious(&self) -> bool { self.confidence() > 0.5 }
+}
+
+impl MemoryLayout {
+    /// Heuristically detects whether `bytes` were encoded with the big-endian byte order instead
+    /// of the little-endian one required by strict encoding.
+    ///
+    /// Numbers in the data are usually much smaller than the maximum value of their type, thus a
+    /// small number encoded in the big-endian order is decoded as a huge value, like `0x01000000`
+    /// for a big-endian `1u32`. The check decodes the data, inspecting all multi-byte integer
+    /// fields up to the first decoding failure, and reports those whose value with the reversed
+    /// byte order fits into the lower half of the integer bits, while the decoded value doesn't.
+    /// Such values may be valid, thus the report provides a confidence rather than a verdict.
+    pub fn sanity_check(&self, bytes: &[u8]) -> SanityReport {
+        let (trace, error) = match self.explain(bytes) {
+            Ok(trace) => (trace, None),
+            Err((trace, err)) => (trace, Some(err)),
+        };
+        let mut report = SanityReport {
+            inspected: 0,
+            suspects: vec![],
+            error,
+        };
+        for step in trace.iter().filter(|step| step.complete) {
+            let Ty::Primitive(prim) = step.info.ty else {
+                continue;
+            };
+            let size = prim.byte_size() as usize;
+            if !is_integer(prim) || !(2..=16).contains(&size) || step.bytes.len() != size {
+                continue;
+            }
+            report.inspected += 1;
+            let mut le = [0u8; 16];
+            le[..size].copy_from_slice(&step.bytes);
+            let value = u128::from_le_bytes(le);
+            le[..size].reverse();
+            let swapped = u128::from_le_bytes(le);
+            if value > swapped && swapped >> (size * 4) == 0 {
+                report.suspects.push(EndianSuspect {
+                    path: step.path.clone(),
+                    offset: step.offset,
+                    value,
+                    swapped,
+                });
+            }
+        }
+        report
+    }
+}
+
+fn is_integer(prim: Primitive) -> bool {
+    prim != Primitive::BYTE
+        && matches!(prim.info().ty, NumCls::Unsigned | NumCls::Signed | NumCls::NonZero)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::TinyString;
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::typesys::SystemBuilder;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Message {
+        pub version: u32,
+        pub kind: u8,
+        pub count: u16,
+        pub name: TinyString,
+    }
+
+    impl StrictSerialize for Message {}
+
+    #[test]
+    fn sanity_check() {
+        let lib = LibBuilder::with("Test", None).transpile::<Message>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("Test.Message").unwrap().to_layout();
+
+        let msg = Message {
+            version: 1,
+            kind: 2,
+            count: 3,
+            name: TinyString::try_from(s!("name")).unwrap(),
+        };
+        let data = msg.to_strict_serialized::<{ u16::MAX as usize }>().unwrap().release();
+        let report = layout.sanity_check(&data);
+        assert_eq!(report.inspected, 2);
+        assert!(report.suspects.is_empty());
+        assert!(report.error.is_none());
+        assert!(!report.is_suspicious());
+
+        let mut swapped = data.clone();
+        swapped[..4].reverse();
+        swapped[5..7].reverse();
+        let report = layout.sanity_check(&swapped);
+        assert_eq!(report.suspects, vec![
+            EndianSuspect {
+                path: FieldPath::from(vec![s!("version")]),
+                offset: 0,
+                value: 0x0100_0000,
+                swapped: 1,
+            },
+            EndianSuspect {
+                path: FieldPath::from(vec![s!("count")]),
+                offset: 5,
+                value: 0x0300,
+                swapped: 3,
+            },
+        ]);
+        assert_eq!(report.confidence(), 1.0);
+        assert!(report.is_suspicious());
+    }
+}
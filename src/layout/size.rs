@@ -0,0 +1,137 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use encoding::Sizing;
+
+use super::memory::len_prefix;
+use super::MemoryLayout;
+use crate::typesys::NestedCase;
+use crate::Ty;
+
+/// Minimal and maximal size of an encoded data, with `None` as a maximum for data which size
+/// can't fit into `usize`.
+type SizeRange = (usize, Option<usize>);
+
+impl MemoryLayout {
+    /// Computes the range of byte sizes which a strict-encoded value of the root type may have.
+    ///
+    /// The returned maximum is `None` when it doesn't fit `usize`, which may happen for types
+    /// containing large or nested collections, or when the type is recursive.
+    pub fn size_range(&self) -> SizeRange {
+        if self.items.is_empty() {
+            return (0, Some(0));
+        }
+        let (range, _) = self.item_size_range(0);
+        range
+    }
+
+    /// Computes size range for the item at `pos` position, returning it together with the
+    /// position of the next item which doesn't belong to the subtree of the item.
+    fn item_size_range(&self, pos: usize) -> (SizeRange, usize) {
+        let info = &self.items[pos];
+        let mut next = pos + 1;
+        let mut children = Vec::with_capacity(info.children_count());
+        for _ in 0..info.children_count() {
+            let (range, after) = self.item_size_range(next);
+            children.push(range);
+            next = after;
+        }
+
+        let (mut min, mut max) = match (&info.ty, info.nested.last()) {
+            // the size of a recursive type depends on the depth of the recursion
+            _ if info.is_recursive() => (0, None),
+            (_, Some(NestedCase::RStr(_, _, sizing))) => collection(*sizing, (1, Some(1))),
+            (_, Some(NestedCase::AsciiStr(_) | NestedCase::ByteStr | NestedCase::UniStr)) => {
+                let Ty::List(_, sizing) = info.ty else {
+                    unreachable!("string is always a list")
+                };
+                collection(sizing, (1, Some(1)))
+            }
+            (Ty::Primitive(prim), _) => fixed(prim.byte_size() as usize),
+            (Ty::UnicodeChar, _) => (1, Some(4)),
+            (Ty::Enum(_), _) => fixed(1),
+            (Ty::Union(_), _) => {
+                let (min, max) = children
+                    .into_iter()
+                    .reduce(|(min1, max1), (min2, max2)| {
+                        (min1.min(min2), max1.zip(max2).map(|(a, b)| a.max(b)))
+                    })
+                    .unwrap_or((0, Some(0)));
+                (min.saturating_add(1), max.and_then(|max| max.checked_add(1)))
+            }
+            (Ty::Tuple(_) | Ty::Struct(_), _) => sum(children),
+            (Ty::Array(_, len), _) if info.ty.is_byte_array() => fixed(*len as usize),
+            (Ty::Array(_, len), _) => repeat(children[0], *len as usize, *len as usize),
+            (Ty::List(_, sizing) | Ty::Set(_, sizing), _) => collection(*sizing, children[0]),
+            (Ty::Map(_, _, sizing), _) => collection(*sizing, sum(children)),
+        };
+
+        for nested in &info.nested {
+            if *nested == NestedCase::Option {
+                min = 1;
+                max = max.and_then(|max| max.checked_add(1));
+            }
+        }
+
+        ((min, max), next)
+    }
+}
+
+fn fixed(size: usize) -> SizeRange { (size, Some(size)) }
+
+fn sum(items: impl IntoIterator<Item = SizeRange>) -> SizeRange {
+    items.into_iter().fold((0, Some(0)), |(min1, max1), (min2, max2)| {
+        (min1.saturating_add(min2), max1.zip(max2).and_then(|(a, b)| a.checked_add(b)))
+    })
+}
+
+fn repeat((min, max): SizeRange, min_count: usize, max_count: usize) -> SizeRange {
+    (min.saturating_mul(min_count), max.and_then(|max| max.checked_mul(max_count)))
+}
+
+fn collection(sizing: Sizing, item: SizeRange) -> SizeRange {
+    let prefix = len_prefix(sizing);
+    let min_count = usize::try_from(sizing.min).unwrap_or(usize::MAX);
+    let max_count = usize::try_from(sizing.max).ok();
+    let (min, max) = match max_count {
+        Some(max_count) => repeat(item, min_count, max_count),
+        None => (repeat(item, min_count, 0).0, None),
+    };
+    (min.saturating_add(prefix), max.and_then(|max| max.checked_add(prefix)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::value::test_helpers::test_system;
+
+    #[test]
+    fn size_range() {
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        // ticker is 1..=100 chars, name is 1..=32 bytes and precision is a single byte
+        assert_eq!(layout.size_range(), (5, Some(135)));
+
+        let layout = sys.type_tree("StrictTypes.SemId").unwrap().to_layout();
+        assert_eq!(layout.size_range(), (32, Some(32)));
+    }
+}
@@ -0,0 +1,933 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+
+use amplify::hex::ToHex;
+use encoding::Sizing;
+
+use super::memory::len_prefix;
+use super::{DecodeLimit, FieldPath, MemoryLayout};
+use crate::ast::ItemCase;
+use crate::typesys::{NestedCase, TypeInfo};
+use crate::Ty;
+
+/// Failure of the strict encoded data validation or decoding against a memory layout.
+///
+/// Each error reports the byte `offset` at which the data don't match the layout, and the number
+/// of `remaining` bytes following that offset. Data ending too early are reported as
+/// [`ValidationError::UnexpectedEnd`], allowing a streaming reader to distinguish a partial frame
+/// from corrupted data.
+#[derive(Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum ValidationError {
+    /// I/O error at byte offset {offset}: {error}.
+    Io {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        error: io::Error,
+    },
+
+    /// data end unexpectedly at byte offset {offset}.
+    UnexpectedEnd {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+    },
+
+    /// unknown tag {tag:#04x} at byte offset {offset}.
+    UnknownTag {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        tag: u8,
+    },
+
+    /// collection length {len} at byte offset {offset} is out of the allowed range.
+    InvalidLength {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        len: u64,
+    },
+
+    /// string at byte offset {offset} contains invalid characters.
+    InvalidString {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+    },
+
+    /// set item or map key #{index} at byte offset {offset} breaks the ascending order.
+    BrokenOrder {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        index: u64,
+    },
+
+    /// set item or map key #{index} at byte offset {offset} repeats the previous one.
+    RepeatedItem {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        index: u64,
+    },
+
+    /// validation of data for a recursive type at byte offset {offset} is not supported.
+    Recursive {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+    },
+
+    /// decoding of the number at byte offset {offset} into a strict value is not supported.
+    Unsupported {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+    },
+
+    /// decoding data at byte offset {offset} exceeds the limit on the {limit}.
+    LimitExceeded {
+        offset: usize,
+        remaining: usize,
+        info: TypeInfo,
+        limit: DecodeLimit,
+    },
+
+    /// {remaining} bytes starting at byte offset {offset} are not a part of the validated type.
+    TrailingData { offset: usize, remaining: usize },
+}
+
+impl ValidationError {
+    /// Byte offset in the data at which the validation has failed.
+    pub fn offset(&self) -> usize {
+        match self {
+            ValidationError::Io { offset, .. }
+            | ValidationError::UnexpectedEnd { offset, .. }
+            | ValidationError::UnknownTag { offset, .. }
+            | ValidationError::InvalidLength { offset, .. }
+            | ValidationError::InvalidString { offset, .. }
+            | ValidationError::BrokenOrder { offset, .. }
+            | ValidationError::RepeatedItem { offset, .. }
+            | ValidationError::Recursive { offset, .. }
+            | ValidationError::Unsupported { offset, .. }
+            | ValidationError::LimitExceeded { offset, .. }
+            | ValidationError::TrailingData { offset, .. } => *offset,
+        }
+    }
+
+    /// Number of data bytes starting at [`ValidationError::offset`]; zero if the data end there.
+    pub fn remaining(&self) -> usize {
+        match self {
+            ValidationError::Io { remaining, .. }
+            | ValidationError::UnexpectedEnd { remaining, .. }
+            | ValidationError::UnknownTag { remaining, .. }
+            | ValidationError::InvalidLength { remaining, .. }
+            | ValidationError::InvalidString { remaining, .. }
+            | ValidationError::BrokenOrder { remaining, .. }
+            | ValidationError::RepeatedItem { remaining, .. }
+            | ValidationError::Recursive { remaining, .. }
+            | ValidationError::Unsupported { remaining, .. }
+            | ValidationError::LimitExceeded { remaining, .. }
+            | ValidationError::TrailingData { remaining, .. } => *remaining,
+        }
+    }
+
+    /// Detects errors caused by the data ending before the complete value was read, which may be
+    /// resolved by providing more data.
+    pub fn is_truncated(&self) -> bool { matches!(self, ValidationError::UnexpectedEnd { .. }) }
+
+    /// Sets the number of remaining bytes for data of `len` bytes in total.
+    ///
+    /// The validator and decoder construct errors with no remaining bytes, since the total
+    /// length of data is known only once the error reaches the public API.
+    pub(super) fn with_len(mut self, len: usize) -> Self {
+        let remaining = len.saturating_sub(self.offset());
+        match &mut self {
+            ValidationError::Io { remaining: r, .. }
+            | ValidationError::UnexpectedEnd { remaining: r, .. }
+            | ValidationError::UnknownTag { remaining: r, .. }
+            | ValidationError::InvalidLength { remaining: r, .. }
+            | ValidationError::InvalidString { remaining: r, .. }
+            | ValidationError::BrokenOrder { remaining: r, .. }
+            | ValidationError::RepeatedItem { remaining: r, .. }
+            | ValidationError::Recursive { remaining: r, .. }
+            | ValidationError::Unsupported { remaining: r, .. }
+            | ValidationError::LimitExceeded { remaining: r, .. }
+            | ValidationError::TrailingData { remaining: r, .. } => *r = remaining,
+        }
+        self
+    }
+
+    /// Layout item which data are not matching; `None` for trailing data.
+    pub fn info(&self) -> Option<&TypeInfo> {
+        match self {
+            ValidationError::Io { info, .. }
+            | ValidationError::UnexpectedEnd { info, .. }
+            | ValidationError::UnknownTag { info, .. }
+            | ValidationError::InvalidLength { info, .. }
+            | ValidationError::InvalidString { info, .. }
+            | ValidationError::BrokenOrder { info, .. }
+            | ValidationError::RepeatedItem { info, .. }
+            | ValidationError::Recursive { info, .. }
+            | ValidationError::Unsupported { info, .. }
+            | ValidationError::LimitExceeded { info, .. } => Some(info),
+            ValidationError::TrailingData { .. } => None,
+        }
+    }
+}
+
+/// Decoding step of a single layout item, produced by [`MemoryLayout::explain`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TraceStep {
+    /// Path to the item from the layout root; items of collections are addressed by their index.
+    pub path: FieldPath,
+    /// Layout item being decoded.
+    pub info: TypeInfo,
+    /// Byte offset at which the item data start.
+    pub offset: usize,
+    /// Data interpreted as the item, including data of the nested items. For a failed step,
+    /// these are the data read before the failure.
+    pub bytes: Vec<u8>,
+    /// Whether the item was decoded successfully.
+    pub complete: bool,
+}
+
+/// Trace of the data decoding, listing layout items in the order they were decoded.
+///
+/// Each step precedes the steps of its nested items, so the trace is a pre-order walk over the
+/// tree of decoded items, which can be restored from the step paths.
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct DecodeTrace(Vec<TraceStep>);
+
+impl Display for DecodeTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for step in &self.0 {
+            let ty = match &step.info.fqn {
+                Some(fqn) => fqn.to_string(),
+                None => step.info.ty.to_string(),
+            };
+            let mark = if step.complete { "" } else { " !" };
+            let path = if step.path.is_empty() { s!("<root>") } else { step.path.to_string() };
+            writeln!(f, "{:#06x} {path}: {ty} = {}{mark}", step.offset, step.bytes.to_hex())?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoding trace being collected by the validator.
+#[derive(Default)]
+struct Tracer {
+    path: FieldPath,
+    /// Index of the collection element which is validated next.
+    index: Option<u64>,
+    steps: Vec<TraceStep>,
+    /// End offsets of the steps, known once a step is completed or failed.
+    ends: Vec<usize>,
+}
+
+struct Validator<'r, R: Read> {
+    reader: &'r mut R,
+    offset: usize,
+    /// Stack of buffers collecting the read data, used to check the order of collection items.
+    captures: Vec<Vec<u8>>,
+    tracer: Option<Tracer>,
+}
+
+impl<R: Read> Validator<'_, R> {
+    fn io_error(&self, info: &TypeInfo, error: io::Error) -> ValidationError {
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            ValidationError::UnexpectedEnd {
+                offset: self.offset,
+                remaining: 0,
+                info: info.clone(),
+            }
+        } else {
+            ValidationError::Io {
+                offset: self.offset,
+                remaining: 0,
+                info: info.clone(),
+                error,
+            }
+        }
+    }
+
+    fn read_bytes(&mut self, info: &TypeInfo, len: usize) -> Result<Vec<u8>, ValidationError> {
+        let mut buf = Vec::new();
+        let read = (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut buf)
+            .map_err(|err| self.io_error(info, err))?;
+        self.offset += read;
+        if read < len {
+            return Err(self.io_error(info, io::ErrorKind::UnexpectedEof.into()));
+        }
+        if let Some(capture) = self.captures.last_mut() {
+            capture.extend_from_slice(&buf);
+        }
+        Ok(buf)
+    }
+
+    fn skip_bytes(&mut self, info: &TypeInfo, len: u64) -> Result<(), ValidationError> {
+        if !self.captures.is_empty() {
+            return self.read_bytes(info, len as usize).map(|_| ());
+        }
+        let read = io::copy(&mut (&mut self.reader).take(len), &mut io::sink())
+            .map_err(|err| self.io_error(info, err))?;
+        self.offset += read as usize;
+        if read < len {
+            return Err(self.io_error(info, io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(())
+    }
+
+    fn start_capture(&mut self) { self.captures.push(vec![]); }
+
+    /// Registers start of the `info` item decoding in the trace, returning position of the trace
+    /// step and the path length to restore once the item is decoded.
+    fn trace_start(&mut self, info: &TypeInfo) -> (usize, usize) {
+        let Some(tracer) = &mut self.tracer else {
+            return (0, 0);
+        };
+        let depth = tracer.path.len();
+        if let Some(index) = tracer.index.take() {
+            tracer.path.push(index.to_string());
+        }
+        match &info.item {
+            Some(ItemCase::NamedField(_, name)) => tracer.path.push(name.to_string()),
+            Some(ItemCase::UnnamedField(pos)) => tracer.path.push(pos.to_string()),
+            Some(ItemCase::UnionVariant(_, name)) => tracer.path.push(name.to_string()),
+            Some(ItemCase::MapKey) => tracer.path.push(s!("key")),
+            Some(ItemCase::MapValue) => tracer.path.push(s!("value")),
+            Some(ItemCase::ArrayItem | ItemCase::ListItem | ItemCase::SetItem) | None => {}
+        }
+        tracer.steps.push(TraceStep {
+            path: tracer.path.clone(),
+            info: info.clone(),
+            offset: self.offset,
+            bytes: vec![],
+            complete: false,
+        });
+        tracer.ends.push(self.offset);
+        (tracer.steps.len() - 1, depth)
+    }
+
+    fn trace_end(&mut self, (step, depth): (usize, usize), complete: bool) {
+        let Some(tracer) = &mut self.tracer else {
+            return;
+        };
+        tracer.path.truncate(depth);
+        tracer.steps[step].complete = complete;
+        tracer.ends[step] = self.offset;
+    }
+
+    /// Sets index of the collection element which is validated next, used in the trace paths.
+    fn next_index(&mut self, index: u64) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.index = Some(index);
+        }
+    }
+
+    fn end_capture(&mut self) -> Vec<u8> {
+        let data = self.captures.pop().expect("no capture started");
+        if let Some(parent) = self.captures.last_mut() {
+            parent.extend_from_slice(&data);
+        }
+        data
+    }
+
+    fn read_u8(&mut self, info: &TypeInfo) -> Result<u8, ValidationError> {
+        Ok(self.read_bytes(info, 1)?[0])
+    }
+
+    fn read_len(&mut self, info: &TypeInfo, sizing: Sizing) -> Result<u64, ValidationError> {
+        let offset = self.offset;
+        let bytes = self.read_bytes(info, len_prefix(sizing))?;
+        let mut le = [0u8; 8];
+        le[..bytes.len()].copy_from_slice(&bytes);
+        let len = u64::from_le_bytes(le);
+        if len < sizing.min || len > sizing.max {
+            return Err(ValidationError::InvalidLength {
+                offset,
+                remaining: 0,
+                info: info.clone(),
+                len,
+            });
+        }
+        Ok(len)
+    }
+
+    fn read_str(
+        &mut self,
+        info: &TypeInfo,
+        sizing: Sizing,
+        check: impl FnOnce(&[u8]) -> bool,
+    ) -> Result<(), ValidationError> {
+        let len = self.read_len(info, sizing)?;
+        let offset = self.offset;
+        let bytes = self.read_bytes(info, len as usize)?;
+        if !check(&bytes) {
+            return Err(ValidationError::InvalidString {
+                offset,
+                remaining: 0,
+                info: info.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl MemoryLayout {
+    /// Checks that the data read from the `reader` are a valid strict encoding of the layout root
+    /// type, without deserializing them.
+    ///
+    /// The reader must contain no other data after the encoded value. On failure, the reader is
+    /// read to its end to count the [`ValidationError::remaining`] bytes; after an I/O error,
+    /// only the bytes read before it are counted.
+    pub fn validate_reader<R: Read>(&self, reader: &mut R) -> Result<(), ValidationError> {
+        let mut validator = Validator {
+            reader,
+            offset: 0,
+            captures: vec![],
+            tracer: None,
+        };
+        self.validate_root(&mut validator).map_err(|err| {
+            let rest = io::copy(&mut validator.reader, &mut io::sink()).unwrap_or_default();
+            err.with_len(validator.offset + rest as usize)
+        })
+    }
+
+    /// Validates `bytes` like [`MemoryLayout::validate_reader`], producing a trace of how each
+    /// byte was interpreted.
+    ///
+    /// On failure, the trace collected up to the failure is returned together with the error.
+    /// The trace then ends with the steps which were not completed: the failed item and the items
+    /// containing it; the last completed step is the last successfully decoded item.
+    pub fn explain(&self, bytes: &[u8]) -> Result<DecodeTrace, (DecodeTrace, ValidationError)> {
+        let mut reader = bytes;
+        let mut validator = Validator {
+            reader: &mut reader,
+            offset: 0,
+            captures: vec![],
+            tracer: Some(Tracer::default()),
+        };
+        let res = self.validate_root(&mut validator);
+        let tracer = validator.tracer.expect("tracer is always present");
+        let steps = tracer
+            .steps
+            .into_iter()
+            .zip(tracer.ends)
+            .map(|(mut step, end)| {
+                step.bytes = bytes[step.offset..end].to_vec();
+                step
+            })
+            .collect();
+        let trace = DecodeTrace(steps);
+        match res {
+            Ok(()) => Ok(trace),
+            Err(err) => Err((trace, err.with_len(bytes.len()))),
+        }
+    }
+
+    fn validate_root<R: Read>(&self, validator: &mut Validator<R>) -> Result<(), ValidationError> {
+        if !self.items.is_empty() {
+            self.validate_item(0, validator)?;
+        }
+        let mut byte = [0u8; 1];
+        match validator.reader.read(&mut byte) {
+            Ok(0) => Ok(()),
+            Ok(_) => {
+                validator.offset += 1;
+                Err(ValidationError::TrailingData {
+                    offset: validator.offset - 1,
+                    remaining: 0,
+                })
+            }
+            Err(error) => Err(ValidationError::Io {
+                offset: validator.offset,
+                remaining: 0,
+                info: self.items[0].clone(),
+                error,
+            }),
+        }
+    }
+
+    /// Validates data for the item at `pos` position, returning position of the next item not
+    /// belonging to the item subtree.
+    fn validate_item<R: Read>(
+        &self,
+        pos: usize,
+        v: &mut Validator<R>,
+    ) -> Result<usize, ValidationError> {
+        let step = v.trace_start(&self.items[pos]);
+        let res = self.validate_data(pos, v);
+        v.trace_end(step, res.is_ok());
+        res
+    }
+
+    /// Validates data for the `index` element of a collection with items at `pos` position.
+    fn validate_element<R: Read>(
+        &self,
+        pos: usize,
+        index: u64,
+        v: &mut Validator<R>,
+    ) -> Result<usize, ValidationError> {
+        v.next_index(index);
+        self.validate_item(pos, v)
+    }
+
+    fn validate_data<R: Read>(
+        &self,
+        pos: usize,
+        v: &mut Validator<R>,
+    ) -> Result<usize, ValidationError> {
+        let info = &self.items[pos];
+        for nested in &info.nested {
+            if *nested != NestedCase::Option {
+                continue;
+            }
+            match v.read_u8(info)? {
+                0 => return Ok(self.subtree_end(pos)),
+                1 => {}
+                tag => {
+                    return Err(ValidationError::UnknownTag {
+                        offset: v.offset - 1,
+                        remaining: 0,
+                        info: info.clone(),
+                        tag,
+                    });
+                }
+            }
+        }
+
+        let first_child = pos + 1;
+        match (&info.ty, info.nested.last()) {
+            _ if info.is_recursive() => {
+                return Err(ValidationError::Recursive {
+                    offset: v.offset,
+                    remaining: 0,
+                    info: info.clone(),
+                });
+            }
+            (_, Some(NestedCase::RStr(_, _, sizing))) => {
+                v.read_str(info, *sizing, |s| s.is_ascii())?;
+            }
+            (Ty::List(_, sizing), Some(NestedCase::AsciiStr(_))) => {
+                v.read_str(info, *sizing, |s| s.is_ascii())?;
+            }
+            (Ty::List(_, sizing), Some(NestedCase::UniStr)) => {
+                v.read_str(info, *sizing, |s| std::str::from_utf8(s).is_ok())?;
+            }
+            (Ty::List(_, sizing), Some(NestedCase::ByteStr)) => {
+                let len = v.read_len(info, *sizing)?;
+                v.skip_bytes(info, len)?;
+            }
+            (Ty::Primitive(prim), _) => v.skip_bytes(info, prim.byte_size() as u64)?,
+            (Ty::UnicodeChar, _) => {
+                let offset = v.offset;
+                let first = v.read_u8(info)?;
+                let len = match first.leading_ones() {
+                    0 => 0,
+                    n @ 2..=4 => n as usize - 1,
+                    _ => usize::MAX,
+                };
+                let rest = if len == usize::MAX { vec![] } else { v.read_bytes(info, len)? };
+                let mut bytes = vec![first];
+                bytes.extend(rest);
+                if std::str::from_utf8(&bytes).is_err() {
+                    return Err(ValidationError::InvalidString {
+                        offset,
+                        remaining: 0,
+                        info: info.clone(),
+                    });
+                }
+            }
+            // TODO: Support open enums with a fallback variant used for unknown tags. This
+            //       requires a `strict_type` attribute marking the variant in `strict_encoding`,
+            //       and storing the mark in `EnumVariants`, which changes the semantic ids of all
+            //       enum types, thus has to come with a new version of the type AST encoding.
+            (Ty::Enum(variants), _) => {
+                let tag = v.read_u8(info)?;
+                if !variants.has_tag(tag) {
+                    return Err(ValidationError::UnknownTag {
+                        offset: v.offset - 1,
+                        remaining: 0,
+                        info: info.clone(),
+                        tag,
+                    });
+                }
+            }
+            (Ty::Union(variants), _) => {
+                let tag = v.read_u8(info)?;
+                let Some(index) = variants.keys().position(|variant| variant.tag == tag) else {
+                    return Err(ValidationError::UnknownTag {
+                        offset: v.offset - 1,
+                        remaining: 0,
+                        info: info.clone(),
+                        tag,
+                    });
+                };
+                let mut child = first_child;
+                for _ in 0..index {
+                    child = self.subtree_end(child);
+                }
+                self.validate_item(child, v)?;
+            }
+            (Ty::Tuple(_) | Ty::Struct(_), _) => {
+                let mut child = first_child;
+                for _ in 0..info.children_count() {
+                    child = self.validate_item(child, v)?;
+                }
+            }
+            (Ty::Array(_, len), _) if info.ty.is_byte_array() => v.skip_bytes(info, *len as u64)?,
+            (Ty::Array(_, len), _) => {
+                for index in 0..*len {
+                    self.validate_element(first_child, index as u64, v)?;
+                }
+            }
+            (Ty::List(_, sizing), _) => {
+                for index in 0..v.read_len(info, *sizing)? {
+                    self.validate_element(first_child, index, v)?;
+                }
+            }
+            (Ty::Set(_, sizing), _) => {
+                let mut last = None;
+                for index in 0..v.read_len(info, *sizing)? {
+                    let offset = v.offset;
+                    v.start_capture();
+                    self.validate_element(first_child, index, v)?;
+                    let item = v.end_capture();
+                    self.check_order(first_child, offset, index, last.as_deref(), &item)?;
+                    last = Some(item);
+                }
+            }
+            (Ty::Map(_, _, sizing), _) => {
+                let mut last = None;
+                for index in 0..v.read_len(info, *sizing)? {
+                    let offset = v.offset;
+                    v.start_capture();
+                    let value = self.validate_element(first_child, index, v)?;
+                    let key = v.end_capture();
+                    self.check_order(first_child, offset, index, last.as_deref(), &key)?;
+                    last = Some(key);
+                    self.validate_element(value, index, v)?;
+                }
+            }
+        }
+        Ok(self.subtree_end(pos))
+    }
+
+    /// Checks that the set item or map key of type at `pos` strictly follows the `last` one.
+    pub(super) fn check_order(
+        &self,
+        pos: usize,
+        offset: usize,
+        index: u64,
+        last: Option<&[u8]>,
+        item: &[u8],
+    ) -> Result<(), ValidationError> {
+        let Some(mut last) = last else {
+            return Ok(());
+        };
+        let info = self.items[pos].clone();
+        match self.cmp_encoded(pos, &mut last, &mut &item[..]) {
+            Ordering::Less => Ok(()),
+            Ordering::Equal => Err(ValidationError::RepeatedItem {
+                offset,
+                remaining: 0,
+                info,
+                index,
+            }),
+            Ordering::Greater => Err(ValidationError::BrokenOrder {
+                offset,
+                remaining: 0,
+                info,
+                index,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::{TinyOrdMap, TinyOrdSet};
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::typesys::{SymTy, SystemBuilder};
+    use crate::value::test_helpers::{test_system, Nominal};
+    use crate::{LibBuilder, LibRef, SemId, SymbolicSys};
+
+    #[test]
+    fn validate_reader() {
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        let data = Nominal::with("TICK", "Some name", 2)
+            .to_strict_serialized::<{ u16::MAX as usize }>()
+            .unwrap()
+            .release();
+
+        layout.validate_reader(&mut data.as_slice()).unwrap();
+
+        let mut trailing = data.clone();
+        trailing.push(0);
+        let err = layout.validate_reader(&mut trailing.as_slice()).unwrap_err();
+        assert!(matches!(err, ValidationError::TrailingData { offset, remaining: 1 }
+            if offset == data.len()));
+
+        let truncated = &data[..data.len() - 1];
+        let err = layout.validate_reader(&mut &truncated[..]).unwrap_err();
+        assert!(err.is_truncated());
+        assert_eq!((err.offset(), err.remaining()), (truncated.len(), 0));
+        assert_eq!(err.info().unwrap().fqn.as_ref().unwrap().to_string(), "TestLib.Precision");
+
+        let mut wrong_tag = data.clone();
+        *wrong_tag.last_mut().unwrap() = 5;
+        let err = layout.validate_reader(&mut wrong_tag.as_slice()).unwrap_err();
+        assert!(matches!(err, ValidationError::UnknownTag { tag: 5, .. }));
+        assert_eq!((err.offset(), err.remaining()), (data.len() - 1, 1));
+        assert!(!err.is_truncated());
+
+        let mut wrong_len = data.clone();
+        wrong_len[0] = 0;
+        let err = layout.validate_reader(&mut wrong_len.as_slice()).unwrap_err();
+        assert!(matches!(err, ValidationError::InvalidLength {
+            offset: 0,
+            remaining: 16,
+            len: 0,
+            ..
+        }));
+    }
+
+    #[test]
+    fn explain() {
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        let data = Nominal::with("TICK", "Some name", 2)
+            .to_strict_serialized::<{ u16::MAX as usize }>()
+            .unwrap()
+            .release();
+
+        let trace = layout.explain(&data).unwrap();
+        let steps = trace
+            .iter()
+            .map(|step| (step.path.to_string(), step.offset, step.bytes.len(), step.complete))
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec![
+            (s!(""), 0, 16, true),
+            (s!("ticker"), 0, 5, true),
+            (s!("name"), 5, 10, true),
+            (s!("precision"), 15, 1, true),
+        ]);
+        assert_eq!(trace[0].bytes, data);
+        assert_eq!(trace[3].info.fqn.as_ref().unwrap().to_string(), "TestLib.Precision");
+        assert_eq!(trace.to_string().lines().count(), 4);
+
+        let (trace, err) = layout.explain(&data[..15]).unwrap_err();
+        assert!(matches!(err, ValidationError::UnexpectedEnd {
+            offset: 15,
+            remaining: 0,
+            ..
+        }));
+        let last = trace.iter().rev().find(|step| step.complete).unwrap();
+        assert_eq!(last.path.to_string(), "name");
+        let failed = trace.last().unwrap();
+        assert_eq!(failed.path.to_string(), "precision");
+        assert!(!failed.complete && failed.bytes.is_empty());
+        assert!(!trace[0].complete);
+        assert_eq!(trace[0].bytes, &data[..15]);
+
+        let (trace, err) = layout.explain(&[data.as_slice(), &[0]].concat()).unwrap_err();
+        assert!(matches!(err, ValidationError::TrailingData {
+            offset: 16,
+            remaining: 1
+        }));
+        assert!(trace.iter().all(|step| step.complete));
+    }
+
+    #[test]
+    fn validate_discriminant_gaps() {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
+        #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        enum Tagged {
+            #[strict_type(dumb)]
+            Low = 1,
+            Middle = 5,
+            High = 200,
+        }
+
+        let lib = LibBuilder::with("TestLib", None).transpile::<Tagged>().compile().unwrap();
+        let Some(Ty::Enum(variants)) = lib.types.get(&tn!("Tagged")) else {
+            panic!("`Tagged` must be an enum");
+        };
+        let tags = variants.iter().map(|variant| variant.tag).collect::<Vec<_>>();
+        assert_eq!(tags, vec![1, 5, 200]);
+
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("TestLib.Tagged").unwrap().to_layout();
+        for tag in [1u8, 5, 200] {
+            layout.validate_reader(&mut &[tag][..]).unwrap();
+        }
+        for tag in [0u8, 2, 4, 6, 199, 201, 255] {
+            let err = layout.validate_reader(&mut &[tag][..]).unwrap_err();
+            assert!(
+                matches!(err, ValidationError::UnknownTag { tag: t, offset: 0, .. } if t == tag)
+            );
+        }
+    }
+
+    #[test]
+    fn validate_map_order() {
+        #[derive(Clone, Eq, PartialEq, Debug, Default)]
+        #[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
+        #[strict_type(lib = "TestLib")]
+        struct Limits {
+            limits: TinyOrdMap<u16, u8>,
+        }
+        impl StrictSerialize for Limits {}
+
+        let lib = LibBuilder::with("TestLib", None).transpile::<Limits>().compile().unwrap();
+        let Ty::Struct(fields) = &lib.types[&tn!("Limits")] else {
+            panic!("`Limits` must be a structure");
+        };
+        assert!(matches!(&fields[0].ty, LibRef::Inline(ty) if matches!(**ty, Ty::Map(..))));
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("TestLib.Limits").unwrap().to_layout();
+
+        let limits = Limits {
+            limits: tiny_bmap! { 1 => 5, 0x100 => 6 },
+        };
+        let data = limits.to_strict_serialized::<{ u16::MAX as usize }>().unwrap().release();
+        assert_eq!(data, vec![2, 1, 0, 5, 0, 1, 6]);
+        layout.validate_reader(&mut data.as_slice()).unwrap();
+
+        let paths = layout
+            .explain(&data)
+            .unwrap()
+            .iter()
+            .map(|step| step.path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec![
+            "",
+            "limits",
+            "limits.0.key",
+            "limits.0.value",
+            "limits.1.key",
+            "limits.1.value"
+        ]);
+
+        let swapped = [2u8, 0, 1, 6, 1, 0, 5];
+        let err = layout.validate_reader(&mut &swapped[..]).unwrap_err();
+        assert!(matches!(err, ValidationError::BrokenOrder {
+            offset: 4,
+            remaining: 3,
+            index: 1,
+            ..
+        }));
+
+        let repeated = [2u8, 0, 1, 6, 0, 1, 5];
+        let err = layout.validate_reader(&mut &repeated[..]).unwrap_err();
+        assert!(matches!(err, ValidationError::RepeatedItem {
+            offset: 4,
+            remaining: 3,
+            index: 1,
+            ..
+        }));
+    }
+
+    #[test]
+    fn validate_unicode_char() {
+        let id = SemId::from([1u8; 32]);
+        let types = bmap! { id => SymTy::unnamed(Ty::UnicodeChar) };
+        let sys = SymbolicSys::with(None, types).unwrap();
+        let layout = sys.type_tree(id).unwrap().to_layout();
+
+        for c in ['a', 'ß', '\u{D7FF}', '\u{E000}', '€', '\u{10FFFF}'] {
+            let mut buf = [0u8; 4];
+            let data = c.encode_utf8(&mut buf).as_bytes();
+            layout.validate_reader(&mut &data[..]).unwrap();
+        }
+        // surrogates, code points above `0x10FFFF`, overlong and truncated encodings
+        for data in [
+            &[0xED, 0xA0, 0x80][..],
+            &[0xED, 0xBF, 0xBF],
+            &[0xF4, 0x90, 0x80, 0x80],
+            &[0xC0, 0x80],
+            &[0x80],
+        ] {
+            let err = layout.validate_reader(&mut &data[..]).unwrap_err();
+            assert!(matches!(err, ValidationError::InvalidString { offset: 0, .. }));
+        }
+        let err = layout.validate_reader(&mut &[0xE2, 0x82][..]).unwrap_err();
+        assert!(matches!(err, ValidationError::UnexpectedEnd { .. }));
+    }
+
+    #[test]
+    fn validate_set_order() {
+        #[derive(Clone, Eq, PartialEq, Debug, Default)]
+        #[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
+        #[strict_type(lib = "TestLib")]
+        struct Tags {
+            tags: TinyOrdSet<u16>,
+        }
+        impl StrictSerialize for Tags {}
+
+        let lib = LibBuilder::with("TestLib", None).transpile::<Tags>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let layout = sys.type_tree("TestLib.Tags").unwrap().to_layout();
+
+        let tags = Tags {
+            tags: tiny_bset! { 1, 2, 0x100 },
+        };
+        let data = tags.to_strict_serialized::<{ u16::MAX as usize }>().unwrap().release();
+        assert_eq!(data, vec![3, 1, 0, 2, 0, 0, 1]);
+        layout.validate_reader(&mut data.as_slice()).unwrap();
+
+        let repeated = [3u8, 1, 0, 2, 0, 2, 0];
+        let err = layout.validate_reader(&mut &repeated[..]).unwrap_err();
+        assert!(matches!(err, ValidationError::RepeatedItem {
+            offset: 5,
+            remaining: 2,
+            index: 2,
+            ..
+        }));
+        assert_eq!(err.info().unwrap().item, Some(ItemCase::SetItem));
+
+        let unordered = [3u8, 1, 0, 0, 1, 2, 0];
+        let err = layout.validate_reader(&mut &unordered[..]).unwrap_err();
+        assert!(matches!(err, ValidationError::BrokenOrder {
+            offset: 5,
+            remaining: 2,
+            index: 2,
+            ..
+        }));
+    }
+}
@@ -67,7 +67,9 @@ pub use typelib::{
     TranspileRef, TypeLib, TypeLibId,
 };
 pub use typesys::{SymbolicSys, SystemBuilder, TypeSymbol, TypeSysId, TypeSystem};
-pub use util::{parse_args, BuildFragment, PreFragment, SemVer, StlFormat, UnknownFormat, Urn};
+pub use util::{
+    parse_args, BuildFragment, IdError, Padding, PreFragment, SemVer, StlFormat, UnknownFormat, Urn,
+};
 pub use value::{decode, ston, typify, KeyStep, Path, PathError, Step, StrictVal};
 
 pub trait CommitConsume {
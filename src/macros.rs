@@ -96,3 +96,14 @@ macro_rules! variants {
         }
     };
 }
+
+/// Transpiles all the listed types with the library builder, in the given order.
+///
+/// `transpile_all!(builder, [A, B<C>])` is the same as
+/// `builder.transpile::<A>().transpile::<B<C>>()`.
+#[macro_export]
+macro_rules! transpile_all {
+    ($builder:expr, [$($ty:ty),* $(,)?]) => {
+        $builder$(.transpile::<$ty>())*
+    };
+}
@@ -50,40 +50,52 @@ pub const LIB_ID_BITCOIN: &str =
 pub const LIB_ID_BITCOIN_TX: &str =
     "stl:9WwTYiP2-OadKCZP-cR0bJ_Y-qruINYX-bXZFj8Y-fsQoGgo#signal-color-cipher";
 
+// TODO: Add bounded integer types (like `RangedU8<MIN, MAX>`) once they are provided by
+//       `strict_encoding::stl`. The `Ty` AST can't express value ranges for primitives, so this
+//       requires a new type variant, which changes the semantic ids; registering the types here
+//       also changes `LIB_ID_STD`, so it must be done together with a new standard library
+//       version.
+// TODO: Add `UnixTimestamp` and `Duration` time types to the standard library. They have to be
+//       defined in `strict_encoding::stl`, which is also the only place where strict encoding
+//       traits can be implemented for `std::time::Duration`. Checking that the nanoseconds are
+//       below 1_000_000_000 needs the same value ranges as the bounded integers above, and the
+//       registration changes `LIB_ID_STD` as well.
 fn _std_sym() -> Result<SymbolicLib, TranspileError> {
-    LibBuilder::with(libname!(LIB_NAME_STD), None)
-        .transpile::<Bool>()
-        .transpile::<U1>()
-        .transpile::<U2>()
-        .transpile::<U3>()
-        .transpile::<U4>()
-        .transpile::<U5>()
-        .transpile::<U6>()
-        .transpile::<U7>()
-        .transpile::<AsciiSym>()
-        .transpile::<AsciiPrintable>()
-        .transpile::<Alpha>()
-        .transpile::<AlphaDot>()
-        .transpile::<AlphaDash>()
-        .transpile::<AlphaLodash>()
-        .transpile::<AlphaCaps>()
-        .transpile::<AlphaCapsDot>()
-        .transpile::<AlphaCapsDash>()
-        .transpile::<AlphaCapsLodash>()
-        .transpile::<AlphaSmall>()
-        .transpile::<AlphaSmallDot>()
-        .transpile::<AlphaSmallDash>()
-        .transpile::<AlphaSmallLodash>()
-        .transpile::<Dec>()
-        .transpile::<DecDot>()
-        .transpile::<HexDecCaps>()
-        .transpile::<HexDecSmall>()
-        .transpile::<AlphaNum>()
-        .transpile::<AlphaCapsNum>()
-        .transpile::<AlphaNumDot>()
-        .transpile::<AlphaNumDash>()
-        .transpile::<AlphaNumLodash>()
-        .compile_symbols()
+    let builder = LibBuilder::with(libname!(LIB_NAME_STD), None);
+    transpile_all!(builder, [
+        Bool,
+        U1,
+        U2,
+        U3,
+        U4,
+        U5,
+        U6,
+        U7,
+        AsciiSym,
+        AsciiPrintable,
+        Alpha,
+        AlphaDot,
+        AlphaDash,
+        AlphaLodash,
+        AlphaCaps,
+        AlphaCapsDot,
+        AlphaCapsDash,
+        AlphaCapsLodash,
+        AlphaSmall,
+        AlphaSmallDot,
+        AlphaSmallDash,
+        AlphaSmallLodash,
+        Dec,
+        DecDot,
+        HexDecCaps,
+        HexDecSmall,
+        AlphaNum,
+        AlphaCapsNum,
+        AlphaNumDot,
+        AlphaNumDash,
+        AlphaNumLodash,
+    ])
+    .compile_symbols()
 }
 
 pub fn std_sym() -> SymbolicLib { _std_sym().unwrap() }
@@ -91,42 +103,46 @@ pub fn std_sym() -> SymbolicLib { _std_sym().unwrap() }
 pub fn std_stl() -> TypeLib { std_sym().compile().unwrap() }
 
 pub fn strict_types_sym() -> SymbolicLib {
-    LibBuilder::with(libname!(STRICT_TYPES_LIB), [std_stl().to_dependency_types()])
-        .transpile::<Ident>()
-        .transpile::<TypeName>()
-        .transpile::<FieldName>()
-        .transpile::<VariantName>()
-        .transpile::<LibName>()
-        .transpile::<SymbolRef>()
-        .transpile::<TypeLib>()
-        .transpile::<TypeSysId>()
-        .transpile::<TypeSymbol>()
-        .transpile::<SymbolicSys>()
-        .transpile::<MemoryLayout>()
-        .compile_symbols()
-        .unwrap()
+    let builder = LibBuilder::with(libname!(STRICT_TYPES_LIB), [std_stl().to_dependency_types()]);
+    transpile_all!(builder, [
+        Ident,
+        TypeName,
+        FieldName,
+        VariantName,
+        LibName,
+        SymbolRef,
+        TypeLib,
+        TypeSysId,
+        TypeSymbol,
+        SymbolicSys,
+        MemoryLayout,
+    ])
+    .compile_symbols()
+    .unwrap()
 }
 
 pub fn strict_types_stl() -> TypeLib { strict_types_sym().compile().unwrap() }
 
 pub fn bitcoin_stl() -> TypeLib {
-    LibBuilder::with(libname!(LIB_NAME_BITCOIN), [])
-        .transpile::<CompressedPublicKey>()
-        .transpile::<LeafScript<ScriptBuf>>()
-        .transpile::<LeafVersion>()
-        .transpile::<OutPoint>()
-        .transpile::<ScriptBuf>()
-        .transpile::<ScriptBytes>()
-        .transpile::<TapNodeHash>()
-        .transpile::<Transaction>()
-        .transpile::<TweakedPublicKey>()
-        .transpile::<TxIn>()
-        .transpile::<TxOut>()
-        .transpile::<Txid>()
-        .transpile::<XOnlyPublicKey>()
-        .transpile::<ChainHash>()
-        .compile()
-        .unwrap()
+    let builder = LibBuilder::with(libname!(LIB_NAME_BITCOIN), []);
+    transpile_all!(builder, [
+        CompressedPublicKey,
+        LeafScript<ScriptBuf>,
+        LeafVersion,
+        OutPoint,
+        ScriptBuf,
+        ScriptBytes,
+        TapNodeHash,
+        Transaction,
+        TweakedPublicKey,
+        TxIn,
+        TxOut,
+        Txid,
+        XOnlyPublicKey,
+        ChainHash,
+    ])
+    .compile()
+    .unwrap()
 }
 
 pub fn bitcoin_tx_stl() -> TypeLib {
@@ -136,6 +152,8 @@ pub fn bitcoin_tx_stl() -> TypeLib {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::typelib::{FlattenError, SubsetError};
+    use crate::TypeLibId;
 
     #[test]
     fn std_lib_id() {
@@ -143,12 +161,221 @@ mod test {
         assert_eq!(lib.id().to_string(), LIB_ID_STD);
     }
 
+    #[test]
+    fn id_from_parts() {
+        let lib = std_stl();
+        let id = TypeLibId::from_parts([
+            0x82, 0x89, 0xeb, 0x4d, 0x0f, 0x0b, 0x70, 0x54, 0xaf, 0x74, 0x4b, 0x05, 0xe8, 0xc1,
+            0xd7, 0x9d, 0x23, 0x03, 0xa6, 0x5c, 0x63, 0xcb, 0xcf, 0xa5, 0x67, 0x98, 0xf9, 0xfa,
+            0x56, 0x3c, 0x31, 0x6a,
+        ]);
+        assert_eq!(id.to_string(), LIB_ID_STD);
+        assert!(id.verify(&lib));
+        assert!(!id.verify(&strict_types_stl()));
+
+        let sys = crate::SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let sys = sys.as_types();
+        let id = TypeSysId::from_parts(sys.id().checksum_bytes());
+        assert!(id.verify(sys));
+        assert_eq!(id.to_string(), sys.id().to_string());
+        assert!(!TypeSysId::from_parts([0u8; 32]).verify(sys));
+    }
+
+    #[test]
+    fn namespaced_id() {
+        let sys = crate::SystemBuilder::new().import(std_stl()).unwrap().finalize().unwrap();
+        let mainnet = sys.namespaced_id("mainnet");
+        let testnet = sys.namespaced_id("testnet");
+        assert_ne!(mainnet, testnet);
+        assert_ne!(mainnet, sys.id());
+        assert_eq!(sys.namespaced_id(""), sys.id());
+        assert!(mainnet.verify_namespaced(sys.as_types(), "mainnet"));
+        assert!(!mainnet.verify_namespaced(sys.as_types(), "testnet"));
+        assert!(!mainnet.verify(sys.as_types()));
+    }
+
+    #[test]
+    fn std_lib_id_transpile_order() {
+        let lib = LibBuilder::with(libname!(LIB_NAME_STD), None)
+            .transpile::<AlphaNumLodash>()
+            .transpile::<AlphaNumDash>()
+            .transpile::<AlphaNumDot>()
+            .transpile::<AlphaCapsNum>()
+            .transpile::<AlphaNum>()
+            .transpile::<HexDecSmall>()
+            .transpile::<HexDecCaps>()
+            .transpile::<DecDot>()
+            .transpile::<Dec>()
+            .transpile::<AlphaSmallLodash>()
+            .transpile::<AlphaSmallDash>()
+            .transpile::<AlphaSmallDot>()
+            .transpile::<AlphaSmall>()
+            .transpile::<AlphaCapsLodash>()
+            .transpile::<AlphaCapsDash>()
+            .transpile::<AlphaCapsDot>()
+            .transpile::<AlphaCaps>()
+            .transpile::<AlphaLodash>()
+            .transpile::<AlphaDash>()
+            .transpile::<AlphaDot>()
+            .transpile::<Alpha>()
+            .transpile::<AsciiPrintable>()
+            .transpile::<AsciiSym>()
+            .transpile::<U7>()
+            .transpile::<U6>()
+            .transpile::<U5>()
+            .transpile::<U4>()
+            .transpile::<U3>()
+            .transpile::<U2>()
+            .transpile::<U1>()
+            .transpile::<Bool>()
+            .compile()
+            .unwrap();
+        assert_eq!(lib.id().to_string(), LIB_ID_STD);
+        assert_eq!(lib, std_stl());
+    }
+
     #[test]
     fn strict_types_lib_id() {
         let lib = strict_types_stl();
         assert_eq!(lib.id().to_string(), LIB_ID_STRICT_TYPES);
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn strict_types_lib_yaml() {
+        let lib = strict_types_stl();
+        let yaml = lib.to_yaml();
+        assert!(yaml.starts_with(&format!("---\nid: {:+}\n---\n", lib.id())));
+        let parsed = TypeLib::from_yaml(&yaml).unwrap();
+        assert_eq!(parsed, lib);
+        assert_eq!(parsed.to_yaml(), yaml);
+
+        let tampered = yaml.replacen("name: StrictTypes", "name: StrictTypez", 1);
+        assert!(matches!(
+            TypeLib::from_yaml(&tampered),
+            Err(crate::typelib::YamlError::IdMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn type_ids() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let refs = st.extern_types.get(&libname!(LIB_NAME_STD)).unwrap();
+        assert!(!refs.is_empty());
+        for (sem_id, name) in refs {
+            assert_eq!(std.type_id(name), Some(*sem_id));
+            assert_eq!(std.type_by_id(*sem_id), Some(name));
+        }
+        assert_eq!(std.type_id(&tn!("Unknown")), None);
+        assert_eq!(std.type_by_id(crate::SemId::unit()), None);
+    }
+
+    #[test]
+    fn dependency_closure() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let mut lib = bitcoin_stl();
+        lib.import(st.to_dependency()).unwrap();
+
+        assert!(std.dependency_closure(&[]).is_empty());
+        assert_eq!(st.dependency_closure(&[]), bset! { std.id() });
+        assert_eq!(lib.dependency_closure(&[]), bset! { st.id() });
+        assert_eq!(lib.dependency_closure(&[&st]), bset! { st.id(), std.id() });
+        assert_eq!(lib.dependency_closure(&[&st, &std]), bset! { st.id(), std.id() });
+
+        assert_eq!(lib.missing_dependencies(&[]), bset! { st.to_dependency() });
+        assert_eq!(lib.missing_dependencies(&[&st]), bset! { std.to_dependency() });
+        assert!(lib.missing_dependencies(&[&st, &std]).is_empty());
+    }
+
+    #[test]
+    fn rename_lib() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let renamed = st.clone().rename_lib(libname!("Vendored"));
+
+        assert_eq!(renamed.name, libname!("Vendored"));
+        assert_ne!(renamed.id(), st.id());
+        assert_eq!(renamed.dependencies, st.dependencies);
+        assert_eq!(renamed.extern_types, st.extern_types);
+        for name in st.types.keys() {
+            assert_eq!(renamed.type_id(name), st.type_id(name));
+        }
+
+        let sym = renamed.to_symbolic().unwrap();
+        assert_eq!(sym.name(), &libname!("Vendored"));
+        assert_eq!(sym.compile().unwrap(), renamed);
+
+        let sys = crate::SystemBuilder::new().import(renamed).unwrap().import(std).unwrap();
+        let sys = sys.finalize().unwrap();
+        assert_eq!(sys.resolve("Vendored.TypeLib"), st.type_id(&tn!("TypeLib")).as_ref());
+    }
+
+    #[test]
+    fn subset() {
+        let std = std_stl();
+        let st = strict_types_stl();
+
+        let lib = st.subset(&[tn!("TypeLib")]).unwrap();
+        assert_ne!(lib.id(), st.id());
+        assert!(lib.types.contains_key(&tn!("TypeLib")));
+        assert!(lib.types.contains_key(&tn!("Sizing")));
+        assert!(!lib.types.contains_key(&tn!("SymbolicSys")));
+        assert!(!lib.types.contains_key(&tn!("MemoryLayout")));
+        for name in lib.types.keys() {
+            assert_eq!(lib.type_id(name), st.type_id(name));
+        }
+        assert_eq!(lib.dependencies, st.dependencies);
+        let sys = crate::SystemBuilder::new().import(lib).unwrap().import(std.clone()).unwrap();
+        sys.finalize().unwrap();
+
+        // no dependency types are used by the root
+        let lib = st.subset(&[tn!("Sizing"), tn!("Unknown")]).unwrap();
+        assert_eq!(lib.types.len(), 1);
+        assert!(lib.dependencies.is_empty());
+        assert!(lib.extern_types.is_empty());
+
+        let all = st.types.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(st.subset(&all).unwrap(), st);
+
+        assert_eq!(
+            st.subset(&[tn!("Unknown")]),
+            Err(SubsetError::UnknownRoots(vec![tn!("Unknown")]))
+        );
+    }
+
+    #[test]
+    fn flatten_dependencies() {
+        let std = std_stl();
+        let st = strict_types_stl();
+
+        let lib = st.flatten_dependencies(&[&std]).unwrap();
+        assert_ne!(lib.id(), st.id());
+        assert!(lib.dependencies.is_empty());
+        assert!(lib.extern_types.is_empty());
+        assert!(lib.types.contains_key(&tn!("TypeLib")));
+        assert!(lib.types.contains_key(&tn!("StdAlphaLodash")));
+        assert!(!lib.types.contains_key(&tn!("AlphaLodash")));
+        // only used dependency types are copied
+        assert!(!lib.types.contains_key(&tn!("StdBool")));
+        assert_eq!(lib.types[&tn!("StdAlphaLodash")], std.types[&tn!("AlphaLodash")]);
+        crate::SystemBuilder::new().import(lib.clone()).unwrap().finalize().unwrap();
+
+        assert_eq!(lib.flatten_dependencies(&[]).unwrap(), lib);
+        assert_eq!(
+            st.flatten_dependencies(&[]),
+            Err(FlattenError::MissingDependency(libname!(LIB_NAME_STD), std.id()))
+        );
+
+        let mut broken = st.clone();
+        broken.types.remove(&tn!("TypeName")).unwrap();
+        assert!(matches!(
+            std.flatten_dependencies(&[&broken]),
+            Err(FlattenError::Inconsistent(name, _)) if name == st.name
+        ));
+    }
+
     #[test]
     fn bitcoin_lib_id() {
         let lib = bitcoin_stl();
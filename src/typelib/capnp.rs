@@ -0,0 +1,466 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Export of type library types in form of a Cap'n Proto schema.
+//!
+//! Cap'n Proto and strict encoding have different wire formats, thus the schema describes the
+//! same data, but not the same bytes. The type classes are mapped as follows:
+//! - numbers become Cap'n Proto integers or floats of the same or larger size; numbers without a
+//!   type of sufficient size, like `U128` or `F16`, become `Data` with their little-endian bytes,
+//!   unicode characters become `UInt32` code points and the unit type becomes `Void`;
+//! - enums become Cap'n Proto enums; since enumerants are numbered sequentially, they are ordered
+//!   by their tags and the tags which differ from the enumerant numbers are given in comments;
+//! - unions become structs with a single unnamed union of the variants, with unit variants being
+//!   `Void`; optional values become such unions with `none` and `some` variants;
+//! - structures and tuples become structs with fields numbered in their order; tuple fields are
+//!   named by their positions, like `field0`, and field names lose underscores;
+//! - newtypes (tuples with a single field) are replaced with the wrapped type;
+//! - ASCII and unicode strings become `Text`, byte strings and byte arrays become `Data`, other
+//!   arrays, lists and sets become `List`s;
+//! - maps have no direct equivalent and become lists of key-value entry structs.
+//!
+//! The ids of the library types are derived from their semantic ids, and the ids of the other
+//! definitions and of the schema file from the semantic id of the library type they belong to,
+//! thus they don't change when the schema is regenerated. Most of the strict type bounds, like the
+//! collection size limits or the fixed array lengths, have no Cap'n Proto equivalent and are lost.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sha2::{Digest, Sha256};
+use strict_encoding::{LibName, NumCls, Primitive, TypeName};
+
+use crate::typelib::SymbolError;
+use crate::{SemId, SymbolicLib, TranspileRef, Ty, TypeLib, TypeRef};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum CapnpError {
+    /// type `{0}` is not defined in the library.
+    UnknownType(TypeName),
+
+    /// type `{0}` doesn't map to a Cap'n Proto struct and can't be a root type.
+    RootNotStruct(TypeName),
+
+    #[display(inner)]
+    #[from]
+    Symbols(SymbolError),
+}
+
+impl TypeLib {
+    /// Exports type `root` and all library types it depends on as a Cap'n Proto schema. See the
+    /// module documentation for the details of the type mapping.
+    ///
+    /// Types from library dependencies are referenced by their names qualified with the library
+    /// name, which is imported from `<LibName>.capnp` schema; since the newtypes and the
+    /// collections don't produce Cap'n Proto definitions, such dependency types must be defined
+    /// manually.
+    pub fn to_capnp(&self, root: &TypeName) -> Result<String, CapnpError> {
+        let lib = self.to_symbolic()?;
+        let Some(root_id) = self.type_id(root) else {
+            return Err(CapnpError::UnknownType(root.clone()));
+        };
+        let mut ctx = CapnpContext {
+            lib: &lib,
+            types: self,
+            owner: (root.to_string(), root_id),
+            named: BTreeMap::new(),
+            defs: BTreeMap::new(),
+            structs: BTreeSet::new(),
+            imports: BTreeSet::new(),
+        };
+        let expr = ctx.named_expr(root);
+        if expr != root.as_str() || !ctx.structs.contains(&expr) {
+            return Err(CapnpError::RootNotStruct(root.clone()));
+        }
+
+        let mut capnp = format!("# Cap'n Proto schema for type {}.{root}\n\n", self.name);
+        capnp.push_str(&format!("@{};\n\n", node_id(root_id, "file")));
+        for lib_name in &ctx.imports {
+            capnp.push_str(&format!("using {lib_name} = import \"{lib_name}.capnp\";\n"));
+        }
+        if !ctx.imports.is_empty() {
+            capnp.push('\n');
+        }
+        let mut defs = ctx.defs.values().peekable();
+        while let Some(def) = defs.next() {
+            capnp.push_str(def);
+            if defs.peek().is_some() {
+                capnp.push('\n');
+            }
+        }
+        Ok(capnp)
+    }
+}
+
+struct CapnpContext<'lib> {
+    lib: &'lib SymbolicLib,
+    types: &'lib TypeLib,
+    /// Name and semantic id of the library type which is being processed.
+    owner: (String, SemId),
+    /// Representations of the already processed library types.
+    named: BTreeMap<TypeName, String>,
+    defs: BTreeMap<String, String>,
+    structs: BTreeSet<String>,
+    imports: BTreeSet<LibName>,
+}
+
+impl CapnpContext<'_> {
+    fn named_expr(&mut self, name: &TypeName) -> String {
+        if let Some(expr) = self.named.get(name) {
+            return expr.clone();
+        }
+        // recursive references always go through collections or optional values, so the type is
+        // a struct unless it is a newtype or a collection
+        self.named.insert(name.clone(), name.to_string());
+        let ty = self.lib.types().get(name).expect("inconsistent type library");
+        let id = self.types.type_id(name).expect("inconsistent type library");
+        let owner = std::mem::replace(&mut self.owner, (name.to_string(), id));
+        let expr = self.ty_expr(ty, name.to_string());
+        self.owner = owner;
+        self.named.insert(name.clone(), expr.clone());
+        expr
+    }
+
+    fn ref_expr(&mut self, r: &TranspileRef, hint: String) -> String {
+        match r {
+            TranspileRef::Embedded(ty) => self.ty_expr(ty, hint),
+            TranspileRef::Named(name) => self.named_expr(name),
+            TranspileRef::Extern(ext) => {
+                self.imports.insert(ext.lib_name.clone());
+                format!("{}.{}", ext.lib_name, ext.ty_name)
+            }
+        }
+    }
+
+    fn is_char(&self, r: &TranspileRef) -> bool {
+        match r {
+            TranspileRef::Embedded(ty) => ty.is_char_enum(),
+            TranspileRef::Named(name) => {
+                self.lib.types().get(name).map(Ty::is_char_enum).unwrap_or_default()
+            }
+            TranspileRef::Extern(_) => false,
+        }
+    }
+
+    fn is_rstring(&self, fields: &[TranspileRef]) -> bool {
+        match fields[1].as_ty() {
+            Some(Ty::List(rest, _)) => self.is_char(&fields[0]) && self.is_char(rest),
+            _ => false,
+        }
+    }
+
+    /// Adds definition of `kind` (`struct` or `enum`) with the `body` lines, returning the name
+    /// of the defined type.
+    fn define(&mut self, kind: &str, name: String, body: Vec<String>) -> String {
+        let (owner, sem_id) = &self.owner;
+        let id = node_id(*sem_id, if owner == &name { "" } else { &name });
+        let mut def = format!("{kind} {name} @{id} {{\n");
+        for line in body {
+            def.push_str(&format!("  {line}\n"));
+        }
+        def.push_str("}\n");
+        if kind == "struct" {
+            self.structs.insert(name.clone());
+        }
+        self.defs.insert(name.clone(), def);
+        name
+    }
+
+    fn define_struct(&mut self, name: String, fields: Vec<(String, String)>) -> String {
+        let body = fields
+            .into_iter()
+            .enumerate()
+            .map(|(no, (field, ty))| format!("{field} @{no} :{ty};"))
+            .collect();
+        self.define("struct", name, body)
+    }
+
+    fn define_union(&mut self, name: String, variants: Vec<(String, String)>) -> String {
+        let mut body = vec![s!("union {")];
+        body.extend(
+            variants
+                .into_iter()
+                .enumerate()
+                .map(|(no, (variant, ty))| format!("  {variant} @{no} :{ty};")),
+        );
+        body.push(s!("}"));
+        self.define("struct", name, body)
+    }
+
+    fn ty_expr(&mut self, ty: &Ty<TranspileRef>, hint: String) -> String {
+        if let Some(some) = ty.as_some() {
+            let some = self.ref_expr(some, format!("{hint}Some"));
+            return self.define_union(hint, vec![(s!("none"), s!("Void")), (s!("some"), some)]);
+        }
+        match ty {
+            Ty::Primitive(prim) => primitive_expr(*prim).to_owned(),
+            Ty::UnicodeChar => s!("UInt32"),
+            Ty::Enum(variants) => {
+                let body = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(no, variant)| {
+                        let name = camel_case(variant.name.as_str());
+                        if variant.tag as usize == no {
+                            format!("{name} @{no};")
+                        } else {
+                            format!("{name} @{no}; # tag {}", variant.tag)
+                        }
+                    })
+                    .collect();
+                self.define("enum", hint, body)
+            }
+            Ty::Union(variants) => {
+                let variants = variants
+                    .iter()
+                    .map(|(variant, ty)| {
+                        let name = variant.name.as_str();
+                        let ty = if ty.as_ty() == Some(&Ty::UNIT) {
+                            s!("Void")
+                        } else {
+                            self.ref_expr(ty, format!("{hint}{}", type_case(name)))
+                        };
+                        (camel_case(name), ty)
+                    })
+                    .collect();
+                self.define_union(hint, variants)
+            }
+            Ty::Tuple(fields) if fields.len() == 2 && self.is_rstring(fields) => s!("Text"),
+            Ty::Tuple(fields) if fields.len() == 1 => self.ref_expr(&fields[0], hint),
+            Ty::Tuple(fields) => {
+                let fields = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(pos, r)| {
+                        (format!("field{pos}"), self.ref_expr(r, format!("{hint}{pos}")))
+                    })
+                    .collect();
+                self.define_struct(hint, fields)
+            }
+            Ty::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let name = field.name.as_str();
+                        let hint = format!("{hint}{}", type_case(name));
+                        (camel_case(name), self.ref_expr(&field.ty, hint))
+                    })
+                    .collect();
+                self.define_struct(hint, fields)
+            }
+            Ty::Array(ty, _) | Ty::List(ty, _) if ty.is_byte() => s!("Data"),
+            Ty::List(ty, _) if ty.is_unicode_char() || self.is_char(ty) => s!("Text"),
+            Ty::Array(ty, _) | Ty::List(ty, _) | Ty::Set(ty, _) => {
+                let item = self.ref_expr(ty, format!("{hint}Item"));
+                format!("List({item})")
+            }
+            Ty::Map(key, value, _) => {
+                let entry = format!("{hint}Entry");
+                let key = self.ref_expr(key, format!("{entry}Key"));
+                let value = self.ref_expr(value, format!("{entry}Value"));
+                let entry = self.define_struct(entry, vec![(s!("key"), key), (s!("value"), value)]);
+                format!("List({entry})")
+            }
+        }
+    }
+}
+
+/// Derives Cap'n Proto id of a definition `name` belonging to the type `sem_id`, or of the type
+/// itself if the name is empty. Cap'n Proto ids always have the highest bit set.
+fn node_id(sem_id: SemId, name: &str) -> String {
+    let mut bytes = [0u8; 8];
+    if name.is_empty() {
+        bytes.copy_from_slice(&sem_id[..8]);
+    } else {
+        let digest = Sha256::new().chain_update(&sem_id[..]).chain_update(name).finalize();
+        bytes.copy_from_slice(&digest[..8]);
+    }
+    format!("{:#018x}", u64::from_le_bytes(bytes) | 1 << 63)
+}
+
+/// Converts a name into Cap'n Proto camel case by removing underscores and capitalizing the
+/// letters following them.
+fn camel_case(name: &str) -> String {
+    let mut converted = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' => upper = !converted.is_empty(),
+            c if upper => {
+                converted.push(c.to_ascii_uppercase());
+                upper = false;
+            }
+            c => converted.push(c),
+        }
+    }
+    converted
+}
+
+fn type_case(name: &str) -> String {
+    let name = camel_case(name);
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => s!(""),
+    }
+}
+
+fn primitive_expr(prim: Primitive) -> &'static str {
+    match (prim.info().ty, prim.byte_size()) {
+        _ if prim == Primitive::UNIT => "Void",
+        _ if prim == Primitive::BYTE => "UInt8",
+        (NumCls::Unsigned | NumCls::NonZero, 1) => "UInt8",
+        (NumCls::Unsigned | NumCls::NonZero, 2) => "UInt16",
+        (NumCls::Unsigned | NumCls::NonZero, 3..=4) => "UInt32",
+        (NumCls::Unsigned | NumCls::NonZero, 5..=8) => "UInt64",
+        (NumCls::Signed, 1) => "Int8",
+        (NumCls::Signed, 2) => "Int16",
+        (NumCls::Signed, 3..=4) => "Int32",
+        (NumCls::Signed, 5..=8) => "Int64",
+        (NumCls::Float, 4) => "Float32",
+        (NumCls::Float, 8) => "Float64",
+        _ => "Data",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{Confined, SmallVec, TinyOrdMap};
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    pub enum Kind {
+        #[default]
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 2,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Item {
+        pub kind: Kind,
+        pub value: u16,
+        pub hash: [u8; 4],
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = order)]
+    pub enum Payload {
+        #[strict_type(dumb)]
+        Empty,
+        Data(Confined<Vec<u8>, 0, 8>),
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub items: SmallVec<Item>,
+        pub limits: TinyOrdMap<u8, u64>,
+        pub payload: Payload,
+        pub parent_id: Option<u32>,
+    }
+
+    #[test]
+    fn to_capnp() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let capnp = lib.to_capnp(&tn!("Record")).unwrap();
+        let id = |name: &'static str| node_id(lib.type_id(&tn!(name)).unwrap(), "");
+        let record = lib.type_id(&tn!("Record")).unwrap();
+        assert_eq!(
+            capnp,
+            format!(
+                r#"# Cap'n Proto schema for type Test.Record
+
+@{file_id};
+
+struct Item @{item_id} {{
+  kind @0 :Kind;
+  value @1 :UInt16;
+  hash @2 :Data;
+}}
+
+enum Kind @{kind_id} {{
+  first @0;
+  second @1; # tag 2
+}}
+
+struct Payload @{payload_id} {{
+  union {{
+    empty @0 :Void;
+    data @1 :Data;
+  }}
+}}
+
+struct Record @{record_id} {{
+  items @0 :List(Item);
+  limits @1 :List(RecordLimitsEntry);
+  payload @2 :Payload;
+  parentId @3 :RecordParentId;
+}}
+
+struct RecordLimitsEntry @{entry_id} {{
+  key @0 :UInt8;
+  value @1 :UInt64;
+}}
+
+struct RecordParentId @{parent_id} {{
+  union {{
+    none @0 :Void;
+    some @1 :UInt32;
+  }}
+}}
+"#,
+                file_id = node_id(record, "file"),
+                record_id = id("Record"),
+                item_id = id("Item"),
+                kind_id = id("Kind"),
+                payload_id = id("Payload"),
+                entry_id = node_id(record, "RecordLimitsEntry"),
+                parent_id = node_id(record, "RecordParentId"),
+            )
+        );
+        assert_eq!(lib.to_capnp(&tn!("Record")).unwrap(), capnp);
+
+        assert_eq!(lib.to_capnp(&tn!("Kind")), Err(CapnpError::RootNotStruct(tn!("Kind"))));
+        assert_eq!(lib.to_capnp(&tn!("Missing")), Err(CapnpError::UnknownType(tn!("Missing"))));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(camel_case("parent_id"), "parentId");
+        assert_eq!(camel_case("_private"), "private");
+        assert_eq!(type_case("parent_id"), "ParentId");
+        assert!(node_id(SemId::from([0u8; 32]), "").starts_with("0x8000"));
+    }
+}
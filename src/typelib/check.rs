@@ -0,0 +1,248 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use strict_encoding::{LibName, TypeName};
+
+use crate::typelib::{ExternRef, InlineRef, InlineRef1, InlineRef2};
+use crate::{LibRef, SemId, Ty, TypeLib, TypeLibId, TypeRef};
+
+/// Defects of an internally inconsistent type library, reported by [`TypeLib::validate_self`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum LibDefect {
+    /// type {0} references type {1} which is not defined by the library.
+    UnknownType(TypeName, SemId),
+
+    /// type {0} references library {1} which is not a dependency of the library.
+    UnknownDependency(TypeName, TypeLibId),
+
+    /// type {0} references type {2} of library {1} which is not listed among the library
+    /// external types.
+    UnknownExtern(TypeName, LibName, SemId),
+
+    /// external types are listed for library {0} which is not a dependency of the library.
+    UndeclaredExtern(LibName),
+
+    /// library {0} is listed as a dependency more than once.
+    DuplicateDependency(LibName),
+
+    /// types {0} and {1} have the same semantic id {2}.
+    DuplicateSemId(TypeName, TypeName, SemId),
+}
+
+impl TypeLib {
+    /// Checks that the library is internally consistent, returning all the found defects.
+    ///
+    /// A consistent library has each of its dependencies listed once, and all references from its
+    /// types resolve either to the library types or to the external types of its dependencies,
+    /// which are listed in the library. Libraries produced by [`crate::LibBuilder`] are always
+    /// consistent, thus the check is intended for the libraries deserialized from untrusted
+    /// sources.
+    ///
+    /// Type names are unique by construction of the type map, and the library id is not stored
+    /// but always computed from the library data (and checked against the id from ASCII armor
+    /// when the armored library is parsed), thus neither of them may be inconsistent.
+    pub fn validate_self(&self) -> Result<(), Vec<LibDefect>> {
+        let mut checker = Checker {
+            lib: self,
+            types: BTreeMap::new(),
+            dependencies: BTreeMap::new(),
+            defects: vec![],
+        };
+        checker.check_lib();
+        if checker.defects.is_empty() {
+            Ok(())
+        } else {
+            Err(checker.defects)
+        }
+    }
+}
+
+struct Checker<'lib> {
+    lib: &'lib TypeLib,
+    types: BTreeMap<SemId, &'lib TypeName>,
+    dependencies: BTreeMap<TypeLibId, &'lib LibName>,
+    defects: Vec<LibDefect>,
+}
+
+impl Checker<'_> {
+    fn check_lib(&mut self) {
+        let mut names = BTreeSet::new();
+        for dep in &self.lib.dependencies {
+            if !names.insert(&dep.name) {
+                self.report(LibDefect::DuplicateDependency(dep.name.clone()));
+            }
+            self.dependencies.insert(dep.id, &dep.name);
+        }
+        for lib_name in self.lib.extern_types.keys() {
+            if !names.contains(lib_name) {
+                self.report(LibDefect::UndeclaredExtern(lib_name.clone()));
+            }
+        }
+        for (name, ty) in &self.lib.types {
+            let id = ty.sem_id_named(name);
+            if let Some(other) = self.types.insert(id, name) {
+                self.report(LibDefect::DuplicateSemId(other.clone(), name.clone(), id));
+            }
+        }
+        for (name, ty) in &self.lib.types {
+            self.check_ty(name, ty);
+        }
+    }
+
+    fn check_ty<Ref: CheckRef>(&mut self, owner: &TypeName, ty: &Ty<Ref>) {
+        for (r, _) in ty.type_refs() {
+            r.check(self, owner);
+        }
+    }
+
+    fn check_named(&mut self, owner: &TypeName, id: SemId) {
+        if !self.types.contains_key(&id) {
+            self.report(LibDefect::UnknownType(owner.clone(), id));
+        }
+    }
+
+    fn check_extern(&mut self, owner: &TypeName, ext: &ExternRef) {
+        let Some(lib_name) = self.dependencies.get(&ext.lib_id).copied() else {
+            self.report(LibDefect::UnknownDependency(owner.clone(), ext.lib_id));
+            return;
+        };
+        let known = self
+            .lib
+            .extern_types
+            .get(lib_name)
+            .map(|types| types.contains_key(&ext.sem_id))
+            .unwrap_or_default();
+        if !known {
+            self.report(LibDefect::UnknownExtern(owner.clone(), lib_name.clone(), ext.sem_id));
+        }
+    }
+
+    fn report(&mut self, defect: LibDefect) {
+        if !self.defects.contains(&defect) {
+            self.defects.push(defect);
+        }
+    }
+}
+
+trait CheckRef: TypeRef {
+    fn check(&self, checker: &mut Checker, owner: &TypeName);
+}
+
+impl CheckRef for LibRef {
+    fn check(&self, checker: &mut Checker, owner: &TypeName) {
+        match self {
+            LibRef::Inline(ty) => checker.check_ty(owner, ty.as_ref()),
+            LibRef::Named(id) => checker.check_named(owner, *id),
+            LibRef::Extern(ext) => checker.check_extern(owner, ext),
+        }
+    }
+}
+
+impl CheckRef for InlineRef {
+    fn check(&self, checker: &mut Checker, owner: &TypeName) {
+        match self {
+            InlineRef::Inline(ty) => checker.check_ty(owner, ty.as_ref()),
+            InlineRef::Named(id) => checker.check_named(owner, *id),
+            InlineRef::Extern(ext) => checker.check_extern(owner, ext),
+        }
+    }
+}
+
+impl CheckRef for InlineRef1 {
+    fn check(&self, checker: &mut Checker, owner: &TypeName) {
+        match self {
+            InlineRef1::Inline(ty) => checker.check_ty(owner, ty),
+            InlineRef1::Named(id) => checker.check_named(owner, *id),
+            InlineRef1::Extern(ext) => checker.check_extern(owner, ext),
+        }
+    }
+}
+
+impl CheckRef for InlineRef2 {
+    fn check(&self, checker: &mut Checker, owner: &TypeName) {
+        match self {
+            InlineRef2::Named(id) => checker.check_named(owner, *id),
+            InlineRef2::Extern(ext) => checker.check_extern(owner, ext),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use encoding::{Sizing, StrictDumb};
+    use strict_encoding::{libname, tn};
+
+    use super::*;
+    use crate::typelib::Dependency;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Item {
+        pub value: u16,
+        pub flag: u8,
+    }
+
+    #[test]
+    fn validate_self() {
+        let mut lib = LibBuilder::with("Test", None).transpile::<Item>().compile().unwrap();
+        assert_eq!(lib.validate_self(), Ok(()));
+
+        let missing = SemId::strict_dumb();
+        let dep_id = TypeLibId::strict_dumb();
+        let own_id = lib.id();
+        let ext = ExternRef::with(own_id, missing);
+        let ty = Ty::tuple(
+            vec![LibRef::Named(missing), LibRef::Extern(ext), LibRef::Named(missing)]
+                .try_into()
+                .unwrap(),
+        );
+        lib.types.insert(tn!("Broken"), ty).unwrap();
+        let ty = Ty::list(
+            LibRef::Inline(Box::new(Ty::list(
+                InlineRef::Extern(ExternRef::with(dep_id, missing)),
+                Sizing::U8,
+            ))),
+            Sizing::U8,
+        );
+        lib.types.insert(tn!("Nested"), ty).unwrap();
+        lib.dependencies.push(Dependency::with(dep_id, libname!("Dep"))).unwrap();
+        lib.extern_types.insert(libname!("Other"), none!()).unwrap();
+
+        assert_eq!(
+            lib.validate_self(),
+            Err(vec![
+                LibDefect::UndeclaredExtern(libname!("Other")),
+                LibDefect::UnknownType(tn!("Broken"), missing),
+                LibDefect::UnknownDependency(tn!("Broken"), own_id),
+                LibDefect::UnknownExtern(tn!("Nested"), libname!("Dep"), missing),
+            ])
+        );
+    }
+}
@@ -0,0 +1,676 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Generation of Rust source code from a type library (reverse transpilation).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use amplify::confinement::NonEmptyOrdMap;
+use strict_encoding::{NumCls, Primitive, Sizing, TypeName};
+
+use crate::typelib::TypeLib;
+use crate::{TranspileRef, Ty, TypeRef};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Configuration for [`TypeLib::to_rust_source`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CodegenConfig {
+    /// Expression put into `#[strict_type(lib = ...)]` attributes. If not provided, a
+    /// `LIB_NAME_<LIB>` constant holding the library name is generated and used.
+    pub lib_const: Option<String>,
+    /// Derives added to all generated types in addition to the strict encoding ones. `Eq`, `Ord`
+    /// and `Hash` are omitted for types containing floating-point numbers, unless the numbers
+    /// come from types of other libraries.
+    pub derives: Vec<String>,
+    /// Whether `use` statements for the items referenced by the generated code should be emitted.
+    pub imports: bool,
+}
+
+impl Default for CodegenConfig {
+    fn default() -> Self {
+        CodegenConfig {
+            lib_const: None,
+            derives: vec![s!("Clone"), s!("Eq"), s!("PartialEq"), s!("Debug")],
+            imports: true,
+        }
+    }
+}
+
+impl TypeLib {
+    /// Generates Rust source code defining types of the library, which strict-encode into the
+    /// same semantic types.
+    ///
+    /// Types from the library dependencies are referenced by their names and must be brought
+    /// into the scope by the user. Inline composite types, which can't be expressed in Rust
+    /// without a name, are generated as separate types named after their parent.
+    ///
+    /// If the library is internally inconsistent, the returned source consists of a
+    /// `compile_error!` describing the problem.
+    pub fn to_rust_source(&self, config: &CodegenConfig) -> String {
+        let lib = match self.to_symbolic() {
+            Ok(lib) => lib,
+            Err(err) => {
+                let msg = format!("inconsistent type library {}: {err}", self.name);
+                return format!("compile_error!({msg:?});\n");
+            }
+        };
+
+        let (lib_const, const_decl) = match &config.lib_const {
+            Some(expr) => (expr.clone(), None),
+            None => {
+                let ident = format!("LIB_NAME_{}", to_snake(self.name.as_str()).to_uppercase());
+                let decl = format!("pub const {ident}: &str = {:?};\n", self.name.as_str());
+                (ident, Some(decl))
+            }
+        };
+
+        let mut gen = Codegen {
+            config,
+            types: lib.types(),
+            lib_const,
+            imports: BTreeMap::new(),
+            taken: lib.types().keys().map(|name| name.to_string()).collect(),
+            pending: vec![],
+            out: String::new(),
+        };
+        gen.import("strict_encoding", "StrictDecode");
+        gen.import("strict_encoding", "StrictDumb");
+        gen.import("strict_encoding", "StrictEncode");
+        gen.import("strict_encoding", "StrictType");
+        for (name, ty) in lib.types() {
+            gen.emit(name.as_str(), ty);
+            while let Some((name, ty)) = gen.pending.pop() {
+                gen.emit(&name, &ty);
+            }
+        }
+
+        let mut src = format!("// Generated from strict type library {}.\n", self.id());
+        for dep in &self.dependencies {
+            writeln!(src, "// Requires types from library {} ({}).", dep.name, dep.id).ok();
+        }
+        src.push('\n');
+        if config.imports {
+            for (path, items) in &gen.imports {
+                let items = items.iter().cloned().collect::<Vec<_>>();
+                match items.as_slice() {
+                    [item] => writeln!(src, "use {path}::{item};"),
+                    _ => writeln!(src, "use {path}::{{{}}};", items.join(", ")),
+                }
+                .ok();
+            }
+            src.push('\n');
+        }
+        if let Some(decl) = const_decl {
+            src.push_str(&decl);
+            src.push('\n');
+        }
+        src.push_str(&gen.out);
+        src
+    }
+}
+
+struct Codegen<'a> {
+    config: &'a CodegenConfig,
+    types: &'a NonEmptyOrdMap<TypeName, Ty<TranspileRef>>,
+    lib_const: String,
+    imports: BTreeMap<&'static str, BTreeSet<String>>,
+    taken: BTreeSet<String>,
+    pending: Vec<(String, Ty<TranspileRef>)>,
+    out: String,
+}
+
+impl Codegen<'_> {
+    fn import(&mut self, path: &'static str, item: impl ToString) {
+        self.imports.entry(path).or_default().insert(item.to_string());
+    }
+
+    fn emit(&mut self, name: &str, ty: &Ty<TranspileRef>) {
+        let ident = sanitize(name);
+        let mut params = vec![format!("lib = {}", self.lib_const)];
+        if ident != name {
+            params.push(format!("rename = {name:?}"));
+        }
+        let mut derives = self.config.derives.clone();
+        if self.has_float(ty, &mut bset![]) {
+            derives.retain(|derive| !matches!(derive.as_str(), "Eq" | "Ord" | "Hash"));
+        }
+
+        let body = match ty {
+            Ty::Enum(variants) => {
+                if !derives.iter().any(|d| d == "Copy") {
+                    derives.insert(0, s!("Copy"));
+                }
+                params.push(s!("tags = repr, into_u8, try_from_u8"));
+                let mut body = format!("#[repr(u8)]\npub enum {ident} {{\n");
+                for (no, variant) in variants.iter().enumerate() {
+                    let (var_ident, rename) = variant_ident(variant.name.as_str());
+                    let mut attrs = rename.into_iter().collect::<Vec<_>>();
+                    if no == 0 {
+                        attrs.push(s!("dumb"));
+                    }
+                    push_attrs(&mut body, &attrs);
+                    writeln!(body, "    {var_ident} = {},", variant.tag).ok();
+                }
+                body.push('}');
+                body
+            }
+            Ty::Union(variants) => {
+                params.push(s!("tags = custom"));
+                let mut dumb = None;
+                let mut body = format!("pub enum {ident} {{\n");
+                for (variant, var_ty) in variants {
+                    let (var_ident, rename) = variant_ident(variant.name.as_str());
+                    let mut attrs = rename.into_iter().collect::<Vec<_>>();
+                    attrs.push(format!("tag = {}", variant.tag));
+                    let hint = format!("{ident}{var_ident}");
+                    let (fields, dumb_expr) = match var_ty.as_ty() {
+                        Some(&Ty::UNIT) => (String::new(), None),
+                        Some(Ty::Tuple(fields)) => {
+                            let list = fields
+                                .iter()
+                                .enumerate()
+                                .map(|(pos, r)| self.ref_ty(r, &format!("{hint}{pos}")))
+                                .collect::<Vec<_>>();
+                            let dumb = fields
+                                .iter()
+                                .map(|r| self.field_dumb(r).unwrap_or_else(|| s!("strict_dumb!()")))
+                                .collect::<Vec<_>>();
+                            let dumb = dumb.join(", ");
+                            (format!("({})", list.join(", ")), Some(format!("({dumb})")))
+                        }
+                        Some(Ty::Struct(fields)) => {
+                            let mut list = vec![];
+                            let mut dumb = vec![];
+                            for field in fields {
+                                let (field_ident, rename) = field_ident(field.name.as_str());
+                                let field_hint = format!("{hint}{}", to_upper_camel(&field_ident));
+                                let ty = self.ref_ty(&field.ty, &field_hint);
+                                let attr = rename
+                                    .map(|r| format!("#[strict_type({r})] "))
+                                    .unwrap_or_default();
+                                list.push(format!("{attr}{field_ident}: {ty}"));
+                                let value = self
+                                    .field_dumb(&field.ty)
+                                    .unwrap_or_else(|| s!("strict_dumb!()"));
+                                dumb.push(format!("{field_ident}: {value}"));
+                            }
+                            let fields = format!(" {{ {} }}", list.join(", "));
+                            (fields, Some(format!(" {{ {} }}", dumb.join(", "))))
+                        }
+                        _ => {
+                            let ty = self.ref_ty(var_ty, &hint);
+                            let dumb =
+                                self.field_dumb(var_ty).unwrap_or_else(|| s!("strict_dumb!()"));
+                            (format!("({ty})"), Some(format!("({dumb})")))
+                        }
+                    };
+                    match (&dumb, dumb_expr) {
+                        (None, None) => {
+                            attrs.push(s!("dumb"));
+                            dumb = Some(None);
+                        }
+                        (None, Some(expr)) => dumb = Some(Some(format!("Self::{var_ident}{expr}"))),
+                        _ => {}
+                    }
+                    push_attrs(&mut body, &attrs);
+                    writeln!(body, "    {var_ident}{fields},").ok();
+                }
+                if let Some(Some(expr)) = dumb {
+                    self.import("strict_encoding", "strict_dumb");
+                    params.push(format!("dumb = {expr}"));
+                }
+                body.push('}');
+                body
+            }
+            Ty::Tuple(fields) => {
+                let mut list = vec![];
+                for (pos, r) in fields.iter().enumerate() {
+                    let ty = self.ref_ty(r, &format!("{ident}{pos}"));
+                    let attr = self
+                        .field_dumb(r)
+                        .map(|dumb| format!("#[strict_type(dumb = {{ {dumb} }})] "))
+                        .unwrap_or_default();
+                    list.push(format!("{attr}pub {ty}"));
+                }
+                format!("pub struct {ident}({});", list.join(", "))
+            }
+            Ty::Struct(fields) => {
+                let mut body = format!("pub struct {ident} {{\n");
+                for field in fields {
+                    let (field_ident, rename) = field_ident(field.name.as_str());
+                    let hint = format!("{ident}{}", to_upper_camel(&field_ident));
+                    let ty = self.ref_ty(&field.ty, &hint);
+                    let mut attrs = rename.into_iter().collect::<Vec<_>>();
+                    if let Some(dumb) = self.field_dumb(&field.ty) {
+                        attrs.push(format!("dumb = {{ {dumb} }}"));
+                    }
+                    push_attrs(&mut body, &attrs);
+                    writeln!(body, "    pub {field_ident}: {ty},").ok();
+                }
+                body.push('}');
+                body
+            }
+            _ => {
+                let ty = self.inline_ty(ty, &ident);
+                writeln!(
+                    self.out,
+                    "// Named non-composite type `{name}` has no Rust equivalent and is emitted \
+                     as an alias.\npub type {ident} = {ty};\n"
+                )
+                .ok();
+                return;
+            }
+        };
+
+        if !derives.is_empty() {
+            writeln!(self.out, "#[derive({})]", derives.join(", ")).ok();
+        }
+        writeln!(self.out, "#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]").ok();
+        writeln!(self.out, "#[strict_type({})]", params.join(", ")).ok();
+        writeln!(self.out, "{body}\n").ok();
+    }
+
+    /// Detects whether a floating-point number is reachable from `ty`, not looking into types of
+    /// other libraries.
+    fn has_float(&self, ty: &Ty<TranspileRef>, seen: &mut BTreeSet<TypeName>) -> bool {
+        if let Ty::Primitive(prim) = ty {
+            return prim.info().ty == NumCls::Float;
+        }
+        (0..=u8::MAX).map_while(|pos| ty.ty_at(pos)).any(|r| match r {
+            TranspileRef::Embedded(ty) => self.has_float(ty, seen),
+            TranspileRef::Named(name) => {
+                seen.insert(name.clone())
+                    && self.types.get(name).is_some_and(|ty| self.has_float(ty, seen))
+            }
+            TranspileRef::Extern(_) => false,
+        })
+    }
+
+    fn ref_ty(&mut self, r: &TranspileRef, hint: &str) -> String {
+        match r {
+            TranspileRef::Embedded(ty) => self.inline_ty(ty, hint),
+            TranspileRef::Named(name) => sanitize(name.as_str()),
+            TranspileRef::Extern(ext) => sanitize(ext.ty_name.as_str()),
+        }
+    }
+
+    fn inline_ty(&mut self, ty: &Ty<TranspileRef>, hint: &str) -> String {
+        if let Some(some) = ty.as_some() {
+            let inner = match some.as_ty() {
+                Some(Ty::Tuple(fields)) if fields.len() == 1 => &fields[0],
+                _ => some,
+            };
+            return format!("Option<{}>", self.ref_ty(inner, hint));
+        }
+        match ty {
+            Ty::Primitive(prim) => self.primitive(*prim),
+            Ty::UnicodeChar => s!("char"),
+            Ty::Array(inner, len) => format!("[{}; {len}]", self.ref_ty(inner, hint)),
+            Ty::List(inner, sizing) if inner.is_byte() => self.confined("Blob", None, *sizing),
+            Ty::List(inner, sizing) if inner.is_unicode_char() => {
+                self.confined("String", None, *sizing)
+            }
+            Ty::List(inner, sizing) => {
+                let inner = self.ref_ty(inner, &format!("{hint}Item"));
+                self.confined("Vec", Some(inner), *sizing)
+            }
+            Ty::Set(inner, sizing) => {
+                let inner = self.ref_ty(inner, &format!("{hint}Item"));
+                self.confined("OrdSet", Some(inner), *sizing)
+            }
+            Ty::Map(key, val, sizing) => {
+                let key = self.ref_ty(key, &format!("{hint}Key"));
+                let val = self.ref_ty(val, &format!("{hint}Value"));
+                self.confined("OrdMap", Some(format!("{key}, {val}")), *sizing)
+            }
+            Ty::Tuple(fields) if fields.is_empty() => s!("()"),
+            Ty::Enum(_) | Ty::Union(_) | Ty::Tuple(_) | Ty::Struct(_) => {
+                let mut name = hint.to_owned();
+                let mut no = 1;
+                while !self.taken.insert(name.clone()) {
+                    no += 1;
+                    name = format!("{hint}{no}");
+                }
+                self.pending.push((name.clone(), ty.clone()));
+                name
+            }
+        }
+    }
+
+    /// Constructs dumb value for collections which can't be empty, since they don't implement
+    /// `StrictDumb`.
+    ///
+    /// Sets and maps requiring more than a single item are filled with consecutive numbers, thus
+    /// must have integer items or keys; otherwise the value is a `compile_error!`, and the dumb
+    /// value has to be provided manually.
+    fn field_dumb(&mut self, r: &TranspileRef) -> Option<String> {
+        let ty = r.as_ty()?;
+        let value = match ty {
+            Ty::List(inner, sizing) if sizing.min == 1 && inner.is_unicode_char() => {
+                s!("Confined::from_checked(\"?\".to_owned())")
+            }
+            Ty::List(inner, sizing) if sizing.min > 1 && inner.is_unicode_char() => {
+                format!("Confined::from_checked(\"?\".repeat({}))", sizing.min)
+            }
+            Ty::List(_, sizing) if sizing.min > 0 => {
+                format!("Confined::from_checked(vec![strict_dumb!(); {}])", sizing.min)
+            }
+            Ty::Set(_, sizing) if sizing.min == 1 => {
+                s!("Confined::from_iter_checked([strict_dumb!()])")
+            }
+            Ty::Map(_, _, sizing) if sizing.min == 1 => {
+                s!("Confined::from_iter_checked([(strict_dumb!(), strict_dumb!())])")
+            }
+            Ty::Set(item, sizing) if sizing.min > 1 => match int_range(item, sizing.min) {
+                Some(range) => format!("Confined::from_iter_checked({range})"),
+                None => return Some(distinct_error(sizing.min)),
+            },
+            Ty::Map(key, _, sizing) if sizing.min > 1 => match int_range(key, sizing.min) {
+                Some(range) => {
+                    format!(
+                        "Confined::from_iter_checked(({range}).map(|key| (key, strict_dumb!())))"
+                    )
+                }
+                None => return Some(distinct_error(sizing.min)),
+            },
+            _ => return None,
+        };
+        self.import("amplify::confinement", "Confined");
+        self.import("strict_encoding", "strict_dumb");
+        Some(value)
+    }
+
+    fn primitive(&mut self, prim: Primitive) -> String {
+        let float = match prim {
+            Primitive::UNIT => return s!("()"),
+            Primitive::BYTE => return s!("u8"),
+            Primitive::F16 => Some("Half"),
+            Primitive::F32 => Some("Single"),
+            Primitive::F64 => Some("Double"),
+            Primitive::F80 => Some("X87DoubleExtended"),
+            Primitive::F128 => Some("Quad"),
+            Primitive::F256 => Some("Oct"),
+            _ => None,
+        };
+        // Floats are strict-encoded only as `amplify` IEEE types, which require `float` feature
+        // of `strict_encoding`.
+        if let Some(float) = float {
+            self.import("amplify::num::apfloat", "ieee");
+            return format!("ieee::{float}");
+        }
+        let info = prim.info();
+        let bits = prim.byte_size() as u32 * 8;
+        match (info.ty, bits) {
+            (NumCls::Unsigned, 8 | 16 | 32 | 64 | 128) => format!("u{bits}"),
+            (NumCls::Signed, 8 | 16 | 32 | 64 | 128) => format!("i{bits}"),
+            (NumCls::NonZero, 8 | 16 | 32 | 64 | 128) => {
+                let ident = format!("NonZeroU{bits}");
+                self.import("std::num", &ident);
+                ident
+            }
+            (NumCls::Unsigned, 24 | 40 | 48 | 56 | 256 | 512 | 1024) => {
+                let ident = format!("u{bits}");
+                self.import("amplify::num", &ident);
+                ident
+            }
+            (NumCls::Signed, 256 | 512 | 1024) => {
+                let ident = format!("i{bits}");
+                self.import("amplify::num", &ident);
+                ident
+            }
+            // Types which have no commonly used Rust counterpart are named after the primitive
+            // and must be brought into the scope by the user.
+            _ => prim.to_string().to_lowercase(),
+        }
+    }
+
+    fn confined(&mut self, kind: &str, params: Option<String>, sizing: Sizing) -> String {
+        let prefix = match (sizing.min, sizing.max) {
+            (0, 0xFF) => Some("Tiny"),
+            (0, 0xFFFF) => Some("Small"),
+            (0, 0xFF_FFFF) => Some("Medium"),
+            (0, 0xFFFF_FFFF) => Some("Large"),
+            _ => None,
+        };
+        let ident = format!("{}{kind}", prefix.unwrap_or("Confined"));
+        self.import("amplify::confinement", &ident);
+        let mut args = params.into_iter().collect::<Vec<_>>();
+        if prefix.is_none() {
+            args.push(sizing.min.to_string());
+            args.push(sizing.max.to_string());
+        }
+        match args.is_empty() {
+            true => ident,
+            false => format!("{ident}<{}>", args.join(", ")),
+        }
+    }
+}
+
+/// Range literal of `len` distinct values of the `ty` type, if it is a Rust integer type.
+fn int_range(ty: &TranspileRef, len: u64) -> Option<String> {
+    let Some(Ty::Primitive(prim)) = ty.as_ty() else {
+        return None;
+    };
+    let bits = prim.byte_size() as u32 * 8;
+    let max = match (prim.info().ty, bits) {
+        _ if *prim == Primitive::BYTE => u8::MAX as u128,
+        (NumCls::Unsigned, 8 | 16 | 32 | 64 | 128) => u128::MAX >> (128 - bits),
+        (NumCls::Signed, 8 | 16 | 32 | 64 | 128) => u128::MAX >> (129 - bits),
+        _ => return None,
+    };
+    (len as u128 - 1 <= max).then(|| format!("0..={}", len - 1))
+}
+
+fn distinct_error(len: u64) -> String {
+    format!(
+        "compile_error!(\"provide a dumb value for a collection of at least {len} unique items\")"
+    )
+}
+
+fn push_attrs(body: &mut String, attrs: &[String]) {
+    if !attrs.is_empty() {
+        writeln!(body, "    #[strict_type({})]", attrs.join(", ")).ok();
+    }
+}
+
+/// Converts strict field name into a Rust field identifier, returning `rename` attribute argument
+/// if the strict encoding derive macros can't restore the original name from the identifier.
+fn field_ident(name: &str) -> (String, Option<String>) {
+    let mut ident = to_snake(name);
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    let rename = (to_lower_camel(&ident) != name).then(|| format!("rename = {name:?}"));
+    (ident, rename)
+}
+
+/// Converts strict variant name into a Rust variant identifier, returning `rename` attribute
+/// argument if the strict encoding derive macros can't restore the original name from the
+/// identifier.
+fn variant_ident(name: &str) -> (String, Option<String>) {
+    let ident = sanitize(&to_upper_camel(name));
+    let rename = (to_lower_camel(&to_snake(&ident)) != name).then(|| format!("rename = {name:?}"));
+    (ident, rename)
+}
+
+fn sanitize(ident: &str) -> String {
+    match RUST_KEYWORDS.contains(&ident) {
+        true => format!("{ident}_"),
+        false => ident.to_owned(),
+    }
+}
+
+fn to_snake(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            snake.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn to_lower_camel(snake: &str) -> String {
+    let mut camel = String::with_capacity(snake.len());
+    for (no, word) in snake.split('_').filter(|w| !w.is_empty()).enumerate() {
+        match no {
+            0 => camel.push_str(word),
+            _ => camel.push_str(&to_upper_camel(word)),
+        }
+    }
+    camel
+}
+
+fn to_upper_camel(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::{LargeVec, TinyBlob, TinyVec};
+
+    use super::*;
+    use crate::ast::UnnamedFields;
+    use crate::stl::{std_stl, strict_types_stl};
+    use crate::value::test_helpers::Nominal;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        #[strict_type(rename = "type")]
+        kind: u16,
+        values: LargeVec<u64>,
+        payload: TinyBlob,
+        nominal: Option<Nominal>,
+        choice: Choice,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+    #[strict_type(lib = "TestLib", tags = order, dumb = Self::Some(strict_dumb!()))]
+    enum Choice {
+        Some(u8),
+        Pair(u8, TinyVec<u16>),
+        Named { value: u32, kind: u8 },
+        Nothing,
+    }
+
+    #[test]
+    fn rust_source() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let lib =
+            LibBuilder::with("TestLib", [std.to_dependency_types(), st.to_dependency_types()])
+                .transpile::<Record>()
+                .compile()
+                .unwrap();
+
+        let src = lib.to_rust_source(&CodegenConfig::default());
+        assert!(src.contains("pub const LIB_NAME_TEST_LIB: &str = \"TestLib\";"));
+        assert!(src.contains("#[strict_type(lib = LIB_NAME_TEST_LIB)]\npub struct Record {"));
+        assert!(src.contains("    pub type_: u16,\n"));
+        assert!(src.contains("    pub values: LargeVec<u64>,\n"));
+        assert!(src.contains("    pub payload: TinyBlob,\n"));
+        assert!(src.contains("    pub nominal: Option<Nominal>,\n"));
+        assert!(src.contains("    pub name: ConfinedString<1, 32>,\n"));
+        assert!(src.contains("    pub ticker: Ident,\n"));
+        assert!(src.contains(
+            "use amplify::confinement::{Confined, ConfinedString, LargeVec, TinyBlob, TinyVec};\n"
+        ));
+        assert!(src.contains(
+            "#[strict_type(dumb = { Confined::from_checked(\"?\".to_owned()) })]\n    pub name"
+        ));
+        assert!(src.contains(
+            "#[strict_type(lib = LIB_NAME_TEST_LIB, tags = custom, dumb = \
+             Self::Some(strict_dumb!()))]\npub enum Choice {\n    #[strict_type(tag = 0)]\n    \
+             Some(u8),\n"
+        ));
+        assert!(src.contains("    Pair(u8, TinyVec<u16>),\n"));
+        assert!(src.contains("    Named { value: u32, kind: u8 },\n"));
+        assert!(src.contains("    #[strict_type(tag = 3)]\n    Nothing,\n"));
+        assert!(src.contains(
+            "#[strict_type(lib = LIB_NAME_TEST_LIB, tags = repr, into_u8, \
+             try_from_u8)]\n#[repr(u8)]\npub enum Precision {\n    #[strict_type(dumb)]\n    \
+             NoDecimals = 0,\n"
+        ));
+
+        let config = CodegenConfig {
+            lib_const: Some(s!("LIB_NAME")),
+            derives: vec![],
+            imports: false,
+        };
+        let src = lib.to_rust_source(&config);
+        assert!(!src.contains("use "));
+        assert!(!src.contains("#[derive(Clone"));
+        assert!(src.contains("#[strict_type(lib = LIB_NAME)]"));
+    }
+
+    #[test]
+    fn float_derives() {
+        let tuple = |refs: Vec<TranspileRef>| Ty::Tuple(UnnamedFields::try_from(refs).unwrap());
+        let mut builder = LibBuilder::with(libname!("TestLib"), None);
+        builder.types.insert(tn!("Level"), tuple(vec![Ty::F32.into()]));
+        builder.types.insert(tn!("Pair"), tuple(vec![tn!("Level").into(), Ty::U8.into()]));
+        builder.types.insert(tn!("Count"), tuple(vec![Ty::U8.into()]));
+        let lib = builder.compile().unwrap();
+
+        let config = CodegenConfig {
+            derives: vec![s!("Clone"), s!("Eq"), s!("PartialEq"), s!("Hash"), s!("Debug")],
+            ..CodegenConfig::default()
+        };
+        let src = lib.to_rust_source(&config);
+        assert!(src.contains("use amplify::num::apfloat::ieee;\n"));
+        assert!(src.contains(
+            "#[derive(Clone, PartialEq, Debug)]\n#[derive(StrictType, StrictDumb, StrictEncode, \
+             StrictDecode)]\n#[strict_type(lib = LIB_NAME_TEST_LIB)]\npub struct Level(pub \
+             ieee::Single);"
+        ));
+        assert!(src.contains(
+            "#[derive(Clone, PartialEq, Debug)]\n#[derive(StrictType, StrictDumb, StrictEncode, \
+             StrictDecode)]\n#[strict_type(lib = LIB_NAME_TEST_LIB)]\npub struct Pair(pub Level, \
+             pub u8);"
+        ));
+        assert!(src.contains(
+            "#[derive(Clone, Eq, PartialEq, Hash, Debug)]\n#[derive(StrictType, StrictDumb, \
+             StrictEncode, StrictDecode)]\n#[strict_type(lib = LIB_NAME_TEST_LIB)]\npub struct \
+             Count(pub u8);"
+        ));
+    }
+}
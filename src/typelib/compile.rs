@@ -24,10 +24,12 @@
 use std::collections::BTreeMap;
 
 use encoding::LibName;
-use strict_encoding::TypeName;
+use strict_encoding::{FieldName, Ident, TypeName, VariantName};
 
-use crate::typelib::{Dependency, ExternTypes, InlineRef, InlineRef1, InlineRef2, LibRef};
-use crate::{SemId, Translate, TranspileError, TranspileRef, Ty};
+use crate::typelib::{
+    Dependency, ExternTypes, InlineRef, InlineRef1, InlineRef2, LibRef, TranspileContext,
+};
+use crate::{SemId, Translate, TranspileError, TranspileRef, Ty, TypeLibId};
 
 pub type TypeIndex = BTreeMap<TypeName, SemId>;
 
@@ -40,6 +42,22 @@ pub enum CompileError {
     /// a different type with name `{0}` is already present
     DuplicateName(TypeName),
 
+    /// instantiations of generic type `{0}` can't be given distinct names automatically; use
+    /// `LibBuilder::transpile_named` to name them.
+    GenericCollision(TypeName),
+
+    /// type `{ty}` has several fields named `{field}`.
+    DuplicateField { ty: String, field: FieldName },
+
+    /// type `{ty}` has several variants named `{variant}`.
+    DuplicateVariant { ty: String, variant: VariantName },
+
+    /// type `{name}` has no variants, thus it has no values which can be encoded.
+    UninhabitedType { name: String },
+
+    /// type `{0}` is a structure without fields, which is denied for the library.
+    EmptyStruct(TypeName),
+
     /// type `{unknown}` referenced inside `{within}` is not known
     UnknownType {
         unknown: TypeName,
@@ -69,14 +87,42 @@ pub enum CompileError {
 
     /// library `{0}` used as a dependency doesn't provide type `{2}` with id {1}.
     DependencyMissesType(LibName, SemId, TypeName),
+
+    /// type `{0}.{1}` is provided by dependencies under different ids {2} and {3}.
+    DependencyTypeMismatch(LibName, TypeName, SemId, SemId),
+
+    /// constant with name `{0}` is already present in the library.
+    DuplicateConstant(Ident),
+
+    /// value of constant `{0}` exceeds the maximal size of library constants.
+    ConstantTooLarge(Ident),
+
+    /// too many constants.
+    TooManyConstants,
+
+    /// library has constants registered with `LibBuilder::transpile_value`, which would be
+    /// discarded; use `LibBuilder::compile_with_constants` to compile them.
+    ConstantsDiscarded,
+
+    /// dependency `{name}` is provided by several different libraries.
+    AmbiguousDependency { name: LibName, ids: Vec<TypeLibId> },
+
+    /// type `{0}` is recursive, and semantic ids of recursive types can't be computed.
+    RecursiveType(TypeName),
+
+    /// while transpiling {context}: {error}
+    InContext {
+        context: TranspileContext,
+        error: Box<CompileError>,
+    },
 }
 
 impl From<TranspileError> for CompileError {
     fn from(err: TranspileError) -> Self {
         match err {
-            TranspileError::UnknownType { unknown, within } => {
-                Self::UnknownType { unknown, within }
-            }
+            TranspileError::UnknownType {
+                unknown, within, ..
+            } => Self::UnknownType { unknown, within },
             TranspileError::UnknownLib(lib) => Self::UnknownLib(lib),
             TranspileError::TooManyDependencies => Self::TooManyDependencies,
             TranspileError::TooManyTypes => Self::TooManyTypes,
@@ -84,6 +130,28 @@ impl From<TranspileError> for CompileError {
             TranspileError::DependencyMissesType(lib, sem_id, type_name) => {
                 Self::DependencyMissesType(lib, sem_id, type_name)
             }
+            TranspileError::DuplicateName(name) => Self::DuplicateName(name),
+            TranspileError::GenericCollision(name) => Self::GenericCollision(name),
+            TranspileError::DuplicateField { ty, field } => Self::DuplicateField { ty, field },
+            TranspileError::DuplicateVariant { ty, variant } => {
+                Self::DuplicateVariant { ty, variant }
+            }
+            TranspileError::UninhabitedType { name } => Self::UninhabitedType { name },
+            TranspileError::EmptyStruct(name) => Self::EmptyStruct(name),
+            TranspileError::DependencyTypeMismatch(lib, name, id1, id2) => {
+                Self::DependencyTypeMismatch(lib, name, id1, id2)
+            }
+            TranspileError::DuplicateConstant(name) => Self::DuplicateConstant(name),
+            TranspileError::ConstantTooLarge(name) => Self::ConstantTooLarge(name),
+            TranspileError::ConstantsDiscarded => Self::ConstantsDiscarded,
+            TranspileError::AmbiguousDependency { name, ids } => {
+                Self::AmbiguousDependency { name, ids }
+            }
+            TranspileError::InContext { context, error } => Self::InContext {
+                context,
+                error: Box::new((*error).into()),
+            },
+            TranspileError::Multiple(errors) => errors.into_first().into(),
         }
     }
 }
@@ -0,0 +1,55 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::confinement::{SmallBlob, TinyOrdMap};
+use strict_encoding::{Ident, STRICT_TYPES_LIB};
+
+use crate::{TypeLib, TypeLibId};
+
+/// Named constant values of a library, kept in their strict-encoded form.
+pub type ConstMap = TinyOrdMap<Ident, SmallBlob>;
+
+/// Constants defined together with a type library using [`crate::LibBuilder::transpile_value`].
+///
+/// The constants are not a part of the [`TypeLib`] and are not committed to by its id, which
+/// keeps the library encoding and the ids of existing libraries unchanged. Instead, they commit
+/// to the id of the library they were compiled with, which should be checked with
+/// [`LibConstants::is_for`] when both are loaded.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictDumb, StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = STRICT_TYPES_LIB)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct LibConstants {
+    pub lib_id: TypeLibId,
+    pub constants: ConstMap,
+}
+
+impl LibConstants {
+    /// Returns strict-encoded value of the constant `name`.
+    pub fn constant(&self, name: &str) -> Option<&[u8]> {
+        self.constants.iter().find(|(n, _)| n.as_str() == name).map(|(_, data)| data.as_slice())
+    }
+
+    /// Checks that the constants were compiled together with the library `lib`.
+    pub fn is_for(&self, lib: &TypeLib) -> bool { self.lib_id.verify(lib) }
+}
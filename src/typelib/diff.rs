@@ -0,0 +1,732 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use strict_encoding::{Sizing, TypeName};
+
+use crate::ast::ItemCase;
+use crate::layout::len_prefix;
+use crate::typelib::SymbolError;
+use crate::{SymbolicLib, TranspileRef, Ty, TypeLib};
+
+/// Hint whether a change in a type library keeps it backward compatible.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Display)]
+#[display(lowercase)]
+pub enum Compatibility {
+    /// The change keeps data produced with the older library version valid.
+    Compatible,
+    /// The change makes data produced with the older library version invalid.
+    Breaking,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+#[display(lowercase)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Reordered,
+    TypeChanged,
+}
+
+/// A single change inside a type definition which is present in both library versions.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TypeChange {
+    /// Field or variant affected by the change; `None` if the whole type definition has changed.
+    pub item: Option<ItemCase>,
+    pub kind: ChangeKind,
+    pub compatibility: Compatibility,
+}
+
+impl TypeChange {
+    fn compatible(item: ItemCase, kind: ChangeKind) -> Self {
+        TypeChange {
+            item: Some(item),
+            kind,
+            compatibility: Compatibility::Compatible,
+        }
+    }
+
+    fn breaking(item: ItemCase, kind: ChangeKind) -> Self {
+        TypeChange {
+            item: Some(item),
+            kind,
+            compatibility: Compatibility::Breaking,
+        }
+    }
+}
+
+/// Structural changelog between two versions of a type library, produced by [`TypeLib::diff`].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct LibDiff {
+    pub added: BTreeSet<TypeName>,
+    pub removed: BTreeSet<TypeName>,
+    pub changed: BTreeMap<TypeName, Vec<TypeChange>>,
+}
+
+impl LibDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Detects whether the newer library version is backward compatible with the older one.
+    pub fn compatibility(&self) -> Compatibility {
+        if !self.removed.is_empty() {
+            return Compatibility::Breaking;
+        }
+        self.changed
+            .values()
+            .flatten()
+            .map(|change| change.compatibility)
+            .max()
+            .unwrap_or(Compatibility::Compatible)
+    }
+}
+
+impl TypeLib {
+    /// Computes structural changes made to the library since its `older` version.
+    ///
+    /// Types are matched by their names. Fields and variants appended to the end of a type are
+    /// reported as compatible changes; their removal, reordering and modification of their types
+    /// are breaking.
+    ///
+    /// Errors if any of the libraries is internally inconsistent and references types it doesn't
+    /// define.
+    pub fn diff(&self, older: &TypeLib) -> Result<LibDiff, SymbolError> {
+        let new = self.to_symbolic()?;
+        let old = older.to_symbolic()?;
+
+        let mut diff = LibDiff::default();
+        for (name, old_ty) in old.types() {
+            let Some(new_ty) = new.types().get(name) else {
+                diff.removed.insert(name.clone());
+                continue;
+            };
+            let changes = ty_changes(old_ty, new_ty);
+            if !changes.is_empty() {
+                diff.changed.insert(name.clone(), changes);
+            }
+        }
+        diff.added =
+            new.types().keys().filter(|name| !old.types().contains_key(*name)).cloned().collect();
+        Ok(diff)
+    }
+}
+
+/// Verdict whether data encoded under one version of a type library can be decoded under
+/// another, produced by [`TypeLib::is_compatible_with`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum WireCompatibility {
+    /// Any data encoded by the writer are decoded by the reader.
+    Compatible,
+    /// The data are decoded by the reader, which must ignore the trailing data: the writer
+    /// appends fields to the listed types at the tail of the encoding.
+    CompatibleWithLoss(Vec<TypeName>),
+    /// Some data encoded by the writer can't be decoded by the reader.
+    Incompatible(Incompatibility),
+}
+
+/// Reason why data encoded by a writer can't be decoded by a reader.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum Incompatibility {
+    /// type `{0}` is not defined by both libraries.
+    RootAbsent(TypeName),
+
+    /// `{path}` is encoded as `{writer}`, which can't be decoded as `{reader}`.
+    TypeMismatch {
+        path: String,
+        writer: String,
+        reader: String,
+    },
+
+    /// `{path}` is encoded with {writer} fields, while the reader expects {reader} fields.
+    MissingFields {
+        path: String,
+        writer: usize,
+        reader: usize,
+    },
+
+    /// `{path}` may be encoded with variant tag {tag}, which is unknown to the reader.
+    UnknownVariant { path: String, tag: u8 },
+
+    /// `{path}` is encoded with {writer} items, which can't be decoded as {reader} items.
+    Sizing {
+        path: String,
+        writer: Sizing,
+        reader: Sizing,
+    },
+
+    /// fields appended to `{0}` are followed by other data, thus the reader can't ignore them.
+    NotAtTail(String),
+}
+
+impl TypeLib {
+    /// Checks whether data of type `root` encoded under this library can be decoded under the
+    /// `reader` library.
+    ///
+    /// The check follows the strict encoding rules: fields must keep their positions and types,
+    /// while the reader may know more enum and union variants than the writer. Fields appended by
+    /// the writer can be ignored only if they are at the very tail of the encoded data, which is
+    /// reported as [`WireCompatibility::CompatibleWithLoss`]. Since names are not encoded, they
+    /// are not compared; use [`TypeLib::diff`] for the analysis of semantic changes.
+    ///
+    /// Errors if any of the libraries is internally inconsistent and references types it doesn't
+    /// define.
+    pub fn is_compatible_with(
+        &self,
+        reader: &TypeLib,
+        root: &TypeName,
+    ) -> Result<WireCompatibility, SymbolError> {
+        let writer = self.to_symbolic()?;
+        let reader = reader.to_symbolic()?;
+        if !writer.types().contains_key(root) || !reader.types().contains_key(root) {
+            return Ok(WireCompatibility::Incompatible(Incompatibility::RootAbsent(root.clone())));
+        }
+        let mut check = WireCheck {
+            writer: &writer,
+            reader: &reader,
+            visited: empty!(),
+            lossy: empty!(),
+            names: vec![],
+            path: vec![root.to_string()],
+        };
+        let root = TranspileRef::Named(root.clone());
+        Ok(match check.check_ref(&root, &root, true) {
+            Err(reason) => WireCompatibility::Incompatible(reason),
+            Ok(()) if check.lossy.is_empty() => WireCompatibility::Compatible,
+            Ok(()) => WireCompatibility::CompatibleWithLoss(check.lossy.into_iter().collect()),
+        })
+    }
+}
+
+struct WireCheck<'lib> {
+    writer: &'lib SymbolicLib,
+    reader: &'lib SymbolicLib,
+    /// Pairs of named types which are already checked or being checked, with the tail flag.
+    visited: BTreeSet<(TypeName, TypeName, bool)>,
+    lossy: BTreeSet<TypeName>,
+    /// Named writer types which are being checked.
+    names: Vec<TypeName>,
+    path: Vec<String>,
+}
+
+impl WireCheck<'_> {
+    fn path(&self) -> String { self.path.join(".") }
+
+    fn mismatch(&self, writer: impl ToString, reader: impl ToString) -> Incompatibility {
+        Incompatibility::TypeMismatch {
+            path: self.path(),
+            writer: writer.to_string(),
+            reader: reader.to_string(),
+        }
+    }
+
+    fn check_at(
+        &mut self,
+        segment: impl ToString,
+        writer: &TranspileRef,
+        reader: &TranspileRef,
+        tail: bool,
+    ) -> Result<(), Incompatibility> {
+        self.path.push(segment.to_string());
+        self.check_ref(writer, reader, tail)?;
+        self.path.pop();
+        Ok(())
+    }
+
+    fn check_ref(
+        &mut self,
+        writer: &TranspileRef,
+        reader: &TranspileRef,
+        tail: bool,
+    ) -> Result<(), Incompatibility> {
+        match (writer, reader) {
+            (TranspileRef::Extern(w), TranspileRef::Extern(r)) if w.sem_id == r.sem_id => Ok(()),
+            (TranspileRef::Extern(_), _) | (_, TranspileRef::Extern(_)) => {
+                Err(self.mismatch(writer, reader))
+            }
+            (w, r) => {
+                if let (TranspileRef::Named(w), TranspileRef::Named(r)) = (w, r) {
+                    // recursive types are compatible if their expansion so far is compatible
+                    if !self.visited.insert((w.clone(), r.clone(), tail)) {
+                        return Ok(());
+                    }
+                }
+                let name = match w {
+                    TranspileRef::Named(name) => Some(name.clone()),
+                    _ => None,
+                };
+                let writer = self.writer;
+                let reader = self.reader;
+                let (Some(w_ty), Some(r_ty)) = (resolve(writer, w), resolve(reader, r)) else {
+                    return Err(self.mismatch(w, r));
+                };
+                if let Some(name) = &name {
+                    self.names.push(name.clone());
+                }
+                let res = self.check_ty(w_ty, r_ty, tail);
+                if name.is_some() {
+                    self.names.pop();
+                }
+                res
+            }
+        }
+    }
+
+    fn check_fields<'a>(
+        &mut self,
+        writer: impl ExactSizeIterator<Item = (String, &'a TranspileRef)>,
+        reader: impl ExactSizeIterator<Item = &'a TranspileRef>,
+        tail: bool,
+    ) -> Result<(), Incompatibility> {
+        let (w_len, r_len) = (writer.len(), reader.len());
+        if w_len < r_len {
+            return Err(Incompatibility::MissingFields {
+                path: self.path(),
+                writer: w_len,
+                reader: r_len,
+            });
+        }
+        for (pos, ((segment, w), r)) in writer.zip(reader).enumerate() {
+            self.check_at(segment, w, r, tail && pos + 1 == w_len)?;
+        }
+        if w_len > r_len {
+            if !tail {
+                return Err(Incompatibility::NotAtTail(self.path()));
+            }
+            if let Some(name) = self.names.last() {
+                self.lossy.insert(name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn check_sizing(&self, writer: Sizing, reader: Sizing) -> Result<(), Incompatibility> {
+        if len_prefix(writer) != len_prefix(reader)
+            || writer.min < reader.min
+            || writer.max > reader.max
+        {
+            return Err(Incompatibility::Sizing {
+                path: self.path(),
+                writer,
+                reader,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_ty(
+        &mut self,
+        writer: &Ty<TranspileRef>,
+        reader: &Ty<TranspileRef>,
+        tail: bool,
+    ) -> Result<(), Incompatibility> {
+        match (writer, reader) {
+            (Ty::Primitive(w), Ty::Primitive(r)) if w == r => Ok(()),
+            (Ty::UnicodeChar, Ty::UnicodeChar) => Ok(()),
+            (Ty::Enum(w), Ty::Enum(r)) => match w.iter().find(|variant| !r.has_tag(variant.tag)) {
+                Some(variant) => Err(Incompatibility::UnknownVariant {
+                    path: self.path(),
+                    tag: variant.tag,
+                }),
+                None => Ok(()),
+            },
+            (Ty::Union(w), Ty::Union(r)) => {
+                for (variant, w_ty) in w.iter() {
+                    let Some(r_ty) = r.ty_by_tag(variant.tag) else {
+                        return Err(Incompatibility::UnknownVariant {
+                            path: self.path(),
+                            tag: variant.tag,
+                        });
+                    };
+                    self.check_at(&variant.name, w_ty, r_ty, tail)?;
+                }
+                Ok(())
+            }
+            (Ty::Tuple(w), Ty::Tuple(r)) => self.check_fields(
+                w.iter().enumerate().map(|(pos, ty)| (pos.to_string(), ty)),
+                r.iter(),
+                tail,
+            ),
+            (Ty::Struct(w), Ty::Struct(r)) => self.check_fields(
+                w.iter().map(|field| (field.name.to_string(), &field.ty)),
+                r.iter().map(|field| &field.ty),
+                tail,
+            ),
+            (Ty::Array(w, w_len), Ty::Array(r, r_len)) if w_len == r_len => {
+                self.check_at("item", w, r, false)
+            }
+            (Ty::List(w, w_sizing), Ty::List(r, r_sizing))
+            | (Ty::Set(w, w_sizing), Ty::Set(r, r_sizing)) => {
+                self.check_sizing(*w_sizing, *r_sizing)?;
+                self.check_at("item", w, r, false)
+            }
+            (Ty::Map(w_key, w, w_sizing), Ty::Map(r_key, r, r_sizing)) => {
+                self.check_sizing(*w_sizing, *r_sizing)?;
+                self.check_at("key", w_key, r_key, false)?;
+                self.check_at("value", w, r, false)
+            }
+            (w, r) => Err(self.mismatch(w, r)),
+        }
+    }
+}
+
+fn resolve<'a>(lib: &'a SymbolicLib, r: &'a TranspileRef) -> Option<&'a Ty<TranspileRef>> {
+    match r {
+        TranspileRef::Embedded(ty) => Some(ty),
+        TranspileRef::Named(name) => lib.types().get(name),
+        TranspileRef::Extern(_) => None,
+    }
+}
+
+fn ty_changes(old: &Ty<TranspileRef>, new: &Ty<TranspileRef>) -> Vec<TypeChange> {
+    let mut changes = vec![];
+    match (old, new) {
+        (Ty::Struct(old), Ty::Struct(new)) => {
+            for (pos, field) in old.iter().enumerate() {
+                let item = ItemCase::NamedField(pos as u8, field.name.clone());
+                match new.iter().position(|f| f.name == field.name) {
+                    None => changes.push(TypeChange::breaking(item, ChangeKind::Removed)),
+                    Some(new_pos) => {
+                        let new_field = &new[new_pos];
+                        if new_pos != pos {
+                            let item = ItemCase::NamedField(new_pos as u8, field.name.clone());
+                            changes.push(TypeChange::breaking(item, ChangeKind::Reordered));
+                        }
+                        if !same_ref(&field.ty, &new_field.ty) {
+                            let item = ItemCase::NamedField(new_pos as u8, field.name.clone());
+                            changes.push(TypeChange::breaking(item, ChangeKind::TypeChanged));
+                        }
+                    }
+                }
+            }
+            for (pos, field) in new.iter().enumerate() {
+                if old.iter().any(|f| f.name == field.name) {
+                    continue;
+                }
+                let item = ItemCase::NamedField(pos as u8, field.name.clone());
+                if pos >= old.len() {
+                    changes.push(TypeChange::compatible(item, ChangeKind::Added));
+                } else {
+                    changes.push(TypeChange::breaking(item, ChangeKind::Added));
+                }
+            }
+        }
+        (Ty::Tuple(old), Ty::Tuple(new)) => {
+            for (pos, ty) in old.iter().enumerate() {
+                let item = ItemCase::UnnamedField(pos as u8);
+                match new.get(pos) {
+                    None => changes.push(TypeChange::breaking(item, ChangeKind::Removed)),
+                    Some(new_ty) if !same_ref(ty, new_ty) => {
+                        changes.push(TypeChange::breaking(item, ChangeKind::TypeChanged))
+                    }
+                    Some(_) => {}
+                }
+            }
+            for pos in old.len()..new.len() {
+                let item = ItemCase::UnnamedField(pos as u8);
+                changes.push(TypeChange::compatible(item, ChangeKind::Added));
+            }
+        }
+        (Ty::Union(old), Ty::Union(new)) => {
+            let max_tag = old.keys().map(|v| v.tag).max().unwrap_or_default();
+            for (variant, ty) in old.iter() {
+                let item = ItemCase::UnionVariant(variant.tag, variant.name.clone());
+                match new.by_name(&variant.name) {
+                    None => changes.push(TypeChange::breaking(item, ChangeKind::Removed)),
+                    Some((new_variant, new_ty)) => {
+                        let item = ItemCase::UnionVariant(new_variant.tag, variant.name.clone());
+                        if new_variant.tag != variant.tag {
+                            changes.push(TypeChange::breaking(item.clone(), ChangeKind::Reordered));
+                        }
+                        if !same_ref(ty, new_ty) {
+                            changes.push(TypeChange::breaking(item, ChangeKind::TypeChanged));
+                        }
+                    }
+                }
+            }
+            for variant in new.keys() {
+                if old.by_name(&variant.name).is_some() {
+                    continue;
+                }
+                let item = ItemCase::UnionVariant(variant.tag, variant.name.clone());
+                if variant.tag > max_tag {
+                    changes.push(TypeChange::compatible(item, ChangeKind::Added));
+                } else {
+                    changes.push(TypeChange::breaking(item, ChangeKind::Added));
+                }
+            }
+        }
+        (Ty::Enum(old), Ty::Enum(new)) => {
+            let max_tag = old.iter().map(|v| v.tag).max().unwrap_or_default();
+            for variant in old.iter() {
+                let item = ItemCase::UnionVariant(variant.tag, variant.name.clone());
+                match new.by_name(&variant.name) {
+                    None => changes.push(TypeChange::breaking(item, ChangeKind::Removed)),
+                    Some(new_variant) if new_variant.tag != variant.tag => {
+                        let item = ItemCase::UnionVariant(new_variant.tag, variant.name.clone());
+                        changes.push(TypeChange::breaking(item, ChangeKind::Reordered))
+                    }
+                    Some(_) => {}
+                }
+            }
+            for variant in new.iter() {
+                if old.has_name(&variant.name) {
+                    continue;
+                }
+                let item = ItemCase::UnionVariant(variant.tag, variant.name.clone());
+                if variant.tag > max_tag {
+                    changes.push(TypeChange::compatible(item, ChangeKind::Added));
+                } else {
+                    changes.push(TypeChange::breaking(item, ChangeKind::Added));
+                }
+            }
+        }
+        (old, new) if !same_ty(old, new) => changes.push(TypeChange {
+            item: None,
+            kind: ChangeKind::TypeChanged,
+            compatibility: Compatibility::Breaking,
+        }),
+        _ => {}
+    }
+    changes
+}
+
+/// Compares types by their structure, matching named types by their names instead of semantic
+/// ids, such that changes inside a named type do not propagate to the types using it.
+fn same_ty(a: &Ty<TranspileRef>, b: &Ty<TranspileRef>) -> bool {
+    match (a, b) {
+        (Ty::Primitive(a), Ty::Primitive(b)) => a == b,
+        (Ty::UnicodeChar, Ty::UnicodeChar) => true,
+        (Ty::Enum(a), Ty::Enum(b)) => a == b,
+        (Ty::Union(a), Ty::Union(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|((va, a), (vb, b))| va == vb && same_ref(a, b))
+        }
+        (Ty::Tuple(a), Ty::Tuple(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_ref(a, b))
+        }
+        (Ty::Struct(a), Ty::Struct(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|(a, b)| a.name == b.name && same_ref(&a.ty, &b.ty))
+        }
+        (Ty::Array(a, la), Ty::Array(b, lb)) => la == lb && same_ref(a, b),
+        (Ty::List(a, sa), Ty::List(b, sb)) | (Ty::Set(a, sa), Ty::Set(b, sb)) => {
+            sa == sb && same_ref(a, b)
+        }
+        (Ty::Map(ka, a, sa), Ty::Map(kb, b, sb)) => sa == sb && same_ref(ka, kb) && same_ref(a, b),
+        _ => false,
+    }
+}
+
+fn same_ref(a: &TranspileRef, b: &TranspileRef) -> bool {
+    match (a, b) {
+        (TranspileRef::Embedded(a), TranspileRef::Embedded(b)) => same_ty(a, b),
+        (TranspileRef::Named(a), TranspileRef::Named(b)) => a == b,
+        (TranspileRef::Extern(a), TranspileRef::Extern(b)) => {
+            a.lib_name == b.lib_name && a.ty_name == b.ty_name
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use super::*;
+    use crate::LibBuilder;
+
+    mod v1 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+            Second = 1,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+            pub value: u8,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Legacy(u8);
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Outer {
+            pub record: Record,
+            pub tail: u8,
+        }
+    }
+
+    mod v1_1 {
+        pub use super::v1::Kind;
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+            pub value: u8,
+            pub extra: u16,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Outer {
+            pub record: Record,
+            pub tail: u8,
+        }
+    }
+
+    mod v2 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+            Second = 1,
+            Third = 2,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+            pub value: u8,
+            pub extra: u16,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Reordered {
+            pub value: u8,
+            pub kind: Kind,
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let old = LibBuilder::with(libname!("Test"), None)
+            .transpile::<v1::Record>()
+            .transpile::<v1::Legacy>()
+            .compile()
+            .unwrap();
+        let new =
+            LibBuilder::with(libname!("Test"), None).transpile::<v2::Record>().compile().unwrap();
+
+        assert!(new.diff(&new).unwrap().is_empty());
+
+        let diff = new.diff(&old).unwrap();
+        assert_eq!(diff.removed, bset![tn!("Legacy")]);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.changed[&tn!("Kind")], vec![TypeChange::compatible(
+            ItemCase::UnionVariant(2, vname!("third")),
+            ChangeKind::Added
+        )]);
+        // the change of `Kind` doesn't affect `Record` field referencing it by name
+        assert_eq!(diff.changed[&tn!("Record")], vec![TypeChange::compatible(
+            ItemCase::NamedField(2, fname!("extra")),
+            ChangeKind::Added
+        )]);
+        assert_eq!(diff.compatibility(), Compatibility::Breaking);
+        assert_eq!(old.diff(&new).unwrap().compatibility(), Compatibility::Breaking);
+    }
+
+    #[test]
+    fn diff_reordered() {
+        let old = LibBuilder::with(libname!("Test"), None)
+            .transpile_named::<v2::Record>(tn!("Record"))
+            .compile()
+            .unwrap();
+        let new = LibBuilder::with(libname!("Test"), None)
+            .transpile_named::<v2::Reordered>(tn!("Record"))
+            .compile()
+            .unwrap();
+
+        let diff = new.diff(&old).unwrap();
+        assert!(diff.changed[&tn!("Record")]
+            .iter()
+            .any(|change| change.kind == ChangeKind::Reordered));
+        assert_eq!(diff.compatibility(), Compatibility::Breaking);
+    }
+
+    #[test]
+    fn wire_compatibility() {
+        let v1 =
+            LibBuilder::with(libname!("Test"), None).transpile::<v1::Outer>().compile().unwrap();
+        let v1_1 =
+            LibBuilder::with(libname!("Test"), None).transpile::<v1_1::Outer>().compile().unwrap();
+        let v2 =
+            LibBuilder::with(libname!("Test"), None).transpile::<v2::Record>().compile().unwrap();
+
+        let record = tn!("Record");
+        assert_eq!(v1.is_compatible_with(&v1, &record).unwrap(), WireCompatibility::Compatible);
+        // the reader knows all the variants of the older `Kind`, but expects more fields
+        assert_eq!(
+            v1.is_compatible_with(&v2, &record).unwrap(),
+            WireCompatibility::Incompatible(Incompatibility::MissingFields {
+                path: s!("Record"),
+                writer: 2,
+                reader: 3
+            })
+        );
+        assert_eq!(
+            v2.is_compatible_with(&v1, &record).unwrap(),
+            WireCompatibility::Incompatible(Incompatibility::UnknownVariant {
+                path: s!("Record.kind"),
+                tag: 2
+            })
+        );
+        assert_eq!(
+            v1_1.is_compatible_with(&v1, &record).unwrap(),
+            WireCompatibility::CompatibleWithLoss(vec![record.clone()])
+        );
+        let outer = tn!("Outer");
+        assert_eq!(
+            v1_1.is_compatible_with(&v1, &outer).unwrap(),
+            WireCompatibility::Incompatible(Incompatibility::NotAtTail(s!("Outer.record")))
+        );
+        assert_eq!(
+            v2.is_compatible_with(&v1, &outer).unwrap(),
+            WireCompatibility::Incompatible(Incompatibility::RootAbsent(outer))
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Rendering of the type relationships inside a type library as a Graphviz digraph.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::ast::TypeRef;
+use crate::typelib::SymbolError;
+use crate::{TranspileRef, Ty, TypeLib};
+
+impl TypeLib {
+    /// Renders relations between the library types as a Graphviz digraph in DOT format.
+    ///
+    /// Nodes are the library types and edges point from a type to the types it references from
+    /// its fields, variants and collection items; the edges are labeled with the field or variant
+    /// names, and the edges going through collections also with their confinement. Types from
+    /// dependencies are drawn with dashed nodes, while primitive types are collapsed into a
+    /// single cluster.
+    ///
+    /// Errors if the library is internally inconsistent and references types it doesn't define.
+    pub fn to_dot(&self) -> Result<String, SymbolError> {
+        let lib = self.to_symbolic()?;
+        let mut graph = DotGraph::default();
+        for (name, ty) in lib.types() {
+            graph.nodes.insert(name.to_string());
+            graph.add_ty(name.as_ref(), ty, "");
+        }
+
+        let mut out = format!("digraph \"{}\" {{\n    node [shape=box];\n", self.name);
+        for node in &graph.nodes {
+            writeln!(out, "    \"{node}\";").ok();
+        }
+        for node in &graph.externs {
+            writeln!(out, "    \"{node}\" [style=dashed];").ok();
+        }
+        if !graph.primitives.is_empty() {
+            out.push_str("    subgraph cluster_primitives {\n");
+            out.push_str("        label=\"primitives\";\n        style=filled;\n");
+            out.push_str("        color=lightgrey;\n        node [shape=ellipse, style=filled, ");
+            out.push_str("fillcolor=white];\n");
+            for node in &graph.primitives {
+                writeln!(out, "        \"{node}\";").ok();
+            }
+            out.push_str("    }\n");
+        }
+        for (from, to, label) in &graph.edges {
+            writeln!(out, "    \"{from}\" -> \"{to}\" [label=\"{}\"];", escape(label)).ok();
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+}
+
+#[derive(Default)]
+struct DotGraph {
+    nodes: BTreeSet<String>,
+    externs: BTreeSet<String>,
+    primitives: BTreeSet<String>,
+    edges: BTreeSet<(String, String, String)>,
+}
+
+impl DotGraph {
+    fn add_ty(&mut self, from: &str, ty: &Ty<TranspileRef>, label: &str) {
+        let join = |name: &dyn ToString| match label {
+            "" => name.to_string(),
+            label => format!("{label}.{}", name.to_string()),
+        };
+        match ty {
+            Ty::Struct(fields) => {
+                for field in fields {
+                    self.add_ref(from, &field.ty, &join(&field.name));
+                }
+            }
+            Ty::Tuple(fields) => {
+                for (pos, ty) in fields.iter().enumerate() {
+                    self.add_ref(from, ty, &join(&pos));
+                }
+            }
+            Ty::Union(variants) => {
+                for (variant, ty) in variants {
+                    if ty.as_ty() == Some(&Ty::UNIT) {
+                        continue;
+                    }
+                    self.add_ref(from, ty, &join(&variant.name));
+                }
+            }
+            Ty::Array(ty, len) => self.add_ref(from, ty, &format!("{label}[{len}]")),
+            Ty::List(ty, sizing) | Ty::Set(ty, sizing) => {
+                self.add_ref(from, ty, &format!("{label}[{}..{}]", sizing.min, sizing.max))
+            }
+            Ty::Map(key, ty, sizing) => {
+                let sizing = format!("{}..{}", sizing.min, sizing.max);
+                self.add_ref(from, key, &format!("{label}{{key}}[{sizing}]"));
+                self.add_ref(from, ty, &format!("{label}{{value}}[{sizing}]"));
+            }
+            Ty::Primitive(_) | Ty::UnicodeChar | Ty::Enum(_) => {}
+        }
+    }
+
+    fn add_ref(&mut self, from: &str, ty: &TranspileRef, label: &str) {
+        let to = match ty {
+            TranspileRef::Named(name) => name.to_string(),
+            TranspileRef::Extern(ext) => {
+                let name = format!("{}.{}", ext.lib_name, ext.ty_name);
+                self.externs.insert(name.clone());
+                name
+            }
+            TranspileRef::Embedded(ty) => match ty.as_ref() {
+                ty @ (Ty::Primitive(_) | Ty::UnicodeChar) => {
+                    let name = ty.to_string();
+                    self.primitives.insert(name.clone());
+                    name
+                }
+                ty => return self.add_ty(from, ty, label),
+            },
+        };
+        self.edges.insert((from.to_owned(), to, label.to_owned()));
+    }
+}
+
+fn escape(s: &str) -> String { s.replace('\\', "\\\\").replace('"', "\\\"") }
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{Confined, SmallVec};
+
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    pub enum Kind {
+        #[default]
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Item {
+        pub kind: Kind,
+        pub value: u16,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub items: SmallVec<Item>,
+        pub tags: Confined<Vec<u8>, 0, 8>,
+        pub hash: [u8; 32],
+    }
+
+    #[test]
+    fn to_dot() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let dot = lib.to_dot().unwrap();
+        assert!(dot.starts_with("digraph \"Test\" {\n"));
+        assert!(dot.ends_with("}\n"));
+        for node in ["\"Kind\";", "\"Item\";", "\"Record\";"] {
+            assert!(dot.contains(node), "missing node {node} in\n{dot}");
+        }
+        for edge in [
+            "\"Item\" -> \"Kind\" [label=\"kind\"];",
+            "\"Item\" -> \"U16\" [label=\"value\"];",
+            "\"Record\" -> \"Item\" [label=\"items[0..65535]\"];",
+            "\"Record\" -> \"Byte\" [label=\"tags[0..8]\"];",
+            "\"Record\" -> \"Byte\" [label=\"hash[32]\"];",
+        ] {
+            assert!(dot.contains(edge), "missing edge {edge} in\n{dot}");
+        }
+        assert!(dot.contains("subgraph cluster_primitives"));
+        assert_eq!(dot, lib.to_dot().unwrap());
+    }
+}
@@ -0,0 +1,457 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Export of type library types in form of a FlatBuffers schema.
+//!
+//! FlatBuffers and strict encoding have different wire formats, thus the schema describes the
+//! same data, but not the same bytes. The type classes are mapped as follows:
+//! - numbers become FlatBuffers scalars of the same or larger size; numbers without a scalar of
+//!   sufficient size, like `U128` or `F16`, become fixed arrays of their little-endian bytes,
+//!   unicode characters become `uint` code points and the unit type becomes `ubyte`;
+//! - enums become `ubyte` enums with the same tags;
+//! - unions become FlatBuffers unions of tables named after the union and the variant, with a
+//!   single `value` field holding the variant data; unit variants are empty tables;
+//! - structures and tuples which contain only fixed-size data become FlatBuffers structs, the rest
+//!   become tables; tuple fields are named by their positions, like `_0`;
+//! - newtypes (tuples with a single field) are replaced with the wrapped type, optional values
+//!   become table fields without a default value;
+//! - ASCII and unicode strings become `string`, byte strings and other lists and sets become
+//!   vectors, fixed arrays become FlatBuffers arrays inside structs and vectors inside tables;
+//! - maps have no direct equivalent and become vectors of key-value entries.
+//!
+//! FlatBuffers doesn't allow vectors of vectors or optional values, so such items are wrapped
+//! into tables with a single `value` field. Most of the strict type bounds, like the collection
+//! size limits, have no FlatBuffers equivalent and are lost.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use strict_encoding::{LibName, NumCls, Primitive, TypeName};
+
+use crate::typelib::SymbolError;
+use crate::{SymbolicLib, TranspileRef, Ty, TypeLib, TypeRef};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum FlatBuffersError {
+    /// type `{0}` is not defined in the library.
+    UnknownType(TypeName),
+
+    /// type `{0}` doesn't map to a FlatBuffers table and can't be a root type.
+    RootNotTable(TypeName),
+
+    #[display(inner)]
+    #[from]
+    Symbols(SymbolError),
+}
+
+impl TypeLib {
+    /// Exports type `root` and all library types it depends on as a FlatBuffers schema, using the
+    /// library name as the schema namespace. See the module documentation for the details of the
+    /// type mapping.
+    ///
+    /// Types from library dependencies are referenced by their names qualified with the library
+    /// name and are expected to be defined by the included `<LibName>.fbs` schemas; since the
+    /// newtypes and the collections don't produce FlatBuffers definitions, such dependency types
+    /// must be defined manually.
+    pub fn to_flatbuffers(&self, root: &TypeName) -> Result<String, FlatBuffersError> {
+        let lib = self.to_symbolic()?;
+        if !lib.types().contains_key(root) {
+            return Err(FlatBuffersError::UnknownType(root.clone()));
+        }
+        let mut ctx = FbsContext {
+            lib: &lib,
+            named: BTreeMap::new(),
+            defs: BTreeMap::new(),
+            includes: BTreeSet::new(),
+        };
+        let expr = ctx.named_expr(root);
+        if expr.kind != Kind::Table || expr.text != root.as_str() {
+            return Err(FlatBuffersError::RootNotTable(root.clone()));
+        }
+
+        let mut fbs = format!("// FlatBuffers schema for type {}.{root}\n\n", self.name);
+        for lib_name in &ctx.includes {
+            fbs.push_str(&format!("include \"{lib_name}.fbs\";\n"));
+        }
+        if !ctx.includes.is_empty() {
+            fbs.push('\n');
+        }
+        fbs.push_str(&format!("namespace {};\n\n", self.name));
+        for def in ctx.defs.values() {
+            fbs.push_str(def);
+            fbs.push('\n');
+        }
+        fbs.push_str(&format!("root_type {root};\n"));
+        Ok(fbs)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Kind {
+    /// Scalar or enum.
+    Scalar,
+    Struct,
+    /// Fixed-size array of fixed-size items.
+    Array(u16),
+    Table,
+    Union,
+    String,
+    Vector,
+}
+
+/// Representation of a strict type in the FlatBuffers schema.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct Expr {
+    /// Type name, scalar type or the item type for the arrays.
+    text: String,
+    kind: Kind,
+    optional: bool,
+}
+
+impl Expr {
+    fn new(text: impl ToString, kind: Kind) -> Self {
+        Expr {
+            text: text.to_string(),
+            kind,
+            optional: false,
+        }
+    }
+
+    /// Whether the type can be used inside FlatBuffers structs.
+    fn is_fixed(&self) -> bool {
+        !self.optional && matches!(self.kind, Kind::Scalar | Kind::Struct | Kind::Array(_))
+    }
+
+    fn struct_field(&self) -> String {
+        match self.kind {
+            Kind::Array(len) => format!("[{}:{len}]", self.text),
+            _ => self.text.clone(),
+        }
+    }
+
+    fn table_field(&self) -> String {
+        match self.kind {
+            Kind::Array(_) => format!("[{}]", self.text),
+            Kind::Scalar if self.optional => format!("{} = null", self.text),
+            _ => self.text.clone(),
+        }
+    }
+}
+
+struct FbsContext<'lib> {
+    lib: &'lib SymbolicLib,
+    /// Representations of the already processed library types.
+    named: BTreeMap<TypeName, Expr>,
+    defs: BTreeMap<String, String>,
+    includes: BTreeSet<LibName>,
+}
+
+impl FbsContext<'_> {
+    fn named_expr(&mut self, name: &TypeName) -> Expr {
+        if let Some(expr) = self.named.get(name) {
+            return expr.clone();
+        }
+        // recursive references always go through vectors or optional fields, so the type is
+        // a table unless it is a newtype or a collection
+        self.named.insert(name.clone(), Expr::new(name, Kind::Table));
+        let ty = self.lib.types().get(name).expect("inconsistent type library");
+        let expr = self.ty_expr(ty, name.to_string());
+        self.named.insert(name.clone(), expr.clone());
+        expr
+    }
+
+    fn ref_expr(&mut self, r: &TranspileRef, hint: String) -> Expr {
+        match r {
+            TranspileRef::Embedded(ty) => self.ty_expr(ty, hint),
+            TranspileRef::Named(name) => self.named_expr(name),
+            TranspileRef::Extern(ext) => {
+                self.includes.insert(ext.lib_name.clone());
+                Expr::new(format!("{}.{}", ext.lib_name, ext.ty_name), Kind::Table)
+            }
+        }
+    }
+
+    fn is_char(&self, r: &TranspileRef) -> bool {
+        match r {
+            TranspileRef::Embedded(ty) => ty.is_char_enum(),
+            TranspileRef::Named(name) => {
+                self.lib.types().get(name).map(Ty::is_char_enum).unwrap_or_default()
+            }
+            TranspileRef::Extern(_) => false,
+        }
+    }
+
+    fn is_rstring(&self, fields: &[TranspileRef]) -> bool {
+        match fields[1].as_ty() {
+            Some(Ty::List(rest, _)) => self.is_char(&fields[0]) && self.is_char(rest),
+            _ => false,
+        }
+    }
+
+    fn define(&mut self, name: String, def: String) { self.defs.insert(name, def); }
+
+    /// Defines a struct or a table `name` with the `fields`.
+    fn define_fields(&mut self, name: String, fields: Vec<(String, Expr)>) -> Expr {
+        let kind = if fields.iter().all(|(_, expr)| expr.is_fixed()) && !fields.is_empty() {
+            Kind::Struct
+        } else {
+            Kind::Table
+        };
+        let mut def =
+            format!("{} {name} {{\n", if kind == Kind::Struct { "struct" } else { "table" });
+        for (field, expr) in fields {
+            let ty = if kind == Kind::Struct { expr.struct_field() } else { expr.table_field() };
+            def.push_str(&format!("  {field}: {ty};\n"));
+        }
+        def.push_str("}\n");
+        self.define(name.clone(), def);
+        Expr::new(name, kind)
+    }
+
+    /// Returns representation of a vector with `item`, wrapping the items which can't be vector
+    /// items into a table.
+    fn vector(&mut self, item: Expr, hint: String) -> Expr {
+        let item = match item.kind {
+            Kind::Scalar | Kind::Struct | Kind::Table | Kind::String if !item.optional => item,
+            _ => self.define_fields(format!("{hint}Item"), vec![(s!("value"), item)]),
+        };
+        Expr::new(format!("[{}]", item.text), Kind::Vector)
+    }
+
+    fn ty_expr(&mut self, ty: &Ty<TranspileRef>, hint: String) -> Expr {
+        if let Some(some) = ty.as_some() {
+            let mut expr = self.ref_expr(some, hint);
+            expr.optional = true;
+            return expr;
+        }
+        match ty {
+            Ty::Primitive(prim) => primitive_expr(*prim),
+            Ty::UnicodeChar => Expr::new("uint", Kind::Scalar),
+            Ty::Enum(variants) => {
+                let mut def = format!("enum {hint} : ubyte {{\n");
+                for variant in variants {
+                    def.push_str(&format!("  {} = {},\n", variant.name, variant.tag));
+                }
+                def.push_str("}\n");
+                self.define(hint.clone(), def);
+                Expr::new(hint, Kind::Scalar)
+            }
+            Ty::Union(variants) => {
+                let mut def = format!("union {hint} {{\n");
+                for (variant, ty) in variants {
+                    let case = format!("{hint}{}", capitalize(variant.name.as_str()));
+                    let fields = if ty.as_ty() == Some(&Ty::UNIT) {
+                        vec![]
+                    } else {
+                        vec![(s!("value"), self.ref_expr(ty, case.clone()))]
+                    };
+                    self.define_fields(case.clone(), fields);
+                    def.push_str(&format!("  {case},\n"));
+                }
+                def.push_str("}\n");
+                self.define(hint.clone(), def);
+                Expr::new(hint, Kind::Union)
+            }
+            Ty::Tuple(fields) if fields.len() == 2 && self.is_rstring(fields) => {
+                Expr::new("string", Kind::String)
+            }
+            Ty::Tuple(fields) if fields.len() == 1 => self.ref_expr(&fields[0], hint),
+            Ty::Tuple(fields) => {
+                let fields = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(pos, r)| (format!("_{pos}"), self.ref_expr(r, format!("{hint}{pos}"))))
+                    .collect();
+                self.define_fields(hint, fields)
+            }
+            Ty::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let name = field.name.as_str();
+                        let hint = format!("{hint}{}", capitalize(name));
+                        (name.to_owned(), self.ref_expr(&field.ty, hint))
+                    })
+                    .collect();
+                self.define_fields(hint, fields)
+            }
+            Ty::Array(ty, len) => {
+                let item = self.ref_expr(ty, format!("{hint}Item"));
+                if item.is_fixed() && !matches!(item.kind, Kind::Array(_)) {
+                    Expr::new(item.text, Kind::Array(*len))
+                } else {
+                    self.vector(item, hint)
+                }
+            }
+            Ty::List(ty, _) if ty.is_byte() => Expr::new("[ubyte]", Kind::Vector),
+            Ty::List(ty, _) if ty.is_unicode_char() || self.is_char(ty) => {
+                Expr::new("string", Kind::String)
+            }
+            Ty::List(ty, _) | Ty::Set(ty, _) => {
+                let item = self.ref_expr(ty, format!("{hint}Item"));
+                self.vector(item, hint)
+            }
+            Ty::Map(key, value, _) => {
+                let entry = format!("{hint}Entry");
+                let key = self.ref_expr(key, format!("{entry}Key"));
+                let value = self.ref_expr(value, format!("{entry}Value"));
+                let entry = self.define_fields(entry, vec![(s!("key"), key), (s!("value"), value)]);
+                Expr::new(format!("[{}]", entry.text), Kind::Vector)
+            }
+        }
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => s!(""),
+    }
+}
+
+fn primitive_expr(prim: Primitive) -> Expr {
+    let size = prim.byte_size();
+    let scalar = match (prim.info().ty, size) {
+        _ if prim == Primitive::UNIT || prim == Primitive::BYTE => "ubyte",
+        (NumCls::Unsigned | NumCls::NonZero, 1) => "ubyte",
+        (NumCls::Unsigned | NumCls::NonZero, 2) => "ushort",
+        (NumCls::Unsigned | NumCls::NonZero, 3..=4) => "uint",
+        (NumCls::Unsigned | NumCls::NonZero, 5..=8) => "ulong",
+        (NumCls::Signed, 1) => "byte",
+        (NumCls::Signed, 2) => "short",
+        (NumCls::Signed, 3..=4) => "int",
+        (NumCls::Signed, 5..=8) => "long",
+        (NumCls::Float, 4) => "float",
+        (NumCls::Float, 8) => "double",
+        _ => return Expr::new("ubyte", Kind::Array(size)),
+    };
+    Expr::new(scalar, Kind::Scalar)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{Confined, SmallVec, TinyOrdMap};
+
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    pub enum Kind {
+        #[default]
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Item {
+        pub kind: Kind,
+        pub value: u16,
+        pub hash: [u8; 4],
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = order)]
+    pub enum Payload {
+        #[strict_type(dumb)]
+        Empty,
+        Data(Confined<Vec<u8>, 0, 8>),
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub items: SmallVec<Item>,
+        pub limits: TinyOrdMap<u8, u64>,
+        pub payload: Payload,
+        pub parent: Option<u32>,
+    }
+
+    #[test]
+    fn to_flatbuffers() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let fbs = lib.to_flatbuffers(&tn!("Record")).unwrap();
+        assert_eq!(
+            fbs,
+            r#"// FlatBuffers schema for type Test.Record
+
+namespace Test;
+
+struct Item {
+  kind: Kind;
+  value: ushort;
+  hash: [ubyte:4];
+}
+
+enum Kind : ubyte {
+  first = 0,
+  second = 1,
+}
+
+union Payload {
+  PayloadEmpty,
+  PayloadData,
+}
+
+table PayloadData {
+  value: [ubyte];
+}
+
+table PayloadEmpty {
+}
+
+table Record {
+  items: [Item];
+  limits: [RecordLimitsEntry];
+  payload: Payload;
+  parent: uint = null;
+}
+
+struct RecordLimitsEntry {
+  key: ubyte;
+  value: ulong;
+}
+
+root_type Record;
+"#
+        );
+
+        assert_eq!(
+            lib.to_flatbuffers(&tn!("Item")),
+            Err(super::FlatBuffersError::RootNotTable(tn!("Item")))
+        );
+        assert_eq!(
+            lib.to_flatbuffers(&tn!("Missing")),
+            Err(super::FlatBuffersError::UnknownType(tn!("Missing")))
+        );
+    }
+}
@@ -32,6 +32,7 @@ use strict_encoding::{StrictDumb, STRICT_TYPES_LIB};
 
 use crate::ast::SemCommit;
 use crate::typelib::{ExternRef, InlineRef, InlineRef1, InlineRef2, TypeLib};
+use crate::util::{parse_baid64_id, IdError};
 use crate::{CommitConsume, Dependency, LibRef, SymbolRef, TranspileRef};
 
 pub const LIB_ID_TAG: [u8; 32] = *b"urn:ubideco:strict-types:lib:v01";
@@ -65,6 +66,27 @@ impl Display for TypeLibId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.fmt_baid64(f) }
 }
 
+impl TypeLibId {
+    /// Returns mnemonic suffix of the identifier (the words following `#` in its string form).
+    pub fn mnemonic(&self) -> String { self.to_baid64_mnemonic() }
+
+    /// Returns raw digest bytes the identifier is made of.
+    pub fn checksum_bytes(&self) -> [u8; 32] { self.to_byte_array() }
+
+    /// Parses the identifier string, reporting [`IdError::MnemonicMismatch`] if the mnemonic
+    /// suffix does not match the digest.
+    ///
+    /// Unlike [`FromStr`], which has to report [`Baid64ParseError`] for compatibility with ASCII
+    /// armoring, this distinguishes mnemonic mismatches from other parse errors.
+    pub fn from_str_checked(s: &str) -> Result<Self, IdError> { parse_baid64_id(s) }
+
+    /// Constructs the identifier from its raw `digest`; the mnemonic is derived from the digest.
+    pub const fn from_parts(digest: [u8; 32]) -> Self { TypeLibId(Bytes32::from_array(digest)) }
+
+    /// Recomputes id of the library `lib` and checks that it matches this identifier.
+    pub fn verify(&self, lib: &TypeLib) -> bool { lib.id() == *self }
+}
+
 impl SemCommit for TypeLibId {
     fn sem_commit(&self, hasher: &mut impl CommitConsume) {
         hasher.commit_consume(self.as_slice());
@@ -148,6 +170,9 @@ impl SemCommit for ExternRef {
 }
 
 impl TypeLib {
+    /// Computes library id, committing to the library name, its dependencies and the semantic
+    /// ids of its types taken in the order of the type names. Thus the id doesn't depend on the
+    /// order in which types were added to the library.
     pub fn id(&self) -> TypeLibId {
         let tag = Sha256::new_with_prefix(LIB_ID_TAG).finalize();
         let mut hasher = Sha256::new();
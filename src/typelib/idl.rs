@@ -0,0 +1,367 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Compact rendering of type libraries in a Protobuf-like interface definition language.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use strict_encoding::{LibName, Sizing, TypeName};
+
+use crate::typelib::{ExternRef, InlineRef, InlineRef1, InlineRef2, LibRef, TypeLib, TypeLibId};
+use crate::{SemId, Ty, TypeRef};
+
+impl TypeLib {
+    /// Renders the library in a compact IDL form, where structures, enums and unions are printed
+    /// like `struct Name { field: Type }` and `enum Name { variant = 0 }`, and collections show
+    /// their confinement as `[Type ^ min..max]`.
+    ///
+    /// The rendering is made for code review and documentation purposes and can't be parsed
+    /// back. Use [`TypeLib`]'s `Display` implementation for the canonical source form.
+    pub fn display_idl(&self) -> String {
+        let names = self.types.iter().map(|(name, ty)| (ty.sem_id_named(name), name)).collect();
+        let libs = self.dependencies.iter().map(|dep| (dep.id, &dep.name)).collect();
+        let ctx = IdlContext {
+            lib: self,
+            names,
+            libs,
+        };
+
+        let mut out = format!("library {}; // {}\n\n", self.name, self.id());
+        for dep in &self.dependencies {
+            writeln!(out, "import {}; // {}", dep.name, dep.id).ok();
+        }
+        if !self.dependencies.is_empty() {
+            out.push('\n');
+        }
+        for (name, ty) in &self.types {
+            ctx.write_decl(name, ty, &mut out);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+trait IdlRef: TypeRef {
+    type Inner: IdlRef;
+    fn as_inline(&self) -> Option<&Ty<Self::Inner>>;
+    fn as_named(&self) -> Option<SemId>;
+    fn as_extern(&self) -> Option<&ExternRef>;
+}
+
+impl IdlRef for LibRef {
+    type Inner = InlineRef;
+    fn as_inline(&self) -> Option<&Ty<Self::Inner>> {
+        match self {
+            LibRef::Inline(ty) => Some(ty),
+            _ => None,
+        }
+    }
+    fn as_named(&self) -> Option<SemId> {
+        match self {
+            LibRef::Named(sem_id) => Some(*sem_id),
+            _ => None,
+        }
+    }
+    fn as_extern(&self) -> Option<&ExternRef> {
+        match self {
+            LibRef::Extern(ext) => Some(ext),
+            _ => None,
+        }
+    }
+}
+
+impl IdlRef for InlineRef {
+    type Inner = InlineRef1;
+    fn as_inline(&self) -> Option<&Ty<Self::Inner>> {
+        match self {
+            InlineRef::Inline(ty) => Some(ty),
+            _ => None,
+        }
+    }
+    fn as_named(&self) -> Option<SemId> {
+        match self {
+            InlineRef::Named(sem_id) => Some(*sem_id),
+            _ => None,
+        }
+    }
+    fn as_extern(&self) -> Option<&ExternRef> {
+        match self {
+            InlineRef::Extern(ext) => Some(ext),
+            _ => None,
+        }
+    }
+}
+
+impl IdlRef for InlineRef1 {
+    type Inner = InlineRef2;
+    fn as_inline(&self) -> Option<&Ty<Self::Inner>> {
+        match self {
+            InlineRef1::Inline(ty) => Some(ty),
+            _ => None,
+        }
+    }
+    fn as_named(&self) -> Option<SemId> {
+        match self {
+            InlineRef1::Named(sem_id) => Some(*sem_id),
+            _ => None,
+        }
+    }
+    fn as_extern(&self) -> Option<&ExternRef> {
+        match self {
+            InlineRef1::Extern(ext) => Some(ext),
+            _ => None,
+        }
+    }
+}
+
+impl IdlRef for InlineRef2 {
+    type Inner = InlineRef2;
+    fn as_inline(&self) -> Option<&Ty<Self::Inner>> { None }
+    fn as_named(&self) -> Option<SemId> {
+        match self {
+            InlineRef2::Named(sem_id) => Some(*sem_id),
+            InlineRef2::Extern(_) => None,
+        }
+    }
+    fn as_extern(&self) -> Option<&ExternRef> {
+        match self {
+            InlineRef2::Extern(ext) => Some(ext),
+            InlineRef2::Named(_) => None,
+        }
+    }
+}
+
+struct IdlContext<'lib> {
+    lib: &'lib TypeLib,
+    names: BTreeMap<SemId, &'lib TypeName>,
+    libs: BTreeMap<TypeLibId, &'lib LibName>,
+}
+
+impl IdlContext<'_> {
+    fn write_decl(&self, name: &TypeName, ty: &Ty<LibRef>, out: &mut String) {
+        match ty {
+            Ty::Struct(fields) => {
+                writeln!(out, "struct {name} {{").ok();
+                for field in fields {
+                    write!(out, "    {}: ", field.name).ok();
+                    self.write_ref(&field.ty, out);
+                    out.push_str(",\n");
+                }
+                out.push_str("}\n");
+            }
+            Ty::Tuple(fields) => {
+                write!(out, "struct {name}").ok();
+                self.write_tuple(fields.iter(), out);
+                out.push_str(";\n");
+            }
+            Ty::Enum(variants) => {
+                writeln!(out, "enum {name} {{").ok();
+                for variant in variants {
+                    writeln!(out, "    {} = {},", variant.name, variant.tag).ok();
+                }
+                out.push_str("}\n");
+            }
+            Ty::Union(variants) if !ty.is_option() => {
+                writeln!(out, "union {name} {{").ok();
+                for (variant, ty) in variants {
+                    out.push_str("    ");
+                    self.write_variant(&variant.name, ty, out);
+                    writeln!(out, " = {},", variant.tag).ok();
+                }
+                out.push_str("}\n");
+            }
+            _ => {
+                write!(out, "type {name} = ").ok();
+                self.write_ty(ty, out);
+                out.push_str(";\n");
+            }
+        }
+    }
+
+    fn write_ref<R: IdlRef>(&self, r: &R, out: &mut String) {
+        if let Some(ty) = r.as_inline() {
+            self.write_ty(ty, out);
+        } else if let Some(sem_id) = r.as_named() {
+            match self.names.get(&sem_id) {
+                Some(name) => write!(out, "{name}"),
+                None => write!(out, "{sem_id}"),
+            }
+            .ok();
+        } else if let Some(ext) = r.as_extern() {
+            let lib_name = self.libs.get(&ext.lib_id);
+            let ty_name = lib_name
+                .and_then(|lib_name| self.lib.extern_types.get(*lib_name)?.get(&ext.sem_id));
+            match (lib_name, ty_name) {
+                (Some(lib_name), Some(ty_name)) => write!(out, "{lib_name}.{ty_name}"),
+                (Some(lib_name), None) => write!(out, "{lib_name}.{}", ext.sem_id),
+                _ => write!(out, "{ext}"),
+            }
+            .ok();
+        }
+    }
+
+    fn write_ty<R: IdlRef>(&self, ty: &Ty<R>, out: &mut String) {
+        if let Some(some) = ty.as_some() {
+            match some.as_inline() {
+                Some(Ty::Tuple(fields)) if fields.len() == 1 => self.write_ref(&fields[0], out),
+                _ => self.write_ref(some, out),
+            }
+            out.push('?');
+            return;
+        }
+        match ty {
+            Ty::Primitive(prim) => write!(out, "{prim}").unwrap_or_default(),
+            Ty::UnicodeChar => out.push_str("Unicode"),
+            Ty::Enum(variants) => {
+                out.push_str("enum { ");
+                let variants = variants
+                    .iter()
+                    .map(|variant| format!("{} = {}", variant.name, variant.tag))
+                    .collect::<Vec<_>>();
+                out.push_str(&variants.join(", "));
+                out.push_str(" }");
+            }
+            Ty::Union(variants) => {
+                out.push_str("union { ");
+                for (no, (variant, ty)) in variants.iter().enumerate() {
+                    if no > 0 {
+                        out.push_str(", ");
+                    }
+                    self.write_variant(&variant.name, ty, out);
+                    write!(out, " = {}", variant.tag).ok();
+                }
+                out.push_str(" }");
+            }
+            Ty::Tuple(fields) => self.write_tuple(fields.iter(), out),
+            Ty::Struct(fields) => {
+                out.push_str("{ ");
+                for (no, field) in fields.iter().enumerate() {
+                    if no > 0 {
+                        out.push_str(", ");
+                    }
+                    write!(out, "{}: ", field.name).ok();
+                    self.write_ref(&field.ty, out);
+                }
+                out.push_str(" }");
+            }
+            Ty::Array(ty, len) => {
+                out.push('[');
+                self.write_ref(ty, out);
+                write!(out, " ^ {len}]").ok();
+            }
+            Ty::List(ty, sizing) => {
+                out.push('[');
+                self.write_ref(ty, out);
+                write_sizing(*sizing, out);
+                out.push(']');
+            }
+            Ty::Set(ty, sizing) => {
+                out.push('{');
+                self.write_ref(ty, out);
+                write_sizing(*sizing, out);
+                out.push('}');
+            }
+            Ty::Map(key, ty, sizing) => {
+                out.push('{');
+                self.write_ref(key, out);
+                out.push_str(" -> ");
+                self.write_ref(ty, out);
+                write_sizing(*sizing, out);
+                out.push('}');
+            }
+        }
+    }
+
+    fn write_variant<R: IdlRef>(&self, name: &impl ToString, ty: &R, out: &mut String) {
+        out.push_str(&name.to_string());
+        match ty.as_inline() {
+            Some(inner) if *inner == Ty::UNIT => {}
+            Some(Ty::Tuple(fields)) => self.write_tuple(fields.iter(), out),
+            Some(inner @ Ty::Struct(_)) => {
+                out.push(' ');
+                self.write_ty(inner, out);
+            }
+            _ => {
+                out.push('(');
+                self.write_ref(ty, out);
+                out.push(')');
+            }
+        }
+    }
+
+    fn write_tuple<'r, R: IdlRef + 'r>(
+        &self,
+        fields: impl Iterator<Item = &'r R>,
+        out: &mut String,
+    ) {
+        out.push('(');
+        for (no, field) in fields.enumerate() {
+            if no > 0 {
+                out.push_str(", ");
+            }
+            self.write_ref(field, out);
+        }
+        out.push(')');
+    }
+}
+
+fn write_sizing(sizing: Sizing, out: &mut String) {
+    write!(out, " ^ {}..{:#x}", sizing.min, sizing.max).ok();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::stl::{std_stl, strict_types_stl};
+    use crate::value::test_helpers::Nominal;
+    use crate::LibBuilder;
+
+    #[test]
+    fn display_idl() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let lib =
+            LibBuilder::with("TestLib", [std.to_dependency_types(), st.to_dependency_types()])
+                .transpile::<Nominal>()
+                .compile()
+                .unwrap();
+
+        let idl = lib.display_idl();
+        assert!(idl.starts_with(&format!("library TestLib; // {}\n\n", lib.id())));
+        assert!(idl.contains(&format!("import Std; // {}\n", std.id())));
+        assert!(
+            idl.contains(
+                "struct Nominal {\n    ticker: StrictTypes.Ident,\n    name: [Unicode ^ \
+                 1..0x20],\n    precision: Precision,\n}\n"
+            )
+        );
+        assert!(idl.contains(
+            "enum Precision {\n    noDecimals = 0,\n    oneDecimal = 1,\n    twoDecimals = 2,\n}\n"
+        ));
+
+        let idl = st.display_idl();
+        assert!(idl.contains("struct TypeLibId([Byte ^ 32]);\n"));
+        assert!(idl.contains("    fqn: TypeFqn?,\n"));
+        assert!(idl.contains("    map(LibRef, LibRef, Sizing) = 10,\n"));
+    }
+}
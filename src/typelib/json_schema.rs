@@ -0,0 +1,294 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Export of type library types in form of JSON Schema (draft 2020-12).
+
+use std::collections::BTreeSet;
+
+use serde_json::{json, Map, Value};
+use strict_encoding::{NumCls, NumInfo, Primitive, Sizing, TypeName};
+
+use crate::typelib::SymbolError;
+use crate::{SymbolicLib, TranspileRef, Ty, TypeLib, TypeRef};
+
+/// Identifier of the JSON Schema dialect used by [`TypeLib::to_json_schema`].
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum JsonSchemaError {
+    /// type `{0}` is not defined in the library.
+    UnknownType(TypeName),
+
+    #[display(inner)]
+    #[from]
+    Symbols(SymbolError),
+}
+
+impl TypeLib {
+    /// Exports type `root` and all library types it depends on as a JSON Schema document.
+    ///
+    /// All named types are put under `$defs` and are referenced with `$ref`, which makes
+    /// recursive types representable. Types from library dependencies are not known and thus
+    /// are not constrained in the schema.
+    pub fn to_json_schema(&self, root: &TypeName) -> Result<Value, JsonSchemaError> {
+        let lib = self.to_symbolic()?;
+        let mut ctx = SchemaContext {
+            lib: &lib,
+            queue: vec![root.clone()],
+        };
+        let mut seen = BTreeSet::new();
+        let mut defs = Map::new();
+        while let Some(name) = ctx.queue.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            let ty = lib.types().get(&name).ok_or(JsonSchemaError::UnknownType(name.clone()))?;
+            let schema = ctx.ty_schema(ty);
+            defs.insert(name.to_string(), schema);
+        }
+        Ok(json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": format!("{}.{root}", self.name),
+            "$ref": def_ref(root),
+            "$defs": defs,
+        }))
+    }
+}
+
+fn def_ref(name: &TypeName) -> String { format!("#/$defs/{name}") }
+
+struct SchemaContext<'lib> {
+    lib: &'lib SymbolicLib,
+    queue: Vec<TypeName>,
+}
+
+impl SchemaContext<'_> {
+    fn is_char(&self, r: &TranspileRef) -> bool {
+        match r {
+            TranspileRef::Embedded(ty) => ty.is_char_enum(),
+            TranspileRef::Named(name) => {
+                self.lib.types().get(name).map(Ty::is_char_enum).unwrap_or_default()
+            }
+            TranspileRef::Extern(_) => false,
+        }
+    }
+
+    fn ref_schema(&mut self, r: &TranspileRef) -> Value {
+        match r {
+            TranspileRef::Embedded(ty) => self.ty_schema(ty),
+            TranspileRef::Named(name) => {
+                self.queue.push(name.clone());
+                json!({ "$ref": def_ref(name) })
+            }
+            TranspileRef::Extern(ext) => json!({
+                "title": format!("{}.{}", ext.lib_name, ext.ty_name),
+                "$comment": format!("type defined in library {}", ext.lib_id),
+            }),
+        }
+    }
+
+    fn ty_schema(&mut self, ty: &Ty<TranspileRef>) -> Value {
+        if let Some(some) = ty.as_some() {
+            return json!({ "oneOf": [{ "type": "null" }, self.ref_schema(some)] });
+        }
+        match ty {
+            Ty::Primitive(prim) => primitive_schema(*prim),
+            Ty::UnicodeChar => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+            Ty::Enum(variants) => {
+                let consts = variants
+                    .iter()
+                    .map(|variant| json!({ "const": variant.name.as_str() }))
+                    .collect::<Vec<_>>();
+                json!({ "oneOf": consts })
+            }
+            Ty::Union(variants) => {
+                let cases = variants
+                    .iter()
+                    .map(|(variant, ty)| {
+                        if ty.as_ty() == Some(&Ty::UNIT) {
+                            return json!({ "const": variant.name.as_str() });
+                        }
+                        let name = variant.name.as_str();
+                        json!({
+                            "type": "object",
+                            "properties": { name: self.ref_schema(ty) },
+                            "required": [name],
+                            "additionalProperties": false,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "oneOf": cases })
+            }
+            Ty::Tuple(fields) if fields.len() == 2 && self.is_rstring(fields) => {
+                let Some(Ty::List(_, sizing)) = fields[1].as_ty() else {
+                    unreachable!("checked by is_rstring")
+                };
+                string_schema(Sizing::new(sizing.min + 1, sizing.max + 1))
+            }
+            Ty::Tuple(fields) if fields.len() == 1 => self.ref_schema(&fields[0]),
+            Ty::Tuple(fields) => {
+                let items = fields.iter().map(|r| self.ref_schema(r)).collect::<Vec<_>>();
+                json!({
+                    "type": "array",
+                    "prefixItems": items,
+                    "items": false,
+                    "minItems": fields.len(),
+                    "maxItems": fields.len(),
+                })
+            }
+            Ty::Struct(fields) => {
+                let mut properties = Map::new();
+                for field in fields {
+                    properties.insert(field.name.to_string(), self.ref_schema(&field.ty));
+                }
+                let required = fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            Ty::Array(ty, len) if ty.is_byte() => bytes_schema(Sizing::fixed(*len as u64)),
+            Ty::Array(ty, len) => json!({
+                "type": "array",
+                "items": self.ref_schema(ty),
+                "minItems": len,
+                "maxItems": len,
+            }),
+            Ty::List(ty, sizing) if ty.is_byte() => bytes_schema(*sizing),
+            Ty::List(ty, sizing) if ty.is_unicode_char() || self.is_char(ty) => {
+                string_schema(*sizing)
+            }
+            Ty::List(ty, sizing) => json!({
+                "type": "array",
+                "items": self.ref_schema(ty),
+                "minItems": sizing.min,
+                "maxItems": sizing.max,
+            }),
+            Ty::Set(ty, sizing) => json!({
+                "type": "array",
+                "items": self.ref_schema(ty),
+                "minItems": sizing.min,
+                "maxItems": sizing.max,
+                "uniqueItems": true,
+            }),
+            Ty::Map(_, val, sizing) => json!({
+                "type": "object",
+                "additionalProperties": self.ref_schema(val),
+                "minProperties": sizing.min,
+                "maxProperties": sizing.max,
+            }),
+        }
+    }
+
+    fn is_rstring(&self, fields: &[TranspileRef]) -> bool {
+        match fields[1].as_ty() {
+            Some(Ty::List(rest, _)) => self.is_char(&fields[0]) && self.is_char(rest),
+            _ => false,
+        }
+    }
+}
+
+fn primitive_schema(prim: Primitive) -> Value {
+    match prim {
+        Primitive::UNIT => return json!({ "type": "null" }),
+        Primitive::BYTE => return json!({ "type": "integer", "minimum": 0, "maximum": 255 }),
+        _ => {}
+    }
+    let NumInfo { ty: cls, .. } = prim.info();
+    let bits = prim.byte_size() as u32 * 8;
+    match cls {
+        NumCls::Float => json!({ "type": "number" }),
+        NumCls::Unsigned | NumCls::NonZero if bits <= 64 => json!({
+            "type": "integer",
+            "minimum": if cls == NumCls::NonZero { 1 } else { 0 },
+            "maximum": u64::MAX >> (64 - bits),
+        }),
+        NumCls::Unsigned | NumCls::NonZero => json!({
+            "type": "integer",
+            "minimum": if cls == NumCls::NonZero { 1 } else { 0 },
+        }),
+        NumCls::Signed if bits <= 64 => json!({
+            "type": "integer",
+            "minimum": i64::MIN >> (64 - bits),
+            "maximum": i64::MAX >> (64 - bits),
+        }),
+        NumCls::Signed => json!({ "type": "integer" }),
+    }
+}
+
+fn string_schema(sizing: Sizing) -> Value {
+    json!({ "type": "string", "minLength": sizing.min, "maxLength": sizing.max })
+}
+
+fn bytes_schema(sizing: Sizing) -> Value {
+    json!({
+        "type": "string",
+        "contentEncoding": "base64",
+        "minLength": sizing.min.div_ceil(3) * 4,
+        "maxLength": sizing.max.div_ceil(3).saturating_mul(4),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stl::{std_stl, strict_types_stl};
+    use crate::value::test_helpers::Nominal;
+    use crate::LibBuilder;
+
+    #[test]
+    fn json_schema() {
+        let std = std_stl();
+        let st = strict_types_stl();
+        let lib =
+            LibBuilder::with("TestLib", [std.to_dependency_types(), st.to_dependency_types()])
+                .transpile::<Nominal>()
+                .compile()
+                .unwrap();
+
+        let schema = lib.to_json_schema(&tn!("Nominal")).unwrap();
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(schema["$ref"], "#/$defs/Nominal");
+
+        let nominal = &schema["$defs"]["Nominal"];
+        assert_eq!(nominal["required"], json!(["ticker", "name", "precision"]));
+        assert_eq!(nominal["properties"]["ticker"]["title"], "StrictTypes.Ident");
+        assert_eq!(nominal["properties"]["precision"]["$ref"], "#/$defs/Precision");
+        assert_eq!(
+            nominal["properties"]["name"],
+            json!({ "type": "string", "minLength": 1, "maxLength": 32 })
+        );
+        assert_eq!(
+            schema["$defs"]["Precision"]["oneOf"],
+            json!([{ "const": "noDecimals" }, { "const": "oneDecimal" }, { "const": "twoDecimals" }])
+        );
+
+        assert_eq!(
+            lib.to_json_schema(&tn!("Missing")),
+            Err(JsonSchemaError::UnknownType(tn!("Missing")))
+        );
+    }
+}
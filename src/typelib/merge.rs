@@ -0,0 +1,238 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Merging of type libraries built independently.
+
+use std::collections::BTreeMap;
+
+use amplify::confinement::{Confined, SmallOrdMap, TinyOrdMap, TinyOrdSet};
+use strict_encoding::{LibName, TypeName};
+
+use crate::typelib::SymbolError;
+use crate::{Dependency, SemId, TypeLib};
+
+/// Policy for resolving types having the same name in both merged libraries, but different
+/// semantic ids.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum MergePolicy {
+    /// Keep the type from the library into which the other one is merged.
+    TakeSelf,
+
+    /// Replace the type with the one from the merged library.
+    TakeOther,
+
+    /// Fail with [`MergeError::Conflict`].
+    #[default]
+    RequireIdentical,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum MergeError {
+    /// type `{name}` has different semantic ids {ours} and {theirs} in the merged libraries.
+    Conflict {
+        name: TypeName,
+        ours: SemId,
+        theirs: SemId,
+    },
+
+    /// dependency {0} conflicts with dependency {1} of the merged library.
+    DependencyConflict(Dependency, Dependency),
+
+    /// too many types in the merged library.
+    TooManyTypes,
+
+    /// too many dependencies in the merged library.
+    TooManyDependencies,
+
+    /// library `{0}` provides too many types to the merged library.
+    LibTooLarge(LibName),
+
+    /// merged library is inconsistent: {0}
+    #[from]
+    Inconsistent(SymbolError),
+}
+
+impl TypeLib {
+    /// Merges types, dependencies and external types from the `other` library into this one,
+    /// resolving types with the same name but different semantic ids according to the `policy`.
+    ///
+    /// The resulting library keeps the name of `self`. Replacing a type under
+    /// [`MergePolicy::TakeOther`] or [`MergePolicy::TakeSelf`] may leave types which reference
+    /// the dropped version by its semantic id; such libraries are rejected with
+    /// [`MergeError::Inconsistent`].
+    pub fn merge(self, other: TypeLib, policy: MergePolicy) -> Result<TypeLib, MergeError> {
+        let mut dependencies = self.dependencies.into_iter().collect::<Vec<_>>();
+        for dep in other.dependencies {
+            match dependencies.iter().find(|d| d.name == dep.name || d.id == dep.id) {
+                Some(present) if present.id == dep.id && present.name == dep.name => {}
+                Some(present) => return Err(MergeError::DependencyConflict(present.clone(), dep)),
+                None => dependencies.push(dep),
+            }
+        }
+        let dependencies =
+            TinyOrdSet::try_from_iter(dependencies).map_err(|_| MergeError::TooManyDependencies)?;
+
+        let mut extern_types = self
+            .extern_types
+            .into_iter()
+            .map(|(lib, types)| (lib, types.release()))
+            .collect::<BTreeMap<_, _>>();
+        for (lib, types) in other.extern_types {
+            extern_types.entry(lib).or_default().extend(types);
+        }
+        let extern_types = extern_types
+            .into_iter()
+            .map(|(lib, types)| {
+                SmallOrdMap::try_from(types)
+                    .map(|types| (lib.clone(), types))
+                    .map_err(|_| MergeError::LibTooLarge(lib))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let extern_types =
+            TinyOrdMap::try_from(extern_types).map_err(|_| MergeError::TooManyDependencies)?;
+
+        let mut types = self.types.release();
+        for (name, ty) in other.types {
+            let Some(present) = types.get(&name) else {
+                types.insert(name, ty);
+                continue;
+            };
+            let (ours, theirs) = (present.sem_id_named(&name), ty.sem_id_named(&name));
+            if ours == theirs {
+                continue;
+            }
+            match policy {
+                MergePolicy::TakeSelf => {}
+                MergePolicy::TakeOther => {
+                    types.insert(name, ty);
+                }
+                MergePolicy::RequireIdentical => {
+                    return Err(MergeError::Conflict { name, ours, theirs });
+                }
+            }
+        }
+        let types = Confined::try_from(types).map_err(|_| MergeError::TooManyTypes)?;
+
+        let lib = TypeLib {
+            name: self.name,
+            dependencies,
+            extern_types,
+            types,
+        };
+        lib.to_symbolic()?;
+        Ok(lib)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use super::*;
+    use crate::LibBuilder;
+
+    mod v1 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+        }
+    }
+
+    mod v2 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+            Second = 1,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Other(u8);
+    }
+
+    #[test]
+    fn merge() {
+        let ours =
+            LibBuilder::with(libname!("Test"), None).transpile::<v1::Record>().compile().unwrap();
+        let theirs = LibBuilder::with(libname!("Test"), None)
+            .transpile::<v2::Record>()
+            .transpile::<v2::Other>()
+            .compile()
+            .unwrap();
+        let kind = tn!("Kind");
+
+        let err = ours.clone().merge(theirs.clone(), MergePolicy::RequireIdentical).unwrap_err();
+        assert_eq!(err, MergeError::Conflict {
+            name: kind.clone(),
+            ours: ours.types[&kind].sem_id_named(&kind),
+            theirs: theirs.types[&kind].sem_id_named(&kind),
+        });
+
+        let merged = ours.clone().merge(theirs.clone(), MergePolicy::TakeSelf).unwrap();
+        assert_eq!(merged.types.len(), 3);
+        assert_eq!(merged.types[&kind], ours.types[&kind]);
+        assert_ne!(merged.id(), ours.id());
+
+        let merged = ours.clone().merge(theirs.clone(), MergePolicy::TakeOther).unwrap();
+        assert_eq!(merged.types.len(), 3);
+        assert_eq!(merged.types[&kind], theirs.types[&kind]);
+
+        // merging a library into itself doesn't change it
+        assert_eq!(ours.clone().merge(ours.clone(), MergePolicy::RequireIdentical).unwrap(), ours);
+
+        // `Record` still references the replaced version of `Kind`
+        let kind_only =
+            LibBuilder::with(libname!("Test"), None).transpile::<v2::Kind>().compile().unwrap();
+        let err = ours.clone().merge(kind_only, MergePolicy::TakeOther).unwrap_err();
+        assert_eq!(
+            err,
+            MergeError::Inconsistent(SymbolError::UnknownType(
+                ours.types[&kind].sem_id_named(&kind)
+            ))
+        );
+    }
+}
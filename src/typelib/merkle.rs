@@ -0,0 +1,189 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use sha2::{Digest, Sha256};
+use strict_encoding::TypeName;
+
+use crate::typelib::TypeLib;
+use crate::{CommitConsume, SemId};
+
+pub const LIB_MERKLE_TAG: [u8; 32] = *b"urn:ubideco:strict-types:mrkl:01";
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Proof of inclusion of a type into a type library with a known [`TypeLib::merkle_root`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MerkleProof {
+    /// Position of the type among the library types ordered by their names.
+    pub index: u16,
+    /// Number of types in the library.
+    pub count: u16,
+    /// Hashes of the sibling nodes on the path from the type leaf to the root.
+    pub path: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Checks that the type with semantic id `sem_id` is a part of the library with the merkle
+    /// `root`.
+    ///
+    /// The semantic id is computed by the verifier from the type definition, thus a successful
+    /// verification proves that the library contains exactly that type under its name.
+    pub fn verify(&self, sem_id: SemId, root: [u8; 32]) -> bool {
+        if self.index >= self.count {
+            return false;
+        }
+        let mut hash = leaf_hash(sem_id);
+        let mut siblings = self.path.iter();
+        let (mut index, mut count) = (self.index as usize, self.count as usize);
+        while count > 1 {
+            if index % 2 == 1 {
+                let Some(left) = siblings.next() else {
+                    return false;
+                };
+                hash = node_hash(left, &hash);
+            } else if index + 1 < count {
+                let Some(right) = siblings.next() else {
+                    return false;
+                };
+                hash = node_hash(&hash, right);
+            }
+            index /= 2;
+            count = count.div_ceil(2);
+        }
+        siblings.next().is_none() && hash == root
+    }
+}
+
+impl TypeLib {
+    /// Computes root of a merkle tree over the semantic ids of the library types, ordered by the
+    /// type names.
+    ///
+    /// Unlike the [`TypeLib::id`], which hashes all the type ids sequentially, the merkle root
+    /// allows checking a single type against a trusted root with an [`MerkleProof`] of a size
+    /// logarithmic to the number of library types. The root doesn't commit to the library name and
+    /// dependencies, thus it is not a replacement of the library id.
+    ///
+    /// Nodes with no pair at their tree level are moved to the next level unchanged; a library
+    /// without types has the root equal to the tagged hash of no data.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        let mut level = self.merkle_leaves();
+        if level.is_empty() {
+            return tagged_hasher().finalize().into();
+        }
+        while level.len() > 1 {
+            level = next_level(&level);
+        }
+        level[0]
+    }
+
+    /// Constructs proof of inclusion of type `name` into the library, which can be checked with
+    /// [`MerkleProof::verify`] against the [`TypeLib::merkle_root`]. Returns `None` if the library
+    /// doesn't have the type.
+    pub fn inclusion_proof(&self, name: &TypeName) -> Option<MerkleProof> {
+        let index = self.types.keys().position(|n| n == name)?;
+        let mut level = self.merkle_leaves();
+        let count = level.len();
+        let mut path = vec![];
+        let mut pos = index;
+        while level.len() > 1 {
+            if pos % 2 == 1 {
+                path.push(level[pos - 1]);
+            } else if pos + 1 < level.len() {
+                path.push(level[pos + 1]);
+            }
+            level = next_level(&level);
+            pos /= 2;
+        }
+        Some(MerkleProof {
+            index: index as u16,
+            count: count as u16,
+            path,
+        })
+    }
+
+    fn merkle_leaves(&self) -> Vec<[u8; 32]> {
+        self.types.iter().map(|(name, ty)| leaf_hash(ty.sem_id_named(name))).collect()
+    }
+}
+
+/// Hashes pairs of the tree level nodes, moving the last node without a pair to the next level.
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks of two"),
+        })
+        .collect()
+}
+
+fn tagged_hasher() -> Sha256 {
+    let tag = Sha256::new_with_prefix(LIB_MERKLE_TAG).finalize();
+    let mut hasher = Sha256::new();
+    hasher.commit_consume(tag);
+    hasher.commit_consume(tag);
+    hasher
+}
+
+fn leaf_hash(sem_id: SemId) -> [u8; 32] {
+    let mut hasher = tagged_hasher();
+    hasher.commit_consume([LEAF_PREFIX]);
+    hasher.commit_consume(sem_id.as_slice());
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = tagged_hasher();
+    hasher.commit_consume([NODE_PREFIX]);
+    hasher.commit_consume(left);
+    hasher.commit_consume(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::stl::std_stl;
+
+    #[test]
+    fn inclusion_proof() {
+        let lib = std_stl();
+        let root = lib.merkle_root();
+        assert_eq!(root, std_stl().merkle_root());
+        assert!(lib.types.len() > 2);
+
+        for name in lib.types.keys() {
+            let proof = lib.inclusion_proof(name).unwrap();
+            let sem_id = lib.type_id(name).unwrap();
+            assert!(proof.verify(sem_id, root), "proof for {name} fails");
+
+            let other = lib.types.keys().find(|n| *n != name).unwrap();
+            assert!(!proof.verify(lib.type_id(other).unwrap(), root));
+            let mut wrong = proof.clone();
+            wrong.index = (proof.index + 1) % proof.count;
+            assert!(!wrong.verify(sem_id, root));
+        }
+        assert_eq!(lib.inclusion_proof(&tn!("Absent")), None);
+    }
+}
@@ -21,27 +21,118 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+//! Type libraries: collections of named strict types, which are transpiled from Rust types,
+//! compiled into a form with semantic ids, and exported into other schema languages.
+//!
+//! # Transpiling Rust types
+//!
+//! [`LibBuilder::transpile`] produces type definitions from the `StrictEncode` implementations of
+//! Rust types. Thus fields marked with `#[strict_type(skip)]` are omitted from the definitions,
+//! just as the derived `StrictEncode` never writes them and the derived `StrictDecode`
+//! initializes them with `Default::default`. Types with manual `StrictEncode` and `StrictDecode`
+//! implementations must keep both in sync with each other, since otherwise the schema will
+//! disagree with the wire format. Note that the derived `StrictDumb` requires all fields to
+//! implement `StrictDumb`, including the skipped ones; implement it manually for types with
+//! runtime-only fields like `PhantomData`.
+//!
+//! Type, field and variant names are the strict names reported by the `StrictType`
+//! implementations, not the Rust identifiers: `#[strict_type(rename = "...")]` attributes keep
+//! the semantic ids unchanged when the Rust items get renamed.
+//!
+//! ## Generic types
+//!
+//! Instantiations of a generic type which share the same strict name, like `Wrapper<ScriptBuf>`
+//! and `Wrapper<ScriptBytes>`, become distinct library types if their definitions differ. The
+//! first instantiation keeps the strict name, and the next ones get the strict names of the types
+//! they use in place of the generic parameters appended, like `WrapperScriptBytes`. If such name
+//! is taken by other type as well, or the instantiation is recursive,
+//! [`TranspileError::GenericCollision`] is reported, and the instantiations have to be named with
+//! [`LibBuilder::transpile_named`].
+//!
+//! ## Unnamed types
+//!
+//! Unnamed types, like `Option<T>`, which is a union of unit `none` and a one-field `some`
+//! tuple, are embedded into the definitions of the types using them, and the library allows only
+//! three levels of such embedding. Directly nested options like `Option<Option<T>>` used as a
+//! field of a named type exceed this depth, and the library compilation fails with
+//! [`CompileError::NestedInline`]; wrapping the inner option into a named newtype solves this.
+//!
+//! `rgb-strict-encoding` implements strict types only for Rust tuples of up to three elements,
+//! which become unnamed tuple types. This crate can't lift the limit, since both the encoding
+//! traits and the Rust tuples are foreign to it: a wider tuple doesn't implement `StrictEncode`
+//! and can't be transpiled, whatever it would be transpiled into. Such tuples have to be wrapped
+//! into a tuple struct deriving the strict encoding traits, which may have up to 255 fields,
+//! including nested tuples. A tuple has the same memory layout and encoding as a structure with
+//! fields `_0`..`_n` of the same types, but the two have different semantic ids, thus tuples are
+//! never converted into structures.
+//!
+//! ## Recursive types
+//!
+//! `Box<T>` is transparent and transpiles into the type of `T`. `Rc<T>` and `Arc<T>` can't be
+//! used in strict types until `rgb-strict-encoding` implements its traits for them. A recursive
+//! type, like a tree with its children in a collection or behind a `Box`, refers to itself by
+//! name; such libraries compile into a [`SymbolicLib`] with [`LibBuilder::compile_symbols`], but
+//! [`LibBuilder::compile`] fails with [`CompileError::RecursiveType`], since the semantic id of a
+//! recursive type can't be computed.
+
 mod id;
 mod type_lib;
 mod compile;
+mod check;
 mod serialize;
 mod transpile;
 mod symbolic;
 mod translate;
+mod diff;
+mod merge;
+mod merkle;
+mod codegen;
+mod capnp;
+mod idl;
+mod dot;
+mod fbs;
+mod visit;
+mod stats;
+mod constants;
+#[cfg(feature = "serde")]
+mod json_schema;
 
+pub use capnp::CapnpError;
+pub use check::LibDefect;
+pub use codegen::CodegenConfig;
 pub(crate) use compile::NestedContext;
 #[allow(deprecated)]
 pub use compile::TranslateError;
 pub use compile::{CompileError, TypeIndex};
+pub use constants::{ConstMap, LibConstants};
+pub use diff::{
+    ChangeKind, Compatibility, Incompatibility, LibDiff, TypeChange, WireCompatibility,
+};
+pub use fbs::FlatBuffersError;
 pub use id::TypeLibId;
-pub use symbolic::{ExternTypes, SymbolRef, SymbolicLib, TranspileError, TranspileRef};
+#[cfg(feature = "serde")]
+pub use json_schema::{JsonSchemaError, JSON_SCHEMA_DIALECT};
+pub use merge::{MergeError, MergePolicy};
+pub use merkle::MerkleProof;
+#[cfg(feature = "armor")]
+pub use serialize::ArmorError;
+pub use serialize::Base64Error;
+#[cfg(feature = "serde")]
+pub use serialize::YamlError;
+pub use stats::LibStats;
+pub use symbolic::{
+    ExternTypes, FlattenError, RenameError, SubsetError, Suggestions, SymbolRef, SymbolicLib,
+    TranspileContext, TranspileError, TranspileErrors, TranspileRef,
+};
 use translate::SymbolContext;
 pub use translate::SymbolError;
 pub use transpile::LibBuilder;
 pub(crate) use type_lib::TypeMap;
 pub use type_lib::{
-    Dependency, ExternRef, InlineRef, InlineRef1, InlineRef2, LibRef, LibSubref, TypeLib,
+    common_types, Dependency, ExternRef, InlineRef, InlineRef1, InlineRef2, LibRef, LibSubref,
+    TypeLib,
 };
+pub use visit::TypeVisitor;
 
 #[deprecated(since = "1.3.0", note = "import from the crate root")]
 pub use super::parse_args;
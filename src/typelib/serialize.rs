@@ -23,11 +23,14 @@
 
 use std::fmt::{Display, Formatter};
 use std::path::Path;
-use std::{fmt, io};
+use std::{fmt, fs, io};
 
 use amplify::confinement::U24 as U24MAX;
 use baid64::DisplayBaid64;
-use encoding::{StreamWriter, StrictDeserialize, StrictEncode, StrictSerialize, StrictWriter};
+use encoding::{
+    DeserializeError, StreamWriter, StrictDecode, StrictDeserialize, StrictEncode, StrictReader,
+    StrictSerialize, StrictWriter,
+};
 
 use crate::{StlFormat, SymbolicLib, TypeLib};
 
@@ -42,7 +45,6 @@ impl TypeLib {
         ver: &'static str,
         header: Option<&'static str>,
     ) -> io::Result<()> {
-        use std::fs;
         use std::io::stdout;
 
         let id = self.id();
@@ -77,6 +79,201 @@ impl TypeLib {
 
         Ok(())
     }
+
+    /// Writes the strict-encoded binary form of the library into the file at `path`, replacing
+    /// it if the file exists.
+    ///
+    /// The file can be read back with [`TypeLib::deserialize_from_file`], or embedded into a
+    /// binary with `include_bytes!` and parsed with [`TypeLib::from_bytes`], allowing build
+    /// scripts to compile libraries at build time.
+    pub fn serialize_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = fs::File::create(path)?;
+        self.strict_encode(StrictWriter::with(StreamWriter::new::<U24MAX>(file)))?;
+        Ok(())
+    }
+
+    /// Reads library from a file written with [`TypeLib::serialize_to_file`].
+    pub fn deserialize_from_file(path: impl AsRef<Path>) -> Result<TypeLib, DeserializeError> {
+        TypeLib::strict_deserialize_from_file::<U24MAX>(path)
+    }
+
+    /// Parses the strict-encoded binary form of the library, as written by
+    /// [`TypeLib::serialize_to_file`], requiring all `data` to be consumed.
+    pub fn from_bytes(data: &[u8]) -> Result<TypeLib, DeserializeError> {
+        let mut reader = StrictReader::in_memory::<U24MAX>(data);
+        let lib = TypeLib::strict_decode(&mut reader)?;
+        if reader.into_cursor().position() != data.len() as u64 {
+            return Err(DeserializeError::DataNotEntirelyConsumed);
+        }
+        Ok(lib)
+    }
+
+    /// Encodes the strict-encoded binary form of the library with URL-safe base64 without
+    /// padding, which, unlike [`TypeLib::to_armored`], carries no headers, checksum or line
+    /// breaks and can be embedded into URLs and QR codes.
+    pub fn to_base64(&self) -> String {
+        use baid64::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use baid64::base64::Engine;
+
+        let data = self.to_strict_serialized::<U24MAX>().expect("type library is too large");
+        URL_SAFE_NO_PAD.encode(data)
+    }
+
+    /// Parses library encoded with [`TypeLib::to_base64`].
+    ///
+    /// The data must be the canonical strict encoding of the library, such that the id
+    /// recomputed from the parsed library commits to exactly the data which were received. Since
+    /// the data carry no id, callers expecting a specific library should compare it with
+    /// [`TypeLib::id`].
+    pub fn from_base64(s: &str) -> Result<TypeLib, Base64Error> {
+        use baid64::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use baid64::base64::Engine;
+
+        let data = URL_SAFE_NO_PAD.decode(s)?;
+        let lib = TypeLib::from_bytes(&data)?;
+        if lib.to_strict_serialized::<U24MAX>().ok().as_deref() != Some(&data) {
+            return Err(Base64Error::NonCanonical(lib.id()));
+        }
+        Ok(lib)
+    }
+}
+
+/// Errors parsing type library from base64.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum Base64Error {
+    /// invalid base64 data: {0}
+    #[from]
+    Base64(baid64::base64::DecodeError),
+
+    /// invalid library data: {0}
+    #[from]
+    Decode(DeserializeError),
+
+    /// library data are not canonically encoded and don't match the data committed to by the
+    /// library id {0}.
+    NonCanonical(crate::TypeLibId),
+}
+
+/// Errors parsing type library from YAML.
+#[cfg(feature = "serde")]
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum YamlError {
+    /// invalid YAML data: {0}
+    #[from]
+    Yaml(serde_yaml::Error),
+
+    /// YAML data must contain a front matter with the library id followed by the library itself.
+    NoFrontMatter,
+
+    /// invalid library data: {0}
+    #[from]
+    Json(serde_json::Error),
+
+    /// invalid library id in the YAML front matter: {0}
+    #[from]
+    InvalidId(baid64::Baid64ParseError),
+
+    /// library id {found} from the YAML front matter doesn't match the actual library id {actual}.
+    IdMismatch {
+        found: crate::TypeLibId,
+        actual: crate::TypeLibId,
+    },
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct FrontMatter {
+    id: String,
+}
+
+#[cfg(feature = "serde")]
+impl TypeLib {
+    /// Serializes library into YAML, prefixed with a front matter containing the library id.
+    ///
+    /// All library collections are ordered, thus serializing the same library always produces
+    /// the same text, making the format suitable for keeping under version control.
+    pub fn to_yaml(&self) -> String {
+        let front = FrontMatter {
+            id: format!("{:+}", self.id()),
+        };
+        let front = serde_yaml::to_string(&front).expect("front matter is always serializable");
+        // YAML serializer doesn't support nested enums, thus we go through the JSON representation
+        let lib = serde_json::to_value(self).expect("type library is always serializable");
+        let lib = serde_yaml::to_string(&lib).expect("JSON value is always serializable");
+        format!("---\n{front}---\n{lib}")
+    }
+
+    /// Parses library serialized with [`TypeLib::to_yaml`], checking that the id from the front
+    /// matter matches the id of the parsed library.
+    pub fn from_yaml(s: &str) -> Result<TypeLib, YamlError> {
+        use std::str::FromStr;
+
+        use serde::Deserialize;
+
+        let mut docs = serde_yaml::Deserializer::from_str(s);
+        let front = docs.next().ok_or(YamlError::NoFrontMatter)?;
+        let front = FrontMatter::deserialize(front)?;
+        let lib = docs.next().ok_or(YamlError::NoFrontMatter)?;
+        let lib = serde_json::Value::deserialize(lib)?;
+        let lib: TypeLib = serde_json::from_value(lib)?;
+        let found = crate::TypeLibId::from_str(&front.id)?;
+        let actual = lib.id();
+        if found != actual {
+            return Err(YamlError::IdMismatch { found, actual });
+        }
+        Ok(lib)
+    }
+}
+
+#[cfg(feature = "armor")]
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum ArmorError {
+    /// ASCII armor misses the checksum of the armored data.
+    NoChecksum,
+
+    /// ASCII armor checksum doesn't match the armored data.
+    ChecksumMismatch,
+
+    /// library id {expected} from the ASCII armor header doesn't match the actual library id
+    /// {actual}.
+    IdMismatch { expected: String, actual: String },
+
+    #[from]
+    #[display(inner)]
+    Armor(armor::StrictArmorError),
+}
+
+#[cfg(feature = "armor")]
+impl TypeLib {
+    /// Produces ASCII-armored library, carrying its id and the checksum of the strict-encoded
+    /// data in the armor headers.
+    pub fn to_armored(&self) -> String {
+        use armor::AsciiArmor;
+        self.to_ascii_armored_string()
+    }
+
+    /// Parses ASCII-armored library produced by [`TypeLib::to_armored`], requiring the armor to
+    /// have a checksum matching the data and an id matching the id of the parsed library.
+    pub fn from_armored(s: &str) -> Result<TypeLib, ArmorError> {
+        use armor::{ArmorParseError, AsciiArmor, StrictArmorError, ASCII_ARMOR_CHECKSUM_SHA256};
+
+        let checksum = format!("{ASCII_ARMOR_CHECKSUM_SHA256}:");
+        if !s.lines().any(|line| line.starts_with(&checksum)) {
+            return Err(ArmorError::NoChecksum);
+        }
+        TypeLib::from_ascii_armored_str(s).map_err(|err| match err {
+            StrictArmorError::Armor(ArmorParseError::MismatchedChecksum) => {
+                ArmorError::ChecksumMismatch
+            }
+            StrictArmorError::MismatchedId { actual, expected } => {
+                ArmorError::IdMismatch { expected, actual }
+            }
+            err => ArmorError::Armor(err),
+        })
+    }
 }
 
 impl StrictSerialize for SymbolicLib {}
@@ -0,0 +1,214 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeSet;
+
+use encoding::{Primitive, TypeName};
+
+use super::symbolic::collect_refs;
+use super::{SymbolError, TypeVisitor};
+use crate::layout::{len_prefix, FieldPath};
+use crate::{SymbolRef, SymbolicLib, TranspileRef, Ty, TypeLib};
+
+/// Summary of the library complexity, returned by [`TypeLib::stats`].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct LibStats {
+    /// Number of the library types.
+    pub types: usize,
+    /// Number of the library types which are not used by other library types.
+    pub roots: usize,
+    /// Maximal nesting depth of the library types, as the number of field, variant and item
+    /// path segments from a root type to its deepest item.
+    pub max_depth: usize,
+    /// Number of the library types which refer to themselves. Compiled libraries can't contain
+    /// recursive types, thus it is zero unless the library is malformed.
+    pub recursive: usize,
+    /// Number of distinct primitive types used by the library types.
+    pub primitives: usize,
+    /// Maximal strict-encoded size of a value among the root types, or `None` if it is unbounded,
+    /// doesn't fit `usize`, or depends on the types from the library dependencies.
+    pub max_size: Option<usize>,
+}
+
+#[derive(Default)]
+struct Collector {
+    max_depth: usize,
+    recursive: BTreeSet<TypeName>,
+    primitives: BTreeSet<Primitive>,
+}
+
+impl TypeVisitor for Collector {
+    fn visit_named(&mut self, path: &FieldPath, _: &TypeName) { self.visit(path) }
+    fn visit_recursive(&mut self, path: &FieldPath, name: &TypeName) {
+        self.visit(path);
+        self.recursive.insert(name.clone());
+    }
+    fn visit_primitive(&mut self, path: &FieldPath, prim: Primitive) {
+        self.visit(path);
+        self.primitives.insert(prim);
+    }
+    fn visit_unicode(&mut self, path: &FieldPath) { self.visit(path) }
+    fn visit_extern(&mut self, path: &FieldPath, _: &SymbolRef) { self.visit(path) }
+}
+
+impl Collector {
+    fn visit(&mut self, path: &FieldPath) { self.max_depth = self.max_depth.max(path.len()) }
+}
+
+impl TypeLib {
+    /// Summarizes the library complexity: the number of types, their nesting depth, primitive
+    /// types used and the maximal size of the encoded data.
+    ///
+    /// The types are walked from each root, thus the time it takes grows with the number of
+    /// the type uses, not just the number of the types.
+    ///
+    /// Errors if the library is internally inconsistent.
+    pub fn stats(&self) -> Result<LibStats, SymbolError> {
+        let lib = self.to_symbolic()?;
+        let mut used = vec![];
+        for ty in lib.types().values() {
+            collect_refs(ty, &mut used, &mut vec![]);
+        }
+        let roots =
+            lib.types().keys().filter(|name| !used.contains(name)).cloned().collect::<Vec<_>>();
+
+        let mut collector = Collector::default();
+        let mut max_size = Some(0usize);
+        for root in &roots {
+            self.accept(root, &mut collector)?;
+            let size = named_size(&lib, root, &mut vec![]);
+            max_size = max_size.zip(size).map(|(a, b)| a.max(b));
+        }
+
+        Ok(LibStats {
+            types: lib.types().len(),
+            roots: roots.len(),
+            max_depth: collector.max_depth,
+            recursive: collector.recursive.len(),
+            primitives: collector.primitives.len(),
+            max_size,
+        })
+    }
+}
+
+fn named_size(lib: &SymbolicLib, name: &TypeName, stack: &mut Vec<TypeName>) -> Option<usize> {
+    if stack.contains(name) {
+        return None;
+    }
+    let ty = lib.types().get(name).expect("inconsistent type library");
+    stack.push(name.clone());
+    let size = max_size(lib, ty, stack);
+    stack.pop();
+    size
+}
+
+fn ref_size(lib: &SymbolicLib, r: &TranspileRef, stack: &mut Vec<TypeName>) -> Option<usize> {
+    match r {
+        TranspileRef::Embedded(ty) => max_size(lib, ty, stack),
+        TranspileRef::Named(name) => named_size(lib, name, stack),
+        // definitions of the dependency types are not known
+        TranspileRef::Extern(_) => None,
+    }
+}
+
+fn max_size(lib: &SymbolicLib, ty: &Ty<TranspileRef>, stack: &mut Vec<TypeName>) -> Option<usize> {
+    let sum = |refs: Vec<&TranspileRef>, stack: &mut Vec<TypeName>| {
+        refs.into_iter().try_fold(0usize, |sum, r| sum.checked_add(ref_size(lib, r, stack)?))
+    };
+    match ty {
+        Ty::Primitive(prim) => Some(prim.byte_size() as usize),
+        Ty::UnicodeChar => Some(4),
+        Ty::Enum(_) => Some(1),
+        Ty::Union(variants) => {
+            let mut max = 0usize;
+            for r in variants.values() {
+                max = max.max(ref_size(lib, r, stack)?);
+            }
+            max.checked_add(1)
+        }
+        Ty::Tuple(fields) => sum(fields.iter().collect(), stack),
+        Ty::Struct(fields) => sum(fields.iter().map(|field| &field.ty).collect(), stack),
+        Ty::Array(r, len) => ref_size(lib, r, stack)?.checked_mul(*len as usize),
+        Ty::List(r, sizing) | Ty::Set(r, sizing) => usize::try_from(sizing.max)
+            .ok()
+            .and_then(|count| ref_size(lib, r, stack)?.checked_mul(count))
+            .and_then(|size| size.checked_add(len_prefix(*sizing))),
+        Ty::Map(key, value, sizing) => usize::try_from(sizing.max)
+            .ok()
+            .and_then(|count| sum(vec![key, value], stack)?.checked_mul(count))
+            .and_then(|size| size.checked_add(len_prefix(*sizing))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::TinyVec;
+
+    use super::*;
+    use crate::stl::std_stl;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    struct Item {
+        value: u16,
+        flag: Option<u8>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    struct Record {
+        items: TinyVec<Item>,
+        hash: [u8; 4],
+    }
+
+    #[test]
+    fn stats() {
+        let lib = LibBuilder::with(libname!("Test"), None)
+            .transpile::<Record>()
+            .transpile::<u64>()
+            .compile()
+            .unwrap();
+        let stats = lib.stats().unwrap();
+        assert_eq!(stats, LibStats {
+            types: 2,
+            roots: 1,
+            // items.item.flag.some.0
+            max_depth: 5,
+            recursive: 0,
+            // `U16`, `U8`, the unit of `none` and the `Byte` of the hash
+            primitives: 4,
+            // one byte of the length prefix, up to 255 items of 2 + 2 bytes, and the hash
+            max_size: Some(1 + 255 * 4 + 4),
+        });
+
+        let stats = std_stl().stats().unwrap();
+        assert_eq!(stats.types, std_stl().types.len());
+        assert!(stats.roots > 0);
+        assert_eq!(stats.max_size, Some(1));
+    }
+}
@@ -21,18 +21,22 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 
-use amplify::confinement::{Confined, NonEmptyOrdMap, SmallOrdMap, TinyOrdMap, TinyOrdSet};
-use amplify::ByteArray;
-use encoding::{LibName, LIB_EMBEDDED};
+use amplify::confinement::{
+    Confined, NonEmptyOrdMap, NonEmptyVec, SmallOrdMap, TinyOrdMap, TinyOrdSet,
+};
+use amplify::{ByteArray, Wrapper};
+use encoding::{FieldName, Ident, LibName, VariantName, LIB_EMBEDDED};
 use sha2::Digest;
-use strict_encoding::{StrictDumb, TypeName, STRICT_TYPES_LIB};
+use strict_encoding::{StrictDumb, StrictEncode, TypeName, STRICT_TYPES_LIB};
 
 use super::{LibBuilder, SymbolContext};
-use crate::ast::{PrimitiveRef, SemCommit, SEM_ID_TAG};
-use crate::typelib::{CompileError, ExternRef, NestedContext, SymbolError, TypeIndex, TypeMap};
+use crate::ast::{Field, NamedFields, PrimitiveRef, SemCommit, UnnamedFields, SEM_ID_TAG};
+use crate::typelib::{
+    CompileError, ExternRef, LibConstants, NestedContext, SymbolError, TypeIndex, TypeMap,
+};
 use crate::{Dependency, LibRef, SemId, Translate, Ty, TypeLib, TypeLibId, TypeRef};
 
 pub type ExternTypes = TinyOrdMap<LibName, SmallOrdMap<SemId, TypeName>>;
@@ -59,7 +63,7 @@ impl StrictDumb for SymbolicLib {
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Display)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = STRICT_TYPES_LIB)]
 #[display("{lib_name}.{ty_name}", alt = "{lib_name}.{ty_name}#{sem_id:#}")]
@@ -170,10 +174,11 @@ impl Display for TranspileRef {
 #[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
 #[display(doc_comments)]
 pub enum TranspileError {
-    /// type `{unknown}` referenced inside `{within}` is not known.
+    /// type `{unknown}` referenced inside `{within}` is not known{did_you_mean}
     UnknownType {
         unknown: TypeName,
         within: Ty<TranspileRef>,
+        did_you_mean: Suggestions,
     },
 
     /// unknown library `{0}` absent from dependencies.
@@ -190,25 +195,247 @@ pub enum TranspileError {
 
     /// library `{0}` used as a dependency doesn't provide type `{2}` with id {1}.
     DependencyMissesType(LibName, SemId, TypeName),
+
+    /// type with name `{0}` is already present in the library.
+    DuplicateName(TypeName),
+
+    /// instantiations of generic type `{0}` can't be given distinct names automatically; use
+    /// `LibBuilder::transpile_named` to name them.
+    GenericCollision(TypeName),
+
+    /// type `{ty}` has several fields named `{field}`.
+    DuplicateField { ty: String, field: FieldName },
+
+    /// type `{ty}` has several variants named `{variant}`.
+    DuplicateVariant { ty: String, variant: VariantName },
+
+    /// type `{name}` has no variants, thus it has no values which can be encoded.
+    UninhabitedType { name: String },
+
+    /// type `{0}` is a structure without fields, which is denied for the library.
+    EmptyStruct(TypeName),
+
+    /// type `{0}.{1}` is provided by dependencies under different ids {2} and {3}.
+    DependencyTypeMismatch(LibName, TypeName, SemId, SemId),
+
+    /// constant with name `{0}` is already present in the library.
+    DuplicateConstant(Ident),
+
+    /// value of constant `{0}` exceeds the maximal size of library constants.
+    ConstantTooLarge(Ident),
+
+    /// library has constants registered with `LibBuilder::transpile_value`, which would be
+    /// discarded; use `LibBuilder::compile_with_constants` to compile them.
+    ConstantsDiscarded,
+
+    /// dependency `{name}` is provided by several different libraries.
+    AmbiguousDependency { name: LibName, ids: Vec<TypeLibId> },
+
+    /// while transpiling {context}: {error}
+    InContext {
+        context: TranspileContext,
+        error: Box<TranspileError>,
+    },
+
+    /// multiple errors found in the library:{0}
+    Multiple(TranspileErrors),
+}
+
+impl TranspileError {
+    /// Returns the error without the context in which it has happened.
+    pub fn root(&self) -> &TranspileError {
+        match self {
+            TranspileError::InContext { error, .. } => error.root(),
+            error => error,
+        }
+    }
+}
+
+/// Names of the types being transpiled when an error has happened, starting from the outermost
+/// type.
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct TranspileContext(Vec<TypeName>);
+
+impl FromIterator<TypeName> for TranspileContext {
+    fn from_iter<T: IntoIterator<Item = TypeName>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Display for TranspileContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (no, name) in self.0.iter().enumerate() {
+            if no > 0 {
+                f.write_str(" -> ")?;
+            }
+            Display::fmt(name, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors found in a library, containing at least one error.
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, From)]
+#[wrapper(Deref)]
+pub struct TranspileErrors(NonEmptyVec<TranspileError>);
+
+impl TranspileErrors {
+    /// Returns the first found error.
+    pub fn into_first(self) -> TranspileError { self.0.release().remove(0) }
+}
+
+impl Display for TranspileErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for error in &self.0 {
+            write!(f, "\n- {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Names of the known types similar to the one which was not found.
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct Suggestions(Vec<TypeName>);
+
+impl Display for Suggestions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (no, name) in self.0.iter().enumerate() {
+            match no {
+                0 => write!(f, ", did you mean `{name}`")?,
+                _ => write!(f, " or `{name}`")?,
+            }
+        }
+        if !self.0.is_empty() {
+            f.write_str("?")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns up to three names from `known` which are close to `name` by their edit distance,
+/// starting from the closest ones.
+fn similar_names<'a>(name: &TypeName, known: impl Iterator<Item = &'a TypeName>) -> Suggestions {
+    let name = name.as_str();
+    let threshold = (name.len() / 3).max(1);
+    let mut similar = known
+        .map(|other| (edit_distance(name, other.as_str()), other))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect::<Vec<_>>();
+    similar.sort();
+    Suggestions(similar.into_iter().take(3).map(|(_, name)| name.clone()).collect())
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum RenameError {
+    /// type `{0}` is not present in the library.
+    UnknownType(TypeName),
+
+    /// type with name `{0}` is already present in the library.
+    DuplicateName(TypeName),
+
+    /// renamed library is inconsistent: {0}
+    #[from]
+    Inconsistent(SymbolError),
+
+    #[from]
+    #[display(inner)]
+    Compile(Box<CompileError>),
+}
+
+/// Errors extracting a library subset with [`TypeLib::subset`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum SubsetError {
+    /// none of the root types {0:?} are present in the library.
+    UnknownRoots(Vec<TypeName>),
+
+    /// library is inconsistent: {0}
+    #[from]
+    Inconsistent(SymbolError),
+
+    #[from]
+    #[display(inner)]
+    Compile(Box<CompileError>),
+}
+
+/// Errors inlining dependency types into a library with [`TypeLib::flatten_dependencies`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum FlattenError {
+    /// library {0} with id {1} is used, but not provided.
+    MissingDependency(LibName, TypeLibId),
+
+    /// library {0} doesn't define type `{1}`.
+    UnknownType(LibName, TypeName),
+
+    /// type `{1}` from library {0} can't be named by prefixing it with the library name.
+    InvalidName(LibName, TypeName),
+
+    /// type `{2}` inlined from library {0} collides with type `{1}`.
+    DuplicateName(LibName, TypeName, TypeName),
+
+    /// library {0} is inconsistent: {1}
+    Inconsistent(LibName, SymbolError),
+
+    #[from]
+    #[display(inner)]
+    Compile(CompileError),
 }
 
 impl LibBuilder {
+    /// Compiles transpiled types into a symbolic library.
+    ///
+    /// Library types are kept ordered by their names, thus neither the library nor its id
+    /// depend on the order in which the types were transpiled.
     pub fn compile_symbols(self) -> Result<SymbolicLib, TranspileError> {
+        self.collect_symbols().map_err(TranspileErrors::into_first)
+    }
+
+    /// Compiles library symbols like [`LibBuilder::compile_symbols`], but instead of returning
+    /// the first found error reports all of them as [`TranspileError::Multiple`].
+    pub fn try_compile_symbols(self) -> Result<SymbolicLib, TranspileError> {
+        self.collect_symbols().map_err(TranspileError::Multiple)
+    }
+
+    fn collect_symbols(self) -> Result<SymbolicLib, TranspileErrors> {
         assert!(
             !self.types.is_empty(),
             "library builder has no types; use `transpile` method to add types to it"
         );
 
-        let (name, known_libs, extern_types, types) =
-            (self.lib_name, self.known_libs, self.extern_types, self.types);
+        let (name, known_libs, extern_types, mut errors) =
+            (self.lib_name, self.known_libs, self.extern_types, self.errors);
+        if !self.constants.is_empty() {
+            errors.push(TranspileError::ConstantsDiscarded);
+        }
+        let types = self.types.into_iter().collect::<BTreeMap<_, _>>();
 
         for ty in types.values() {
             for (subty, _) in ty.type_refs() {
                 if let TranspileRef::Named(name) = subty {
                     if !types.contains_key(name) {
-                        return Err(TranspileError::UnknownType {
+                        errors.push(TranspileError::UnknownType {
                             unknown: name.clone(),
                             within: ty.clone(),
+                            did_you_mean: similar_names(name, types.keys()),
                         });
                     }
                 }
@@ -216,13 +443,14 @@ impl LibBuilder {
         }
 
         let mut used_dependencies = HashMap::<LibName, Dependency>::new();
+        let extern_types = extern_types.into_iter().collect::<BTreeMap<_, _>>();
         for lib in extern_types.keys() {
             if lib == &libname!(LIB_EMBEDDED) {
                 continue;
             }
             match known_libs.keys().find(|dep| &dep.name == lib) {
                 None if !used_dependencies.contains_key(lib) => {
-                    return Err(TranspileError::UnknownLib(lib.clone()));
+                    errors.push(TranspileError::UnknownLib(lib.clone()));
                 }
                 None => {}
                 Some(dep) => {
@@ -231,50 +459,329 @@ impl LibBuilder {
             }
         }
 
-        let types = Confined::try_from_iter(types).map_err(|_| TranspileError::TooManyTypes)?;
+        let types =
+            Confined::try_from(types).map_err(|_| errors.push(TranspileError::TooManyTypes)).ok();
         let mut type_map = BTreeMap::new();
         for (dep_name, dep_types) in extern_types {
+            let Some(dep) = used_dependencies.get(&dep_name) else {
+                // the library is either embedded or unknown, which was reported above
+                continue;
+            };
+            let Some(dependency_types) = known_libs.get(dep).expect("the presence checked above")
+            else {
+                eprintln!(
+                    "use of deprecated API for strict_types::LibBuilder put at risk of \
+                     non-determinism"
+                );
+                continue;
+            };
             for (sem_id, type_name) in &dep_types {
-                let Some(dependency_types) = used_dependencies
-                    .get(&dep_name)
-                    .and_then(|dep| known_libs.get(dep))
-                    .expect("the presence checked above")
-                else {
-                    eprintln!(
-                        "use of deprecated API for strict_types::LibBuilder put at risk of \
-                         non-determinism"
-                    );
-                    continue;
-                };
                 if !dependency_types.contains(sem_id) {
-                    return Err(TranspileError::DependencyMissesType(
-                        dep_name,
+                    errors.push(TranspileError::DependencyMissesType(
+                        dep_name.clone(),
                         *sem_id,
                         type_name.clone(),
                     ));
                 }
             }
-            let dep_types = SmallOrdMap::try_from_iter(dep_types)
-                .map_err(|_| TranspileError::LibTooLarge(dep_name.clone()))?;
-            type_map.insert(dep_name, dep_types);
+            match SmallOrdMap::try_from_iter(dep_types) {
+                Ok(dep_types) => {
+                    type_map.insert(dep_name, dep_types);
+                }
+                Err(_) => errors.push(TranspileError::LibTooLarge(dep_name)),
+            }
         }
-        let extern_types =
-            TinyOrdMap::try_from(type_map).map_err(|_| TranspileError::TooManyDependencies)?;
+        let extern_types = TinyOrdMap::try_from(type_map)
+            .map_err(|_| errors.push(TranspileError::TooManyDependencies))
+            .ok();
         let dependencies = TinyOrdSet::try_from_iter(used_dependencies.into_values())
-            .map_err(|_| TranspileError::TooManyDependencies)?;
+            .map_err(|_| errors.push(TranspileError::TooManyDependencies))
+            .ok();
 
-        Ok(SymbolicLib {
-            name,
-            extern_types,
-            dependencies,
-            types,
-        })
+        match (types, extern_types, dependencies) {
+            (Some(types), Some(extern_types), Some(dependencies)) if errors.is_empty() => {
+                Ok(SymbolicLib {
+                    name,
+                    extern_types,
+                    dependencies,
+                    types,
+                })
+            }
+            _ => {
+                let errors = NonEmptyVec::try_from(errors)
+                    .expect("library which can't be compiled always has errors reported");
+                Err(TranspileErrors(errors))
+            }
+        }
     }
 
+    /// Compiles transpiled types into a type library; see [`LibBuilder::compile_symbols`] for
+    /// the details on the type ordering.
     pub fn compile(self) -> Result<TypeLib, CompileError> { self.compile_symbols()?.compile() }
+
+    /// Compiles transpiled types into a type library, like [`LibBuilder::compile`], returning
+    /// also the constants registered with [`LibBuilder::transpile_value`]. The other compilation
+    /// methods fail with [`TranspileError::ConstantsDiscarded`] if the builder has constants.
+    pub fn compile_with_constants(mut self) -> Result<(TypeLib, LibConstants), CompileError> {
+        let constants = Confined::try_from(std::mem::take(&mut self.constants))
+            .map_err(|_| CompileError::TooManyConstants)?;
+        let lib = self.compile()?;
+        let constants = LibConstants {
+            lib_id: lib.id(),
+            constants,
+        };
+        Ok((lib, constants))
+    }
 }
 
 impl SymbolicLib {
+    /// Returns all types from the other libraries referenced by the library types, including
+    /// the references from the embedded types.
+    ///
+    /// The libraries which have to be provided to [`LibBuilder::with`] when the same types are
+    /// transpiled or compiled can be checked against this set before the compilation.
+    pub fn external_refs(&self) -> BTreeSet<SymbolRef> {
+        let mut names = vec![];
+        let mut externs = vec![];
+        for ty in self.types.values() {
+            collect_refs(ty, &mut names, &mut externs);
+        }
+        externs.into_iter().collect()
+    }
+
+    /// Renames library type `from` into `to`, updating all references to it from other types of
+    /// the library.
+    ///
+    /// Since the library id commits to the type names, the library compiled after the renaming
+    /// gets a different id.
+    pub fn rename_type(&mut self, from: &TypeName, to: TypeName) -> Result<(), RenameError> {
+        if !self.types.contains_key(from) {
+            return Err(RenameError::UnknownType(from.clone()));
+        }
+        if from == &to {
+            return Ok(());
+        }
+        if self.types.contains_key(&to) {
+            return Err(RenameError::DuplicateName(to));
+        }
+        let types = self
+            .types
+            .iter()
+            .map(|(name, ty)| {
+                let name = if name == from { to.clone() } else { name.clone() };
+                (name, rename_refs(ty.clone(), from, &to))
+            })
+            .collect::<BTreeMap<_, _>>();
+        self.types = Confined::from_checked(types);
+        Ok(())
+    }
+
+    /// Transpiles type `T` and adds it to the library together with all the types it depends
+    /// on, which are not yet present in the library.
+    ///
+    /// Types from the library dependencies can be used by `T` only if they are already used by
+    /// the library; otherwise, [`TranspileError::DependencyMissesType`] or
+    /// [`TranspileError::UnknownLib`] is returned, and the library should be re-built with
+    /// [`LibBuilder`] instead. See [`SymbolicLib::extend_types`] for the handling of duplicates.
+    pub fn extend<T: StrictEncode + StrictDumb>(&mut self) -> Result<(), TranspileError> {
+        let known_libs = self.dependencies.iter().map(|dep| {
+            let types = self.extern_types.get(&dep.name).into_iter().flatten();
+            (dep.clone(), types.map(|(sem_id, _)| *sem_id).collect::<HashSet<_>>())
+        });
+        let other =
+            LibBuilder::with(self.name.clone(), known_libs).transpile::<T>().compile_symbols()?;
+        // since the builder knows only the dependency types which are already used by the
+        // library, the external types of `other` are always a subset of ours
+        self.extend_types(other.types)
+    }
+
+    /// Adds types to the library.
+    ///
+    /// Types which are already present in the library under the same name with the same
+    /// definition are skipped, such that types shared by several added types don't conflict;
+    /// for other name collisions [`TranspileError::DuplicateName`] is returned. All named types
+    /// referenced by the added ones must be either present in the library or added together
+    /// with them. The library is left unchanged if an error happens.
+    pub fn extend_types(
+        &mut self,
+        types: impl IntoIterator<Item = (TypeName, Ty<TranspileRef>)>,
+    ) -> Result<(), TranspileError> {
+        let mut all =
+            self.types.iter().map(|(n, ty)| (n.clone(), ty.clone())).collect::<BTreeMap<_, _>>();
+        let mut added = vec![];
+        for (name, ty) in types {
+            match all.get(&name) {
+                Some(existing) if existing == &ty => continue,
+                Some(_) => return Err(TranspileError::DuplicateName(name)),
+                None => {}
+            }
+            all.insert(name.clone(), ty.clone());
+            added.push(ty);
+        }
+        for ty in &added {
+            for (subty, _) in ty.type_refs() {
+                if let TranspileRef::Named(name) = subty {
+                    if !all.contains_key(name) {
+                        return Err(TranspileError::UnknownType {
+                            unknown: name.clone(),
+                            within: ty.clone(),
+                            did_you_mean: similar_names(name, all.keys()),
+                        });
+                    }
+                }
+            }
+        }
+        self.types = Confined::try_from(all).map_err(|_| TranspileError::TooManyTypes)?;
+        Ok(())
+    }
+
+    /// Returns a library with only those types which are reachable from the `roots`, keeping
+    /// references only to the used types from the dependencies. Roots which are not a part of the
+    /// library are ignored.
+    ///
+    /// Returns `None` if none of the roots are the library types.
+    pub(super) fn subset(&self, roots: &[TypeName]) -> Option<SymbolicLib> {
+        let mut types = BTreeMap::new();
+        let mut externs = vec![];
+        let mut queue = roots.to_vec();
+        while let Some(name) = queue.pop() {
+            if types.contains_key(&name) {
+                continue;
+            }
+            let Some(ty) = self.types.get(&name) else {
+                continue;
+            };
+            collect_refs(ty, &mut queue, &mut externs);
+            types.insert(name, ty.clone());
+        }
+
+        let mut extern_types = ExternTypes::new();
+        for sym in externs {
+            let lib = extern_types.entry(sym.lib_name).expect("subset of the library externals");
+            let ids = lib.or_default();
+            ids.insert(sym.sem_id, sym.ty_name).expect("subset of the library externals");
+        }
+        let dependencies =
+            self.dependencies.iter().filter(|dep| extern_types.contains_key(&dep.name)).cloned();
+
+        Some(SymbolicLib {
+            name: self.name.clone(),
+            dependencies: Confined::from_iter_checked(dependencies),
+            extern_types,
+            types: Confined::try_from(types).ok()?,
+        })
+    }
+
+    /// Returns a library without dependencies, where all types used from the dependencies are
+    /// copied into the library under the names prefixed with their library names.
+    pub(super) fn flatten(&self, deps: &[&TypeLib]) -> Result<SymbolicLib, FlattenError> {
+        let libs = deps
+            .iter()
+            .map(|lib| {
+                let sym = lib
+                    .to_symbolic()
+                    .map_err(|err| FlattenError::Inconsistent(lib.name.clone(), err))?;
+                Ok((lib.id(), sym))
+            })
+            .collect::<Result<HashMap<_, _>, FlattenError>>()?;
+
+        let mut types = BTreeMap::new();
+        let mut inlined = BTreeMap::<(TypeLibId, TypeName), TypeName>::new();
+        let mut queue = self.types.keys().map(|name| (None, name.clone())).collect::<Vec<_>>();
+        while let Some((lib_id, name)) = queue.pop() {
+            let (ty, local) = match lib_id {
+                None => (&self.types[&name], name.clone()),
+                Some(id) => {
+                    let lib = &libs[&id];
+                    let ty = lib
+                        .types
+                        .get(&name)
+                        .ok_or_else(|| FlattenError::UnknownType(lib.name.clone(), name.clone()))?;
+                    (ty, inlined[&(id, name.clone())].clone())
+                }
+            };
+
+            let mut names = vec![];
+            let mut externs = vec![];
+            collect_refs(ty, &mut names, &mut externs);
+            let used = names
+                .into_iter()
+                .filter_map(|name| lib_id.map(|id| (id, libs[&id].name.clone(), name)))
+                .chain(externs.into_iter().map(|sym| (sym.lib_id, sym.lib_name, sym.ty_name)));
+            for (id, lib_name, name) in used {
+                if inlined.contains_key(&(id, name.clone())) {
+                    continue;
+                }
+                let lib = libs.get(&id).ok_or(FlattenError::MissingDependency(lib_name, id))?;
+                let local = TypeName::try_from(format!("{}{name}", lib.name))
+                    .map_err(|_| FlattenError::InvalidName(lib.name.clone(), name.clone()))?;
+                if self.types.contains_key(&local) || inlined.values().any(|n| n == &local) {
+                    return Err(FlattenError::DuplicateName(lib.name.clone(), name, local));
+                }
+                inlined.insert((id, name.clone()), local);
+                queue.push((Some(id), name));
+            }
+
+            let ty = map_refs(ty.clone(), &|r| match r {
+                TranspileRef::Named(name) => match lib_id {
+                    None => TranspileRef::Named(name),
+                    Some(id) => TranspileRef::Named(inlined[&(id, name)].clone()),
+                },
+                TranspileRef::Extern(sym) => {
+                    TranspileRef::Named(inlined[&(sym.lib_id, sym.ty_name)].clone())
+                }
+                r => r,
+            });
+            types.insert(local, ty);
+        }
+
+        Ok(SymbolicLib {
+            name: self.name.clone(),
+            dependencies: none!(),
+            extern_types: none!(),
+            types: Confined::try_from(types).map_err(|_| CompileError::TooManyTypes)?,
+        })
+    }
+
+    /// Returns names of the library types in the canonical order: each type follows all the
+    /// library types it references, while the types which may be placed at the same step are
+    /// ordered lexicographically. Cycles of recursive types go after the other cycles they
+    /// reference, and each cycle is entered at its lexicographically first type.
+    pub(super) fn canonical_order(&self) -> Vec<TypeName> {
+        let mut deps = self
+            .types
+            .iter()
+            .map(|(name, ty)| {
+                let mut names = vec![];
+                collect_refs(ty, &mut names, &mut vec![]);
+                let names = names.into_iter().filter(|n| n != name).collect::<BTreeSet<_>>();
+                (name.clone(), names)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let mut order = Vec::with_capacity(deps.len());
+        while !deps.is_empty() {
+            let mut ready = deps
+                .iter()
+                .filter(|(_, refs)| refs.is_empty())
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            if ready.is_empty() {
+                // all the remaining types are recursive or reference recursive types
+                ready.push(cycle_entry(&deps).clone());
+            }
+            for name in &ready {
+                deps.remove(name);
+            }
+            for refs in deps.values_mut() {
+                for name in &ready {
+                    refs.remove(name);
+                }
+            }
+            order.extend(ready);
+        }
+        order
+    }
+
     pub fn compile(self) -> Result<TypeLib, CompileError> {
         let name = self.name;
         let dependencies = self.dependencies;
@@ -313,7 +820,11 @@ impl SymbolicLib {
                 new_types.insert(name.clone(), ty);
                 old_types.remove(name);
             }
-            debug_assert!(found, "incomplete type definition found in the library");
+            if !found {
+                // the remaining types are only blocked by references to each other
+                let name = old_types.keys().next().expect("non-empty").clone();
+                return Err(CompileError::RecursiveType(name));
+            }
         }
 
         let mut used_dependencies = BTreeSet::<Dependency>::new();
@@ -344,6 +855,149 @@ impl SymbolicLib {
     }
 }
 
+/// Collects names of the library types and external types referenced by `ty`, including the
+/// references from the types embedded into it.
+/// Returns the lexicographically first type among the cycles of the `graph` which don't reference
+/// types outside of their cycle.
+///
+/// The cycles are found as the strongly connected components of the graph with Tarjan's
+/// algorithm. The graph must have no types without references, thus each type either is a part
+/// of some cycle or references one.
+fn cycle_entry(graph: &BTreeMap<TypeName, BTreeSet<TypeName>>) -> &TypeName {
+    struct Visit {
+        index: usize,
+        lowlink: usize,
+    }
+
+    let mut visits = BTreeMap::<&TypeName, Visit>::new();
+    let mut stack = Vec::<&TypeName>::new();
+    let mut on_stack = BTreeSet::<&TypeName>::new();
+    let mut entry = None::<&TypeName>;
+
+    for root in graph.keys() {
+        if visits.contains_key(root) {
+            continue;
+        }
+        let mut path = vec![(root, graph[root].iter())];
+        visits.insert(root, Visit {
+            index: visits.len(),
+            lowlink: visits.len(),
+        });
+        stack.push(root);
+        on_stack.insert(root);
+        while let Some((node, refs)) = path.last_mut() {
+            let node = *node;
+            if let Some(next) = refs.next() {
+                let Some(next_refs) = graph.get(next) else {
+                    continue;
+                };
+                match visits.get(next) {
+                    None => {
+                        let index = visits.len();
+                        visits.insert(next, Visit {
+                            index,
+                            lowlink: index,
+                        });
+                        stack.push(next);
+                        on_stack.insert(next);
+                        path.push((next, next_refs.iter()));
+                    }
+                    Some(visit) if on_stack.contains(next) => {
+                        let index = visit.index;
+                        let visit = visits.get_mut(node).expect("visited");
+                        visit.lowlink = visit.lowlink.min(index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+            path.pop();
+            let Visit { index, lowlink } = visits[node];
+            if let Some((parent, _)) = path.last() {
+                let visit = visits.get_mut(*parent).expect("visited");
+                visit.lowlink = visit.lowlink.min(lowlink);
+            }
+            if index != lowlink {
+                continue;
+            }
+            let pos = stack.iter().rposition(|n| *n == node).expect("node on stack");
+            let component = stack.split_off(pos).into_iter().collect::<BTreeSet<_>>();
+            for name in &component {
+                on_stack.remove(name);
+            }
+            let closed = component.iter().all(|name| {
+                graph[*name].iter().all(|r| component.contains(r) || !graph.contains_key(r))
+            });
+            if closed {
+                let first = *component.first().expect("non-empty component");
+                entry = Some(entry.map_or(first, |entry| entry.min(first)));
+            }
+        }
+    }
+    entry.expect("graph without types")
+}
+
+pub(super) fn collect_refs(
+    ty: &Ty<TranspileRef>,
+    names: &mut Vec<TypeName>,
+    externs: &mut Vec<SymbolRef>,
+) {
+    for (subty, _) in ty.type_refs() {
+        match subty {
+            TranspileRef::Embedded(ty) => collect_refs(ty, names, externs),
+            TranspileRef::Named(name) => names.push(name.clone()),
+            TranspileRef::Extern(sym) => {
+                externs.push(sym.clone());
+            }
+        }
+    }
+}
+
+fn rename_refs(ty: Ty<TranspileRef>, from: &TypeName, to: &TypeName) -> Ty<TranspileRef> {
+    map_refs(ty, &|r| match r {
+        TranspileRef::Named(name) if &name == from => TranspileRef::Named(to.clone()),
+        r => r,
+    })
+}
+
+/// Replaces named and extern references inside the type and its embedded types using `f`.
+fn map_refs(ty: Ty<TranspileRef>, f: &impl Fn(TranspileRef) -> TranspileRef) -> Ty<TranspileRef> {
+    let rename = |r: TranspileRef| match r {
+        TranspileRef::Embedded(ty) => TranspileRef::Embedded(Box::new(map_refs(*ty, f))),
+        r => f(r),
+    };
+    match ty {
+        Ty::Union(variants) => {
+            let variants = variants
+                .into_iter()
+                .map(|(variant, ty)| (variant, rename(ty)))
+                .collect::<BTreeMap<_, _>>();
+            Ty::Union(Confined::from_checked(variants).into())
+        }
+        Ty::Struct(fields) => {
+            let fields = fields
+                .into_iter()
+                .map(|field| Field {
+                    name: field.name,
+                    ty: rename(field.ty),
+                })
+                .collect::<Vec<_>>();
+            Ty::Struct(NamedFields::try_from(fields).expect("re-packing existing fields structure"))
+        }
+        Ty::Tuple(fields) => {
+            let fields = fields.into_iter().map(rename).collect::<Vec<_>>();
+            Ty::Tuple(
+                UnnamedFields::try_from(fields).expect("re-packing existing fields structure"),
+            )
+        }
+        Ty::Array(ty, len) => Ty::Array(rename(ty), len),
+        Ty::List(ty, sizing) => Ty::List(rename(ty), sizing),
+        Ty::Set(ty, sizing) => Ty::Set(rename(ty), sizing),
+        Ty::Map(key, ty, sizing) => Ty::Map(rename(key), rename(ty), sizing),
+        ty @ (Ty::Primitive(_) | Ty::UnicodeChar | Ty::Enum(_)) => ty,
+    }
+}
+
 impl TypeLib {
     pub fn to_symbolic(&self) -> Result<SymbolicLib, SymbolError> {
         let lib_index = self.dependencies.iter().map(|dep| (dep.id, dep.name.clone())).collect();
@@ -371,3 +1025,73 @@ impl TypeLib {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use encoding::Sizing;
+
+    use super::*;
+
+    #[test]
+    fn unknown_type_suggestions() {
+        let mut builder = LibBuilder::with(libname!("Test"), None);
+        let within = Ty::List(TranspileRef::Named(tn!("Txin")), Sizing::U16);
+        builder.types.insert(tn!("TxIn"), Ty::U32);
+        builder.types.insert(tn!("TxOut"), Ty::U64);
+        builder.types.insert(tn!("Tx"), within.clone());
+
+        let err = builder.compile_symbols().unwrap_err();
+        assert_eq!(err, TranspileError::UnknownType {
+            unknown: tn!("Txin"),
+            within,
+            did_you_mean: Suggestions::from(vec![tn!("TxIn")]),
+        });
+        assert_eq!(
+            err.to_string(),
+            "type `Txin` referenced inside `[Txin]` is not known, did you mean `TxIn`?"
+        );
+
+        let names = [tn!("Abc"), tn!("Abd"), tn!("Abe"), tn!("Abf"), tn!("Xyz")];
+        assert_eq!(similar_names(&tn!("Abx"), names.iter()).len(), 3);
+        assert!(similar_names(&tn!("Other"), names.iter()).is_empty());
+    }
+
+    #[test]
+    fn canonical_order() {
+        let mut builder = LibBuilder::with(libname!("Test"), None);
+        let named = |name: &'static str| TranspileRef::Named(TypeName::from(name));
+        builder.types.insert(tn!("Tx"), Ty::List(named("TxOut"), Sizing::U16));
+        builder.types.insert(tn!("TxOut"), Ty::U64);
+        builder.types.insert(tn!("Block"), Ty::List(named("Tx"), Sizing::U16));
+        builder.types.insert(tn!("Amount"), Ty::U64);
+        builder.types.insert(tn!("Header"), Ty::Array(named("Amount"), 2));
+        builder.types.insert(tn!("Node"), Ty::List(named("Nodes"), Sizing::U8));
+        builder.types.insert(tn!("Nodes"), Ty::List(named("Node"), Sizing::U8));
+        let lib = builder.compile_symbols().unwrap();
+        assert_eq!(lib.canonical_order(), vec![
+            tn!("Amount"),
+            tn!("TxOut"),
+            tn!("Header"),
+            tn!("Tx"),
+            tn!("Block"),
+            tn!("Node"),
+            tn!("Nodes")
+        ]);
+
+        // a type referencing a cycle goes after it, as well as a cycle referencing other cycle
+        let mut builder = LibBuilder::with(libname!("Test"), None);
+        builder.types.insert(tn!("Forest"), Ty::List(named("Tree"), Sizing::U8));
+        builder.types.insert(tn!("Tree"), Ty::List(named("Trees"), Sizing::U8));
+        builder.types.insert(tn!("Trees"), Ty::List(named("Tree"), Sizing::U8));
+        builder.types.insert(tn!("Alpha"), Ty::Map(named("Beta"), named("Forest"), Sizing::U8));
+        builder.types.insert(tn!("Beta"), Ty::List(named("Alpha"), Sizing::U8));
+        let lib = builder.compile_symbols().unwrap();
+        assert_eq!(lib.canonical_order(), vec![
+            tn!("Tree"),
+            tn!("Forest"),
+            tn!("Trees"),
+            tn!("Alpha"),
+            tn!("Beta")
+        ]);
+    }
+}
@@ -22,24 +22,67 @@
 // the License.
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::io;
 use std::io::Sink;
+use std::{any, io, mem};
 
-use amplify::confinement::{Confined, NonEmptyVec, U64 as U64MAX};
+use amplify::confinement::{Confined, NonEmptyVec, SmallBlob, U64 as U64MAX};
 use amplify::Wrapper;
 use encoding::StreamWriter;
 use strict_encoding::{
-    DefineEnum, DefineStruct, DefineTuple, DefineUnion, FieldName, LibName, Primitive, Sizing,
-    SplitParent, StrictDumb, StrictEncode, StrictEnum, StrictParent, StrictStruct, StrictSum,
-    StrictTuple, StrictUnion, StrictWriter, StructWriter, TypeName, TypedParent, TypedWrite,
-    UnionWriter, VariantName, WriteEnum, WriteStruct, WriteTuple, WriteUnion, LIB_EMBEDDED,
+    DefineEnum, DefineStruct, DefineTuple, DefineUnion, FieldName, Ident, LibName, Primitive,
+    Sizing, SplitParent, StrictDumb, StrictEncode, StrictEnum, StrictParent, StrictStruct,
+    StrictSum, StrictTuple, StrictType, StrictUnion, StrictWriter, StructWriter, TypeName,
+    TypedParent, TypedWrite, UnionWriter, VariantName, WriteEnum, WriteStruct, WriteTuple,
+    WriteUnion, LIB_EMBEDDED,
 };
 
 use crate::ast::{EnumVariants, Field, NamedFields, UnionVariants, UnnamedFields};
-use crate::{Dependency, SemId, SymbolRef, TranspileRef, Ty, TypeLibId};
+use crate::{
+    Dependency, SemId, SymbolRef, TranspileError, TranspileRef, Ty, TypeLib, TypeLibId, TypeRef,
+};
 
 const MAX_WRITE_COUNT: usize = U64MAX;
 
+/// Returns the name of the Rust type `T`, ignoring references.
+///
+/// The name is used only as a key recognizing the types which are already transpiled or are being
+/// transpiled, since `TypeId` requires the types to be `'static`. It never affects the names of
+/// the library types, which are derived from the strict names and the type definitions.
+fn rust_type<T>() -> &'static str { any::type_name::<T>().trim_start_matches('&') }
+
+/// Constructs a type name suffix for an instantiation of a generic type with definition `ty`,
+/// joining the names of the types it uses instead of the types used by other instantiation with
+/// definition `other`.
+fn generic_suffix(ty: &Ty<TranspileRef>, other: &Ty<TranspileRef>) -> String {
+    let mut suffix = s!("");
+    push_arg_names(ty, Some(other), &mut suffix);
+    suffix
+}
+
+fn push_arg_names(ty: &Ty<TranspileRef>, other: Option<&Ty<TranspileRef>>, suffix: &mut String) {
+    // definitions of different kinds don't match field by field
+    let other = other.filter(|other| {
+        mem::discriminant(*other) == mem::discriminant(ty)
+            && other.type_refs().count() == ty.type_refs().count()
+    });
+    let mut other_refs = other.map(Ty::type_refs);
+    for (r, _) in ty.type_refs() {
+        let other = other_refs.as_mut().and_then(Iterator::next).map(|(r, _)| r);
+        if other == Some(r) {
+            continue;
+        }
+        match r {
+            TranspileRef::Named(name) => suffix.push_str(name.as_str()),
+            TranspileRef::Extern(ext) => suffix.push_str(ext.ty_name.as_str()),
+            TranspileRef::Embedded(inner) => match inner.as_ref() {
+                Ty::Primitive(Primitive::UNIT) => suffix.push_str("Unit"),
+                Ty::Primitive(_) | Ty::UnicodeChar => suffix.push_str(&inner.to_string()),
+                inner => push_arg_names(inner, other.and_then(TypeRef::as_ty), suffix),
+            },
+        }
+    }
+}
+
 pub trait BuilderParent: StrictParent<StreamWriter<Sink>> {
     /// Converts strict-encodable value into a type information. Must be propagated back to the
     /// lib builder which does the TypedWrite implementation to call strict encode on the type
@@ -47,16 +90,36 @@ pub trait BuilderParent: StrictParent<StreamWriter<Sink>> {
     /// Notifies lib builder about complete type built, even for unnamed inline types, such that it
     /// can register last compiled type for the `compile_type` procedure.
     fn report_compiled(self, lib: LibName, name: Option<TypeName>, ty: Ty<TranspileRef>) -> Self;
+    /// Notifies lib builder about an error in the type definition, which is reported once the
+    /// library is compiled.
+    fn report_error(self, error: TranspileError) -> Self;
 }
 
+// TODO: Carry type and field documentation through the transpilation. This requires the
+//       `StrictType` derive from `rgb-strict-encoding` to expose `///` doc comments, which it
+//       doesn't do yet; the documentation would then be kept with `TypeSymbol` outside of its
+//       strict encoding, so that it doesn't affect type library and type system ids.
 #[derive(Debug)]
 pub struct LibBuilder {
     pub(super) lib_name: LibName,
     pub(super) known_libs: HashMap<Dependency, Option<HashSet<SemId>>>,
     pub(super) extern_types: HashMap<LibName, BTreeMap<SemId, TypeName>>,
     pub(super) types: HashMap<TypeName, Ty<TranspileRef>>,
+    pub(super) constants: BTreeMap<Ident, SmallBlob>,
+    pub(super) errors: Vec<TranspileError>,
+    dependency_types: HashMap<LibName, HashMap<TypeName, SemId>>,
     sink: StreamWriter<Sink>,
     last_compiled: Option<TranspileRef>,
+    rename: Option<(Option<TypeName>, TypeName)>,
+    /// Named types which are being transpiled, with their library, Rust type name, the name they
+    /// are registered under and whether they were referenced by this name before their definition
+    /// was complete. Used to detect recursive types of this library and to provide context for
+    /// the errors.
+    in_progress: Vec<(LibName, &'static str, TypeName, bool)>,
+    /// Names of the transpiled library types by their Rust type names, such that repeated uses of
+    /// a type are not transpiled again.
+    instances: HashMap<&'static str, TypeName>,
+    deny_empty_structs: bool,
 }
 
 impl LibBuilder {
@@ -74,8 +137,15 @@ impl LibBuilder {
             known_libs: known_libs.into_iter().map(|d| (d, None)).collect(),
             extern_types: empty!(),
             types: empty!(),
+            constants: empty!(),
+            errors: empty!(),
+            dependency_types: empty!(),
             sink: StreamWriter::sink::<MAX_WRITE_COUNT>(),
             last_compiled: None,
+            rename: None,
+            in_progress: empty!(),
+            instances: empty!(),
+            deny_empty_structs: false,
         }
     }
 
@@ -83,20 +153,294 @@ impl LibBuilder {
         name: impl Into<LibName>,
         known_libs: impl IntoIterator<Item = (Dependency, HashSet<SemId>)>,
     ) -> LibBuilder {
-        LibBuilder {
+        let mut builder = LibBuilder {
             lib_name: name.into(),
             known_libs: known_libs.into_iter().map(|(d, types)| (d, Some(types))).collect(),
             extern_types: empty!(),
             types: empty!(),
+            constants: empty!(),
+            errors: empty!(),
+            dependency_types: empty!(),
             sink: StreamWriter::sink::<MAX_WRITE_COUNT>(),
             last_compiled: None,
+            rename: None,
+            in_progress: empty!(),
+            instances: empty!(),
+            deny_empty_structs: false,
+        };
+        builder.check_ambiguous();
+        builder
+    }
+
+    /// Reports dependencies with the same name but different ids, which can't be told apart
+    /// when the types of the library refer to them by the library name.
+    fn check_ambiguous(&mut self) {
+        let mut ids = BTreeMap::<LibName, BTreeSet<TypeLibId>>::new();
+        for dep in self.known_libs.keys() {
+            ids.entry(dep.name.clone()).or_default().insert(dep.id);
+        }
+        for (name, ids) in ids {
+            if ids.len() > 1 {
+                let ids = ids.into_iter().collect();
+                self.push_error(TranspileError::AmbiguousDependency { name, ids });
+            }
+        }
+    }
+
+    /// Adds library `lib` as a dependency, together with those of its own dependencies which are
+    /// used by the types from `lib`.
+    ///
+    /// Types shared by several dependencies are merged. If the same type name of a library is
+    /// provided by different dependencies under different semantic ids, the
+    /// [`TranspileError::DependencyTypeMismatch`] is reported during library compilation. Two
+    /// different libraries with the same name, like two versions of a library used by different
+    /// dependencies, are reported as [`TranspileError::AmbiguousDependency`].
+    pub fn with_dependency(mut self, lib: &TypeLib) -> Self {
+        let types = lib.types.iter().map(|(name, ty)| (ty.sem_id_named(name), name.clone()));
+        self.add_dependency(lib.to_dependency(), types);
+        for dep in &lib.dependencies {
+            let types = lib
+                .extern_types
+                .get(&dep.name)
+                .into_iter()
+                .flatten()
+                .map(|(sem_id, name)| (*sem_id, name.clone()));
+            self.add_dependency(dep.clone(), types);
         }
+        self
     }
 
+    fn add_dependency(
+        &mut self,
+        dependency: Dependency,
+        types: impl IntoIterator<Item = (SemId, TypeName)>,
+    ) {
+        let known = self.known_libs.keys().find(|dep| dep.name == dependency.name).cloned();
+        if let Some(dep) = known.as_ref().filter(|dep| dep.id != dependency.id) {
+            let mut ids = vec![dep.id, dependency.id];
+            ids.sort();
+            let name = dependency.name.clone();
+            self.push_error(TranspileError::AmbiguousDependency { name, ids });
+        }
+        let known = match known {
+            Some(dep) => self.known_libs.get_mut(&dep).expect("key is just found"),
+            None => self.known_libs.entry(dependency.clone()).or_insert_with(|| Some(empty!())),
+        };
+        let names = self.dependency_types.entry(dependency.name.clone()).or_default();
+        for (sem_id, name) in types {
+            match names.get(&name) {
+                Some(id) if *id != sem_id => {
+                    self.errors.push(TranspileError::DependencyTypeMismatch(
+                        dependency.name.clone(),
+                        name,
+                        *id,
+                        sem_id,
+                    ));
+                    continue;
+                }
+                Some(_) => {}
+                None => {
+                    names.insert(name, sem_id);
+                }
+            }
+            // Libraries added with the deprecated constructor accept any type
+            if let Some(known) = known {
+                known.insert(sem_id);
+            }
+        }
+    }
+
+    /// Transpiles type `T` and all types it depends on into the library.
+    ///
+    /// The type definition is produced by the `StrictEncode` implementation of `T`, and the type,
+    /// field and variant names are the strict names reported by its `StrictType` implementation.
+    /// See the [module documentation](crate::typelib#transpiling-rust-types) for how skipped
+    /// fields, renames, generic types, options, tuples and recursive types are transpiled.
     pub fn transpile<T: StrictEncode + StrictDumb>(self) -> Self {
         T::strict_dumb().strict_encode(self).expect("memory encoding doesn't error")
     }
 
+    /// Transpiles type `T` and registers strict-encoded `value` as a library constant `name`,
+    /// which is returned by [`LibBuilder::compile_with_constants`] as a part of
+    /// [`LibConstants`](crate::typelib::LibConstants); the other compilation methods fail with
+    /// [`TranspileError::ConstantsDiscarded`] for libraries with constants.
+    ///
+    /// The constants are kept apart from the library and don't affect its id, since storing them
+    /// in [`TypeLib`](crate::TypeLib) would change its strict encoding, and thus the ids of all
+    /// the already published libraries. Instead, the constants commit to the library id.
+    ///
+    /// If the `name` is already taken by other constant, the [`TranspileError::DuplicateConstant`]
+    /// is reported during library compilation.
+    pub fn transpile_value<T: StrictEncode + StrictDumb>(
+        mut self,
+        name: impl Into<Ident>,
+        value: T,
+    ) -> Self {
+        let name = name.into();
+        self = self.transpile::<T>();
+        if self.constants.contains_key(&name) {
+            self.errors.push(TranspileError::DuplicateConstant(name));
+            return self;
+        }
+        let writer = StrictWriter::in_memory::<{ u16::MAX as usize }>();
+        let data = value
+            .strict_encode(writer)
+            .ok()
+            .and_then(|writer| SmallBlob::try_from(writer.unbox().unconfine()).ok());
+        match data {
+            Some(data) => {
+                self.constants.insert(name, data);
+            }
+            None => self.errors.push(TranspileError::ConstantTooLarge(name)),
+        }
+        self
+    }
+
+    /// Transpiles type `T` registering it under an explicit `name` instead of the one provided by
+    /// its [`StrictType`] implementation. Nested types keep their own names.
+    ///
+    /// Useful for generic types, where different instantiations share the same strict name. If
+    /// the `name` is already taken by other type in the library, the
+    /// [`TranspileError::DuplicateName`] is reported during library compilation.
+    ///
+    /// Unnamed types, like primitives, get a library type with the same definition, which works
+    /// as a named alias: `transpile_named::<u64>(tn!("Sats"))` defines `Sats` as `U64`, with the
+    /// same encoding and memory layout, but its own semantic id and name reported by the layout
+    /// items. Rust type aliases are transparent, thus the fields declared with an alias still
+    /// reference the target type; use a newtype for the fields to reference the named type.
+    ///
+    /// Conversion of a static string into a [`TypeName`] panics if the string is not a valid
+    /// identifier; names coming from external sources should be checked with
+    /// `TypeName::try_from` first. Type, field and variant names provided by the [`StrictType`]
+    /// implementations are validated even before the transpilation, when they are constructed.
+    pub fn transpile_named<T: StrictEncode + StrictDumb>(
+        mut self,
+        name: impl Into<TypeName>,
+    ) -> Self {
+        let name = name.into();
+        if self.types.contains_key(&name) {
+            self.errors.push(TranspileError::DuplicateName(name));
+            return self;
+        }
+        let own_name = T::strict_name().filter(|_| T::STRICT_LIB_NAME == self.lib_name.as_str());
+        self.rename = Some((own_name, name));
+        self = self.transpile::<T>();
+        // The type was not defined by this library (it is unnamed or foreign), so we register the
+        // compiled type under the requested name directly
+        if let Some((_, name)) = self.rename.take() {
+            let ty = match self.last_compiled.clone().expect("no type found after transpiling") {
+                TranspileRef::Embedded(ty) => *ty,
+                r => Ty::Tuple(UnnamedFields::from_inner(NonEmptyVec::with(r))),
+            };
+            self.types.insert(name.clone(), ty);
+            self.last_compiled = Some(TranspileRef::Named(name));
+        }
+        self
+    }
+
+    /// Applies type registrations, like `LibBuilder::transpile::<T>`, collected from different
+    /// sources, in the order they are provided.
+    ///
+    /// For a list of types known at compile time use [`transpile_all!`](crate::transpile_all)
+    /// instead.
+    pub fn transpile_dyn(self, registrars: &[&dyn Fn(LibBuilder) -> LibBuilder]) -> Self {
+        registrars.iter().fold(self, |builder, registrar| registrar(builder))
+    }
+
+    /// Makes the library compilation fail with [`TranspileError::EmptyStruct`] for the library
+    /// types which are structures or tuples without fields.
+    ///
+    /// Such types encode as the unit type and are accepted by default, since they are commonly
+    /// used as markers.
+    pub fn deny_empty_structs(mut self) -> Self {
+        self.deny_empty_structs = true;
+        self
+    }
+
+    /// Returns names of all types which are already registered in the library, in arbitrary
+    /// order.
+    ///
+    /// These include both the types transpiled directly and the ones they depend on, which are
+    /// transpiled together with them. Types provided by the library dependencies are not a part of
+    /// the library and are not returned.
+    pub fn known_types(&self) -> impl Iterator<Item = &TypeName> { self.types.keys() }
+
+    /// Checks whether a type with the `name` is already registered in the library, either by
+    /// being transpiled directly or as a dependency of some other transpiled type.
+    pub fn contains(&self, name: &TypeName) -> bool { self.types.contains_key(name) }
+
+    /// Returns name under which type `T` must be registered, taking into account the name
+    /// requested via [`LibBuilder::transpile_named`].
+    fn strict_name<T: StrictType>(&mut self) -> Option<TypeName> {
+        let name = T::strict_name();
+        if T::STRICT_LIB_NAME != self.lib_name.as_str() {
+            return name;
+        }
+        match (&self.rename, name) {
+            (Some((Some(from), _)), Some(name)) if from == &name => {
+                self.rename.take().map(|(_, to)| to)
+            }
+            (_, name) => name,
+        }
+    }
+
+    /// Returns name under which a type of this library with the strict `name` and definition `ty`
+    /// is registered, or `None` if it can't be registered.
+    ///
+    /// If the name is taken by a type with other definition, which happens for instantiations of
+    /// a generic type sharing the same strict name, the names of the types used by the
+    /// instantiation are appended to the name. [`TranspileError::GenericCollision`] is reported if
+    /// such name is taken as well, or if the type is `recursive` and thus already refers to itself
+    /// by the strict name.
+    fn instance_name(
+        &mut self,
+        name: TypeName,
+        ty: &Ty<TranspileRef>,
+        recursive: bool,
+    ) -> Option<TypeName> {
+        let Some(other) = self.types.get(&name).filter(|other| *other != ty) else {
+            return Some(name);
+        };
+        let instance = TypeName::try_from(format!("{name}{}", generic_suffix(ty, other)))
+            .ok()
+            .filter(|instance| self.types.get(instance).is_none_or(|other| other == ty));
+        match instance {
+            Some(instance) if !recursive => Some(instance),
+            _ => {
+                self.push_error(TranspileError::GenericCollision(name));
+                None
+            }
+        }
+    }
+
+    /// Marks type `T`, registered under `name`, as being transpiled, such that recursive uses of
+    /// it refer to the type by its name instead of transpiling it again.
+    fn enter<T: StrictType>(&mut self, name: &Option<TypeName>) {
+        if let Some(name) = name {
+            let lib = libname!(T::STRICT_LIB_NAME);
+            self.in_progress.push((lib, rust_type::<T>(), name.clone(), false));
+        }
+    }
+
+    /// Registers an error, unless the same error was already reported, which happens since the
+    /// types of union variants are both defined and written.
+    ///
+    /// Errors found inside a type nested into other types are wrapped into
+    /// [`TranspileError::InContext`] with the names of the types being transpiled.
+    fn push_error(&mut self, error: TranspileError) {
+        if self.errors.iter().any(|known| known.root() == &error) {
+            return;
+        }
+        let error = match self.in_progress.len() {
+            0 | 1 => error,
+            _ => TranspileError::InContext {
+                context: self.in_progress.iter().map(|(_, _, name, _)| name.clone()).collect(),
+                error: Box::new(error),
+            },
+        };
+        self.errors.push(error);
+    }
+
     fn dependency_id(&self, lib_name: &LibName) -> TypeLibId {
         self.known_libs
             .keys()
@@ -134,22 +478,24 @@ impl TypedWrite for LibBuilder {
     }
 
     fn write_tuple<T: StrictTuple>(
-        self,
+        mut self,
         inner: impl FnOnce(Self::TupleWriter) -> io::Result<Self>,
     ) -> io::Result<Self> {
+        let name = self.strict_name::<T>();
+        self.enter::<T>(&name);
         let writer = StructWriter::tuple::<T>(self);
-        let builder =
-            StructBuilder::with(libname!(T::STRICT_LIB_NAME), T::strict_name(), writer, false);
+        let builder = StructBuilder::with(libname!(T::STRICT_LIB_NAME), name, writer, false);
         inner(builder)
     }
 
     fn write_struct<T: StrictStruct>(
-        self,
+        mut self,
         inner: impl FnOnce(Self::StructWriter) -> io::Result<Self>,
     ) -> io::Result<Self> {
+        let name = self.strict_name::<T>();
+        self.enter::<T>(&name);
         let writer = StructWriter::structure::<T>(self);
-        let builder =
-            StructBuilder::with(libname!(T::STRICT_LIB_NAME), T::strict_name(), writer, false);
+        let builder = StructBuilder::with(libname!(T::STRICT_LIB_NAME), name, writer, false);
         inner(builder)
     }
 
@@ -165,6 +511,11 @@ impl TypedWrite for LibBuilder {
         self
     }
 
+    // TODO: Transpile Rust `char` into `Ty::UnicodeChar`. This requires `rgb-strict-encoding`
+    //       to implement strict encoding for `char` and to provide a `TypedWrite` method
+    //       registering single characters; a dedicated type in the `Std` library would change
+    //       the library id instead. Validation of `Ty::UnicodeChar` data already rejects
+    //       surrogates and code points above `0x10FFFF`, since the characters are UTF-8 encoded.
     unsafe fn register_unicode(mut self, sizing: Sizing) -> Self {
         self.last_compiled = Some(Ty::List(Ty::UnicodeChar.into(), sizing).into());
         self
@@ -188,34 +539,30 @@ impl TypedWrite for LibBuilder {
         self
     }
 
-    unsafe fn register_list(mut self, ty: &impl StrictEncode, sizing: Sizing) -> Self {
-        self = ty.strict_encode(self).expect("in-memory encoding");
-        let ty = self.last_compiled.expect("can't compile type");
-        self.last_compiled = Some(Ty::List(ty, sizing).into());
-        self
+    unsafe fn register_list(self, ty: &impl StrictEncode, sizing: Sizing) -> Self {
+        // collection items are compiled as references, so recursive types terminate here
+        let (mut me, ty) = self.compile_type(ty);
+        me.last_compiled = Some(Ty::List(ty, sizing).into());
+        me
     }
 
-    unsafe fn register_set(mut self, ty: &impl StrictEncode, sizing: Sizing) -> Self {
-        self = ty.strict_encode(self).expect("in-memory encoding");
-        let ty = self.last_compiled.expect("can't compile type");
-        self.last_compiled = Some(Ty::Set(ty, sizing).into());
-        self
+    unsafe fn register_set(self, ty: &impl StrictEncode, sizing: Sizing) -> Self {
+        // collection items are compiled as references, so recursive types terminate here
+        let (mut me, ty) = self.compile_type(ty);
+        me.last_compiled = Some(Ty::Set(ty, sizing).into());
+        me
     }
 
     unsafe fn register_map(
-        mut self,
+        self,
         key: &impl StrictEncode,
         ty: &impl StrictEncode,
         sizing: Sizing,
     ) -> Self {
-        self = key.strict_encode(self).expect("in-memory encoding");
-        let key_ref = self.last_compiled.clone().expect("can't compile key type");
-
-        self = ty.strict_encode(self).expect("in-memory encoding");
-        let val_ref = self.last_compiled.clone().expect("can't compile value type");
-
-        self.last_compiled = Some(Ty::Map(key_ref, val_ref, sizing).into());
-        self
+        let (me, key_ref) = self.compile_type(key);
+        let (mut me, val_ref) = me.compile_type(ty);
+        me.last_compiled = Some(Ty::Map(key_ref, val_ref, sizing).into());
+        me
     }
 }
 
@@ -230,13 +577,28 @@ impl StrictParent<StreamWriter<Sink>> for LibBuilder {
     }
 }
 impl BuilderParent for LibBuilder {
-    fn compile_type<T: StrictEncode>(self, value: &T) -> (Self, TranspileRef) {
+    fn compile_type<T: StrictEncode>(mut self, value: &T) -> (Self, TranspileRef) {
         let _compile = |mut me: Self| -> (Self, TranspileRef) {
             me = value.strict_encode(me).expect("too many types in the library");
             let r =
                 me.last_compiled.clone().expect("no type found after strict encoding procedure");
             (me, r)
         };
+        let rust = rust_type::<T>();
+        if T::STRICT_LIB_NAME == self.lib_name.as_str() {
+            let lib_name = &self.lib_name;
+            if let Some((_, _, name, referenced)) =
+                self.in_progress.iter_mut().rev().find(|(lib, r, ..)| lib == lib_name && *r == rust)
+            {
+                *referenced = true;
+                let name = name.clone();
+                return (self, TranspileRef::Named(name));
+            }
+            if let Some(name) = self.instances.get(rust) {
+                let name = name.clone();
+                return (self, TranspileRef::Named(name));
+            }
+        }
         match (T::STRICT_LIB_NAME, T::strict_name()) {
             (LIB_EMBEDDED, _) | (_, None) => _compile(self),
             (lib, Some(name)) if lib != self.lib_name.as_str() => {
@@ -245,8 +607,13 @@ impl BuilderParent for LibBuilder {
                 let lib_id = me.dependency_id(&lib_name);
                 (me, TranspileRef::Extern(SymbolRef::with(lib_name, name, lib_id, r.id())))
             }
-            (_, Some(name)) if self.types.contains_key(&name) => (self, TranspileRef::Named(name)),
-            (_, Some(_)) => _compile(self),
+            (_, Some(_)) => {
+                let (mut me, r) = _compile(self);
+                if let TranspileRef::Named(name) = &r {
+                    me.instances.insert(rust, name.clone());
+                }
+                (me, r)
+            }
         }
     }
 
@@ -256,16 +623,28 @@ impl BuilderParent for LibBuilder {
         name: Option<TypeName>,
         ty: Ty<TranspileRef>,
     ) -> Self {
+        let mut recursive = false;
+        if let Some(name) = &name {
+            // reported before the type is removed from the context
+            if self.deny_empty_structs && lib == self.lib_name && ty == Ty::UNIT {
+                self.push_error(TranspileError::EmptyStruct(name.clone()));
+            }
+            if let Some(pos) =
+                self.in_progress.iter().rposition(|(l, _, n, _)| l == &lib && n == name)
+            {
+                recursive = self.in_progress.remove(pos).3;
+            }
+        }
         let r = match (lib, name) {
             (lib, Some(name)) if lib == self.lib_name => {
-                if let Some(old_ty) = self.types.get(&name) {
-                    assert_eq!(
-                        old_ty, &ty,
-                        "repeated type name '{name}' for two different types '{old_ty}' and '{ty}'",
-                    );
+                // keep the definition of other type if the name can't be registered
+                match self.instance_name(name.clone(), &ty, recursive) {
+                    Some(name) => {
+                        self.types.insert(name.clone(), ty);
+                        TranspileRef::Named(name)
+                    }
+                    None => TranspileRef::Named(name),
                 }
-                self.types.insert(name.clone(), ty);
-                TranspileRef::Named(name)
             }
             (lib, Some(name)) => {
                 let id = ty.sem_id_named(&name);
@@ -278,6 +657,11 @@ impl BuilderParent for LibBuilder {
         self.last_compiled = Some(r);
         self
     }
+
+    fn report_error(mut self, error: TranspileError) -> Self {
+        self.push_error(error);
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -334,6 +718,20 @@ impl<P: BuilderParent> StructBuilder<P> {
         Ok(self)
     }
 
+    /// Detects field names used by several fields of the structure.
+    fn _duplicate_error(&self) -> Option<TranspileError> {
+        let mut names = BTreeSet::new();
+        let field = self
+            .fields
+            .iter()
+            .filter_map(|(name, _)| name.as_ref())
+            .find(|name| !names.insert(*name))?;
+        Some(TranspileError::DuplicateField {
+            ty: self.name().to_owned(),
+            field: field.clone(),
+        })
+    }
+
     fn _build_struct(&self) -> Ty<TranspileRef> {
         if self.fields.is_empty() {
             Ty::UNIT
@@ -366,11 +764,17 @@ impl<P: BuilderParent> StructBuilder<P> {
 
     fn _complete_definition(self) -> P {
         let ty = self._build_struct();
-        if self.writer.is_tuple() {
-            DefineTuple::complete(self.writer).report_compiled(self.lib, self.name, ty)
+        let error = self._duplicate_error();
+        let parent = if self.writer.is_tuple() {
+            DefineTuple::complete(self.writer)
         } else {
-            DefineStruct::complete(self.writer).report_compiled(self.lib, self.name, ty)
-        }
+            DefineStruct::complete(self.writer)
+        };
+        let parent = match error {
+            Some(error) => parent.report_error(error),
+            None => parent,
+        };
+        parent.report_compiled(self.lib, self.name, ty)
     }
 
     fn _complete_write(self) -> P {
@@ -383,11 +787,17 @@ impl<P: BuilderParent> StructBuilder<P> {
                 self.writer.name()
             );
         }
-        if self.writer.is_tuple() {
-            WriteTuple::complete(self.writer).report_compiled(self.lib, self.name, ty)
+        let error = self._duplicate_error();
+        let parent = if self.writer.is_tuple() {
+            WriteTuple::complete(self.writer)
         } else {
-            WriteStruct::complete(self.writer).report_compiled(self.lib, self.name, ty)
-        }
+            WriteStruct::complete(self.writer)
+        };
+        let parent = match error {
+            Some(error) => parent.report_error(error),
+            None => parent,
+        };
+        parent.report_compiled(self.lib, self.name, ty)
     }
 }
 
@@ -395,7 +805,10 @@ impl<P: BuilderParent> DefineStruct for StructBuilder<P> {
     type Parent = P;
 
     fn define_field<T: StrictEncode + StrictDumb>(mut self, name: FieldName) -> Self {
-        self.writer = DefineStruct::define_field::<T>(self.writer, name.clone());
+        // the writer panics on repeated fields, which are reported once the struct is complete
+        if !self.fields.iter().any(|(field, _)| field.as_ref() == Some(&name)) {
+            self.writer = DefineStruct::define_field::<T>(self.writer, name.clone());
+        }
         self._define_field::<T>(Some(name))
     }
 
@@ -445,10 +858,12 @@ pub struct UnionBuilder {
 }
 
 impl UnionBuilder {
-    pub fn with<T: StrictSum>(parent: LibBuilder) -> Self {
+    pub fn with<T: StrictSum>(mut parent: LibBuilder) -> Self {
+        let name = parent.strict_name::<T>();
+        parent.enter::<T>(&name);
         UnionBuilder {
             lib: libname!(T::STRICT_LIB_NAME),
-            name: T::strict_name(),
+            name,
             variants: empty!(),
             parent,
             writer: UnionWriter::with::<T>(StrictWriter::sink::<MAX_WRITE_COUNT>()),
@@ -467,13 +882,39 @@ impl UnionBuilder {
 
     pub fn name(&self) -> &str { self.name.as_ref().map(|n| n.as_str()).unwrap_or("<unnamed>") }
 
+    /// Checks whether the variant `name` is already defined, reporting it as an error.
+    ///
+    /// The repeated variants are not passed to the writer, which doesn't accept them.
+    fn _is_duplicate(&mut self, name: &VariantName) -> bool {
+        if !self.writer.variants().keys().any(|variant| &variant.name == name) {
+            return false;
+        }
+        self.parent.push_error(TranspileError::DuplicateVariant {
+            ty: self.name().to_owned(),
+            variant: name.clone(),
+        });
+        true
+    }
+
     fn _define_variant(&mut self, name: &VariantName) {
         let ty = self.parent.last_compiled.clone().expect("no compiled type found");
         let tag = self.writer.tag_by_name(name);
         self.variants.insert(tag, ty);
     }
 
-    fn _build_union(&self) -> Ty<TranspileRef> {
+    /// Reports types without variants, which have no values, returning a placeholder type for
+    /// them.
+    fn _uninhabited(&mut self) -> Ty<TranspileRef> {
+        self.parent.push_error(TranspileError::UninhabitedType {
+            name: self.name().to_owned(),
+        });
+        Ty::U8
+    }
+
+    fn _build_union(&mut self) -> Ty<TranspileRef> {
+        if self.writer.variants().is_empty() {
+            return self._uninhabited();
+        }
         let variants = self
             .writer
             .variants()
@@ -488,7 +929,10 @@ impl UnionBuilder {
         Ty::Union(variants)
     }
 
-    fn _build_enum(&self) -> Ty<TranspileRef> {
+    fn _build_enum(&mut self) -> Ty<TranspileRef> {
+        if self.writer.variants().is_empty() {
+            return self._uninhabited();
+        }
         let variants = self.writer.variants().keys().cloned().collect::<BTreeSet<_>>();
         let variants = EnumVariants::try_from(variants)
             .unwrap_or_else(|_| panic!("enum '{}' has invalid number of variants", self.name()));
@@ -496,13 +940,18 @@ impl UnionBuilder {
     }
 
     fn _complete_definition(mut self, ty: Ty<TranspileRef>) -> UnionBuilder {
-        self.writer = DefineUnion::complete(self.writer);
+        // the writer panics on types without variants, which are reported as errors instead
+        if !self.writer.variants().is_empty() {
+            self.writer = DefineUnion::complete(self.writer);
+        }
         self.parent = self.parent.report_compiled(self.lib.clone(), self.name.clone(), ty);
         self
     }
 
     fn _complete_write(self, ty: Ty<TranspileRef>) -> LibBuilder {
-        let _ = WriteUnion::complete(self.writer);
+        if !self.writer.variants().is_empty() {
+            let _ = WriteUnion::complete(self.writer);
+        }
         self.parent.report_compiled(self.lib, self.name, ty)
     }
 
@@ -549,6 +998,10 @@ impl BuilderParent for UnionBuilder {
         self.parent = self.parent.report_compiled(lib, name, ty);
         self
     }
+    fn report_error(mut self, error: TranspileError) -> Self {
+        self.parent.push_error(error);
+        self
+    }
 }
 
 impl DefineEnum for UnionBuilder {
@@ -556,13 +1009,16 @@ impl DefineEnum for UnionBuilder {
     type EnumWriter = Self;
 
     fn define_variant(mut self, name: VariantName) -> Self {
+        if self._is_duplicate(&name) {
+            return self;
+        }
         self.parent = self.parent.report_compiled(self.lib.clone(), None, Ty::U8);
         self.writer = DefineEnum::define_variant(self.writer, name.clone());
         self._define_variant(&name);
         self
     }
 
-    fn complete(self) -> Self::EnumWriter {
+    fn complete(mut self) -> Self::EnumWriter {
         let ty = self._build_enum();
         self._complete_definition(ty)
     }
@@ -573,11 +1029,13 @@ impl WriteEnum for UnionBuilder {
 
     fn write_variant(mut self, name: VariantName) -> io::Result<Self> {
         self.parent = self.parent.report_compiled(self.lib.clone(), None, Ty::U8);
-        self.writer = WriteEnum::write_variant(self.writer, name)?;
+        if !self.writer.variants().is_empty() {
+            self.writer = WriteEnum::write_variant(self.writer, name)?;
+        }
         Ok(self)
     }
 
-    fn complete(self) -> LibBuilder {
+    fn complete(mut self) -> LibBuilder {
         let ty = self._build_enum();
         self._complete_write(ty)
     }
@@ -590,6 +1048,9 @@ impl DefineUnion for UnionBuilder {
     type UnionWriter = Self;
 
     fn define_unit(mut self, name: VariantName) -> Self {
+        if self._is_duplicate(&name) {
+            return self;
+        }
         self.parent = self.parent.report_compiled(self.lib.clone(), None, Ty::UNIT);
         self.writer = DefineUnion::define_unit(self.writer, name.clone());
         self._define_variant(&name);
@@ -601,6 +1062,9 @@ impl DefineUnion for UnionBuilder {
         name: VariantName,
         inner: impl FnOnce(Self::TupleDefiner) -> Self,
     ) -> Self {
+        if self._is_duplicate(&name) {
+            return self;
+        }
         let lib = self.lib.clone();
         let (writer, remnant) = self.into_split();
         let mut clone = remnant._fork();
@@ -625,6 +1089,9 @@ impl DefineUnion for UnionBuilder {
         name: VariantName,
         inner: impl FnOnce(Self::StructDefiner) -> Self,
     ) -> Self {
+        if self._is_duplicate(&name) {
+            return self;
+        }
         let lib = self.lib.clone();
         let (writer, remnant) = self.into_split();
         let mut clone = remnant._fork();
@@ -644,7 +1111,7 @@ impl DefineUnion for UnionBuilder {
         self
     }
 
-    fn complete(self) -> Self::UnionWriter {
+    fn complete(mut self) -> Self::UnionWriter {
         let ty = self._build_union();
         self._complete_definition(ty)
     }
@@ -707,7 +1174,7 @@ impl WriteUnion for UnionBuilder {
         Ok(self)
     }
 
-    fn complete(self) -> LibBuilder {
+    fn complete(mut self) -> LibBuilder {
         let ty = self._build_union();
         self._complete_write(ty)
     }
@@ -22,7 +22,7 @@
 // the License.
 
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::hash::{Hash, Hasher};
 
@@ -31,10 +31,11 @@ use baid64::DisplayBaid64;
 use encoding::StrictDumb;
 use strict_encoding::{LibName, TypeName, STRICT_TYPES_LIB};
 
+use crate::layout::FieldPath;
 use crate::typelib::compile::CompileError;
 use crate::typelib::id::TypeLibId;
-use crate::typelib::ExternTypes;
-use crate::{SemId, Ty, TypeRef};
+use crate::typelib::{ExternTypes, FlattenError, RenameError, SubsetError, SymbolError};
+use crate::{SemId, TranspileRef, Ty, TypeRef};
 
 pub trait LibSubref: TypeRef {}
 impl LibSubref for LibRef {}
@@ -290,6 +291,144 @@ impl TypeLib {
         (dependency, types)
     }
 
+    /// Returns ids of all libraries the library depends on, directly or transitively.
+    ///
+    /// A library records only its direct dependencies, thus the transitive ones are discovered
+    /// through the `available` libraries. Dependencies which are not available are still included,
+    /// but their own dependencies remain unknown; use [`TypeLib::missing_dependencies`] to detect
+    /// them.
+    pub fn dependency_closure(&self, available: &[&TypeLib]) -> BTreeSet<TypeLibId> {
+        self.walk_dependencies(available).into_iter().map(|dep| dep.id).collect()
+    }
+
+    /// Returns direct and transitive dependencies of the library which are not present among the
+    /// `available` libraries.
+    pub fn missing_dependencies(&self, available: &[&TypeLib]) -> BTreeSet<Dependency> {
+        let ids = available.iter().map(|lib| lib.id()).collect::<BTreeSet<_>>();
+        self.walk_dependencies(available).into_iter().filter(|dep| !ids.contains(&dep.id)).collect()
+    }
+
+    fn walk_dependencies(&self, available: &[&TypeLib]) -> BTreeSet<Dependency> {
+        let index = available.iter().map(|lib| (lib.id(), *lib)).collect::<BTreeMap<_, _>>();
+        let mut found = BTreeSet::new();
+        let mut queue = self.dependencies.iter().cloned().collect::<Vec<_>>();
+        while let Some(dep) = queue.pop() {
+            if found.contains(&dep) {
+                continue;
+            }
+            if let Some(lib) = index.get(&dep.id) {
+                queue.extend(lib.dependencies.iter().cloned());
+            }
+            found.insert(dep);
+        }
+        found
+    }
+
+    /// Iterates over the library types in a canonical order, which is the same on all platforms:
+    /// each type follows the library types it references, and the types at the same step of
+    /// this topological order go lexicographically. Recursive types referencing each other
+    /// follow the lexicographical order.
+    ///
+    /// Errors if the library is internally inconsistent.
+    pub fn types_ordered(
+        &self,
+    ) -> Result<impl Iterator<Item = (&TypeName, &Ty<LibRef>)>, SymbolError> {
+        let lib = self.to_symbolic()?;
+        Ok(lib
+            .canonical_order()
+            .into_iter()
+            .map(|name| self.types.get_key_value(&name).expect("type from the same library")))
+    }
+
+    /// Returns semantic id of the library type `name`, which is the same id as used by other
+    /// libraries referencing the type.
+    pub fn type_id(&self, name: &TypeName) -> Option<SemId> {
+        self.types.get(name).map(|ty| ty.sem_id_named(name))
+    }
+
+    /// Returns name of the library type with semantic id `id`.
+    pub fn type_by_id(&self, id: SemId) -> Option<&TypeName> {
+        self.types.iter().find(|(name, ty)| ty.sem_id_named(name) == id).map(|(name, _)| name)
+    }
+
+    /// Returns all library types referencing the library type `name`, together with the paths of
+    /// the fields where the reference occurs.
+    ///
+    /// Path segments are field names, tuple field positions and union variant names; items of
+    /// arrays, lists and sets are addressed as `item`, and keys and values of maps as `key` and
+    /// `value`. A type referencing `name` multiple times is listed once per each reference.
+    ///
+    /// Errors if the library is internally inconsistent.
+    pub fn find_references_to(
+        &self,
+        name: &TypeName,
+    ) -> Result<Vec<(TypeName, FieldPath)>, SymbolError> {
+        let lib = self.to_symbolic()?;
+        let mut found = vec![];
+        for (referrer, ty) in lib.types() {
+            let mut paths = vec![];
+            find_refs(ty, name, FieldPath::default(), &mut paths);
+            found.extend(paths.into_iter().map(|path| (referrer.clone(), path)));
+        }
+        Ok(found)
+    }
+
+    /// Returns a library with only those types which are reachable from the `roots`, together
+    /// with the dependencies they use. Roots which are not a part of the library are ignored.
+    ///
+    /// The types keep their semantic ids, while the library gets a new id, unless all its types
+    /// are reachable from the roots.
+    ///
+    /// If none of the `roots` are types of the library, [`SubsetError::UnknownRoots`] is returned.
+    pub fn subset(&self, roots: &[TypeName]) -> Result<TypeLib, SubsetError> {
+        let lib = self
+            .to_symbolic()?
+            .subset(roots)
+            .ok_or_else(|| SubsetError::UnknownRoots(roots.to_vec()))?;
+        Ok(lib.compile().map_err(Box::new)?)
+    }
+
+    /// Returns the same library published under a new `name`.
+    ///
+    /// Library types reference each other and the types of dependencies by semantic ids, which
+    /// don't commit to the library name, thus all type definitions, type ids and dependencies are
+    /// kept intact. The library id changes, so the libraries depending on the original one must be
+    /// re-compiled against the renamed library.
+    pub fn rename_lib(mut self, name: LibName) -> TypeLib {
+        self.name = name;
+        self
+    }
+
+    /// Renames library type `from` into `to`, updating all references to it from other types of
+    /// the library; see [`crate::SymbolicLib::rename_type`] for the details.
+    ///
+    /// Semantic ids commit to the type names, thus the renamed type, the library types using it
+    /// and the library itself get new ids.
+    ///
+    /// If the library is internally inconsistent, [`RenameError::Inconsistent`] or
+    /// [`RenameError::Compile`] is returned.
+    pub fn rename_type(self, from: &TypeName, to: TypeName) -> Result<TypeLib, RenameError> {
+        let mut lib = self.to_symbolic()?;
+        lib.rename_type(from, to)?;
+        Ok(lib.compile().map_err(Box::new)?)
+    }
+
+    /// Returns a self-contained library without dependencies, where all types used from the
+    /// dependencies, directly or transitively, are copied into the library. The `deps` must
+    /// provide all such dependencies; other libraries in `deps` are ignored.
+    ///
+    /// The copied types are named by prefixing their names with the names of their libraries,
+    /// like `StdBool` for `Std.Bool`. Since semantic ids commit to the type names, the copied
+    /// types and the library types using them get new ids.
+    ///
+    /// If the library or any of the `deps` is internally inconsistent,
+    /// [`FlattenError::Inconsistent`] is returned.
+    pub fn flatten_dependencies(&self, deps: &[&TypeLib]) -> Result<TypeLib, FlattenError> {
+        let lib =
+            self.to_symbolic().map_err(|err| FlattenError::Inconsistent(self.name.clone(), err))?;
+        Ok(lib.flatten(deps)?.compile()?)
+    }
+
     pub fn import(&mut self, dependency: Dependency) -> Result<(), CompileError> {
         if self.dependencies.contains(&dependency) {
             return Err(CompileError::DuplicatedDependency(dependency));
@@ -308,3 +447,52 @@ impl TypeLib {
 
     // TODO: Check that all dependencies are used
 }
+
+/// Groups types which are shared by several of the `libs`, i.e. have the same semantic id, which
+/// means that they have the same name and structure.
+///
+/// Each group lists the libraries defining the type in the order of `libs`; types which are defined
+/// by a single library only are not returned. Such shared types are candidates for factoring out
+/// into a common dependency.
+pub fn common_types(libs: &[&TypeLib]) -> BTreeMap<SemId, Vec<(LibName, TypeName)>> {
+    let mut groups = BTreeMap::<SemId, Vec<(LibName, TypeName)>>::new();
+    for lib in libs {
+        for (name, ty) in &lib.types {
+            groups.entry(ty.sem_id_named(name)).or_default().push((lib.name.clone(), name.clone()));
+        }
+    }
+    groups.retain(|_, group| group.len() > 1);
+    groups
+}
+
+/// Collects paths to all references to the type `name` inside `ty` and its embedded types.
+fn find_refs(ty: &Ty<TranspileRef>, name: &TypeName, path: FieldPath, found: &mut Vec<FieldPath>) {
+    let mut visit = |r: &TranspileRef, path: FieldPath| match r {
+        TranspileRef::Named(n) if n == name => found.push(path),
+        TranspileRef::Embedded(ty) => find_refs(ty, name, path, found),
+        TranspileRef::Named(_) | TranspileRef::Extern(_) => {}
+    };
+    match ty {
+        Ty::Primitive(_) | Ty::Enum(_) | Ty::UnicodeChar => {}
+        Ty::Union(variants) => {
+            for (variant, r) in variants.iter() {
+                visit(r, path.with(&variant.name));
+            }
+        }
+        Ty::Tuple(fields) => {
+            for (no, r) in fields.iter().enumerate() {
+                visit(r, path.with(no));
+            }
+        }
+        Ty::Struct(fields) => {
+            for field in fields.iter() {
+                visit(&field.ty, path.with(&field.name));
+            }
+        }
+        Ty::Array(r, _) | Ty::List(r, _) | Ty::Set(r, _) => visit(r, path.with("item")),
+        Ty::Map(key, r, _) => {
+            visit(key, path.with("key"));
+            visit(r, path.with("value"));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Depth-first traversal of the library types, allowing custom exporters to work with the type
+//! graph without knowing the details of the type representation.
+
+use encoding::{Primitive, Sizing, TypeName};
+
+use crate::ast::{EnumVariants, NamedFields, UnionVariants, UnnamedFields};
+use crate::layout::FieldPath;
+use crate::typelib::SymbolError;
+use crate::{SymbolRef, SymbolicLib, TranspileRef, Ty, TypeLib};
+
+/// Callbacks for the items met during the traversal of the library types with
+/// [`TypeLib::accept`].
+///
+/// Each callback receives path to the item from the traversal root, made of field and variant
+/// names, tuple field positions, `item` segment for the items of arrays, lists and sets, and `key`
+/// and `value` segments for maps. A compound type is reported before its nested items. All
+/// callbacks do nothing by default.
+#[allow(unused_variables)]
+pub trait TypeVisitor {
+    /// Called when a named library type is entered, before its definition is visited.
+    fn visit_named(&mut self, path: &FieldPath, name: &TypeName) {}
+    /// Called when a library type is referenced from its own definition, directly or through
+    /// other types. The definition is not visited again.
+    fn visit_recursive(&mut self, path: &FieldPath, name: &TypeName) {}
+    /// Called for the types from the library dependencies, which definitions are not known.
+    fn visit_extern(&mut self, path: &FieldPath, sym: &SymbolRef) {}
+    fn visit_primitive(&mut self, path: &FieldPath, prim: Primitive) {}
+    fn visit_unicode(&mut self, path: &FieldPath) {}
+    fn visit_enum(&mut self, path: &FieldPath, variants: &EnumVariants) {}
+    fn visit_union(&mut self, path: &FieldPath, variants: &UnionVariants<TranspileRef>) {}
+    fn visit_tuple(&mut self, path: &FieldPath, fields: &UnnamedFields<TranspileRef>) {}
+    fn visit_struct(&mut self, path: &FieldPath, fields: &NamedFields<TranspileRef>) {}
+    /// Called for arrays, lists, sets and maps; `sizing` is `None` for fixed-size arrays.
+    fn visit_collection(
+        &mut self,
+        path: &FieldPath,
+        ty: &Ty<TranspileRef>,
+        sizing: Option<Sizing>,
+    ) {
+    }
+}
+
+impl TypeLib {
+    /// Walks the types reachable from the library type `root` depth-first, calling `visitor` for
+    /// each met item.
+    ///
+    /// Returns `false` if the library has no type with the name `root`, and errors if the library
+    /// is internally inconsistent.
+    pub fn accept<V: TypeVisitor>(
+        &self,
+        root: &TypeName,
+        visitor: &mut V,
+    ) -> Result<bool, SymbolError> {
+        let lib = self.to_symbolic()?;
+        if !lib.types().contains_key(root) {
+            return Ok(false);
+        }
+        let mut walker = Walker {
+            lib: &lib,
+            visitor,
+            path: FieldPath::default(),
+            stack: vec![],
+        };
+        walker.walk_named(root);
+        Ok(true)
+    }
+}
+
+struct Walker<'a, V: TypeVisitor> {
+    lib: &'a SymbolicLib,
+    visitor: &'a mut V,
+    path: FieldPath,
+    /// Named types which definitions are being visited, used to detect recursion.
+    stack: Vec<TypeName>,
+}
+
+impl<V: TypeVisitor> Walker<'_, V> {
+    fn walk_named(&mut self, name: &TypeName) {
+        if self.stack.contains(name) {
+            self.visitor.visit_recursive(&self.path, name);
+            return;
+        }
+        self.visitor.visit_named(&self.path, name);
+        let ty = self.lib.types().get(name).expect("inconsistent type library");
+        self.stack.push(name.clone());
+        self.walk_ty(ty);
+        self.stack.pop();
+    }
+
+    fn walk_ref(&mut self, segment: impl ToString, r: &TranspileRef) {
+        self.path.push(segment.to_string());
+        match r {
+            TranspileRef::Embedded(ty) => self.walk_ty(ty),
+            TranspileRef::Named(name) => self.walk_named(name),
+            TranspileRef::Extern(sym) => self.visitor.visit_extern(&self.path, sym),
+        }
+        self.path.pop();
+    }
+
+    fn walk_ty(&mut self, ty: &Ty<TranspileRef>) {
+        match ty {
+            Ty::Primitive(prim) => self.visitor.visit_primitive(&self.path, *prim),
+            Ty::UnicodeChar => self.visitor.visit_unicode(&self.path),
+            Ty::Enum(variants) => self.visitor.visit_enum(&self.path, variants),
+            Ty::Union(variants) => {
+                self.visitor.visit_union(&self.path, variants);
+                for (variant, r) in variants {
+                    self.walk_ref(&variant.name, r);
+                }
+            }
+            Ty::Tuple(fields) => {
+                self.visitor.visit_tuple(&self.path, fields);
+                for (pos, r) in fields.iter().enumerate() {
+                    self.walk_ref(pos, r);
+                }
+            }
+            Ty::Struct(fields) => {
+                self.visitor.visit_struct(&self.path, fields);
+                for field in fields {
+                    self.walk_ref(&field.name, &field.ty);
+                }
+            }
+            Ty::Array(r, _) => {
+                self.visitor.visit_collection(&self.path, ty, None);
+                self.walk_ref("item", r);
+            }
+            Ty::List(r, sizing) | Ty::Set(r, sizing) => {
+                self.visitor.visit_collection(&self.path, ty, Some(*sizing));
+                self.walk_ref("item", r);
+            }
+            Ty::Map(key, value, sizing) => {
+                self.visitor.visit_collection(&self.path, ty, Some(*sizing));
+                self.walk_ref("key", key);
+                self.walk_ref("value", value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::SmallVec;
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    pub enum Kind {
+        #[default]
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Item {
+        pub kind: Kind,
+        pub value: u16,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    pub struct Record {
+        pub items: SmallVec<Item>,
+        pub hash: [u8; 32],
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl TypeVisitor for Recorder {
+        fn visit_named(&mut self, path: &FieldPath, name: &TypeName) {
+            self.0.push(format!("{path}: {name}"));
+        }
+        fn visit_primitive(&mut self, path: &FieldPath, prim: Primitive) {
+            self.0.push(format!("{path}: {prim}"));
+        }
+        fn visit_enum(&mut self, path: &FieldPath, variants: &EnumVariants) {
+            self.0.push(format!("{path}: enum {}", variants.len()));
+        }
+        fn visit_struct(&mut self, path: &FieldPath, fields: &NamedFields<TranspileRef>) {
+            self.0.push(format!("{path}: struct {}", fields.len()));
+        }
+        fn visit_collection(
+            &mut self,
+            path: &FieldPath,
+            _ty: &Ty<TranspileRef>,
+            sizing: Option<Sizing>,
+        ) {
+            self.0.push(format!("{path}: collection {}", sizing.is_some()));
+        }
+    }
+
+    #[test]
+    fn accept() {
+        let lib = LibBuilder::with("Test", None).transpile::<Record>().compile().unwrap();
+        let mut recorder = Recorder::default();
+        assert!(lib.accept(&tn!("Record"), &mut recorder).unwrap());
+        assert_eq!(recorder.0, [
+            ": Record",
+            ": struct 2",
+            "items: collection true",
+            "items.item: Item",
+            "items.item: struct 2",
+            "items.item.kind: Kind",
+            "items.item.kind: enum 2",
+            "items.item.value: U16",
+            "hash: collection false",
+            "hash.item: Byte",
+        ]);
+
+        let mut recorder = Recorder::default();
+        assert!(!lib.accept(&tn!("Unknown"), &mut recorder).unwrap());
+        assert!(recorder.0.is_empty());
+    }
+}
@@ -31,9 +31,11 @@ use sha2::{Digest, Sha256};
 use strict_encoding::STRICT_TYPES_LIB;
 
 use crate::ast::SemCommit;
+use crate::util::{parse_baid64_id, IdError};
 use crate::{CommitConsume, TypeSystem};
 
 pub const TYPESYS_ID_TAG: [u8; 32] = *b"urn:ubideco:strict-types:sys:v01";
+pub const TYPESYS_NS_ID_TAG: [u8; 32] = *b"urn:ubideco:strict-types:nsys:01";
 
 #[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
 #[wrapper(Deref, BorrowSlice, Hex, Index, RangeOps)]
@@ -63,6 +65,36 @@ impl Display for TypeSysId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.fmt_baid64(f) }
 }
 
+impl TypeSysId {
+    /// Returns mnemonic suffix of the identifier (the words following `#` in its string form).
+    pub fn mnemonic(&self) -> String { self.to_baid64_mnemonic() }
+
+    /// Returns raw digest bytes the identifier is made of.
+    pub fn checksum_bytes(&self) -> [u8; 32] { self.to_byte_array() }
+
+    /// Parses the identifier string, reporting [`IdError::MnemonicMismatch`] if the mnemonic
+    /// suffix does not match the digest.
+    ///
+    /// Unlike [`FromStr`], which has to report [`Baid64ParseError`] for compatibility with ASCII
+    /// armoring, this distinguishes mnemonic mismatches from other parse errors.
+    pub fn from_str_checked(s: &str) -> Result<Self, IdError> { parse_baid64_id(s) }
+
+    /// Constructs the identifier from its raw `digest`; the mnemonic is derived from the digest.
+    pub const fn from_parts(digest: [u8; 32]) -> Self { TypeSysId(Bytes32::from_array(digest)) }
+
+    /// Recomputes id of the type system `sys` and checks that it matches this identifier.
+    pub fn verify(&self, sys: &TypeSystem) -> bool { sys.id() == *self }
+
+    /// Recomputes id of the type system `sys` in the `namespace` (see
+    /// [`TypeSystem::namespaced_id`]) and checks that it matches this identifier.
+    ///
+    /// The identifier is a digest and doesn't keep the namespace, thus it can only be checked
+    /// against a known one.
+    pub fn verify_namespaced(&self, sys: &TypeSystem, namespace: &str) -> bool {
+        sys.namespaced_id(namespace) == *self
+    }
+}
+
 impl SemCommit for TypeSystem {
     fn sem_commit(&self, hasher: &mut impl CommitConsume) {
         hasher.commit_consume(self.len_u24().to_le_bytes());
@@ -73,6 +105,12 @@ impl SemCommit for TypeSystem {
 }
 
 impl TypeSystem {
+    /// Computes id of the type system, which commits to the ordered semantic ids of its types.
+    ///
+    /// The semantic ids are kept as the type system keys and are not recomputed from the type
+    /// definitions, so the cost of the id computation is a single hash over 32 bytes per type.
+    /// Since the id is a hash over the full ordered list of type ids, it can't be updated
+    /// incrementally without changing the ids of the existing type systems.
     pub fn id(&self) -> TypeSysId {
         let tag = Sha256::new_with_prefix(TYPESYS_ID_TAG).finalize();
         let mut hasher = Sha256::new();
@@ -81,4 +119,23 @@ impl TypeSystem {
         self.sem_commit(&mut hasher);
         TypeSysId::from_byte_array(hasher.finalize())
     }
+
+    /// Computes id of the type system salted with the `namespace`, such that the same types get
+    /// different ids in different namespaces, like the networks sharing type definitions.
+    ///
+    /// The namespace affects only the id, leaving the types and their semantic ids untouched.
+    /// An empty namespace produces the same id as [`TypeSystem::id`].
+    pub fn namespaced_id(&self, namespace: &str) -> TypeSysId {
+        if namespace.is_empty() {
+            return self.id();
+        }
+        let tag = Sha256::new_with_prefix(TYPESYS_NS_ID_TAG).finalize();
+        let mut hasher = Sha256::new();
+        hasher.commit_consume(tag);
+        hasher.commit_consume(tag);
+        hasher.commit_consume((namespace.len() as u64).to_le_bytes());
+        hasher.commit_consume(namespace);
+        self.sem_commit(&mut hasher);
+        TypeSysId::from_byte_array(hasher.finalize())
+    }
 }
@@ -21,14 +21,15 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use std::mem::swap;
+use std::collections::BTreeSet;
+use std::mem::{swap, take};
 
 use amplify::confinement::{Confined, TinyVec};
 use encoding::Sizing;
-use strict_encoding::STRICT_TYPES_LIB;
+use strict_encoding::{TypeName, STRICT_TYPES_LIB};
 
 use crate::ast::ItemCase;
-use crate::layout::MemoryLayout;
+use crate::layout::{FieldPath, LayoutError, MemoryLayout};
 use crate::typesys::TypeFqn;
 use crate::{ast, SemId, SymbolicSys, Ty};
 
@@ -52,11 +53,95 @@ impl<'sys> TypeTree<'sys> {
             path: vec![],
             sys: self.sys,
             nested: vec![],
+            emitted: 0,
         }
     }
 
+    /// Detects cycles in the graph of types referenced by the tree root, returning each of them
+    /// as a chain of the names of the types involved. Unnamed types are omitted from the chains.
+    pub fn detect_cycles(&self) -> Vec<Vec<TypeName>> {
+        let mut cycles = vec![];
+        let mut done = BTreeSet::new();
+        let mut stack = vec![];
+        self.visit_cycles(self.sem_id, &mut stack, &mut done, &mut cycles);
+        cycles
+    }
+
+    fn visit_cycles(
+        &self,
+        sem_id: SemId,
+        stack: &mut Vec<SemId>,
+        done: &mut BTreeSet<SemId>,
+        cycles: &mut Vec<Vec<TypeName>>,
+    ) {
+        if let Some(pos) = stack.iter().position(|id| *id == sem_id) {
+            let names = stack[pos..]
+                .iter()
+                .filter_map(|id| self.sys.lookup(*id))
+                .map(|fqn| fqn.name.clone())
+                .collect();
+            cycles.push(names);
+            return;
+        }
+        if !done.insert(sem_id) {
+            return;
+        }
+        let Some(ty) = self.sys.get(sem_id) else {
+            return;
+        };
+        stack.push(sem_id);
+        for (id, _) in ty.iter() {
+            self.visit_cycles(*id, stack, done, cycles);
+        }
+        stack.pop();
+    }
+
+    /// Iterates over the leaves of the tree, which are the items without nested items (like
+    /// primitives, enums, strings and byte arrays), together with their dot-separated paths from
+    /// the root.
+    ///
+    /// Path segments are the names of structure fields and union variants and the positions of
+    /// tuple fields; the items of arrays, lists and sets are addressed with the `item` segment,
+    /// and map keys and values with `key` and `value` segments. The root, if it is a leaf itself,
+    /// has an empty path. Back-references of recursive types are not leaves and are skipped.
+    pub fn iter_leaves(&'sys self) -> impl Iterator<Item = (FieldPath, TypeInfo)> + 'sys {
+        let mut path = FieldPath::default();
+        self.iter().filter_map(move |info| {
+            path.truncate(info.depth.saturating_sub(1) as usize);
+            if let Some(item) = &info.item {
+                path.push(match item {
+                    ItemCase::UnnamedField(pos) => pos.to_string(),
+                    ItemCase::NamedField(_, name) => name.to_string(),
+                    ItemCase::UnionVariant(_, name) => name.to_string(),
+                    ItemCase::ArrayItem | ItemCase::ListItem | ItemCase::SetItem => s!("item"),
+                    ItemCase::MapKey => s!("key"),
+                    ItemCase::MapValue => s!("value"),
+                });
+            }
+            (info.children_count() == 0 && !info.is_recursive()).then(|| (path.clone(), info))
+        })
+    }
+
+    /// Returns the maximal nesting depth of the tree items, with the root having depth zero.
+    ///
+    /// Each structure, tuple, union and collection adds a level of nesting for its items, while
+    /// newtypes, strings and byte arrays don't. Back-references of recursive types are not
+    /// expanded, thus recursive types have a finite depth. The whole tree is walked, so for trees
+    /// from untrusted type systems use [`MemoryLayout::with_max_depth`], which stops as soon as
+    /// the limit is exceeded.
+    pub fn max_depth(&self) -> usize {
+        self.iter().map(|info| info.depth as usize).max().unwrap_or_default()
+    }
+
     #[inline]
     pub fn to_layout(&self) -> MemoryLayout { MemoryLayout::from(self) }
+
+    /// Constructs memory layout of the tree, failing if it has more than `max_items` items. See
+    /// [`MemoryLayout::try_from_tree`] for the details.
+    #[inline]
+    pub fn try_to_layout(&self, max_items: usize) -> Result<MemoryLayout, LayoutError> {
+        MemoryLayout::try_from_tree(self, max_items)
+    }
 }
 
 /*
@@ -80,6 +165,7 @@ where 'tree: 'sys
 #[derive(Clone, Eq, PartialEq, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = STRICT_TYPES_LIB, tags = custom)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NestedCase {
     #[strict_type(tag = 0x0)]
     NewType(Option<TypeFqn>),
@@ -110,12 +196,128 @@ pub struct NestedInfo<'sys> {
 #[derive(Clone, Eq, PartialEq, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
 #[strict_type(lib = STRICT_TYPES_LIB)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TypeInfo {
     pub depth: u32,
     pub ty: Ty<SemId>,
     pub fqn: Option<TypeFqn>,
     pub item: Option<ItemCase>,
     pub nested: TinyVec<NestedCase>,
+    /// For a back-reference to a type which is already being expanded, used for recursive types,
+    /// keeps the position of the layout item at which the expansion of the type starts. Such items
+    /// have no nested items.
+    ///
+    /// The back-reference is not strict-encoded, such that the item keeps the definition from
+    /// the `StrictTypes` library; it is restored when the layout is decoded, since the item has
+    /// the same type as the ancestor it refers to.
+    #[strict_type(skip)]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub recursive: Option<u32>,
+}
+
+impl TypeInfo {
+    /// Detects whether the item is a back-reference to a recursive type, which is not expanded.
+    pub fn is_recursive(&self) -> bool { self.recursive.is_some() }
+
+    /// Returns bounds on the number of elements for collection types (including strings) and fixed
+    /// arrays; for the arrays both bounds are equal to the array length.
+    pub fn sizing(&self) -> Option<Sizing> {
+        if let Some(NestedCase::RStr(_, _, sizing)) = self.nested.last() {
+            return Some(*sizing);
+        }
+        match self.ty {
+            Ty::Array(_, len) => Some(Sizing::fixed(len as u64)),
+            Ty::List(_, sizing) | Ty::Set(_, sizing) | Ty::Map(_, _, sizing) => Some(sizing),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the item, describing its outermost form: newtypes and optional types
+    /// are reported as [`TypeKind::Alias`] and [`TypeKind::Option`] even if they wrap a string or
+    /// a collection.
+    pub fn kind(&self) -> TypeKind {
+        match self.nested.first() {
+            Some(NestedCase::NewType(_)) => return TypeKind::Alias,
+            Some(NestedCase::Option) => return TypeKind::Option,
+            _ => {}
+        }
+        if self.is_recursive() {
+            return TypeKind::Recursive;
+        }
+        match self.nested.last() {
+            Some(NestedCase::AsciiStr(_) | NestedCase::UniStr | NestedCase::RStr(..)) => {
+                return TypeKind::String
+            }
+            Some(NestedCase::ByteStr) => return TypeKind::Bytes,
+            _ => {}
+        }
+        match self.ty {
+            Ty::Primitive(_) => TypeKind::Primitive,
+            Ty::UnicodeChar => TypeKind::Char,
+            Ty::Enum(_) => TypeKind::Enum,
+            Ty::Union(_) => TypeKind::Union,
+            Ty::Tuple(_) => TypeKind::Tuple,
+            Ty::Struct(_) => TypeKind::Struct,
+            Ty::Array(..) if self.ty.is_byte_array() => TypeKind::Bytes,
+            Ty::Array(..) => TypeKind::Array,
+            Ty::List(..) => TypeKind::List,
+            Ty::Set(..) => TypeKind::Set,
+            Ty::Map(..) => TypeKind::Map,
+        }
+    }
+
+    /// Returns name of the item type, which for newtypes is the name of the outermost newtype.
+    pub fn name(&self) -> Option<&TypeName> {
+        let fqn = match self.nested.first() {
+            Some(NestedCase::NewType(fqn)) => fqn.as_ref(),
+            _ => self.fqn.as_ref(),
+        };
+        fqn.map(|fqn| &fqn.name)
+    }
+
+    /// Returns number of items which directly follow this one in the [`MemoryLayout`] and
+    /// represent its nested types.
+    pub(crate) fn children_count(&self) -> usize {
+        match self.nested.last() {
+            _ if self.is_recursive() => 0,
+            Some(
+                NestedCase::RStr(..)
+                | NestedCase::AsciiStr(_)
+                | NestedCase::ByteStr
+                | NestedCase::UniStr,
+            ) => 0,
+            _ if self.ty.is_byte_array() => 0,
+            _ => self.ty.iter().count(),
+        }
+    }
+}
+
+/// Kind of a [`TypeInfo`] item, returned by [`TypeInfo::kind`].
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[display(lowercase)]
+pub enum TypeKind {
+    /// Integer, float or unit type.
+    Primitive,
+    /// Unicode character.
+    Char,
+    /// ASCII, restricted or Unicode string.
+    String,
+    /// Byte string or byte array.
+    Bytes,
+    Enum,
+    Union,
+    /// Optional type, having its inner type as the only nested item.
+    Option,
+    Tuple,
+    Struct,
+    Array,
+    List,
+    Set,
+    Map,
+    /// Newtype wrapping another type.
+    Alias,
+    /// Back-reference to a recursive type, which is not expanded.
+    Recursive,
 }
 
 /*
@@ -137,9 +339,19 @@ pub struct TypeTreeIter<'sys> {
     ty: Option<&'sys Ty<SemId>>,
     item: Option<ItemCase>,
     depth: u32,
-    path: Vec<(u32, ast::Iter<'sys, SemId>)>,
+    path: Vec<PathItem<'sys>>,
     sys: &'sys SymbolicSys,
     nested: Vec<NestedCase>,
+    emitted: u32,
+}
+
+/// Type which is being expanded by [`TypeTreeIter`].
+struct PathItem<'sys> {
+    depth: u32,
+    sem_id: SemId,
+    /// Number of the first item emitted for the type.
+    index: u32,
+    iter: ast::Iter<'sys, SemId>,
 }
 
 impl Iterator for TypeTreeIter<'_> {
@@ -150,6 +362,19 @@ impl Iterator for TypeTreeIter<'_> {
             let fqn = self.sys.symbols.lookup(self.sem_id);
             self.ty = None;
 
+            if let Some(ancestor) = self.path.iter().find(|item| item.sem_id == self.sem_id) {
+                let info = TypeInfo {
+                    depth: self.depth,
+                    ty: ty.clone(),
+                    fqn: fqn.cloned(),
+                    item: self.item.take(),
+                    nested: Confined::from_checked(take(&mut self.nested)),
+                    recursive: Some(ancestor.index),
+                };
+                self.emitted += 1;
+                return Some(info);
+            }
+
             let mut nested = vec![];
             let mut dive = true;
             let mut push = true;
@@ -205,7 +430,12 @@ impl Iterator for TypeTreeIter<'_> {
                 self.depth += 1;
             }
             if push {
-                self.path.push((self.depth, iter));
+                self.path.push(PathItem {
+                    depth: self.depth,
+                    sem_id: self.sem_id,
+                    index: self.emitted,
+                    iter,
+                });
             }
             self.nested.extend(nested);
             if ret {
@@ -217,15 +447,17 @@ impl Iterator for TypeTreeIter<'_> {
                     fqn: fqn.cloned(),
                     item,
                     nested: Confined::from_checked(self.nested.clone()),
+                    recursive: None,
                 };
                 self.nested = vec![];
+                self.emitted += 1;
                 return Some(info);
             }
         }
         loop {
-            let (depth, iter) = self.path.last_mut()?;
-            self.depth = *depth;
-            match iter.next() {
+            let last = self.path.last_mut()?;
+            self.depth = last.depth;
+            match last.iter.next() {
                 None => {
                     self.path.pop();
                     self.nested = vec![];
@@ -243,3 +475,129 @@ impl Iterator for TypeTreeIter<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use amplify::Wrapper;
+    use encoding::{StrictDeserialize, StrictSerialize};
+
+    use super::*;
+    use crate::ast::{Field, NamedFields, UnnamedFields};
+    use crate::typesys::SymTy;
+    use crate::value::test_helpers::test_system;
+
+    #[test]
+    fn recursive() {
+        // Semantic ids of recursive types can't be computed, thus the type system has to be forged
+        let node = SemId::from([1u8; 32]);
+        let children = SemId::from([2u8; 32]);
+        let value = SemId::from([3u8; 32]);
+        let fields = Confined::try_from(vec![
+            Field {
+                name: fname!("value"),
+                ty: value,
+            },
+            Field {
+                name: fname!("children"),
+                ty: children,
+            },
+        ])
+        .unwrap();
+        let node_ty = Ty::Struct(NamedFields::from_inner(fields));
+        let types = bmap! {
+            node => SymTy::named(libname!("Test"), tn!("Node"), node_ty),
+            children => SymTy::unnamed(Ty::List(node, Sizing::U8)),
+            value => SymTy::unnamed(Ty::U8)
+        };
+        let sys = SymbolicSys::with(None, types).unwrap();
+        let tree = sys.type_tree(node).unwrap();
+
+        assert_eq!(tree.detect_cycles(), vec![vec![tn!("Node")]]);
+        assert!(sys.type_tree(value).unwrap().detect_cycles().is_empty());
+
+        let items = tree.iter().collect::<Vec<_>>();
+        assert_eq!(items.len(), 4);
+        assert!(items[..3].iter().all(|info| !info.is_recursive()));
+        assert!(items[3].is_recursive());
+        assert_eq!(items[3].depth, 2);
+        assert_eq!(items[3].recursive, Some(0));
+        assert_eq!(items[3].fqn, items[0].fqn);
+        assert_eq!(items[0].sizing(), None);
+        assert_eq!(items[2].sizing(), Some(Sizing::U8));
+
+        let layout = tree.to_layout();
+        assert_eq!(layout.size_range(), (2, None));
+        let data = layout.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(MemoryLayout::from_strict_serialized(data).unwrap(), layout);
+        assert_eq!(tree.try_to_layout(4).unwrap(), layout);
+        assert_eq!(tree.try_to_layout(3), Err(LayoutError::TooLarge(3)));
+
+        assert_eq!(tree.max_depth(), 2);
+        assert_eq!(MemoryLayout::with_max_depth(&tree, 2).unwrap(), layout);
+        assert_eq!(MemoryLayout::with_max_depth(&tree, 1), Err(LayoutError::TooDeep(1)));
+        assert_eq!(sys.type_tree(value).unwrap().max_depth(), 0);
+
+        let leaves = tree.iter_leaves().map(|(path, info)| (path.to_string(), info.ty));
+        assert_eq!(leaves.collect::<Vec<_>>(), vec![(s!("value"), Ty::U8)]);
+    }
+
+    #[test]
+    fn recursive_newtype() {
+        let node = SemId::from([1u8; 32]);
+        let children = SemId::from([2u8; 32]);
+        let fields = Confined::try_from(vec![children]).unwrap();
+        let node_ty = Ty::Tuple(UnnamedFields::from_inner(fields));
+        let types = bmap! {
+            node => SymTy::named(libname!("Test"), tn!("Node"), node_ty),
+            children => SymTy::unnamed(Ty::List(node, Sizing::U8))
+        };
+        let sys = SymbolicSys::with(None, types).unwrap();
+        let tree = sys.type_tree(node).unwrap();
+        let items = tree.iter().collect::<Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].recursive, Some(0));
+
+        let layout = tree.to_layout();
+        let data = layout.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(MemoryLayout::from_strict_serialized(data).unwrap(), layout);
+    }
+
+    #[test]
+    fn iter_leaves() {
+        let sys = test_system();
+        let tree = sys.type_tree("TestLib.Nominal").unwrap();
+        let paths = tree.iter_leaves().map(|(path, _)| path.to_string()).collect::<Vec<_>>();
+        assert_eq!(paths, vec![s!("ticker"), s!("name"), s!("precision")]);
+
+        let tree = sys.type_tree("StrictTypes.TypeLib").unwrap();
+        let paths = tree.iter_leaves().map(|(path, _)| path.to_string()).collect::<Vec<_>>();
+        assert!(paths.contains(&s!("name")));
+        assert!(paths.contains(&s!("dependencies.item.id")));
+        assert!(paths.contains(&s!("types.key")));
+
+        let tree = sys.type_tree("StrictTypes.TypeName").unwrap();
+        let leaves = tree.iter_leaves().collect::<Vec<_>>();
+        assert_eq!(leaves.len(), 1);
+        assert!(leaves[0].0.is_empty());
+    }
+
+    #[test]
+    fn kind() {
+        let sys = test_system();
+        let tree = sys.type_tree("TestLib.Nominal").unwrap();
+        let kinds = tree.iter().map(|info| (info.kind(), info.name().cloned())).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![
+            (TypeKind::Struct, Some(tn!("Nominal"))),
+            (TypeKind::Alias, Some(tn!("Ident"))),
+            (TypeKind::String, None),
+            (TypeKind::Enum, Some(tn!("Precision"))),
+        ]);
+        assert_eq!(TypeKind::Alias.to_string(), "alias");
+
+        let tree = sys.type_tree("StrictTypes.TypeLib").unwrap();
+        let kinds = tree.iter().map(|info| info.kind()).collect::<BTreeSet<_>>();
+        assert!(kinds.contains(&TypeKind::Map));
+        assert!(kinds.contains(&TypeKind::Union));
+        assert!(kinds.contains(&TypeKind::Set));
+    }
+}
@@ -26,9 +26,11 @@ mod translate;
 mod id;
 mod symbols;
 mod iter;
+mod path;
 
 pub use id::TypeSysId;
-pub use iter::{NestedCase, TypeInfo, TypeTree, TypeTreeIter};
-pub use symbols::{SymbolicSys, Symbols};
+pub use iter::{NestedCase, TypeInfo, TypeKind, TypeTree, TypeTreeIter};
+pub use path::PathError;
+pub use symbols::{ImportError, LibSystemError, ResolutionReport, SymbolicSys, Symbols};
 pub use translate::{Error, SystemBuilder, TypeSymbol};
 pub use type_sys::{SymTy, TypeFqn, TypeSystem, UnknownType};
@@ -0,0 +1,163 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Resolution of nested types by their path inside a type system.
+
+use std::iter;
+use std::str::FromStr;
+
+use encoding::{FieldName, LibName, TypeName, VariantName};
+
+use crate::typesys::{TypeFqn, TypeSymbol};
+use crate::{SemId, SymbolicSys, Ty};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum PathError {
+    /// path starts with `{0}`, which is not a known type.
+    UnknownType(String),
+
+    /// type name `{0}` is provided by multiple libraries; please use fully qualified name.
+    AmbiguousType(TypeName),
+
+    /// path segment #{pos} `{segment}` can't be resolved within type `{within}`.
+    UnresolvedSegment {
+        pos: usize,
+        segment: String,
+        within: TypeSymbol,
+    },
+}
+
+impl SymbolicSys {
+    /// Resolves type located at some dot-separated path inside other type.
+    ///
+    /// The path starts with either a fully qualified or just a type name, which is followed by
+    /// struct field names, tuple field indexes and union variant names. Elements of arrays,
+    /// lists and sets are selected with either `[]` or `0`; map keys are selected with `0` and
+    /// map values - with `1` or `[]`. Examples: `Bitcoin.Tx.inputs[].prevOutput.txid`,
+    /// `Tx.inputs.0.prevOutput`.
+    pub fn resolve_path(&self, path: &str) -> Result<TypeSymbol, PathError> {
+        let mut segments = vec![];
+        for segment in path.split('.') {
+            let name = segment.trim_end_matches("[]");
+            let count = (segment.len() - name.len()) / 2;
+            if !name.is_empty() || count == 0 {
+                segments.push(name);
+            }
+            segments.extend(iter::repeat_n("[]", count));
+        }
+
+        let (mut sem_id, skip) = self.resolve_path_root(&segments)?;
+        for (pos, segment) in segments.iter().enumerate().skip(skip) {
+            let ty = self.as_types().get(sem_id).expect("inconsistent type system");
+            let next = match (ty, *segment) {
+                (Ty::Struct(fields), segment) => FieldName::from_str(segment)
+                    .ok()
+                    .and_then(|name| fields.ty_by_name(&name))
+                    .or_else(|| segment.parse().ok().and_then(|pos| fields.ty_by_pos(pos))),
+                (Ty::Tuple(fields), segment) => {
+                    segment.parse().ok().and_then(|pos| fields.ty_by_pos(pos))
+                }
+                (Ty::Union(variants), segment) => {
+                    VariantName::from_str(segment).ok().and_then(|name| variants.ty_by_name(&name))
+                }
+                (Ty::Array(ty, _) | Ty::List(ty, _) | Ty::Set(ty, _), "[]" | "0") => Some(ty),
+                (Ty::Map(key, _, _), "0") => Some(key),
+                (Ty::Map(_, val, _), "1" | "[]") => Some(val),
+                _ => None,
+            };
+            sem_id = *next.ok_or_else(|| PathError::UnresolvedSegment {
+                pos,
+                segment: segment.to_string(),
+                within: self.type_symbol(sem_id),
+            })?;
+        }
+        Ok(self.type_symbol(sem_id))
+    }
+
+    fn resolve_path_root(&self, segments: &[&str]) -> Result<(SemId, usize), PathError> {
+        let first = segments.first().expect("split always returns at least one segment");
+        if let Some(second) = segments.get(1) {
+            let lib = LibName::from_str(first);
+            let name = TypeName::from_str(second);
+            if let Some(sem_id) = lib
+                .ok()
+                .zip(name.ok())
+                .and_then(|(lib, name)| self.resolve(TypeFqn::with(lib, name)))
+            {
+                return Ok((*sem_id, 2));
+            }
+        }
+        let name =
+            TypeName::from_str(first).map_err(|_| PathError::UnknownType(first.to_string()))?;
+        let mut found = self.symbols.find_by_name(&name);
+        match (found.next(), found.next()) {
+            (None, _) => Err(PathError::UnknownType(first.to_string())),
+            (Some(sym), None) => Ok((sym.id, 1)),
+            (Some(_), Some(_)) => Err(PathError::AmbiguousType(name.clone())),
+        }
+    }
+
+    fn type_symbol(&self, sem_id: SemId) -> TypeSymbol {
+        TypeSymbol {
+            id: sem_id,
+            fqn: self.lookup(sem_id).cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value::test_helpers::test_system;
+
+    #[test]
+    fn resolve_path() {
+        let sys = test_system();
+        let nominal = sys.resolve_path("TestLib.Nominal").unwrap();
+        assert_eq!(nominal.fqn, Some(TypeFqn::from("TestLib.Nominal")));
+        assert_eq!(sys.resolve_path("Nominal").unwrap(), nominal);
+
+        let precision = sys.resolve_path("Nominal.precision").unwrap();
+        assert_eq!(precision.fqn, Some(TypeFqn::from("TestLib.Precision")));
+        assert_eq!(sys.resolve_path("TestLib.Nominal.2").unwrap(), precision);
+
+        let ident = sys.resolve_path("Nominal.ticker").unwrap();
+        assert_eq!(
+            sys.resolve_path("Nominal.ticker.0.1[]").unwrap(),
+            sys.resolve_path("Nominal.ticker.0.1.0").unwrap()
+        );
+        assert_ne!(sys.resolve_path("Nominal.ticker.0.1[]").unwrap(), ident);
+
+        assert_eq!(sys.resolve_path(""), Err(PathError::UnknownType(s!(""))));
+        assert_eq!(sys.resolve_path("Unknown.field"), Err(PathError::UnknownType(s!("Unknown"))));
+        assert_eq!(
+            sys.resolve_path("Nominal.unknown"),
+            Err(PathError::UnresolvedSegment {
+                pos: 1,
+                segment: s!("unknown"),
+                within: nominal,
+            })
+        );
+    }
+}
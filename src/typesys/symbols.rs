@@ -21,16 +21,19 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter};
 use std::ops::Index;
 
-use amplify::confinement::{self, MediumOrdSet, SmallOrdSet};
-use encoding::{StrictDeserialize, StrictSerialize, STRICT_TYPES_LIB};
+use amplify::confinement::{self, Confined, MediumOrdSet, SmallOrdSet};
+use encoding::{StrictDeserialize, StrictSerialize, TypeName, STRICT_TYPES_LIB};
 
-use crate::typesys::{translate, SymTy, TypeFqn, TypeSymbol, TypeSysId, TypeTree};
+use crate::typelib::MergePolicy;
+use crate::typesys::{
+    translate, SymTy, SystemBuilder, TypeFqn, TypeSymbol, TypeSysId, TypeTree, UnknownType,
+};
 use crate::typify::TypeSpec;
-use crate::{Dependency, SemId, Translate, Ty, TypeSystem};
+use crate::{Dependency, SemId, Translate, Ty, TypeLib, TypeSystem};
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
@@ -81,6 +84,13 @@ impl Symbols {
     pub fn lookup(&self, sem_id: SemId) -> Option<&TypeFqn> {
         self.symbols.iter().find(|sym| sym.id == sem_id).and_then(|sym| sym.fqn.as_ref())
     }
+
+    pub(super) fn find_by_name<'a>(
+        &'a self,
+        name: &'a TypeName,
+    ) -> impl Iterator<Item = &'a TypeSymbol> + 'a {
+        self.symbols.iter().filter(move |sym| sym.fqn.as_ref().map(|fqn| &fqn.name) == Some(name))
+    }
 }
 
 impl Index<&'static str> for Symbols {
@@ -107,6 +117,59 @@ impl Display for Symbols {
     }
 }
 
+/// Errors importing one symbolic type system into another.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum ImportError {
+    /// type `{fqn}` has different semantic ids {ours} and {theirs} in the imported type systems.
+    Conflict {
+        fqn: TypeFqn,
+        ours: SemId,
+        theirs: SemId,
+    },
+
+    /// library {0} conflicts with library {1} of the imported type system.
+    LibConflict(Dependency, Dependency),
+
+    #[from]
+    #[display(inner)]
+    Confinement(confinement::Error),
+}
+
+/// Errors building a type system out of a single type library with [`SymbolicSys::from_lib`].
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, From, Error)]
+#[wrapper(Deref)]
+pub struct LibSystemError(Vec<translate::Error>);
+
+impl Display for LibSystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("unable to build type system from the type library:")?;
+        for error in &self.0 {
+            write!(f, "\n- {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Report on resolving the symbols of a [`SymbolicSys`] into the types of its type system,
+/// produced by [`SymbolicSys::compile_reporting`].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ResolutionReport {
+    /// Symbols referring to the types present in the type system.
+    pub resolved: BTreeMap<TypeFqn, SemId>,
+    /// Symbols referring to the types absent from the type system.
+    pub unresolved: BTreeMap<TypeFqn, SemId>,
+    /// Types absent from the type system, together with the types referencing them.
+    pub dangling: BTreeMap<SemId, BTreeSet<TypeSymbol>>,
+    /// Types known under several names, which are collapsed into a single type.
+    pub aliases: BTreeMap<SemId, BTreeSet<TypeFqn>>,
+}
+
+impl ResolutionReport {
+    /// Detects whether all symbols and type references were resolved.
+    pub fn is_complete(&self) -> bool { self.unresolved.is_empty() && self.dangling.is_empty() }
+}
+
 #[derive(Getters, Clone, Eq, PartialEq, Debug)]
 #[getter(prefix = "as_")]
 #[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
@@ -141,8 +204,22 @@ impl SymbolicSys {
 
     pub fn new(types: TypeSystem, symbols: Symbols) -> Self { Self { symbols, types } }
 
+    /// Builds type system out of the types of `lib`, which must not have dependencies.
+    pub fn from_lib(lib: &TypeLib) -> Result<Self, LibSystemError> {
+        SystemBuilder::new()
+            .import(lib.clone())
+            .map_err(|err| LibSystemError(vec![err]))?
+            .finalize()
+            .map_err(LibSystemError)
+    }
+
     pub fn id(&self) -> TypeSysId { self.types.id() }
 
+    /// Computes id of the type system in the `namespace`; see [`TypeSystem::namespaced_id`].
+    pub fn namespaced_id(&self, namespace: &str) -> TypeSysId {
+        self.types.namespaced_id(namespace)
+    }
+
     pub fn get(&self, spec: impl Into<TypeSpec>) -> Option<&Ty<SemId>> {
         let sem_id = self.to_sem_id(spec)?;
         self.types.get(sem_id)
@@ -166,6 +243,196 @@ impl SymbolicSys {
     }
 
     pub fn into_type_system(self) -> TypeSystem { self.types }
+
+    /// Converts the symbolic type system into a type system, reporting how its symbols and type
+    /// references are resolved.
+    ///
+    /// Unlike [`SymbolicSys::into_type_system`], checks the type system for completeness and
+    /// fails with all the found errors if some of the symbols or type references point to types
+    /// which are not a part of the system. The report is produced in both cases.
+    pub fn compile_reporting(
+        self,
+    ) -> (Result<TypeSystem, Vec<translate::Error>>, ResolutionReport) {
+        let mut report = ResolutionReport::default();
+        let mut errors = vec![];
+
+        let mut names = BTreeMap::<SemId, BTreeSet<TypeFqn>>::new();
+        for sym in &self.symbols.symbols {
+            let Some(fqn) = &sym.fqn else {
+                continue;
+            };
+            names.entry(sym.id).or_default().insert(fqn.clone());
+            if self.types.get(sym.id).is_some() {
+                report.resolved.insert(fqn.clone(), sym.id);
+            } else {
+                report.unresolved.insert(fqn.clone(), sym.id);
+                errors.push(translate::Error::UnknownType(sym.id));
+            }
+        }
+        report.aliases = names.into_iter().filter(|(_, names)| names.len() > 1).collect();
+
+        for (id, ty) in self.types.as_unconfined() {
+            for (inner_id, _) in ty.type_refs() {
+                if self.types.get(*inner_id).is_some() {
+                    continue;
+                }
+                let referrer = match self.lookup(*id) {
+                    Some(fqn) => TypeSymbol::with(*id, fqn.clone()),
+                    None => TypeSymbol::unnamed(*id),
+                };
+                report.dangling.entry(*inner_id).or_default().insert(referrer);
+                errors.push(translate::Error::InnerTypeAbsent {
+                    unknown: *inner_id,
+                    known: *id,
+                });
+            }
+        }
+
+        if !errors.is_empty() {
+            return (Err(errors), report);
+        }
+        (Ok(self.types), report)
+    }
+
+    /// Imports types and symbols from `other` type system, failing if the same type name refers
+    /// to different types in the two systems. See [`SymbolicSys::import_with`] for other ways of
+    /// resolving such conflicts.
+    pub fn import(&mut self, other: &SymbolicSys) -> Result<(), ImportError> {
+        self.import_with(other, MergePolicy::RequireIdentical)
+    }
+
+    /// Imports types and symbols from `other` type system, resolving type names which refer to
+    /// different types in the two systems according to the `policy`.
+    ///
+    /// The type system is not modified if the import fails.
+    pub fn import_with(
+        &mut self,
+        other: &SymbolicSys,
+        policy: MergePolicy,
+    ) -> Result<(), ImportError> {
+        let mut symbols = self.symbols.symbols.iter().cloned().collect::<BTreeSet<_>>();
+        for sym in &other.symbols.symbols {
+            let present = sym
+                .fqn
+                .as_ref()
+                .and_then(|fqn| symbols.iter().find(|s| s.fqn.as_ref() == Some(fqn)))
+                .cloned();
+            match present {
+                Some(present) if present.id == sym.id => {}
+                Some(present) => match policy {
+                    MergePolicy::TakeSelf => {}
+                    MergePolicy::TakeOther => {
+                        symbols.remove(&present);
+                        symbols.insert(sym.clone());
+                    }
+                    MergePolicy::RequireIdentical => {
+                        return Err(ImportError::Conflict {
+                            fqn: present.fqn.expect("matched by the name"),
+                            ours: present.id,
+                            theirs: sym.id,
+                        });
+                    }
+                },
+                None => {
+                    symbols.insert(sym.clone());
+                }
+            }
+        }
+
+        let mut libs = self.symbols.libs.iter().cloned().collect::<BTreeSet<_>>();
+        for dep in &other.symbols.libs {
+            match libs.iter().find(|d| d.name == dep.name).cloned() {
+                Some(present) if &present == dep => {}
+                Some(present) => match policy {
+                    MergePolicy::TakeSelf => {}
+                    MergePolicy::TakeOther => {
+                        libs.remove(&present);
+                        libs.insert(dep.clone());
+                    }
+                    MergePolicy::RequireIdentical => {
+                        return Err(ImportError::LibConflict(present, dep.clone()));
+                    }
+                },
+                None => {
+                    libs.insert(dep.clone());
+                }
+            }
+        }
+
+        let mut types = self.types.clone();
+        types.extend(other.types.clone())?;
+        self.symbols = Symbols {
+            libs: Confined::try_from(libs)?,
+            symbols: Confined::try_from(symbols)?,
+        };
+        self.types = types;
+        Ok(())
+    }
+
+    /// Returns names of the types which are not reachable from any of the `roots`.
+    ///
+    /// Roots are matched by their type names in any of the libraries. Unnamed unreachable types
+    /// are not reported, but they are removed by [`SymbolicSys::prune`] nevertheless.
+    ///
+    /// Errors if the type system is inconsistent and misses some of the types used by the roots.
+    pub fn unused_types(&self, roots: &[TypeName]) -> Result<BTreeSet<TypeName>, UnknownType> {
+        let used = self.reachable(roots)?;
+        Ok(self
+            .symbols
+            .symbols
+            .iter()
+            .filter(|sym| used.get(sym.id).is_none())
+            .filter_map(|sym| sym.fqn.as_ref())
+            .map(|fqn| fqn.name.clone())
+            .collect())
+    }
+
+    /// Removes all types, named and unnamed, which are not reachable from any of the `roots`.
+    ///
+    /// See [`SymbolicSys::unused_types`] for the details on how the roots are matched and when
+    /// the error is returned; the type system is left unchanged on error.
+    pub fn prune(&mut self, roots: &[TypeName]) -> Result<(), UnknownType> {
+        let used = self.reachable(roots)?;
+        let symbols = self.symbols.symbols.iter().filter(|sym| used.get(sym.id).is_some());
+        self.symbols.symbols = Confined::from_checked(symbols.cloned().collect());
+        self.types = used;
+        Ok(())
+    }
+
+    /// Produces a textual dump of the named types, one line per type, sorted by the type names.
+    ///
+    /// Each line contains the fully qualified type name, its semantic id and its kind: the type
+    /// class, or `alias` followed by the wrapped type for the newtypes (tuples with a single
+    /// field). Since the names are qualified with the library names, the same type name from
+    /// different libraries results in distinct lines.
+    pub fn describe(&self) -> String {
+        let mut lines = BTreeMap::new();
+        for sym in &self.symbols.symbols {
+            let Some(fqn) = &sym.fqn else {
+                continue;
+            };
+            let Some(ty) = self.types.get(sym.id) else {
+                continue;
+            };
+            let kind = match ty {
+                Ty::Tuple(fields) if fields.len() == 1 => {
+                    let inner = fields[0];
+                    match self.lookup(inner) {
+                        Some(fqn) => format!("alias {fqn}"),
+                        None => format!("alias {inner}"),
+                    }
+                }
+                ty => ty.cls().to_string(),
+            };
+            lines.insert(fqn.to_string(), format!("{fqn} {} {kind}\n", sym.id));
+        }
+        lines.into_values().collect()
+    }
+
+    fn reachable(&self, roots: &[TypeName]) -> Result<TypeSystem, UnknownType> {
+        let ids = roots.iter().flat_map(|name| self.symbols.find_by_name(name)).map(|sym| sym.id);
+        self.types.extract(ids)
+    }
 }
 
 impl Display for SymbolicSys {
@@ -193,3 +460,136 @@ impl armor::StrictArmor for SymbolicSys {
 
     fn armor_id(&self) -> Self::Id { self.id() }
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use super::*;
+    use crate::stl::std_stl;
+    use crate::value::test_helpers::test_system;
+    use crate::{LibBuilder, SystemBuilder};
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "OtherLib")]
+    struct Plugin {
+        id: u32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "OtherLib")]
+    struct Host(Plugin);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", rename = "Nominal")]
+    struct FakeNominal {
+        ticker: u8,
+    }
+
+    fn system<T: encoding::StrictEncode + encoding::StrictDumb>(lib: &'static str) -> SymbolicSys {
+        let lib = LibBuilder::with(lib, None).transpile::<T>().compile().unwrap();
+        SystemBuilder::new().import(lib).unwrap().import(std_stl()).unwrap().finalize().unwrap()
+    }
+
+    #[test]
+    fn import() {
+        let mut sys = test_system();
+        let plugin = system::<Plugin>("OtherLib");
+        sys.import(&plugin).unwrap();
+        assert!(sys.type_tree("TestLib.Nominal").is_some());
+        assert!(sys.type_tree("OtherLib.Plugin").is_some());
+        assert!(sys.resolve("Std.AlphaCaps").is_some());
+
+        let imported = sys.clone();
+        sys.import(&plugin).unwrap();
+        assert_eq!(sys, imported);
+
+        let fake = system::<FakeNominal>("TestLib");
+        let ours = *sys.resolve("TestLib.Nominal").unwrap();
+        let theirs = *fake.resolve("TestLib.Nominal").unwrap();
+        assert_eq!(
+            sys.import(&fake),
+            Err(ImportError::Conflict {
+                fqn: TypeFqn::from("TestLib.Nominal"),
+                ours,
+                theirs
+            })
+        );
+        assert_eq!(sys, imported);
+
+        sys.import_with(&fake, MergePolicy::TakeOther).unwrap();
+        assert_eq!(sys.resolve("TestLib.Nominal"), Some(&theirs));
+    }
+
+    #[test]
+    fn prune() {
+        let mut sys = test_system();
+        let unused = sys.unused_types(&[tn!("Nominal")]).unwrap();
+        assert!(unused.contains(&tn!("TypeLib")));
+        assert!(unused.contains(&tn!("AlphaCaps")));
+        assert!(!unused.contains(&tn!("Nominal")));
+        assert!(!unused.contains(&tn!("Precision")));
+        assert!(!unused.contains(&tn!("Ident")));
+        assert!(!unused.contains(&tn!("AlphaNumLodash")));
+
+        let id = sys.id();
+        sys.prune(&[tn!("Nominal")]).unwrap();
+        assert_ne!(sys.id(), id);
+        assert!(sys.unused_types(&[tn!("Nominal")]).unwrap().is_empty());
+        assert!(sys.type_tree("StrictTypes.TypeLib").is_none());
+        assert!(sys.type_tree("TestLib.Nominal").is_some());
+        assert!(sys.resolve("StrictTypes.TypeLib").is_none());
+    }
+
+    #[test]
+    fn describe() {
+        let mut sys = system::<Host>("OtherLib");
+        sys.import(&system::<FakeNominal>("TestLib")).unwrap();
+        let dump = sys.describe();
+        assert_eq!(dump, sys.describe());
+
+        let lines = dump.lines().collect::<Vec<_>>();
+        let mut sorted = lines.clone();
+        sorted.sort();
+        assert_eq!(lines, sorted);
+
+        let nominal = sys.resolve("TestLib.Nominal").unwrap();
+        assert!(lines.contains(&format!("TestLib.Nominal {nominal} struct").as_str()));
+        let plugin = sys.resolve("OtherLib.Plugin").unwrap();
+        assert!(lines.contains(&format!("OtherLib.Plugin {plugin} struct").as_str()));
+        let host = sys.resolve("OtherLib.Host").unwrap();
+        assert!(lines.contains(&format!("OtherLib.Host {host} alias OtherLib.Plugin").as_str()));
+        let alpha = sys.resolve("Std.AlphaCaps").unwrap();
+        assert!(lines.contains(&format!("Std.AlphaCaps {alpha} enum").as_str()));
+    }
+
+    #[test]
+    fn compile_reporting() {
+        let mut sys = system::<Host>("OtherLib");
+        let host = *sys.resolve("OtherLib.Host").unwrap();
+        let plugin = *sys.resolve("OtherLib.Plugin").unwrap();
+        sys.symbols.update_unchecked(host, Some(TypeFqn::with("Alias", "Host"))).unwrap();
+
+        let (res, report) = sys.clone().compile_reporting();
+        assert_eq!(res.unwrap(), sys.as_types().clone());
+        assert!(report.is_complete());
+        assert_eq!(report.resolved.get(&TypeFqn::with("OtherLib", "Plugin")), Some(&plugin));
+        assert_eq!(report.aliases.len(), 1);
+        assert_eq!(report.aliases[&host].len(), 2);
+
+        let mut types = TypeSystem::new();
+        types.insert_unchecked(host, sys.as_types()[host].clone()).unwrap();
+        let broken = SymbolicSys::new(types, sys.as_symbols().clone());
+        let (res, report) = broken.compile_reporting();
+        let errors = res.unwrap_err();
+        assert!(!report.is_complete());
+        assert_eq!(errors.len(), report.unresolved.len() + 1);
+        assert_eq!(report.resolved.len(), 2);
+        assert_eq!(report.unresolved.get(&TypeFqn::with("OtherLib", "Plugin")), Some(&plugin));
+        assert_eq!(report.dangling.len(), 1);
+        assert!(report.dangling[&plugin].iter().all(|sym| sym.id == host));
+    }
+}
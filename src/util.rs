@@ -21,12 +21,17 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use std::env;
+use std::convert::Infallible;
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
+use std::{env, io};
 
 use amplify::confinement::TinyVec;
-use strict_encoding::{Ident, STRICT_TYPES_LIB};
+use baid64::{Baid64ParseError, FromBaid64Str};
+use strict_encoding::{
+    DecodeError, Ident, StrictDecode, StrictDumb, StrictEncode, StrictType, TypeName, TypedRead,
+    TypedWrite, LIB_EMBEDDED, STRICT_TYPES_LIB,
+};
 
 use crate::typelib::TypeLibId;
 use crate::SemId;
@@ -60,6 +65,41 @@ impl FromStr for StlFormat {
     }
 }
 
+/// Errors parsing Baid64-encoded identifiers (type library, type system and semantic ids).
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum IdError {
+    #[from]
+    #[display(inner)]
+    Baid64(Baid64ParseError),
+
+    /// mnemonic suffix '{found}' of identifier '{id}' does not match its digest, which requires
+    /// '{expected}'.
+    MnemonicMismatch {
+        id: String,
+        expected: String,
+        found: String,
+    },
+}
+
+/// Parses Baid64-encoded identifier, checking that the optional mnemonic suffix matches the digest.
+pub(crate) fn parse_baid64_id<T>(s: &str) -> Result<T, IdError>
+where T: FromBaid64Str + TryFrom<[u8; 32], Error = Infallible> {
+    let Some((body, found)) = s.split_once('#') else {
+        return T::from_baid64_str(s).map_err(IdError::from);
+    };
+    let id = T::from_baid64_str(body)?;
+    let expected = id.to_baid64_mnemonic();
+    if expected != found {
+        return Err(IdError::MnemonicMismatch {
+            id: s.to_owned(),
+            expected,
+            found: found.to_owned(),
+        });
+    }
+    Ok(id)
+}
+
 pub fn parse_args() -> (StlFormat, Option<String>) {
     let args: Vec<String> = env::args().collect();
     let ext = args.get(1).map(String::as_str).map(|s| s.trim_start_matches("--")).unwrap_or("sty");
@@ -211,3 +251,34 @@ pub enum Urn {
     #[display("urn:sten:id:{0}", alt = "urn:sten:id:{0:#}")]
     Type(SemId),
 }
+
+/// Reserved bytes of a fixed-size encoding, used to match binary layouts with explicit padding
+/// between fields, like the ones of `#[repr(C)]` structures.
+///
+/// Padding is represented in type libraries as a byte array of `LEN` bytes, so the memory layout
+/// of a type using it keeps the exact positions of all its fields. The bytes are always encoded
+/// as zeros, and their values are ignored on decoding.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Padding<const LEN: usize>;
+
+impl<const LEN: usize> StrictDumb for Padding<LEN> {
+    fn strict_dumb() -> Self { Padding }
+}
+
+impl<const LEN: usize> StrictType for Padding<LEN> {
+    const STRICT_LIB_NAME: &'static str = LIB_EMBEDDED;
+    fn strict_name() -> Option<TypeName> { None }
+}
+
+impl<const LEN: usize> StrictEncode for Padding<LEN> {
+    fn strict_encode<W: TypedWrite>(&self, writer: W) -> io::Result<W> {
+        [0u8; LEN].strict_encode(writer)
+    }
+}
+
+impl<const LEN: usize> StrictDecode for Padding<LEN> {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        <[u8; LEN]>::strict_decode(reader)?;
+        Ok(Padding)
+    }
+}
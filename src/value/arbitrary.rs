@@ -0,0 +1,440 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Generation of arbitrary strict values of a given type, for property-based testing and fuzzing
+//! of the code consuming the values.
+
+use std::collections::HashMap;
+
+use encoding::{NumCls, Primitive, Sizing, TypeName};
+use indexmap::IndexMap;
+
+use crate::layout::MemoryLayout;
+use crate::typesys::{LibSystemError, SymbolicSys, TypeFqn};
+use crate::typify::TypeSpec;
+use crate::value::Blob;
+use crate::{SemId, StrictVal, Ty, TypeLib, TypeRef, TypeSystem};
+
+/// Maximal number of items added to a collection above its minimal length.
+const MAX_EXTRA_ITEMS: u64 = 8;
+/// Nesting depth after which collections get only their minimal number of items.
+const MAX_DEPTH: usize = 4;
+/// Characters used in unicode strings besides ASCII, of different UTF-8 lengths.
+const UNICODE_CHARS: [char; 4] = ['ß', 'Ж', '€', '𝄞'];
+
+/// Errors generating arbitrary values with [`StrictVal::arbitrary_for`] and
+/// [`StrictVal::arbitrary_in`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum ArbitraryError {
+    /// unknown type `{0}`.
+    TypeAbsent(TypeSpec),
+
+    /// generation of values of type `{0}` is not supported.
+    Unsupported(String),
+
+    /// type `{0}` doesn't have enough distinct values for a set or a map with {1} items.
+    NotUnique(SemId, u64),
+
+    #[display(inner)]
+    Library(LibSystemError),
+}
+
+impl StrictVal {
+    /// Generates a pseudo-random value of the library type `root`, which is fully determined by
+    /// the `seed`; see [`StrictVal::arbitrary_in`] for the details.
+    ///
+    /// The library must not depend on other libraries; use [`StrictVal::arbitrary_in`] with a
+    /// type system containing all the dependencies otherwise.
+    pub fn arbitrary_for(
+        lib: &TypeLib,
+        root: &TypeName,
+        seed: u64,
+    ) -> Result<StrictVal, ArbitraryError> {
+        let sys = SymbolicSys::from_lib(lib).map_err(ArbitraryError::Library)?;
+        let fqn = TypeFqn::with(lib.name.clone(), root.clone());
+        Self::arbitrary_in(&sys, fqn, seed)
+    }
+
+    /// Generates a pseudo-random value of the type `spec` from the `sys` type system, which is
+    /// fully determined by the `seed`.
+    ///
+    /// The values respect the confinement bounds of collections and strings, pick only the
+    /// defined enum and union variants and use the full range of integers of each width. The
+    /// values have the same form as the ones produced by
+    /// [`TypeSystem::strict_deserialize_type`], such that they compare equal after a round trip
+    /// through the strict encoding, and sets and maps are ordered as the strict encoding requires.
+    /// Collections get at most few items above their minimal length to keep the values small.
+    ///
+    /// Property-based testing frameworks can use the function by mapping an arbitrary `u64`
+    /// seed into the value, which also allows them to reproduce failures from the reported seed.
+    ///
+    /// Floats, integers wider than 64 bits and unicode characters outside of strings are not
+    /// supported, as well as they are not supported by the strict value decoding.
+    pub fn arbitrary_in(
+        sys: &SymbolicSys,
+        spec: impl Into<TypeSpec>,
+        seed: u64,
+    ) -> Result<StrictVal, ArbitraryError> {
+        let spec = spec.into();
+        let sem_id = sys.to_sem_id(spec.clone()).ok_or(ArbitraryError::TypeAbsent(spec))?;
+        let mut gen = Generator {
+            symbols: sys,
+            sys: sys.as_types(),
+            layouts: HashMap::new(),
+            state: seed,
+            depth: 0,
+        };
+        gen.val(sem_id)
+    }
+}
+
+struct Generator<'sys> {
+    symbols: &'sys SymbolicSys,
+    sys: &'sys TypeSystem,
+    /// Layouts of the set item and map key types, used for ordering the generated items.
+    layouts: HashMap<SemId, MemoryLayout>,
+    state: u64,
+    depth: usize,
+}
+
+impl<'sys> Generator<'sys> {
+    /// SplitMix64 step.
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        debug_assert!(n > 0);
+        self.next() % n
+    }
+
+    fn len(&mut self, sizing: Sizing) -> u64 {
+        let extra = if self.depth > MAX_DEPTH { 0 } else { MAX_EXTRA_ITEMS };
+        let max = sizing.max.min(sizing.min.saturating_add(extra));
+        sizing.min + self.below(max - sizing.min + 1)
+    }
+
+    fn ty(&self, sem_id: SemId) -> Result<&'sys Ty<SemId>, ArbitraryError> {
+        let sys: &'sys TypeSystem = self.sys;
+        sys.get(sem_id).ok_or(ArbitraryError::TypeAbsent(TypeSpec::SemId(sem_id)))
+    }
+
+    fn layout(&mut self, sem_id: SemId) -> Result<&MemoryLayout, ArbitraryError> {
+        if !self.layouts.contains_key(&sem_id) {
+            let tree = self
+                .symbols
+                .type_tree(sem_id)
+                .ok_or(ArbitraryError::TypeAbsent(TypeSpec::SemId(sem_id)))?;
+            self.layouts.insert(sem_id, tree.to_layout());
+        }
+        Ok(&self.layouts[&sem_id])
+    }
+
+    fn val(&mut self, sem_id: SemId) -> Result<StrictVal, ArbitraryError> {
+        self.depth += 1;
+        let val = self.ty(sem_id).and_then(|ty| self.val_ty(sem_id, ty));
+        self.depth -= 1;
+        val
+    }
+
+    fn val_ty(&mut self, sem_id: SemId, ty: &'sys Ty<SemId>) -> Result<StrictVal, ArbitraryError> {
+        let unsupported = || ArbitraryError::Unsupported(sem_id.to_string());
+        Ok(match ty {
+            Ty::Primitive(Primitive::UNIT) => StrictVal::Unit,
+            Ty::Primitive(prim) => self.num(*prim).ok_or_else(unsupported)?,
+            Ty::UnicodeChar => return Err(unsupported()),
+
+            // ASCII and restricted strings:
+            Ty::List(item, sizing) if self.ty(*item)?.is_char_enum() => {
+                let chars = self.ty(*item)?;
+                let len = self.len(*sizing);
+                StrictVal::String(self.ascii(chars, len))
+            }
+            Ty::Tuple(fields) if self.sys.is_rstring(fields).map_err(|_| unsupported())? => {
+                let (rest, sizing) = self
+                    .sys
+                    .rstring_sizing(fields)
+                    .map_err(|_| unsupported())?
+                    .expect("checked in match");
+                let first = self.ty(fields[0])?;
+                let rest = self.ty(rest)?;
+                let len = self.len(sizing);
+                let mut s = self.ascii(first, 1);
+                s.push_str(&self.ascii(rest, len - 1));
+                StrictVal::String(s)
+            }
+
+            Ty::Enum(variants) => {
+                let pos = self.below(variants.len() as u64) as usize;
+                let variant = variants.iter().nth(pos).expect("position within the range");
+                StrictVal::enumer(variant.name.clone())
+            }
+            Ty::Union(variants) => {
+                let pos = self.below(variants.len() as u64) as usize;
+                let (variant, ty) = variants.iter().nth(pos).expect("position within the range");
+                StrictVal::union(variant.name.clone(), self.val(*ty)?)
+            }
+            Ty::Tuple(fields) => {
+                let mut items = Vec::with_capacity(fields.len());
+                for ty in fields {
+                    items.push(self.val(*ty)?);
+                }
+                StrictVal::tuple(items)
+            }
+            Ty::Struct(fields) => {
+                let mut items = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    items.insert(field.name.clone(), self.val(field.ty)?);
+                }
+                StrictVal::Struct(items)
+            }
+
+            Ty::Array(ty, len) if ty.is_byte() => StrictVal::Bytes(self.bytes(*len as u64)),
+            Ty::Array(ty, len) => StrictVal::List(self.list(*ty, *len as u64)?),
+            Ty::List(ty, sizing) if ty.is_byte() => {
+                let len = self.len(*sizing);
+                StrictVal::Bytes(self.bytes(len))
+            }
+            Ty::List(ty, sizing) if ty.is_unicode_char() => {
+                let len = self.len(*sizing);
+                StrictVal::String(self.unicode(len))
+            }
+            Ty::List(ty, sizing) => {
+                let len = self.len(*sizing);
+                StrictVal::List(self.list(*ty, len)?)
+            }
+            Ty::Set(ty, sizing) => {
+                let len = self.len(*sizing);
+                let set = self.unique(*ty, len, sizing.min)?;
+                StrictVal::Set(set)
+            }
+            Ty::Map(key, ty, sizing) => {
+                let len = self.len(*sizing);
+                let keys = self.unique(*key, len, sizing.min)?;
+                let mut map = Vec::with_capacity(keys.len());
+                for key in keys {
+                    map.push((key, self.val(*ty)?));
+                }
+                StrictVal::Map(map)
+            }
+        })
+    }
+
+    fn num(&mut self, prim: Primitive) -> Option<StrictVal> {
+        let bits = prim.byte_size() as u32 * 8;
+        if prim == Primitive::BYTE {
+            return Some(StrictVal::num(self.next() as u8));
+        }
+        if bits == 0 || bits > 64 {
+            return None;
+        }
+        let raw = self.next();
+        // pick the bounds of the range more often than a random number would hit them
+        let raw = match self.below(8) {
+            0 => 0,
+            1 => u64::MAX,
+            _ => raw,
+        };
+        match prim.info().ty {
+            NumCls::Unsigned => {
+                let val = if bits == 64 { raw } else { raw & ((1u64 << bits) - 1) };
+                Some(StrictVal::num(val))
+            }
+            NumCls::Signed => {
+                let shift = 64 - bits;
+                Some(StrictVal::num(((raw << shift) as i64) >> shift))
+            }
+            _ => None,
+        }
+    }
+
+    fn bytes(&mut self, len: u64) -> Blob { Blob((0..len).map(|_| self.next() as u8).collect()) }
+
+    fn ascii(&mut self, chars: &Ty<SemId>, len: u64) -> String {
+        let Ty::Enum(variants) = chars else {
+            unreachable!("char enum is checked by the caller")
+        };
+        (0..len)
+            .map(|_| {
+                let pos = self.below(variants.len() as u64) as usize;
+                variants.iter().nth(pos).expect("position within the range").tag as char
+            })
+            .collect()
+    }
+
+    /// Generates a unicode string of `len` bytes.
+    fn unicode(&mut self, len: u64) -> String {
+        let mut s = String::with_capacity(len as usize);
+        while (s.len() as u64) < len {
+            let left = len as usize - s.len();
+            let c = UNICODE_CHARS[self.below(UNICODE_CHARS.len() as u64) as usize];
+            if self.below(2) == 0 && c.len_utf8() <= left {
+                s.push(c);
+            } else {
+                s.push((b'a' + self.below(26) as u8) as char);
+            }
+        }
+        s
+    }
+
+    fn list(&mut self, ty: SemId, len: u64) -> Result<Vec<StrictVal>, ArbitraryError> {
+        let mut list = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            list.push(self.val(ty)?);
+        }
+        Ok(list)
+    }
+
+    /// Generates up to `len`, but no less than `min`, distinct values, in the order of their
+    /// strict encoding.
+    fn unique(&mut self, ty: SemId, len: u64, min: u64) -> Result<Vec<StrictVal>, ArbitraryError> {
+        let mut items = Vec::<StrictVal>::with_capacity(len as usize);
+        let mut attempts = 0u64;
+        while (items.len() as u64) < len && attempts < len * 8 + 8 {
+            attempts += 1;
+            let item = self.val(ty)?;
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        if (items.len() as u64) < min {
+            return Err(ArbitraryError::NotUnique(ty, min));
+        }
+        // strict encoding requires set items and map keys to be ordered in the same way as the
+        // encoded data are checked by the memory layout
+        let sys = self.sys;
+        let mut encoded = items
+            .into_iter()
+            .map(|item| {
+                let mut buf = vec![];
+                sys.strict_write_val(&item, ty, &mut buf).expect("memory buffer");
+                (buf, item)
+            })
+            .collect::<Vec<_>>();
+        let layout = self.layout(ty)?;
+        encoded.sort_by(|(a, _), (b, _)| layout.cmp_values(a, b));
+        Ok(encoded.into_iter().map(|(_, item)| item).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::confinement::{TinyOrdMap, TinyOrdSet};
+
+    use super::*;
+    use crate::stl::{std_stl, strict_types_stl};
+    use crate::value::test_helpers::test_system;
+    use crate::{typesys, LibBuilder};
+
+    #[test]
+    fn round_trip() {
+        let sys = test_system();
+        for spec in ["TestLib.Nominal", "StrictTypes.TypeLib", "Std.Bool"] {
+            for seed in 0..200 {
+                let val = StrictVal::arbitrary_in(&sys, spec, seed).unwrap();
+                assert_eq!(val, StrictVal::arbitrary_in(&sys, spec, seed).unwrap());
+                let typed = sys.typify(val.clone(), spec).unwrap();
+                let data = sys.as_types().strict_serialize_value::<0xFFFFFF>(&typed).unwrap();
+                eprintln!("len {}", data.len());
+                let decoded = sys.strict_deserialize_type(spec, &data).unwrap();
+                assert_eq!(decoded.unbox(), val, "{spec} with seed {seed}");
+            }
+        }
+        assert_ne!(
+            StrictVal::arbitrary_in(&sys, "TestLib.Nominal", 0).unwrap(),
+            StrictVal::arbitrary_in(&sys, "TestLib.Nominal", 1).unwrap()
+        );
+        assert_eq!(
+            StrictVal::arbitrary_in(&sys, "TestLib.Absent", 0).unwrap_err(),
+            ArbitraryError::TypeAbsent(TypeSpec::from("TestLib.Absent"))
+        );
+    }
+
+    #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    struct Key {
+        sign: i8,
+        wide: i32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    struct Sorted {
+        ints: TinyOrdSet<i16>,
+        keys: TinyOrdMap<Key, u8>,
+        nested: TinyOrdSet<TinyOrdSet<i8>>,
+    }
+
+    #[test]
+    fn validate() {
+        let lib = LibBuilder::with("Test", None).transpile::<Sorted>().compile().unwrap();
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+        let layout = sys.type_tree("Test.Sorted").unwrap().to_layout();
+        for seed in 0..200 {
+            let val = StrictVal::arbitrary_in(&sys, "Test.Sorted", seed).unwrap();
+            let typed = sys.typify(val, "Test.Sorted").unwrap();
+            let data = sys.as_types().strict_serialize_value::<0xFFFFFF>(&typed).unwrap();
+            layout
+                .validate_reader(&mut data.as_slice())
+                .unwrap_or_else(|err| panic!("seed {seed}: {err}"));
+        }
+
+        let sys = test_system();
+        let layout = sys.type_tree("TestLib.Nominal").unwrap().to_layout();
+        for seed in 0..200 {
+            let val = StrictVal::arbitrary_in(&sys, "TestLib.Nominal", seed).unwrap();
+            let typed = sys.typify(val, "TestLib.Nominal").unwrap();
+            let data = sys.as_types().strict_serialize_value::<0xFFFFFF>(&typed).unwrap();
+            layout.validate_reader(&mut data.as_slice()).unwrap();
+        }
+    }
+
+    #[test]
+    fn library() {
+        let std = std_stl();
+        let root = tn!("AlphaNumLodash");
+        let sys = SymbolicSys::from_lib(&std).unwrap();
+        for seed in 0..20 {
+            assert_eq!(
+                StrictVal::arbitrary_for(&std, &root, seed).unwrap(),
+                StrictVal::arbitrary_in(&sys, "Std.AlphaNumLodash", seed).unwrap()
+            );
+        }
+        let err = StrictVal::arbitrary_for(&strict_types_stl(), &tn!("TypeLib"), 0).unwrap_err();
+        let ArbitraryError::Library(errors) = &err else {
+            panic!("unexpected error {err}");
+        };
+        assert!(errors.iter().all(|err| matches!(err, typesys::Error::AbsentImport(_))));
+        assert!(err.to_string().starts_with(
+            "unable to build type system from the type library:\n- required dependency"
+        ));
+    }
+}
@@ -0,0 +1,418 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Construction of strict values field by field, validated against the type as they are built.
+
+use std::fmt::{self, Display, Formatter};
+
+use encoding::{FieldName, TypeName, VariantName};
+use indexmap::IndexMap;
+
+use crate::typesys::{LibSystemError, TypeFqn};
+use crate::typify::{self, TypeSpec};
+use crate::{Cls, SemId, StrictVal, SymbolicSys, Ty, TypeLib};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum BuildError {
+    #[display(inner)]
+    Library(LibSystemError),
+
+    /// unknown type `{0}`.
+    TypeAbsent(TypeSpec),
+
+    /// `{method}` can't be used for building a value of {cls} type.
+    WrongMethod { method: &'static str, cls: Cls },
+
+    /// values of {0} types can't be built from parts.
+    NotComposite(Cls),
+
+    /// structure has no field `{0}`.
+    UnknownField(FieldName),
+
+    /// field `{0}` is set more than once.
+    RepeatedField(FieldName),
+
+    /// required fields {0} are not set.
+    MissingFields(FieldList),
+
+    /// type has no variant `{0}`.
+    UnknownVariant(VariantName),
+
+    /// variant is already chosen.
+    RepeatedVariant,
+
+    /// variant is not chosen.
+    NoVariant,
+
+    /// tuple has only {0} fields.
+    TooManyFields(usize),
+
+    /// value of {at} doesn't match the type: {error}
+    Mismatch { at: String, error: typify::Error },
+}
+
+/// List of structure fields, displayed comma-separated.
+#[derive(Wrapper, Clone, Eq, PartialEq, Debug, Default, From)]
+#[wrapper(Deref)]
+pub struct FieldList(Vec<FieldName>);
+
+impl Display for FieldList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (no, name) in self.0.iter().enumerate() {
+            if no > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{name}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder of a strict value of a composite type.
+///
+/// Structures are built with [`StrictValBuilder::field`], enums and unions with
+/// [`StrictValBuilder::variant`] and [`StrictValBuilder::variant_with`], tuples, arrays, lists and
+/// sets with [`StrictValBuilder::push`], and maps with [`StrictValBuilder::entry`]. Each part is
+/// checked against the type as soon as it is added; the first failure is reported by
+/// [`StrictValBuilder::build`], which also checks that the value is complete.
+#[derive(Clone, Debug)]
+pub struct StrictValBuilder {
+    sys: SymbolicSys,
+    sem_id: SemId,
+    fields: IndexMap<FieldName, StrictVal>,
+    variant: Option<(VariantName, StrictVal)>,
+    items: Vec<StrictVal>,
+    entries: Vec<(StrictVal, StrictVal)>,
+    error: Option<BuildError>,
+}
+
+impl StrictValBuilder {
+    /// Starts building a value of the type `root` from the type library `lib`, which must not have
+    /// dependencies; use [`StrictValBuilder::with_system`] for libraries which have them.
+    pub fn new(lib: &TypeLib, root: &TypeName) -> Result<Self, BuildError> {
+        let sys = SymbolicSys::from_lib(lib).map_err(BuildError::Library)?;
+        Self::with_system(sys, TypeFqn::with(lib.name.clone(), root.clone()))
+    }
+
+    /// Starts building a value of the type `spec` from the type system `sys`.
+    pub fn with_system(sys: SymbolicSys, spec: impl Into<TypeSpec>) -> Result<Self, BuildError> {
+        let spec = spec.into();
+        let sem_id = sys
+            .to_sem_id(spec.clone())
+            .filter(|sem_id| sys.as_types().find(*sem_id).is_some())
+            .ok_or(BuildError::TypeAbsent(spec))?;
+        Ok(StrictValBuilder {
+            sys,
+            sem_id,
+            fields: empty!(),
+            variant: None,
+            items: vec![],
+            entries: vec![],
+            error: None,
+        })
+    }
+
+    /// Sets structure field `name` to `value`.
+    pub fn field(mut self, name: impl Into<FieldName>, value: impl Into<StrictVal>) -> Self {
+        let res = self.add_field(name.into(), value.into());
+        self.record(res)
+    }
+
+    /// Chooses enum variant `name`, or union variant `name` which has no data.
+    pub fn variant(mut self, name: impl Into<VariantName>) -> Self {
+        let res = self.add_variant(name.into(), None);
+        self.record(res)
+    }
+
+    /// Chooses union variant `name` with the variant data `value`.
+    pub fn variant_with(
+        mut self,
+        name: impl Into<VariantName>,
+        value: impl Into<StrictVal>,
+    ) -> Self {
+        let res = self.add_variant(name.into(), Some(value.into()));
+        self.record(res)
+    }
+
+    /// Appends the next tuple field, or an item of an array, list or set.
+    pub fn push(mut self, value: impl Into<StrictVal>) -> Self {
+        let res = self.add_item(value.into());
+        self.record(res)
+    }
+
+    /// Appends map entry with `key` and `value`.
+    pub fn entry(mut self, key: impl Into<StrictVal>, value: impl Into<StrictVal>) -> Self {
+        let res = self.add_entry(key.into(), value.into());
+        self.record(res)
+    }
+
+    /// Completes the value, failing with the first error found while building it.
+    ///
+    /// Optional structure fields which were not set are set to `none`; the other missing fields
+    /// are reported with [`BuildError::MissingFields`].
+    pub fn build(self) -> Result<StrictVal, BuildError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let ty = self.sys.as_types().find(self.sem_id).expect("builder type is present");
+        let val = match ty {
+            Ty::Struct(fields) => {
+                let mut missing = vec![];
+                let mut val = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let optional = self.sys.as_types().find(field.ty).is_some_and(Ty::is_option);
+                    match self.fields.get(&field.name) {
+                        Some(value) => {
+                            val.insert(field.name.clone(), value.clone());
+                        }
+                        None if optional => {
+                            val.insert(field.name.clone(), StrictVal::none());
+                        }
+                        None => missing.push(field.name.clone()),
+                    }
+                }
+                if !missing.is_empty() {
+                    return Err(BuildError::MissingFields(FieldList(missing)));
+                }
+                StrictVal::Struct(val)
+            }
+            Ty::Enum(_) => {
+                let (name, _) = self.variant.ok_or(BuildError::NoVariant)?;
+                StrictVal::enumer(name)
+            }
+            Ty::Union(_) => {
+                let (name, value) = self.variant.ok_or(BuildError::NoVariant)?;
+                StrictVal::union(name, value)
+            }
+            Ty::Tuple(_) => StrictVal::Tuple(self.items),
+            Ty::Array(..) | Ty::List(..) => StrictVal::List(self.items),
+            Ty::Set(..) => StrictVal::Set(self.items),
+            Ty::Map(..) => StrictVal::Map(self.entries),
+            ty => return Err(BuildError::NotComposite(ty.cls())),
+        };
+        self.sys.as_types().typify(val, self.sem_id).map(|typed| typed.unbox()).map_err(|error| {
+            BuildError::Mismatch {
+                at: s!("the value"),
+                error,
+            }
+        })
+    }
+
+    fn ty(&self) -> &Ty<SemId> {
+        self.sys.as_types().find(self.sem_id).expect("builder type is present")
+    }
+
+    fn wrong_method(&self, method: &'static str) -> BuildError {
+        BuildError::WrongMethod {
+            method,
+            cls: self.ty().cls(),
+        }
+    }
+
+    fn record(mut self, res: Result<(), BuildError>) -> Self {
+        if let Err(err) = res {
+            self.error.get_or_insert(err);
+        }
+        self
+    }
+
+    fn add_field(&mut self, name: FieldName, value: StrictVal) -> Result<(), BuildError> {
+        let Ty::Struct(fields) = self.ty() else {
+            return Err(self.wrong_method("field"));
+        };
+        let Some(field) = fields.iter().find(|field| field.name == name) else {
+            return Err(BuildError::UnknownField(name));
+        };
+        if self.fields.contains_key(&name) {
+            return Err(BuildError::RepeatedField(name));
+        }
+        let value = self.check(value, field.ty, &name)?;
+        self.fields.insert(name, value);
+        Ok(())
+    }
+
+    fn add_variant(
+        &mut self,
+        name: VariantName,
+        value: Option<StrictVal>,
+    ) -> Result<(), BuildError> {
+        let value = match (self.ty(), value) {
+            (Ty::Enum(variants), None) if variants.tag_by_name(&name).is_some() => StrictVal::Unit,
+            (Ty::Enum(_), None) => return Err(BuildError::UnknownVariant(name)),
+            (Ty::Union(variants), value) => {
+                let Some((_, sem_id)) = variants.by_name(&name) else {
+                    return Err(BuildError::UnknownVariant(name));
+                };
+                self.check(value.unwrap_or(StrictVal::Unit), *sem_id, &name)?
+            }
+            (_, None) => return Err(self.wrong_method("variant")),
+            (_, Some(_)) => return Err(self.wrong_method("variant_with")),
+        };
+        if self.variant.is_some() {
+            return Err(BuildError::RepeatedVariant);
+        }
+        self.variant = Some((name, value));
+        Ok(())
+    }
+
+    fn add_item(&mut self, value: StrictVal) -> Result<(), BuildError> {
+        let index = self.items.len();
+        let sem_id = match self.ty() {
+            Ty::Tuple(fields) => *u8::try_from(index)
+                .ok()
+                .and_then(|pos| fields.ty_by_pos(pos))
+                .ok_or(BuildError::TooManyFields(fields.len()))?,
+            Ty::Array(sem_id, _) | Ty::List(sem_id, _) | Ty::Set(sem_id, _) => *sem_id,
+            _ => return Err(self.wrong_method("push")),
+        };
+        let value = self.check(value, sem_id, index)?;
+        self.items.push(value);
+        Ok(())
+    }
+
+    fn add_entry(&mut self, key: StrictVal, value: StrictVal) -> Result<(), BuildError> {
+        let Ty::Map(key_id, value_id, _) = self.ty() else {
+            return Err(self.wrong_method("entry"));
+        };
+        let index = self.entries.len();
+        let key = self.check(key, *key_id, format_args!("key #{index}"))?;
+        let value = self.check(value, *value_id, index)?;
+        self.entries.push((key, value));
+        Ok(())
+    }
+
+    fn check(
+        &self,
+        val: StrictVal,
+        sem_id: SemId,
+        at: impl Display,
+    ) -> Result<StrictVal, BuildError> {
+        self.sys.as_types().typify(val, sem_id).map(|typed| typed.unbox()).map_err(|error| {
+            BuildError::Mismatch {
+                at: format!("`{at}`"),
+                error,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyOrdMap, TinyString, TinyVec};
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = order, dumb = Command::Stop)]
+    enum Command {
+        Stop,
+        Move(i16, i16),
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Message {
+        id: u32,
+        note: Option<u16>,
+        commands: TinyVec<Command>,
+        labels: TinyOrdMap<u8, TinyString>,
+    }
+    impl StrictSerialize for Message {}
+
+    fn lib() -> TypeLib {
+        LibBuilder::with("TestLib", None).transpile::<Message>().compile().unwrap()
+    }
+
+    #[test]
+    fn build() {
+        let lib = lib();
+        let stop = StrictValBuilder::new(&lib, &tn!("Command")).unwrap().variant("stop");
+        let step = StrictValBuilder::new(&lib, &tn!("Command"))
+            .unwrap()
+            .variant_with("move", StrictVal::tuple([StrictVal::num(1i16), StrictVal::num(-1i16)]));
+        let commands = StrictValBuilder::new(&lib, &tn!("Message"))
+            .unwrap()
+            .field("commands", StrictVal::list([stop.build().unwrap(), step.build().unwrap()]));
+        let val = commands
+            .field("id", 7u32)
+            .field("labels", StrictVal::map([(1u8, StrictVal::str("one"))]))
+            .build()
+            .unwrap();
+
+        let message = Message {
+            id: 7,
+            note: None,
+            commands: tiny_vec![Command::Stop, Command::Move(1, -1)],
+            labels: tiny_bmap!(1 => tiny_s!("one")),
+        };
+        let mut data = vec![];
+        lib.encode_value(&val, &tn!("Message"), &mut data).unwrap();
+        assert_eq!(data, message.to_strict_serialized::<0xFFFF>().unwrap().release());
+    }
+
+    #[test]
+    fn build_errors() {
+        let lib = lib();
+        let builder = || StrictValBuilder::new(&lib, &tn!("Message")).unwrap();
+
+        let err = builder().field("id", 7u32).build().unwrap_err();
+        assert_eq!(
+            err,
+            BuildError::MissingFields(FieldList(vec![fname!("commands"), fname!("labels")]))
+        );
+        assert_eq!(err.to_string(), "required fields `commands`, `labels` are not set.");
+
+        let err = builder().field("other", 7u32).build().unwrap_err();
+        assert_eq!(err, BuildError::UnknownField(fname!("other")));
+        let err = builder().field("id", 7u32).field("id", 8u32).build().unwrap_err();
+        assert_eq!(err, BuildError::RepeatedField(fname!("id")));
+        let err = builder().field("id", "seven").build().unwrap_err();
+        assert!(matches!(err, BuildError::Mismatch { at, .. } if at == "`id`"));
+        let err = builder().push(7u32).variant("stop").build().unwrap_err();
+        assert_eq!(err, BuildError::WrongMethod {
+            method: "push",
+            cls: Cls::Struct
+        });
+
+        let command = || StrictValBuilder::new(&lib, &tn!("Command")).unwrap();
+        assert_eq!(command().build().unwrap_err(), BuildError::NoVariant);
+        let err = command().variant("jump").build().unwrap_err();
+        assert_eq!(err, BuildError::UnknownVariant(vname!("jump")));
+        let err = command().variant("move").build().unwrap_err();
+        assert!(matches!(err, BuildError::Mismatch { .. }));
+        let err = command().variant("stop").variant("stop").build().unwrap_err();
+        assert_eq!(err, BuildError::RepeatedVariant);
+
+        assert!(matches!(
+            StrictValBuilder::new(&lib, &tn!("Absent")),
+            Err(BuildError::TypeAbsent(_))
+        ));
+    }
+}
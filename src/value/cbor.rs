@@ -0,0 +1,653 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Type-guided conversion between strict values and CBOR ([RFC 8949]) encoding.
+//!
+//! CBOR data types are less strict than strict types, thus the type library is used to
+//! disambiguate the representation. The produced CBOR is deterministic: integers and lengths
+//! always use the shortest form, structure fields follow the order of their declaration and map
+//! keys are sorted in the bytewise order of their encoding, as required by the core deterministic
+//! encoding of [RFC 8949]. The decoder rejects data in any other form, including maps with
+//! unsorted or repeated keys, such that decoding CBOR data and encoding the resulting value back
+//! yields identical bytes.
+//!
+//! The conversion reads and writes CBOR bytes directly instead of going through a generic CBOR
+//! value model, like `ciborium::Value`: such models don't preserve the encoding form of integers
+//! and lengths, so they can't reject non-canonical data, and the type library already provides
+//! all the information the generic model would add.
+//!
+//! | Strict type                     | CBOR representation                          |
+//! |---------------------------------|----------------------------------------------|
+//! | unit                            | `null`                                       |
+//! | integers up to 64 bits          | unsigned or negative integer                 |
+//! | ASCII, restricted and Unicode strings, Unicode char | text string              |
+//! | byte strings and byte arrays    | byte string                                  |
+//! | enums                           | unsigned integer with the variant tag        |
+//! | unions                          | array of the variant tag and its value       |
+//! | tuples, arrays, lists and sets  | array                                        |
+//! | structures                      | map with field names as text keys            |
+//! | maps                            | map                                          |
+//!
+//! [RFC 8949]: https://www.rfc-editor.org/rfc/rfc8949
+
+use std::cmp::Ordering;
+
+use encoding::{NumCls, Primitive, Sizing, TypeName};
+use indexmap::IndexMap;
+
+use super::format::{check_chars, fits, get, int_range, repr, Repr};
+use crate::typesys::{LibSystemError, TypeFqn, UnknownType};
+use crate::typify::TypeSpec;
+use crate::value::{Blob, EnumTag, StrictNum};
+use crate::{SemId, StrictVal, SymbolicSys, Ty, TypeLib, TypeSystem};
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NINT: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+const SIMPLE_NULL: u64 = 22;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum Error {
+    #[display(inner)]
+    #[from]
+    Library(LibSystemError),
+
+    /// unknown type `{0}`.
+    TypeAbsent(TypeSpec),
+
+    #[display(inner)]
+    #[from]
+    UnknownType(UnknownType),
+
+    /// value {val:?} doesn't match type `{ty}`.
+    ValueMismatch { val: StrictVal, ty: Ty<SemId> },
+
+    /// {0} is not yet supported by the CBOR conversion.
+    NotImplemented(String),
+
+    /// CBOR data at byte offset {0} are invalid or don't match the type.
+    InvalidData(usize),
+
+    /// CBOR data at byte offset {0} don't use the shortest encoding form.
+    NonCanonical(usize),
+
+    /// CBOR map at byte offset {0} repeats a key.
+    RepeatedKey(usize),
+
+    /// CBOR map key at byte offset {0} doesn't follow the bytewise order of key encodings.
+    UnsortedKey(usize),
+
+    /// CBOR data are not entirely consumed.
+    NotEntirelyConsumed,
+}
+
+/// Encodes strict value of type `root` from the type library `lib` into CBOR.
+///
+/// The library must not have dependencies; use [`SymbolicSys::to_cbor`] for libraries which
+/// have them.
+pub fn to_cbor(val: &StrictVal, lib: &TypeLib, root: &TypeName) -> Result<Vec<u8>, Error> {
+    let sys = SymbolicSys::from_lib(lib)?;
+    sys.to_cbor(TypeFqn::with(lib.name.clone(), root.clone()), val)
+}
+
+/// Decodes strict value of type `root` from the type library `lib` from CBOR `data`.
+///
+/// The library must not have dependencies; use [`SymbolicSys::from_cbor`] for libraries which
+/// have them.
+pub fn from_cbor(data: &[u8], lib: &TypeLib, root: &TypeName) -> Result<StrictVal, Error> {
+    let sys = SymbolicSys::from_lib(lib)?;
+    sys.from_cbor(TypeFqn::with(lib.name.clone(), root.clone()), data)
+}
+
+impl SymbolicSys {
+    /// Encodes strict value of type `spec` into CBOR.
+    pub fn to_cbor(&self, spec: impl Into<TypeSpec>, val: &StrictVal) -> Result<Vec<u8>, Error> {
+        let sem_id = self.cbor_type(spec.into())?;
+        let mut encoder = Encoder {
+            sys: self.as_types(),
+            buf: vec![],
+        };
+        encoder.encode(sem_id, val)?;
+        Ok(encoder.buf)
+    }
+
+    /// Decodes strict value of type `spec` from CBOR `data`.
+    pub fn from_cbor(&self, spec: impl Into<TypeSpec>, data: &[u8]) -> Result<StrictVal, Error> {
+        let sem_id = self.cbor_type(spec.into())?;
+        let mut decoder = Decoder {
+            sys: self.as_types(),
+            data,
+            pos: 0,
+        };
+        let val = decoder.decode(sem_id)?;
+        if decoder.pos != data.len() {
+            return Err(Error::NotEntirelyConsumed);
+        }
+        Ok(val)
+    }
+
+    fn cbor_type(&self, spec: TypeSpec) -> Result<SemId, Error> {
+        self.to_sem_id(spec.clone())
+            .filter(|sem_id| self.as_types().get(*sem_id).is_some())
+            .ok_or(Error::TypeAbsent(spec))
+    }
+}
+
+struct Encoder<'sys> {
+    sys: &'sys TypeSystem,
+    buf: Vec<u8>,
+}
+
+impl Encoder<'_> {
+    fn write_head(&mut self, major: u8, arg: u64) {
+        let major = major << 5;
+        match arg {
+            0..=23 => self.buf.push(major | arg as u8),
+            24..=0xFF => self.buf.extend([major | 24, arg as u8]),
+            0x100..=0xFFFF => {
+                self.buf.push(major | 25);
+                self.buf.extend((arg as u16).to_be_bytes());
+            }
+            0x1_0000..=0xFFFF_FFFF => {
+                self.buf.push(major | 26);
+                self.buf.extend((arg as u32).to_be_bytes());
+            }
+            _ => {
+                self.buf.push(major | 27);
+                self.buf.extend(arg.to_be_bytes());
+            }
+        }
+    }
+
+    fn write_payload(&mut self, major: u8, data: &[u8]) {
+        self.write_head(major, data.len() as u64);
+        self.buf.extend_from_slice(data);
+    }
+
+    fn encode(&mut self, sem_id: SemId, val: &StrictVal) -> Result<(), Error> {
+        let ty = get(self.sys, sem_id);
+        let mismatch = || Error::ValueMismatch {
+            val: val.clone(),
+            ty: ty.clone(),
+        };
+
+        match (repr(self.sys, ty)?, val) {
+            (Repr::Text(first, rest, sizing), StrictVal::String(s)) => {
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(self.sys, s, first, rest)? {
+                    return Err(mismatch());
+                }
+                self.write_payload(MAJOR_TEXT, s.as_bytes());
+            }
+            (Repr::Bytes(sizing), StrictVal::Bytes(Blob(data))) if fits(sizing, data.len()) => {
+                self.write_payload(MAJOR_BYTES, data);
+            }
+            (Repr::Other(Ty::Primitive(Primitive::UNIT)), StrictVal::Unit) => {
+                self.write_head(MAJOR_SIMPLE, SIMPLE_NULL)
+            }
+            (Repr::Other(Ty::Primitive(prim)), StrictVal::Number(num)) => {
+                let (major, arg) = num_head(*prim, num)?.ok_or_else(mismatch)?;
+                self.write_head(major, arg);
+            }
+            (Repr::Other(Ty::Enum(variants)), StrictVal::Enum(tag)) => {
+                let tag = match tag {
+                    EnumTag::Name(name) => variants.tag_by_name(name),
+                    EnumTag::Ord(tag) => variants.has_tag(*tag).then_some(*tag),
+                };
+                self.write_head(MAJOR_UINT, tag.ok_or_else(mismatch)? as u64);
+            }
+            (Repr::Other(Ty::Union(variants)), StrictVal::Union(tag, inner)) => {
+                let variant = match tag {
+                    EnumTag::Name(name) => variants.by_name(name),
+                    EnumTag::Ord(tag) => variants.by_tag(*tag),
+                };
+                let (variant, ty) = variant.ok_or_else(mismatch)?;
+                self.write_head(MAJOR_ARRAY, 2);
+                self.write_head(MAJOR_UINT, variant.tag as u64);
+                self.encode(*ty, inner)?;
+            }
+            (Repr::Other(Ty::Tuple(fields)), StrictVal::Tuple(items))
+                if fields.len() == items.len() =>
+            {
+                self.write_head(MAJOR_ARRAY, items.len() as u64);
+                for (ty, item) in fields.iter().zip(items) {
+                    self.encode(*ty, item)?;
+                }
+            }
+            (Repr::Other(Ty::Struct(fields)), StrictVal::Struct(items))
+                if fields.len() == items.len()
+                    && fields.iter().zip(items.keys()).all(|(field, name)| &field.name == name) =>
+            {
+                self.write_head(MAJOR_MAP, items.len() as u64);
+                for (field, item) in fields.iter().zip(items.values()) {
+                    self.write_payload(MAJOR_TEXT, field.name.as_str().as_bytes());
+                    self.encode(field.ty, item)?;
+                }
+            }
+            (Repr::Other(Ty::Array(ty, len)), StrictVal::List(items))
+                if items.len() == *len as usize =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::List(ty, sizing)), StrictVal::List(items))
+            | (Repr::Other(Ty::Set(ty, sizing)), StrictVal::Set(items))
+                if fits(*sizing, items.len()) =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), StrictVal::Map(items))
+                if fits(*sizing, items.len()) =>
+            {
+                // map keys are sorted in the bytewise order of their encoding (RFC 8949, 4.2.1)
+                let mut entries = Vec::with_capacity(items.len());
+                for (key, item) in items {
+                    let mut encoder = Encoder {
+                        sys: self.sys,
+                        buf: vec![],
+                    };
+                    encoder.encode(*key_ty, key)?;
+                    let key_len = encoder.buf.len();
+                    encoder.encode(*ty, item)?;
+                    entries.push((key_len, encoder.buf));
+                }
+                entries.sort_unstable_by(|(a_len, a), (b_len, b)| a[..*a_len].cmp(&b[..*b_len]));
+                if entries.windows(2).any(|w| w[0].1[..w[0].0] == w[1].1[..w[1].0]) {
+                    return Err(mismatch());
+                }
+                self.write_head(MAJOR_MAP, items.len() as u64);
+                entries.into_iter().for_each(|(_, entry)| self.buf.extend(entry));
+            }
+            _ => return Err(mismatch()),
+        }
+        Ok(())
+    }
+
+    fn encode_items(&mut self, ty: SemId, items: &[StrictVal]) -> Result<(), Error> {
+        self.write_head(MAJOR_ARRAY, items.len() as u64);
+        for item in items {
+            self.encode(ty, item)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns bit size of an integer primitive supported by the CBOR conversion.
+fn int_bits(prim: Primitive) -> Result<u32, Error> {
+    let bits = prim.byte_size() as u32 * 8;
+    match prim.info().ty {
+        NumCls::Unsigned | NumCls::Signed if bits <= 64 => Ok(bits),
+        _ => Err(Error::NotImplemented(format!("CBOR conversion of {prim}"))),
+    }
+}
+
+/// Returns CBOR major type and argument for a number, or `None` if the number doesn't fit the
+/// primitive type.
+fn num_head(prim: Primitive, num: &StrictNum) -> Result<Option<(u8, u64)>, Error> {
+    let bits = int_bits(prim)?;
+    let signed = prim.info().ty == NumCls::Signed;
+    let value = match *num {
+        StrictNum::Uint(n) => n as i128,
+        StrictNum::Int(n) => n as i128,
+        _ => return Ok(None),
+    };
+    let (min, max) = int_range(bits, signed);
+    if value < min || value > max {
+        return Ok(None);
+    }
+    Ok(Some(if value >= 0 {
+        (MAJOR_UINT, value as u64)
+    } else {
+        (MAJOR_NINT, (-1 - value) as u64)
+    }))
+}
+
+struct Decoder<'sys, 'data> {
+    sys: &'sys TypeSystem,
+    data: &'data [u8],
+    pos: usize,
+}
+
+impl<'data> Decoder<'_, 'data> {
+    fn read(&mut self, len: usize) -> Result<&'data [u8], Error> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len());
+        let end = end.ok_or(Error::InvalidData(self.pos))?;
+        let data = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(data)
+    }
+
+    fn read_head(&mut self) -> Result<(u8, u64), Error> {
+        let offset = self.pos;
+        let byte = self.read(1)?[0];
+        let (major, info) = (byte >> 5, byte & 0x1F);
+        let (arg, min) = match info {
+            0..=23 => (info as u64, 0),
+            24 => (self.read(1)?[0] as u64, 24),
+            25 => (u16::from_be_bytes(self.read(2)?.try_into().expect("fixed size")) as u64, 0x100),
+            26 => {
+                (u32::from_be_bytes(self.read(4)?.try_into().expect("fixed size")) as u64, 0x1_0000)
+            }
+            27 => {
+                (u64::from_be_bytes(self.read(8)?.try_into().expect("fixed size")), 0x1_0000_0000)
+            }
+            _ => return Err(Error::InvalidData(offset)),
+        };
+        if arg < min {
+            return Err(Error::NonCanonical(offset));
+        }
+        Ok((major, arg))
+    }
+
+    fn expect_head(&mut self, major: u8) -> Result<u64, Error> {
+        let offset = self.pos;
+        match self.read_head()? {
+            (m, arg) if m == major => Ok(arg),
+            _ => Err(Error::InvalidData(offset)),
+        }
+    }
+
+    fn read_len(&mut self, major: u8, sizing: Sizing) -> Result<usize, Error> {
+        let offset = self.pos;
+        let len = self.expect_head(major)?;
+        if len < sizing.min || len > sizing.max {
+            return Err(Error::InvalidData(offset));
+        }
+        usize::try_from(len).map_err(|_| Error::InvalidData(offset))
+    }
+
+    fn decode(&mut self, sem_id: SemId) -> Result<StrictVal, Error> {
+        let ty = get(self.sys, sem_id);
+        let offset = self.pos;
+
+        Ok(match repr(self.sys, ty)? {
+            Repr::Text(first, rest, sizing) => {
+                let len = self.expect_head(MAJOR_TEXT)?;
+                let data = self.read(len as usize)?;
+                let s = std::str::from_utf8(data).map_err(|_| Error::InvalidData(offset))?;
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(self.sys, s, first, rest)? {
+                    return Err(Error::InvalidData(offset));
+                }
+                StrictVal::String(s.to_owned())
+            }
+            Repr::Bytes(sizing) => {
+                let len = self.read_len(MAJOR_BYTES, sizing)?;
+                StrictVal::Bytes(Blob(self.read(len)?.to_vec()))
+            }
+            Repr::Other(Ty::Primitive(Primitive::UNIT)) => {
+                if self.read_head()? != (MAJOR_SIMPLE, SIMPLE_NULL) {
+                    return Err(Error::InvalidData(offset));
+                }
+                StrictVal::Unit
+            }
+            Repr::Other(Ty::Primitive(prim)) => {
+                let bits = int_bits(*prim)?;
+                let signed = prim.info().ty == NumCls::Signed;
+                let value = match self.read_head()? {
+                    (MAJOR_UINT, arg) => arg as i128,
+                    (MAJOR_NINT, arg) => -1 - arg as i128,
+                    _ => return Err(Error::InvalidData(offset)),
+                };
+                let (min, max) = int_range(bits, signed);
+                if value < min || value > max {
+                    return Err(Error::InvalidData(offset));
+                }
+                if signed {
+                    StrictVal::num(value as i64)
+                } else {
+                    StrictVal::num(value as u64)
+                }
+            }
+            Repr::Other(Ty::Enum(variants)) => {
+                let tag = u8::try_from(self.expect_head(MAJOR_UINT)?)
+                    .map_err(|_| Error::InvalidData(offset))?;
+                let name = variants.name_by_tag(tag).ok_or(Error::InvalidData(offset))?;
+                StrictVal::enumer(name.clone())
+            }
+            Repr::Other(Ty::Union(variants)) => {
+                if self.expect_head(MAJOR_ARRAY)? != 2 {
+                    return Err(Error::InvalidData(offset));
+                }
+                let tag_offset = self.pos;
+                let tag = u8::try_from(self.expect_head(MAJOR_UINT)?)
+                    .map_err(|_| Error::InvalidData(tag_offset))?;
+                let (variant, ty) = variants.by_tag(tag).ok_or(Error::InvalidData(tag_offset))?;
+                StrictVal::union(variant.name.clone(), self.decode(*ty)?)
+            }
+            Repr::Other(Ty::Tuple(fields)) => {
+                if self.expect_head(MAJOR_ARRAY)? != fields.len() as u64 {
+                    return Err(Error::InvalidData(offset));
+                }
+                let items = fields.iter().map(|ty| self.decode(*ty)).collect::<Result<_, _>>()?;
+                StrictVal::Tuple(items)
+            }
+            Repr::Other(Ty::Struct(fields)) => {
+                if self.expect_head(MAJOR_MAP)? != fields.len() as u64 {
+                    return Err(Error::InvalidData(offset));
+                }
+                let mut items = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let name_offset = self.pos;
+                    let len = self.expect_head(MAJOR_TEXT)?;
+                    if self.read(len as usize)? != field.name.as_str().as_bytes() {
+                        return Err(Error::InvalidData(name_offset));
+                    }
+                    items.insert(field.name.clone(), self.decode(field.ty)?);
+                }
+                StrictVal::Struct(items)
+            }
+            Repr::Other(Ty::Array(ty, len)) => {
+                let len = self.read_len(MAJOR_ARRAY, Sizing::fixed(*len as u64))?;
+                StrictVal::List(self.decode_items(*ty, len)?)
+            }
+            Repr::Other(Ty::List(ty, sizing)) => {
+                let len = self.read_len(MAJOR_ARRAY, *sizing)?;
+                StrictVal::List(self.decode_items(*ty, len)?)
+            }
+            Repr::Other(Ty::Set(ty, sizing)) => {
+                let len = self.read_len(MAJOR_ARRAY, *sizing)?;
+                StrictVal::Set(self.decode_items(*ty, len)?)
+            }
+            Repr::Other(Ty::Map(key_ty, ty, sizing)) => {
+                let len = self.read_len(MAJOR_MAP, *sizing)?;
+                let mut items = Vec::with_capacity(len.min(self.data.len()));
+                let mut prev_key: Option<&[u8]> = None;
+                for _ in 0..len {
+                    let key_offset = self.pos;
+                    let key = self.decode(*key_ty)?;
+                    let key_data = &self.data[key_offset..self.pos];
+                    match prev_key.map(|prev| prev.cmp(key_data)) {
+                        Some(Ordering::Equal) => return Err(Error::RepeatedKey(key_offset)),
+                        Some(Ordering::Greater) => return Err(Error::UnsortedKey(key_offset)),
+                        _ => {}
+                    }
+                    prev_key = Some(key_data);
+                    items.push((key, self.decode(*ty)?));
+                }
+                StrictVal::Map(items)
+            }
+            Repr::Other(Ty::UnicodeChar) => unreachable!("represented as text"),
+        })
+    }
+
+    fn decode_items(&mut self, ty: SemId, len: usize) -> Result<Vec<StrictVal>, Error> {
+        // the capacity is limited by the data size to prevent allocation of huge vectors
+        let mut items = Vec::with_capacity(len.min(self.data.len()));
+        for _ in 0..len {
+            items.push(self.decode(ty)?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyBlob, TinyOrdMap, TinyString, TinyVec};
+    use encoding::{StrictDeserialize, StrictSerialize};
+
+    use super::*;
+    use crate::value::test_helpers::{test_system, Nominal};
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Kind {
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        kind: Kind,
+        small: i8,
+        big: u64,
+        name: TinyString,
+        data: TinyBlob,
+        id: [u8; 4],
+        opt: Option<u16>,
+        list: TinyVec<i32>,
+        map: TinyOrdMap<u8, i16>,
+    }
+    impl StrictSerialize for Record {}
+    impl StrictDeserialize for Record {}
+
+    #[test]
+    fn cbor_roundtrip() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+
+        let record = Record {
+            kind: Kind::Second,
+            small: -20,
+            big: 0x1_0000_0000,
+            name: TinyString::try_from(s!("name")).unwrap(),
+            data: TinyBlob::try_from(vec![1, 2, 3]).unwrap(),
+            id: [0xFF; 4],
+            opt: Some(500),
+            list: tiny_vec![-1, 24],
+            map: tiny_bmap! { 1 => -300, 2 => 300 },
+        };
+        let data = record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Record", &data).unwrap().val;
+
+        let cbor = to_cbor(&val, &lib, &tn!("Record")).unwrap();
+        assert_eq!(cbor[0], 0xA9); // map with nine fields
+        let decoded = from_cbor(&cbor, &lib, &tn!("Record")).unwrap();
+        assert_eq!(decoded, val);
+        assert_eq!(to_cbor(&decoded, &lib, &tn!("Record")).unwrap(), cbor);
+
+        let mut trailing = cbor.clone();
+        trailing.push(0);
+        assert_eq!(from_cbor(&trailing, &lib, &tn!("Record")), Err(Error::NotEntirelyConsumed));
+        assert!(matches!(
+            to_cbor(&StrictVal::Unit, &lib, &tn!("Record")),
+            Err(Error::ValueMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn cbor_map_keys() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+        let record = Record {
+            map: tiny_bmap! { 1 => -300, 2 => 300 },
+            ..strict_dumb!()
+        };
+        let data = record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Record", &data).unwrap().val;
+        let cbor = to_cbor(&val, &lib, &tn!("Record")).unwrap();
+
+        let entries = [0xA2, 0x01, 0x39, 0x01, 0x2B, 0x02, 0x19, 0x01, 0x2C];
+        let pos = cbor.windows(entries.len()).position(|w| w == entries).unwrap();
+        let with_entries = |entries: [u8; 9]| {
+            let mut data = cbor.clone();
+            data[pos..pos + entries.len()].copy_from_slice(&entries);
+            from_cbor(&data, &lib, &tn!("Record"))
+        };
+        assert_eq!(
+            with_entries([0xA2, 0x02, 0x19, 0x01, 0x2C, 0x01, 0x39, 0x01, 0x2B]),
+            Err(Error::UnsortedKey(pos + 5))
+        );
+        assert_eq!(
+            with_entries([0xA2, 0x01, 0x39, 0x01, 0x2B, 0x01, 0x19, 0x01, 0x2C]),
+            Err(Error::RepeatedKey(pos + 5))
+        );
+
+        // the encoder doesn't depend on the order of keys in the value
+        let StrictVal::Struct(mut fields) = val else {
+            unreachable!()
+        };
+        let Some(StrictVal::Map(items)) = fields.get_mut(&fname!("map")) else {
+            unreachable!()
+        };
+        items.reverse();
+        let reversed = StrictVal::Struct(fields.clone());
+        assert_eq!(to_cbor(&reversed, &lib, &tn!("Record")).unwrap(), cbor);
+
+        let Some(StrictVal::Map(items)) = fields.get_mut(&fname!("map")) else {
+            unreachable!()
+        };
+        items[0].0 = items[1].0.clone();
+        let repeated = StrictVal::Struct(fields);
+        assert!(matches!(
+            to_cbor(&repeated, &lib, &tn!("Record")),
+            Err(Error::ValueMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn cbor_strict() {
+        let sys = test_system();
+        let nominal = Nominal::with("TICK", "Some name", 2);
+        let data = nominal.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Nominal", &data).unwrap().val;
+        let cbor = sys.to_cbor("TestLib.Nominal", &val).unwrap();
+        assert_eq!(sys.from_cbor("TestLib.Nominal", &cbor).unwrap(), val);
+
+        // the ticker must start with a letter
+        let mut wrong = cbor.clone();
+        let pos = cbor.windows(4).position(|w| w == b"TICK").unwrap();
+        wrong[pos] = b'1';
+        assert!(matches!(sys.from_cbor("TestLib.Nominal", &wrong), Err(Error::InvalidData(_))));
+
+        // non-shortest encoding of the precision
+        let mut long = cbor[..cbor.len() - 1].to_vec();
+        long.extend([0x18, 0x02]);
+        assert_eq!(
+            sys.from_cbor("TestLib.Nominal", &long),
+            Err(Error::NonCanonical(cbor.len() - 1))
+        );
+    }
+}
@@ -0,0 +1,634 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Validation of strict values against type library definitions, reporting all found
+//! violations together with the paths of the offending values.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::{TinyBlob, TinyString};
+use encoding::{FieldName, NumCls, Primitive, Sizing, TypeName, VariantName};
+
+use crate::ast::{EnumVariants, UnionVariants, UnnamedFields};
+use crate::typelib::SymbolicLib;
+use crate::value::{EnumTag, KeyStep, Path, Step, StrictNum};
+use crate::{StrictVal, TranspileRef, Ty, TypeLib, TypeRef};
+
+/// The reason why a value doesn't match its type.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(doc_comments)]
+pub enum ViolationReason {
+    /// value has a shape which doesn't match the type.
+    TypeMismatch,
+
+    /// number is out of the range of the primitive type.
+    NumberOutOfRange,
+
+    /// variant `{0}` is not defined by the type.
+    UnknownVariant(EnumTag),
+
+    /// collection has {len} items, which is out of the type bounds{sizing}.
+    OutOfBounds { len: usize, sizing: Sizing },
+
+    /// {expected} fields are required, while {found} fields were given.
+    FieldCount { expected: usize, found: usize },
+
+    /// required field `{0}` is absent.
+    MissingField(FieldName),
+
+    /// field `{0}` is not defined by the type.
+    ExtraField(FieldName),
+
+    /// set item or map key is repeated.
+    RepeatedItem,
+
+    /// type `{0}` is not defined in the library.
+    UnknownType(TypeName),
+
+    /// the library is internally inconsistent.
+    InconsistentLib,
+}
+
+/// Violation of a type requirement by a part of a value.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FieldViolation {
+    /// Path to the offending part of the value.
+    pub path: Path,
+    /// Type which the value was expected to have.
+    pub expected: Ty<TranspileRef>,
+    /// The offending value.
+    pub found: StrictVal,
+    pub reason: ViolationReason,
+}
+
+impl Display for FieldViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { s!("value") } else { self.path.to_string() };
+        write!(f, "{path} (`{}`): {}", self.expected, self.reason)
+    }
+}
+
+impl TypeLib {
+    /// Validates value against the library type `root`, reporting all found violations instead
+    /// of stopping at the first one.
+    ///
+    /// Values of the types coming from the library dependencies are not checked, since their
+    /// definitions are not known to the library.
+    pub fn validate_value(
+        &self,
+        val: &StrictVal,
+        root: &TypeName,
+    ) -> Result<(), Vec<FieldViolation>> {
+        let violation = |reason| FieldViolation {
+            path: Path::new(),
+            expected: Ty::UNIT,
+            found: val.clone(),
+            reason,
+        };
+        let Ok(lib) = self.to_symbolic() else {
+            return Err(vec![violation(ViolationReason::InconsistentLib)]);
+        };
+        let Some(ty) = lib.types().get(root) else {
+            return Err(vec![violation(ViolationReason::UnknownType(root.clone()))]);
+        };
+        let mut checker = Checker {
+            lib: &lib,
+            path: Path::new(),
+            violations: vec![],
+        };
+        checker.check(val, ty);
+        match checker.violations.is_empty() {
+            true => Ok(()),
+            false => Err(checker.violations),
+        }
+    }
+}
+
+struct Checker<'lib> {
+    lib: &'lib SymbolicLib,
+    path: Path,
+    violations: Vec<FieldViolation>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, val: &StrictVal, ty: &Ty<TranspileRef>, reason: ViolationReason) {
+        self.violations.push(FieldViolation {
+            path: self.path.clone(),
+            expected: ty.clone(),
+            found: val.clone(),
+            reason,
+        });
+    }
+
+    fn check_at(&mut self, step: Step, val: &StrictVal, r: &TranspileRef) {
+        self.path.push(step).ok();
+        self.check_ref(val, r);
+        self.path.pop();
+    }
+
+    fn check_ref(&mut self, val: &StrictVal, r: &TranspileRef) {
+        let lib = self.lib;
+        match r {
+            TranspileRef::Embedded(ty) => self.check(val, ty),
+            TranspileRef::Named(name) => match lib.types().get(name) {
+                Some(ty) => self.check(val, ty),
+                None => self.report(val, &Ty::UNIT, ViolationReason::UnknownType(name.clone())),
+            },
+            // definitions of external types are not known
+            TranspileRef::Extern(_) => {}
+        }
+    }
+
+    fn check_sizing(&mut self, val: &StrictVal, ty: &Ty<TranspileRef>, len: usize, sizing: Sizing) {
+        if !sizing.check(len) {
+            self.report(val, ty, ViolationReason::OutOfBounds { len, sizing });
+        }
+    }
+
+    fn check(&mut self, val: &StrictVal, ty: &Ty<TranspileRef>) {
+        if let Some(some) = ty.as_some() {
+            return match val {
+                StrictVal::Unit => {}
+                StrictVal::Enum(EnumTag::Name(name)) if name.as_str() == "none" => {}
+                StrictVal::Union(EnumTag::Name(name), _) if name.as_str() == "none" => {}
+                StrictVal::Union(EnumTag::Ord(0), _) => {}
+                StrictVal::Union(EnumTag::Name(name), inner) if name.as_str() == "some" => {
+                    self.check_ref(inner, some)
+                }
+                StrictVal::Union(EnumTag::Ord(1), inner) => self.check_ref(inner, some),
+                StrictVal::Union(tag, _) => {
+                    self.report(val, ty, ViolationReason::UnknownVariant(tag.clone()))
+                }
+                val => self.check_ref(val, some),
+            };
+        }
+
+        match (val, ty) {
+            (StrictVal::Unit, Ty::Primitive(Primitive::UNIT)) => {}
+            (
+                StrictVal::Tuple(items) | StrictVal::List(items) | StrictVal::Set(items),
+                Ty::Primitive(Primitive::UNIT),
+            ) if items.is_empty() => {}
+            (StrictVal::Struct(fields), Ty::Primitive(Primitive::UNIT)) if fields.is_empty() => {}
+            (StrictVal::Number(num), Ty::Primitive(prim)) if *prim != Primitive::UNIT => {
+                if !fits(num, *prim) {
+                    self.report(val, ty, ViolationReason::NumberOutOfRange);
+                }
+            }
+
+            (StrictVal::String(s), Ty::UnicodeChar) if s.chars().count() == 1 => {}
+
+            (StrictVal::Enum(tag), Ty::Enum(variants)) => {
+                let known = match tag {
+                    EnumTag::Name(name) => variants.has_name(name),
+                    EnumTag::Ord(ord) => variants.has_tag(*ord),
+                };
+                if !known {
+                    self.report(val, ty, ViolationReason::UnknownVariant(tag.clone()));
+                }
+            }
+            (StrictVal::String(s), Ty::Enum(variants)) if s.len() == 1 && ty.is_char_enum() => {
+                if !variants.has_tag(s.as_bytes()[0]) {
+                    self.report(val, ty, ViolationReason::TypeMismatch);
+                }
+            }
+            (StrictVal::String(s), Ty::Enum(variants)) => match VariantName::try_from(s.clone()) {
+                Ok(name) if variants.has_name(&name) => {}
+                Ok(name) => self.report(val, ty, ViolationReason::UnknownVariant(name.into())),
+                Err(_) => self.report(val, ty, ViolationReason::TypeMismatch),
+            },
+            (StrictVal::Number(StrictNum::Uint(tag)), Ty::Enum(variants)) => {
+                match u8::try_from(*tag) {
+                    Ok(tag) if variants.has_tag(tag) => {}
+                    Ok(tag) => self.report(val, ty, ViolationReason::UnknownVariant(tag.into())),
+                    Err(_) => self.report(val, ty, ViolationReason::TypeMismatch),
+                }
+            }
+
+            (StrictVal::Union(tag, content), Ty::Union(variants)) => {
+                let variant = variants.keys().find(|variant| match tag {
+                    EnumTag::Name(name) => &variant.name == name,
+                    EnumTag::Ord(ord) => variant.tag == *ord,
+                });
+                match variant {
+                    Some(variant) => {
+                        let inner = variants.ty_by_tag(variant.tag).expect("variant is present");
+                        match FieldName::from_str(variant.name.as_str()) {
+                            Ok(name) => self.check_at(Step::NamedField(name), content, inner),
+                            Err(_) => self.check_ref(content, inner),
+                        }
+                    }
+                    None => self.report(val, ty, ViolationReason::UnknownVariant(tag.clone())),
+                }
+            }
+            (StrictVal::Enum(tag), Ty::Union(variants)) => self.check_unit(val, ty, variants, tag),
+            (StrictVal::String(s), Ty::Union(variants)) => match VariantName::try_from(s.clone()) {
+                Ok(name) => self.check_unit(val, ty, variants, &EnumTag::Name(name)),
+                Err(_) => self.report(val, ty, ViolationReason::TypeMismatch),
+            },
+
+            (StrictVal::String(s), Ty::Tuple(fields)) if self.check_rstring(val, ty, s, fields) => {
+            }
+            (StrictVal::Tuple(items) | StrictVal::List(items), Ty::Tuple(fields)) => {
+                if items.len() != fields.len() {
+                    let reason = ViolationReason::FieldCount {
+                        expected: fields.len(),
+                        found: items.len(),
+                    };
+                    self.report(val, ty, reason);
+                }
+                for (no, (item, field)) in items.iter().zip(fields).enumerate() {
+                    self.check_at(Step::UnnamedField(no as u8), item, field);
+                }
+            }
+            // newtypes may be represented by their inner value
+            (val, Ty::Tuple(fields)) if fields.len() == 1 => self.check_ref(val, &fields[0]),
+
+            (StrictVal::Struct(items), Ty::Struct(fields)) => {
+                for field in fields {
+                    match items.get(&field.name) {
+                        Some(item) => self.check_at(field.name.clone().into(), item, &field.ty),
+                        None => {
+                            let reason = ViolationReason::MissingField(field.name.clone());
+                            self.report(val, ty, reason);
+                        }
+                    }
+                }
+                for name in items.keys() {
+                    if fields.ty_by_name(name).is_none() {
+                        self.report(val, ty, ViolationReason::ExtraField(name.clone()));
+                    }
+                }
+            }
+
+            (StrictVal::Bytes(bytes), Ty::Array(inner, len)) if inner.is_byte() => {
+                self.check_sizing(val, ty, bytes.len(), Sizing::fixed(*len as u64));
+            }
+            (StrictVal::String(s), Ty::Array(inner, len)) => {
+                self.check_chars(val, ty, s, inner);
+                self.check_sizing(val, ty, s.len(), Sizing::fixed(*len as u64));
+            }
+            (StrictVal::List(items), Ty::Array(inner, len)) => {
+                self.check_sizing(val, ty, items.len(), Sizing::fixed(*len as u64));
+                self.check_items(items, inner);
+            }
+
+            (StrictVal::Bytes(bytes), Ty::List(inner, sizing)) if inner.is_byte() => {
+                self.check_sizing(val, ty, bytes.len(), *sizing);
+            }
+            (StrictVal::String(s), Ty::List(inner, sizing)) if inner.is_byte() => {
+                self.check_sizing(val, ty, s.len(), *sizing);
+            }
+            (StrictVal::String(s), Ty::List(inner, sizing))
+                if inner.is_unicode_char() || s.is_ascii() =>
+            {
+                self.check_chars(val, ty, s, inner);
+                self.check_sizing(val, ty, s.len(), *sizing);
+            }
+            (StrictVal::List(items), Ty::List(inner, sizing)) => {
+                self.check_sizing(val, ty, items.len(), *sizing);
+                self.check_items(items, inner);
+            }
+
+            (StrictVal::Set(items) | StrictVal::List(items), Ty::Set(inner, sizing)) => {
+                self.check_sizing(val, ty, items.len(), *sizing);
+                for (no, item) in items.iter().enumerate() {
+                    if items[..no].contains(item) {
+                        self.path.push(Step::Index(no as u32)).ok();
+                        self.report(item, ty, ViolationReason::RepeatedItem);
+                        self.path.pop();
+                    }
+                }
+                self.check_items(items, inner);
+            }
+
+            (StrictVal::Map(items), Ty::Map(key_ty, val_ty, sizing)) => {
+                self.check_sizing(val, ty, items.len(), *sizing);
+                for (no, (key, item)) in items.iter().enumerate() {
+                    let step = key_step(key).map(Step::Key).unwrap_or(Step::Index(no as u32));
+                    if items[..no].iter().any(|(prev, _)| prev == key) {
+                        self.path.push(step.clone()).ok();
+                        self.report(key, ty, ViolationReason::RepeatedItem);
+                        self.path.pop();
+                    }
+                    self.check_at(step.clone(), key, key_ty);
+                    self.check_at(step, item, val_ty);
+                }
+            }
+
+            _ => self.report(val, ty, ViolationReason::TypeMismatch),
+        }
+    }
+
+    /// Checks the string value against the restricted string type with the `fields`, returning
+    /// `false` if the type is not a restricted string.
+    fn check_rstring(
+        &mut self,
+        val: &StrictVal,
+        ty: &Ty<TranspileRef>,
+        s: &str,
+        fields: &UnnamedFields<TranspileRef>,
+    ) -> bool {
+        let lib = self.lib;
+        if fields.len() != 2 {
+            return false;
+        }
+        let (rest, sizing) = match resolve(lib, &fields[1]) {
+            Some(Ty::List(rest, sizing)) => (rest, Some(*sizing)),
+            Some(_) => return false,
+            None => (&fields[1], None),
+        };
+        let (Some(first), Some(rest)) = (char_set(lib, &fields[0]), char_set(lib, rest)) else {
+            return false;
+        };
+        let mut chars = s.bytes();
+        let valid = s.is_ascii()
+            && chars.next().is_some_and(|c| allows(first, c))
+            && chars.all(|c| allows(rest, c));
+        if !valid {
+            self.report(val, ty, ViolationReason::TypeMismatch);
+        } else if let Some(sizing) = sizing {
+            let sizing = Sizing::new(sizing.min + 1, sizing.max + 1);
+            self.check_sizing(val, ty, s.len(), sizing);
+        }
+        true
+    }
+
+    /// Checks that the string value consists of the characters allowed by the `chars` type, if
+    /// it is a character enum.
+    fn check_chars(
+        &mut self,
+        val: &StrictVal,
+        ty: &Ty<TranspileRef>,
+        s: &str,
+        chars: &TranspileRef,
+    ) {
+        match char_set(self.lib, chars) {
+            Some(set) if s.is_ascii() && s.bytes().all(|c| allows(set, c)) => {}
+            Some(_) => self.report(val, ty, ViolationReason::TypeMismatch),
+            None => {}
+        }
+    }
+
+    fn check_unit(
+        &mut self,
+        val: &StrictVal,
+        ty: &Ty<TranspileRef>,
+        variants: &UnionVariants<TranspileRef>,
+        tag: &EnumTag,
+    ) {
+        let found = match tag {
+            EnumTag::Name(name) => variants.by_name(name),
+            EnumTag::Ord(ord) => variants.by_tag(*ord),
+        };
+        match found {
+            Some((_, r)) if r.as_ty() == Some(&Ty::UNIT) => {}
+            Some(_) => self.report(val, ty, ViolationReason::TypeMismatch),
+            None => self.report(val, ty, ViolationReason::UnknownVariant(tag.clone())),
+        }
+    }
+
+    fn check_items(&mut self, items: &[StrictVal], ty: &TranspileRef) {
+        for (no, item) in items.iter().enumerate() {
+            self.check_at(Step::Index(no as u32), item, ty);
+        }
+    }
+}
+
+/// Resolves the reference into the type definition, if the type is defined by the library.
+fn resolve<'a>(lib: &'a SymbolicLib, r: &'a TranspileRef) -> Option<&'a Ty<TranspileRef>> {
+    match r {
+        TranspileRef::Embedded(ty) => Some(ty),
+        TranspileRef::Named(name) => lib.types().get(name),
+        TranspileRef::Extern(_) => None,
+    }
+}
+
+/// Returns the variants of the character enum referenced by `r`, which are `None` for the
+/// external types whose characters are not known, or nothing if the type is not a character
+/// enum.
+fn char_set<'a>(lib: &'a SymbolicLib, r: &'a TranspileRef) -> Option<Option<&'a EnumVariants>> {
+    if let TranspileRef::Extern(_) = r {
+        return Some(None);
+    }
+    match resolve(lib, r)? {
+        ty @ Ty::Enum(variants) if ty.is_char_enum() => Some(Some(variants)),
+        Ty::Tuple(fields) if fields.len() == 1 => char_set(lib, &fields[0]),
+        _ => None,
+    }
+}
+
+fn allows(chars: Option<&EnumVariants>, c: u8) -> bool {
+    chars.is_none_or(|variants| variants.has_tag(c))
+}
+
+fn fits(num: &StrictNum, prim: Primitive) -> bool {
+    if prim == Primitive::BYTE {
+        return matches!(num, StrictNum::Uint(n) if *n <= u8::MAX as u64);
+    }
+    let info = prim.info();
+    let bits = prim.byte_size() as u32 * 8;
+    match (info.ty, num) {
+        (NumCls::Unsigned, StrictNum::Uint(n)) => bits >= 64 || *n >> bits == 0,
+        (NumCls::NonZero, StrictNum::Uint(n)) => *n != 0 && (bits >= 64 || *n >> bits == 0),
+        (NumCls::Unsigned, StrictNum::BigUint(_)) => bits > 64,
+        (NumCls::Signed, StrictNum::Int(n)) => bits >= 64 || matches!(*n >> (bits - 1), -1 | 0),
+        (NumCls::Signed, StrictNum::Uint(n)) => bits > 64 || *n >> (bits - 1) == 0,
+        (NumCls::Signed, StrictNum::BigInt(_)) => bits > 64,
+        (NumCls::Float, _) => true,
+        _ => false,
+    }
+}
+
+fn key_step(key: &StrictVal) -> Option<KeyStep> {
+    match key {
+        StrictVal::Number(StrictNum::Uint(n)) => Some(KeyStep::Number(*n as u128)),
+        StrictVal::Enum(EnumTag::Ord(n)) => Some(KeyStep::Number(*n as u128)),
+        StrictVal::String(s) => TinyString::try_from(s.clone()).ok().map(KeyStep::TinyString),
+        StrictVal::Enum(EnumTag::Name(name)) => {
+            TinyString::try_from(name.to_string()).ok().map(KeyStep::TinyString)
+        }
+        StrictVal::Bytes(bytes) => TinyBlob::try_from(bytes.to_vec()).ok().map(KeyStep::TinyBlob),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{Confined, TinyString, TinyVec};
+    use encoding::stl::RString;
+    use encoding::{RestrictedCharSet, StrictDeserialize, StrictSerialize};
+
+    use super::*;
+    use crate::typesys::SystemBuilder;
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Kind {
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        kind: Kind,
+        small: i8,
+        name: TinyString,
+        id: [u8; 4],
+        opt: Option<u16>,
+        list: TinyVec<i32>,
+    }
+    impl StrictSerialize for Record {}
+    impl StrictDeserialize for Record {}
+
+    #[test]
+    fn validate_value() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib.clone()).unwrap().finalize().unwrap();
+
+        let record = Record {
+            kind: Kind::Second,
+            small: -20,
+            name: TinyString::try_from(s!("name")).unwrap(),
+            id: [0xFF; 4],
+            opt: Some(500),
+            list: tiny_vec![-1, 24],
+        };
+        let data = record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Record", &data).unwrap().val;
+        assert_eq!(lib.validate_value(&val, &tn!("Record")), Ok(()));
+
+        let StrictVal::Struct(mut fields) = val else {
+            panic!()
+        };
+        fields.insert(fname!("kind"), StrictVal::Enum(vname!("third").into()));
+        fields.insert(fname!("small"), svnum!(200u64));
+        fields.insert(fname!("list"), StrictVal::List(vec![svnum!(1u64); 256]));
+        fields.shift_remove(&fname!("id"));
+        let violations =
+            lib.validate_value(&StrictVal::Struct(fields), &tn!("Record")).unwrap_err();
+        let reasons = violations.iter().map(|v| v.reason.clone()).collect::<Vec<_>>();
+        assert_eq!(reasons, vec![
+            ViolationReason::UnknownVariant(vname!("third").into()),
+            ViolationReason::NumberOutOfRange,
+            ViolationReason::MissingField(fname!("id")),
+            ViolationReason::OutOfBounds {
+                len: 256,
+                sizing: Sizing::new(0, 0xFF)
+            },
+        ]);
+        assert_eq!(violations[0].path.to_string(), ".kind");
+        assert_eq!(violations[2].path.to_string(), "");
+
+        assert_eq!(
+            lib.validate_value(&StrictVal::Unit, &tn!("Unknown")).unwrap_err()[0].reason,
+            ViolationReason::UnknownType(tn!("Unknown"))
+        );
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Letter {
+        #[strict_type(dumb, rename = "_A")]
+        A = b'A',
+        #[strict_type(rename = "_B")]
+        B = b'B',
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Digit {
+        #[strict_type(dumb)]
+        Zero = b'0',
+        One = b'1',
+    }
+
+    impl Display for Letter {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}", *self as u8 as char) }
+    }
+    impl Display for Digit {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{}", *self as u8 as char) }
+    }
+    impl RestrictedCharSet for Letter {}
+    impl RestrictedCharSet for Digit {}
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Labels {
+        code: RString<Letter, Digit, 1, 4>,
+        digits: Confined<Vec<Digit>, 0, 4>,
+        note: Confined<String, 0, 3>,
+    }
+
+    #[test]
+    fn validate_strings() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Labels>().compile().unwrap();
+        let labels = |code: &str, digits: &str, note: &str| {
+            StrictVal::struc([
+                ("code", StrictVal::str(code)),
+                ("digits", StrictVal::str(digits)),
+                ("note", StrictVal::str(note)),
+            ])
+        };
+        let reasons = |code, digits, note| {
+            lib.validate_value(&labels(code, digits, note), &tn!("Labels"))
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| (v.path.to_string(), v.reason))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(reasons("A01", "10", "ab"), vec![]);
+        assert_eq!(reasons("B", "", "ß"), vec![]);
+        let mismatch = |path: &str| (path.to_owned(), ViolationReason::TypeMismatch);
+        assert_eq!(reasons("0A", "1", ""), vec![mismatch(".code")]);
+        assert_eq!(reasons("AB", "1", ""), vec![mismatch(".code")]);
+        assert_eq!(reasons("", "1", ""), vec![mismatch(".code")]);
+        assert_eq!(reasons("A", "12", ""), vec![mismatch(".digits")]);
+        assert_eq!(reasons("A0000", "", "ßß"), vec![
+            (s!(".code"), ViolationReason::OutOfBounds {
+                len: 5,
+                sizing: Sizing::new(1, 4)
+            }),
+            (s!(".note"), ViolationReason::OutOfBounds {
+                len: 4,
+                sizing: Sizing::new(0, 3)
+            }),
+        ]);
+    }
+}
@@ -0,0 +1,662 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Coercion of strict values to a compatible version of their type, for the migration of the
+//! values decoded under an older library version.
+
+use std::collections::BTreeSet;
+
+use amplify::num::i1024;
+use encoding::{FieldName, NumCls, Primitive, Sizing, TypeName};
+use indexmap::IndexMap;
+
+use crate::typelib::{SymbolError, SymbolicLib};
+use crate::value::{Blob, EnumTag, Path, Step, StrictNum};
+use crate::{StrictVal, TranspileRef, Ty, TypeLib, TypeRef};
+
+/// Errors coercing a value to the target type with [`StrictVal::coerce`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum CoerceError {
+    /// type `{0}` is not defined by both libraries.
+    RootAbsent(TypeName),
+
+    #[display(inner)]
+    #[from]
+    Symbols(SymbolError),
+
+    /// value{path} doesn't match its type `{ty}`.
+    ValueMismatch { path: Path, ty: String },
+
+    /// value{path} of type `{from}` can't be converted into `{to}`.
+    TypeMismatch {
+        path: Path,
+        from: String,
+        to: String,
+    },
+
+    /// value{path} of type `{from}` can't be narrowed to `{to}` without a loss.
+    Narrowing {
+        path: Path,
+        from: Primitive,
+        to: Primitive,
+    },
+
+    /// value{path} has variant `{variant}`, which is not defined by the target type.
+    UnknownVariant { path: Path, variant: EnumTag },
+
+    /// value{path} has {from} fields, while the target type has only {to} fields.
+    RemovedFields { path: Path, from: usize, to: usize },
+
+    /// field `{from}` of value{path} is named `{to}` in the target type.
+    RenamedField {
+        path: Path,
+        from: FieldName,
+        to: FieldName,
+    },
+
+    /// value{0} appended in the target type doesn't have a default value.
+    NoDefault(Path),
+
+    /// value{path} has {from} items, which don't fit {to} items of the target type.
+    Sizing {
+        path: Path,
+        from: Sizing,
+        to: Sizing,
+    },
+}
+
+impl StrictVal {
+    /// Converts the value of type `root` from the `from` library into the value of the type with
+    /// the same name from the `to` library, which is a newer version of the type.
+    ///
+    /// The conversion is lossless and follows the compatibility rules of [`TypeLib::diff`]:
+    /// - integers are widened to integers of the same sign and a larger size, or from unsigned to
+    ///   signed integers of a larger size;
+    /// - enum and union variants are matched by their tags, thus the target type may add new
+    ///   variants or rename the existing ones;
+    /// - structure and tuple fields keep their positions and names, while fields appended by the
+    ///   target type are filled with their default values: zero for numbers, `none` for optional
+    ///   values, empty collections if they allow no items and defaults of each field for nested
+    ///   structures and tuples;
+    /// - collections may only extend their bounds.
+    ///
+    /// Conversions are checked against the types, not the particular value, thus narrowing or
+    /// removal of anything is refused even if the value would fit into the target type.
+    ///
+    /// Libraries which are internally inconsistent and reference types they don't define are
+    /// reported with [`CoerceError::Symbols`].
+    pub fn coerce(
+        &self,
+        from: &TypeLib,
+        to: &TypeLib,
+        root: &TypeName,
+    ) -> Result<StrictVal, CoerceError> {
+        let from = from.to_symbolic()?;
+        let to = to.to_symbolic()?;
+        if !from.types().contains_key(root) || !to.types().contains_key(root) {
+            return Err(CoerceError::RootAbsent(root.clone()));
+        }
+        let mut coercer = Coercer {
+            from: &from,
+            to: &to,
+            path: Path::new(),
+            defaulted: empty!(),
+        };
+        let root = TranspileRef::Named(root.clone());
+        coercer.coerce_ref(self, &root, &root)
+    }
+}
+
+struct Coercer<'lib> {
+    from: &'lib SymbolicLib,
+    to: &'lib SymbolicLib,
+    path: Path,
+    /// Named types whose default values are being constructed.
+    defaulted: BTreeSet<TypeName>,
+}
+
+impl Coercer<'_> {
+    fn path(&self) -> Path { self.path.clone() }
+
+    fn value_mismatch(&self, ty: &Ty<TranspileRef>) -> CoerceError {
+        CoerceError::ValueMismatch {
+            path: self.path(),
+            ty: ty.to_string(),
+        }
+    }
+
+    fn type_mismatch(&self, from: impl ToString, to: impl ToString) -> CoerceError {
+        CoerceError::TypeMismatch {
+            path: self.path(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    fn coerce_at(
+        &mut self,
+        step: Step,
+        val: &StrictVal,
+        from: &TranspileRef,
+        to: &TranspileRef,
+    ) -> Result<StrictVal, CoerceError> {
+        self.at(step, |c| c.coerce_ref(val, from, to))
+    }
+
+    fn at<T>(
+        &mut self,
+        step: Step,
+        f: impl FnOnce(&mut Self) -> Result<T, CoerceError>,
+    ) -> Result<T, CoerceError> {
+        self.path.push(step).ok();
+        let res = f(self);
+        self.path.pop();
+        res
+    }
+
+    fn coerce_ref(
+        &mut self,
+        val: &StrictVal,
+        from: &TranspileRef,
+        to: &TranspileRef,
+    ) -> Result<StrictVal, CoerceError> {
+        match (from, to) {
+            (TranspileRef::Extern(f), TranspileRef::Extern(t)) if f.sem_id == t.sem_id => {
+                Ok(val.clone())
+            }
+            (TranspileRef::Extern(_), _) | (_, TranspileRef::Extern(_)) => {
+                Err(self.type_mismatch(from, to))
+            }
+            (f, t) => {
+                let (Some(from_ty), Some(to_ty)) = (resolve(self.from, f), resolve(self.to, t))
+                else {
+                    return Err(self.type_mismatch(f, t));
+                };
+                self.coerce(val, from_ty, to_ty)
+            }
+        }
+    }
+
+    fn coerce_items(
+        &mut self,
+        items: &[StrictVal],
+        from: &TranspileRef,
+        to: &TranspileRef,
+    ) -> Result<Vec<StrictVal>, CoerceError> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(no, item)| self.coerce_at(Step::Index(no as u32), item, from, to))
+            .collect()
+    }
+
+    fn check_sizing(&self, from: Sizing, to: Sizing) -> Result<(), CoerceError> {
+        if to.min > from.min || to.max < from.max {
+            return Err(CoerceError::Sizing {
+                path: self.path(),
+                from,
+                to,
+            });
+        }
+        Ok(())
+    }
+
+    fn coerce(
+        &mut self,
+        val: &StrictVal,
+        from: &Ty<TranspileRef>,
+        to: &Ty<TranspileRef>,
+    ) -> Result<StrictVal, CoerceError> {
+        // types without references to named types are the same in both libraries
+        if from == to && is_closed(from) {
+            return Ok(val.clone());
+        }
+        match (from, to) {
+            (Ty::Primitive(f), Ty::Primitive(t)) if f == t => Ok(val.clone()),
+            (Ty::Primitive(f), Ty::Primitive(t)) if is_number(*f) && is_number(*t) => {
+                if !widens(*f, *t) {
+                    return Err(CoerceError::Narrowing {
+                        path: self.path(),
+                        from: *f,
+                        to: *t,
+                    });
+                }
+                match val {
+                    StrictVal::Number(num) => Ok(StrictVal::Number(widen(*num, *t))),
+                    _ => Err(self.value_mismatch(from)),
+                }
+            }
+            (Ty::UnicodeChar, Ty::UnicodeChar) => Ok(val.clone()),
+
+            (Ty::Enum(f), Ty::Enum(t)) => {
+                let StrictVal::Enum(tag) = val else {
+                    return Err(self.value_mismatch(from));
+                };
+                let variant = match tag {
+                    EnumTag::Name(name) => f.by_name(name),
+                    EnumTag::Ord(ord) => f.by_tag(*ord),
+                };
+                let Some(variant) = variant else {
+                    return Err(self.value_mismatch(from));
+                };
+                match t.name_by_tag(variant.tag) {
+                    Some(name) => Ok(StrictVal::enumer(name.clone())),
+                    None => Err(CoerceError::UnknownVariant {
+                        path: self.path(),
+                        variant: tag.clone(),
+                    }),
+                }
+            }
+            (Ty::Union(f), Ty::Union(t)) => {
+                let (tag, inner) = match val {
+                    StrictVal::Union(tag, inner) => (tag, inner.as_ref()),
+                    StrictVal::Enum(tag) => (tag, &StrictVal::Unit),
+                    _ => return Err(self.value_mismatch(from)),
+                };
+                let variant = match tag {
+                    EnumTag::Name(name) => f.by_name(name),
+                    EnumTag::Ord(ord) => f.by_tag(*ord),
+                };
+                let Some((variant, from_ty)) = variant else {
+                    return Err(self.value_mismatch(from));
+                };
+                let Some((target, to_ty)) = t.by_tag(variant.tag) else {
+                    return Err(CoerceError::UnknownVariant {
+                        path: self.path(),
+                        variant: tag.clone(),
+                    });
+                };
+                let inner = self.coerce_ref(inner, from_ty, to_ty)?;
+                Ok(StrictVal::union(target.name.clone(), inner))
+            }
+
+            (Ty::Tuple(f), Ty::Tuple(t)) => {
+                let StrictVal::Tuple(items) = val else {
+                    return Err(self.value_mismatch(from));
+                };
+                if items.len() != f.len() {
+                    return Err(self.value_mismatch(from));
+                }
+                if t.len() < f.len() {
+                    return Err(CoerceError::RemovedFields {
+                        path: self.path(),
+                        from: f.len(),
+                        to: t.len(),
+                    });
+                }
+                let mut fields = Vec::with_capacity(t.len());
+                for (pos, (item, (from_ty, to_ty))) in items.iter().zip(f.iter().zip(t)).enumerate()
+                {
+                    fields.push(self.coerce_at(
+                        Step::UnnamedField(pos as u8),
+                        item,
+                        from_ty,
+                        to_ty,
+                    )?);
+                }
+                for (pos, to_ty) in t.iter().enumerate().skip(f.len()) {
+                    fields.push(self.at(Step::UnnamedField(pos as u8), |c| c.default_ref(to_ty))?);
+                }
+                Ok(StrictVal::Tuple(fields))
+            }
+            (Ty::Struct(f), Ty::Struct(t)) => {
+                let StrictVal::Struct(items) = val else {
+                    return Err(self.value_mismatch(from));
+                };
+                if t.len() < f.len() {
+                    return Err(CoerceError::RemovedFields {
+                        path: self.path(),
+                        from: f.len(),
+                        to: t.len(),
+                    });
+                }
+                let mut fields = IndexMap::with_capacity(t.len());
+                for (from_field, to_field) in f.iter().zip(t) {
+                    if from_field.name != to_field.name {
+                        return Err(CoerceError::RenamedField {
+                            path: self.path(),
+                            from: from_field.name.clone(),
+                            to: to_field.name.clone(),
+                        });
+                    }
+                    let Some(item) = items.get(&from_field.name) else {
+                        return Err(self.value_mismatch(from));
+                    };
+                    let step = Step::NamedField(from_field.name.clone());
+                    let item = self.coerce_at(step, item, &from_field.ty, &to_field.ty)?;
+                    fields.insert(to_field.name.clone(), item);
+                }
+                for to_field in t.iter().skip(f.len()) {
+                    let step = Step::NamedField(to_field.name.clone());
+                    let val = self.at(step, |c| c.default_ref(&to_field.ty))?;
+                    fields.insert(to_field.name.clone(), val);
+                }
+                Ok(StrictVal::Struct(fields))
+            }
+
+            (Ty::Array(f, f_len), Ty::Array(t, t_len)) if f_len == t_len => match val {
+                StrictVal::Bytes(_) | StrictVal::String(_) if f.is_byte() && t.is_byte() => {
+                    Ok(val.clone())
+                }
+                StrictVal::List(items) => Ok(StrictVal::List(self.coerce_items(items, f, t)?)),
+                _ => Err(self.value_mismatch(from)),
+            },
+            (Ty::List(f, f_sizing), Ty::List(t, t_sizing)) => {
+                self.check_sizing(*f_sizing, *t_sizing)?;
+                match val {
+                    StrictVal::Bytes(_) | StrictVal::String(_) if is_same_closed(f, t) => {
+                        Ok(val.clone())
+                    }
+                    StrictVal::List(items) => Ok(StrictVal::List(self.coerce_items(items, f, t)?)),
+                    _ => Err(self.value_mismatch(from)),
+                }
+            }
+            (Ty::Set(f, f_sizing), Ty::Set(t, t_sizing)) => {
+                self.check_sizing(*f_sizing, *t_sizing)?;
+                match val {
+                    StrictVal::Set(items) => Ok(StrictVal::Set(self.coerce_items(items, f, t)?)),
+                    _ => Err(self.value_mismatch(from)),
+                }
+            }
+            (Ty::Map(f_key, f, f_sizing), Ty::Map(t_key, t, t_sizing)) => {
+                self.check_sizing(*f_sizing, *t_sizing)?;
+                let StrictVal::Map(items) = val else {
+                    return Err(self.value_mismatch(from));
+                };
+                let mut map = Vec::with_capacity(items.len());
+                for (no, (key, item)) in items.iter().enumerate() {
+                    let key = self.coerce_at(Step::Index(no as u32), key, f_key, t_key)?;
+                    let item = self.coerce_at(Step::Index(no as u32), item, f, t)?;
+                    map.push((key, item));
+                }
+                Ok(StrictVal::Map(map))
+            }
+
+            (f, t) => Err(self.type_mismatch(f, t)),
+        }
+    }
+
+    fn default_ref(&mut self, r: &TranspileRef) -> Result<StrictVal, CoerceError> {
+        match r {
+            TranspileRef::Embedded(ty) => self.default_ty(ty),
+            TranspileRef::Named(name) => {
+                // a recursive type can't have a default value without an optional field
+                if !self.defaulted.insert(name.clone()) {
+                    return Err(CoerceError::NoDefault(self.path()));
+                }
+                let to = self.to;
+                let ty = to.types().get(name).ok_or_else(|| CoerceError::NoDefault(self.path()));
+                let val = ty.and_then(|ty| self.default_ty(ty));
+                self.defaulted.remove(name);
+                val
+            }
+            TranspileRef::Extern(_) => Err(CoerceError::NoDefault(self.path())),
+        }
+    }
+
+    fn default_ty(&mut self, ty: &Ty<TranspileRef>) -> Result<StrictVal, CoerceError> {
+        if ty.is_option() {
+            return Ok(StrictVal::none());
+        }
+        match ty {
+            Ty::Primitive(Primitive::UNIT) => Ok(StrictVal::Unit),
+            Ty::Primitive(prim) if *prim == Primitive::BYTE || is_number(*prim) => {
+                match prim.info().ty {
+                    NumCls::Unsigned => Ok(StrictVal::num(0u8)),
+                    NumCls::Signed if *prim != Primitive::BYTE => Ok(StrictVal::num(0i8)),
+                    NumCls::Signed => Ok(StrictVal::num(0u8)),
+                    _ => Err(CoerceError::NoDefault(self.path())),
+                }
+            }
+            Ty::Tuple(fields) => {
+                let mut items = Vec::with_capacity(fields.len());
+                for (pos, ty) in fields.iter().enumerate() {
+                    items.push(self.at(Step::UnnamedField(pos as u8), |c| c.default_ref(ty))?);
+                }
+                Ok(StrictVal::Tuple(items))
+            }
+            Ty::Struct(fields) => {
+                let mut items = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let step = Step::NamedField(field.name.clone());
+                    items.insert(field.name.clone(), self.at(step, |c| c.default_ref(&field.ty))?);
+                }
+                Ok(StrictVal::Struct(items))
+            }
+            Ty::Array(ty, len) if ty.is_byte() => {
+                Ok(StrictVal::Bytes(Blob(vec![0; *len as usize])))
+            }
+            Ty::Array(ty, len) => {
+                let item = self.default_ref(ty)?;
+                Ok(StrictVal::List(vec![item; *len as usize]))
+            }
+            Ty::List(ty, sizing) if sizing.min == 0 && ty.is_byte() => {
+                Ok(StrictVal::Bytes(Blob(vec![])))
+            }
+            Ty::List(ty, sizing) if sizing.min == 0 && ty.is_unicode_char() => {
+                Ok(StrictVal::String(s!("")))
+            }
+            Ty::List(_, sizing) if sizing.min == 0 => Ok(StrictVal::List(vec![])),
+            Ty::Set(_, sizing) if sizing.min == 0 => Ok(StrictVal::Set(vec![])),
+            Ty::Map(_, _, sizing) if sizing.min == 0 => Ok(StrictVal::Map(vec![])),
+            _ => Err(CoerceError::NoDefault(self.path())),
+        }
+    }
+}
+
+fn resolve<'a>(lib: &'a SymbolicLib, r: &'a TranspileRef) -> Option<&'a Ty<TranspileRef>> {
+    match r {
+        TranspileRef::Embedded(ty) => Some(ty),
+        TranspileRef::Named(name) => lib.types().get(name),
+        TranspileRef::Extern(_) => None,
+    }
+}
+
+/// Detects types which don't reference named library types, and thus are defined in the same way
+/// by any library.
+fn is_closed(ty: &Ty<TranspileRef>) -> bool {
+    ty.type_refs().all(|(r, _)| match r {
+        TranspileRef::Embedded(ty) => is_closed(ty),
+        TranspileRef::Named(_) => false,
+        TranspileRef::Extern(_) => true,
+    })
+}
+
+fn is_same_closed(from: &TranspileRef, to: &TranspileRef) -> bool {
+    match (from, to) {
+        (TranspileRef::Embedded(f), TranspileRef::Embedded(t)) => f == t && is_closed(f),
+        (TranspileRef::Extern(f), TranspileRef::Extern(t)) => f.sem_id == t.sem_id,
+        _ => false,
+    }
+}
+
+fn is_number(prim: Primitive) -> bool { prim != Primitive::UNIT && prim != Primitive::BYTE }
+
+fn widens(from: Primitive, to: Primitive) -> bool {
+    let (from_size, to_size) = (from.byte_size(), to.byte_size());
+    match (from.info().ty, to.info().ty) {
+        _ if from == to => true,
+        (NumCls::Unsigned | NumCls::NonZero, NumCls::Unsigned)
+        | (NumCls::NonZero, NumCls::NonZero)
+        | (NumCls::Signed, NumCls::Signed) => to_size >= from_size,
+        (NumCls::Unsigned | NumCls::NonZero, NumCls::Signed) => to_size > from_size,
+        _ => false,
+    }
+}
+
+fn widen(num: StrictNum, to: Primitive) -> StrictNum {
+    match (num, to.info().ty) {
+        (StrictNum::Uint(n), NumCls::Signed) => match i64::try_from(n) {
+            Ok(n) => StrictNum::Int(n),
+            Err(_) => StrictNum::BigInt(i1024::from(n)),
+        },
+        (num, _) => num,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use super::*;
+    use crate::LibBuilder;
+
+    mod v1 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+            Second = 1,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+            pub value: u16,
+            pub delta: i8,
+        }
+    }
+
+    mod v2 {
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test", tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            First = 0,
+            Second = 1,
+            Third = 2,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Extra {
+            pub count: u8,
+            pub note: Option<u8>,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = "Test")]
+        pub struct Record {
+            pub kind: Kind,
+            pub value: u32,
+            pub delta: i32,
+            pub extra: Extra,
+        }
+    }
+
+    #[test]
+    fn coerce() {
+        let old = LibBuilder::with("Test", None).transpile::<v1::Record>().compile().unwrap();
+        let new = LibBuilder::with("Test", None).transpile::<v2::Record>().compile().unwrap();
+        let root = tn!("Record");
+
+        let val = StrictVal::struc([
+            ("kind", StrictVal::enumer(vname!("second"))),
+            ("value", StrictVal::num(0xFFFFu16)),
+            ("delta", StrictVal::num(-1i8)),
+        ]);
+        let migrated = val.coerce(&old, &new, &root).unwrap();
+        assert_eq!(
+            migrated,
+            StrictVal::struc([
+                ("kind", StrictVal::enumer(vname!("second"))),
+                ("value", StrictVal::num(0xFFFFu16)),
+                ("delta", StrictVal::num(-1i8)),
+                (
+                    "extra",
+                    StrictVal::struc([("count", StrictVal::num(0u8)), ("note", StrictVal::none())])
+                ),
+            ])
+        );
+        assert_eq!(new.validate_value(&migrated, &root), Ok(()));
+
+        assert_eq!(migrated_back(&new, &old, &root), CoerceError::RemovedFields {
+            path: Path::new(),
+            from: 4,
+            to: 3
+        });
+        assert_eq!(
+            StrictVal::num(1u8).coerce(&old, &new, &tn!("Absent")),
+            Err(CoerceError::RootAbsent(tn!("Absent")))
+        );
+    }
+
+    #[test]
+    fn coerce_errors() {
+        let old = LibBuilder::with("Test", None).transpile::<v1::Record>().compile().unwrap();
+        let new = LibBuilder::with("Test", None).transpile::<v2::Record>().compile().unwrap();
+        let root = tn!("Record");
+
+        let val = StrictVal::struc([
+            ("kind", StrictVal::enumer(vname!("second"))),
+            ("value", StrictVal::str("ten")),
+            ("delta", StrictVal::num(-1i8)),
+        ]);
+        assert_eq!(
+            val.coerce(&old, &new, &root),
+            Err(CoerceError::ValueMismatch {
+                path: Path::with(Step::NamedField(fname!("value"))),
+                ty: s!("U16")
+            })
+        );
+
+        let mut inconsistent = old.clone();
+        inconsistent.types.remove(&tn!("Kind")).unwrap();
+        assert!(matches!(val.coerce(&inconsistent, &new, &root), Err(CoerceError::Symbols(_))));
+    }
+
+    fn migrated_back(from: &TypeLib, to: &TypeLib, root: &TypeName) -> CoerceError {
+        let val = StrictVal::struc([
+            ("kind", StrictVal::enumer(vname!("third"))),
+            ("value", StrictVal::num(1u32)),
+            ("delta", StrictVal::num(1i32)),
+            (
+                "extra",
+                StrictVal::struc([("count", StrictVal::num(0u8)), ("note", StrictVal::none())]),
+            ),
+        ]);
+        val.coerce(from, to, root).unwrap_err()
+    }
+
+    #[test]
+    fn coerce_narrowing() {
+        assert!(widens(Primitive::U16, Primitive::U32));
+        assert!(widens(Primitive::U16, Primitive::I32));
+        assert!(widens(Primitive::I16, Primitive::I64));
+        assert!(!widens(Primitive::U16, Primitive::I16));
+        assert!(!widens(Primitive::U32, Primitive::U16));
+        assert!(!widens(Primitive::I8, Primitive::U64));
+        assert!(!widens(Primitive::F32, Primitive::F64));
+        assert_eq!(widen(StrictNum::Uint(5), Primitive::I32), StrictNum::Int(5));
+    }
+}
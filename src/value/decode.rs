@@ -23,6 +23,8 @@
 
 //! Reification module: reads & writes strict values from binary strict encodings.
 
+use std::io;
+
 use amplify::ascii::AsciiString;
 use amplify::confinement::{
     Confined, LargeAscii, LargeBlob, LargeString, MediumAscii, MediumBlob, MediumString,
@@ -55,10 +57,53 @@ pub enum Error {
     #[from]
     Decode(DecodeError),
 
+    /// data decoding failed after {offset} bytes were consumed, with {remaining} bytes left.
+    /// Details: {error}
+    DecodeAt {
+        offset: usize,
+        remaining: usize,
+        error: DecodeError,
+    },
+
     /// data provided to reify operation are not entirely consumed during deserialization.
     NotEntirelyConsumed,
 }
 
+impl Error {
+    /// Detects whether the decoding has failed because the data end before the value does, such
+    /// that the data may be a truncated, but otherwise valid, value encoding.
+    pub fn is_truncated(&self) -> bool {
+        match self {
+            Error::Decode(DecodeError::Io(err))
+            | Error::DecodeAt {
+                error: DecodeError::Io(err),
+                ..
+            } => err.kind() == io::ErrorKind::UnexpectedEof,
+            _ => false,
+        }
+    }
+}
+
+/// Reader counting the number of consumed bytes.
+struct CountingReader<R: ReadRaw> {
+    reader: R,
+    offset: usize,
+}
+
+impl<R: ReadRaw> ReadRaw for CountingReader<R> {
+    fn read_raw<const MAX_LEN: usize>(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let data = self.reader.read_raw::<MAX_LEN>(len)?;
+        self.offset += len;
+        Ok(data)
+    }
+
+    fn read_raw_array<const LEN: usize>(&mut self) -> io::Result<[u8; LEN]> {
+        let data = self.reader.read_raw_array::<LEN>()?;
+        self.offset += LEN;
+        Ok(data)
+    }
+}
+
 impl SymbolicSys {
     pub fn strict_deserialize_type(
         &self,
@@ -112,10 +157,25 @@ impl TypeSystem {
         Ok(list)
     }
 
+    /// Deserializes `data` as a value of type `sem_id`.
+    ///
+    /// Decoding errors are reported as [`Error::DecodeAt`], providing the number of bytes
+    /// consumed before the failure and the number of bytes left; use [`Error::is_truncated`] to
+    /// distinguish data which end too early from the corrupted ones.
     pub fn strict_deserialize_type(&self, sem_id: SemId, data: &[u8]) -> Result<TypedVal, Error> {
-        let mut cursor = StreamReader::cursor::<MAX32>(data);
-        let ty = self.strict_read_type(sem_id, &mut cursor)?;
-        if cursor.unconfine().position() as usize != data.len() {
+        let mut reader = CountingReader {
+            reader: StreamReader::cursor::<MAX32>(data),
+            offset: 0,
+        };
+        let ty = self.strict_read_type(sem_id, &mut reader).map_err(|err| match err {
+            Error::Decode(error) => Error::DecodeAt {
+                offset: reader.offset,
+                remaining: data.len() - reader.offset,
+                error,
+            },
+            err => err,
+        })?;
+        if reader.offset != data.len() {
             return Err(Error::NotEntirelyConsumed);
         }
         Ok(ty)
@@ -396,8 +456,10 @@ impl TypeSystem {
 
 #[cfg(test)]
 mod test {
+    use encoding::StrictSerialize;
+
     use super::super::test_helpers::*;
-    // use super::*;
+    use super::*;
 
     #[test]
     fn typify() {
@@ -410,4 +472,33 @@ mod test {
             r#"name "Some name", ticker "TICK", precision twoDecimals"#
         );
     }
+
+    #[test]
+    fn decode_position() {
+        let sys = test_system();
+        let data = Nominal::with("TICK", "Some name", 2)
+            .to_strict_serialized::<{ u16::MAX as usize }>()
+            .unwrap()
+            .release();
+        assert_eq!(data.len(), 16);
+        sys.strict_deserialize_type("TestLib.Nominal", &data).unwrap();
+
+        let err = sys.strict_deserialize_type("TestLib.Nominal", &data[..11]).unwrap_err();
+        assert!(matches!(err, Error::DecodeAt {
+            offset: 6,
+            remaining: 5,
+            ..
+        }));
+        assert!(err.is_truncated());
+
+        let mut corrupted = data.clone();
+        corrupted[15] = 0xFF;
+        let err = sys.strict_deserialize_type("TestLib.Nominal", &corrupted).unwrap_err();
+        assert!(matches!(err, Error::DecodeAt {
+            offset: 16,
+            remaining: 0,
+            error: DecodeError::EnumTagNotKnown(..),
+        }));
+        assert!(!err.is_truncated());
+    }
 }
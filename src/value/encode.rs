@@ -29,10 +29,32 @@ use encoding::{
     Primitive, SerializeError, Sizing, StrictEncode, StrictSerialize, StrictType, TypeName,
     TypedWrite, WriteRaw,
 };
+use sha2::{Digest, Sha256};
 
-use crate::typify::TypedVal;
+use crate::typesys::{LibSystemError, TypeFqn};
+use crate::typify::{self, TypedVal};
 use crate::value::{EnumTag, StrictNum};
-use crate::{SemId, StrictVal, Ty, TypeSystem};
+use crate::{SemId, StrictVal, SymbolicSys, Ty, TypeLib, TypeSystem};
+
+/// Errors encoding strict value with the type library.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum EncodeError {
+    #[display(inner)]
+    #[from]
+    Library(LibSystemError),
+
+    /// value doesn't match the type: {0}
+    #[from]
+    Mismatch(typify::Error),
+
+    /// unable to write encoded value: {0}
+    #[from]
+    Io(io::Error),
+
+    /// value doesn't match its type {0}.
+    Unmatched(SemId),
+}
 
 #[derive(Clone, Debug)]
 pub struct SerializedType<const MAX_LEN: usize>(Confined<Vec<u8>, 0, MAX_LEN>);
@@ -88,7 +110,161 @@ impl TypeSystem {
         self.strict_write_val(&typed.val, typed.orig.id, writer)
     }
 
-    fn strict_write_val(
+    /// Computes the length of the strict-encoded value without encoding it.
+    ///
+    /// Errors if the value doesn't match its type, which happens only for values typified with
+    /// some other type system.
+    pub fn strict_value_len(&self, typed: &TypedVal) -> Result<usize, EncodeError> {
+        self.strict_val_len(&typed.val, typed.orig.id)
+    }
+
+    /// Produces canonical strict encoding of the value, where items of sets and entries of maps
+    /// are ordered by their canonical encoding.
+    ///
+    /// Values which differ only in the representation, like enum variants given by name and by
+    /// tag, or the order of set items, get the same canonical encoding. For values with ordered
+    /// sets and maps it may differ from [`TypeSystem::strict_write_value`] output, since the
+    /// canonical order is the order of the encoded bytes.
+    pub fn strict_canonical_value(&self, typed: &TypedVal) -> Vec<u8> {
+        let mut buf = vec![];
+        self.strict_canonical_val(&typed.val, typed.orig.id, &mut buf);
+        buf
+    }
+
+    fn strict_canonical_val(&self, val: &StrictVal, sem_id: SemId, buf: &mut Vec<u8>) {
+        let ty = if sem_id == SemId::unit() {
+            &Ty::UNIT
+        } else {
+            self.find(sem_id).expect("typified with some other TypeSystem")
+        };
+        let canonical = |val: &StrictVal, sem_id: SemId| {
+            let mut buf = vec![];
+            self.strict_canonical_val(val, sem_id, &mut buf);
+            buf
+        };
+        match (val, ty) {
+            (StrictVal::Tuple(vals), Ty::Tuple(fields)) => {
+                for (val, sem_id) in vals.iter().zip(fields) {
+                    self.strict_canonical_val(val, *sem_id, buf);
+                }
+            }
+            (StrictVal::Struct(vals), Ty::Struct(fields)) => {
+                for field in fields {
+                    if let Some(val) = vals.get(&field.name) {
+                        self.strict_canonical_val(val, field.ty, buf);
+                    }
+                }
+            }
+            (StrictVal::Union(tag, val), Ty::Union(variants)) => {
+                let (tag, sem_id) = match tag {
+                    EnumTag::Ord(tag) => {
+                        (*tag, variants.ty_by_tag(*tag).expect("Type::System::typify guarantees"))
+                    }
+                    EnumTag::Name(name) => {
+                        let (variant, sem_id) =
+                            variants.by_name(name).expect("Type::System::typify guarantees");
+                        (variant.tag, sem_id)
+                    }
+                };
+                buf.push(tag);
+                self.strict_canonical_val(val, *sem_id, buf);
+            }
+            (StrictVal::List(list), Ty::List(sem_id, sizing)) => {
+                buf.extend_from_slice(&list.len().to_le_bytes()[0..sizing.byte_size()]);
+                for val in list {
+                    self.strict_canonical_val(val, *sem_id, buf);
+                }
+            }
+            (StrictVal::Set(list), Ty::Set(sem_id, sizing)) => {
+                let mut items = list.iter().map(|val| canonical(val, *sem_id)).collect::<Vec<_>>();
+                items.sort();
+                buf.extend_from_slice(&list.len().to_le_bytes()[0..sizing.byte_size()]);
+                items.into_iter().for_each(|item| buf.extend(item));
+            }
+            (StrictVal::Map(list), Ty::Map(key_id, sem_id, sizing)) => {
+                let mut items = list
+                    .iter()
+                    .map(|(key, val)| (canonical(key, *key_id), canonical(val, *sem_id)))
+                    .collect::<Vec<_>>();
+                items.sort();
+                buf.extend_from_slice(&list.len().to_le_bytes()[0..sizing.byte_size()]);
+                for (key, val) in items {
+                    buf.extend(key);
+                    buf.extend(val);
+                }
+            }
+            (val, ty) => {
+                self.strict_write_ty(val, ty, buf).expect("writing to memory buffer never fails")
+            }
+        }
+    }
+
+    fn strict_val_len(&self, val: &StrictVal, sem_id: SemId) -> Result<usize, EncodeError> {
+        let ty = if sem_id == SemId::unit() {
+            &Ty::UNIT
+        } else {
+            self.find(sem_id).ok_or(EncodeError::Unmatched(sem_id))?
+        };
+        self.strict_ty_len(val, ty).ok_or(EncodeError::Unmatched(sem_id))?
+    }
+
+    /// Returns `None` if the value doesn't match the type `ty` itself; mismatches of the nested
+    /// values are reported as errors with their own types.
+    fn strict_ty_len(&self, val: &StrictVal, ty: &Ty<SemId>) -> Option<Result<usize, EncodeError>> {
+        let sum = |items: &mut dyn Iterator<Item = (&StrictVal, SemId)>| {
+            items.map(|(val, sem_id)| self.strict_val_len(val, sem_id)).sum::<Result<usize, _>>()
+        };
+        Some(match (val, ty) {
+            (_, Ty::Primitive(prim)) => Ok(prim.byte_size() as usize),
+            (StrictVal::String(s), Ty::UnicodeChar) => Ok(s.len()),
+            (StrictVal::List(list), Ty::Array(sem_id, len)) if list.len() == *len as usize => {
+                sum(&mut list.iter().map(|val| (val, *sem_id)))
+            }
+            (StrictVal::Bytes(_) | StrictVal::String(_), Ty::Array(_, len)) => Ok(*len as usize),
+            (_, Ty::Enum(_)) => Ok(1),
+
+            (StrictVal::Tuple(vals), Ty::Tuple(fields)) if vals.len() == fields.len() => {
+                sum(&mut vals.iter().zip(fields.iter().copied()))
+            }
+            (StrictVal::Struct(vals), Ty::Struct(fields)) => {
+                let mut items = vec![];
+                for field in fields {
+                    items.push((vals.get(&field.name)?, field.ty));
+                }
+                sum(&mut items.into_iter())
+            }
+            (StrictVal::Union(EnumTag::Ord(tag), val), Ty::Union(variants)) => {
+                let sem_id = variants.ty_by_tag(*tag)?;
+                self.strict_val_len(val, *sem_id).map(|len| 1 + len)
+            }
+            (StrictVal::Union(EnumTag::Name(tag), val), Ty::Union(variants)) => {
+                let (_, sem_id) = variants.by_name(tag)?;
+                self.strict_val_len(val, *sem_id).map(|len| 1 + len)
+            }
+
+            (StrictVal::String(s), Ty::List(_, sizing)) => Ok(sizing.byte_size() + s.len()),
+            (StrictVal::Bytes(s), Ty::List(_, sizing)) => Ok(sizing.byte_size() + s.len()),
+            (StrictVal::List(list), Ty::List(sem_id, sizing))
+            | (StrictVal::Set(list), Ty::Set(sem_id, sizing)) => {
+                sum(&mut list.iter().map(|val| (val, *sem_id))).map(|len| sizing.byte_size() + len)
+            }
+            (StrictVal::Map(list), Ty::Map(key_id, sem_id, sizing)) => {
+                let mut items = list.iter().flat_map(|(key, val)| [(key, *key_id), (val, *sem_id)]);
+                sum(&mut items).map(|len| sizing.byte_size() + len)
+            }
+
+            (StrictVal::String(s), Ty::Tuple(fields))
+                if s.is_ascii() && self.is_rstring(fields).ok()? =>
+            {
+                let (_, sizing) = self.rstring_sizing(fields).ok()??;
+                Ok(sizing.byte_size() + s.len())
+            }
+
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn strict_write_val(
         &self,
         val: &StrictVal,
         sem_id: SemId,
@@ -237,6 +413,77 @@ impl TypeSystem {
     }
 }
 
+impl TypeLib {
+    /// Strict-encodes value of the library type `root` into the `writer`.
+    ///
+    /// The value is checked against the type prior to writing, thus nothing is written if it
+    /// doesn't match. The library must not have dependencies.
+    pub fn encode_value<W: io::Write>(
+        &self,
+        val: &StrictVal,
+        root: &TypeName,
+        writer: &mut W,
+    ) -> Result<(), EncodeError> {
+        let (sys, typed) = self.typify_value(val, root)?;
+        sys.as_types().strict_write_value(&typed, writer)?;
+        Ok(())
+    }
+
+    /// Computes the length of the strict-encoded value of the library type `root` without
+    /// encoding it.
+    ///
+    /// The value is checked against the type, and the length accounts for the actual number of
+    /// items in its collections. The library must not have dependencies.
+    pub fn encoded_len(&self, val: &StrictVal, root: &TypeName) -> Result<usize, EncodeError> {
+        let (sys, typed) = self.typify_value(val, root)?;
+        sys.as_types().strict_value_len(&typed)
+    }
+
+    /// Produces canonical strict encoding of the value of the library type `root`; see
+    /// [`TypeSystem::strict_canonical_value`]. The library must not have dependencies.
+    pub fn canonical_encoding(
+        &self,
+        val: &StrictVal,
+        root: &TypeName,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let (sys, typed) = self.typify_value(val, root)?;
+        Ok(sys.as_types().strict_canonical_value(&typed))
+    }
+
+    fn typify_value(
+        &self,
+        val: &StrictVal,
+        root: &TypeName,
+    ) -> Result<(SymbolicSys, TypedVal), EncodeError> {
+        let sys = SymbolicSys::from_lib(self)?;
+        let typed = sys.typify(val.clone(), TypeFqn::with(self.name.clone(), root.clone()))?;
+        Ok((sys, typed))
+    }
+}
+
+impl StrictVal {
+    /// Checks whether two values of the library type `root` are equal under the schema, i.e.
+    /// have the same canonical encoding (see [`TypeSystem::strict_canonical_value`]).
+    ///
+    /// This treats as equal values which differ only in the representation, like enum variants
+    /// given by name and by tag, or items of sets and maps given in a different order. Values
+    /// which don't match the type are never equal.
+    pub fn schema_eq(&self, other: &StrictVal, lib: &TypeLib, root: &TypeName) -> bool {
+        match (lib.canonical_encoding(self, root), lib.canonical_encoding(other, root)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Computes a deterministic hash of the value of the library type `root`, which is the same
+    /// for all values equal under [`StrictVal::schema_eq`]: a SHA256 digest of the canonical
+    /// value encoding.
+    pub fn schema_hash(&self, lib: &TypeLib, root: &TypeName) -> Result<[u8; 32], EncodeError> {
+        let data = lib.canonical_encoding(self, root)?;
+        Ok(Sha256::digest(data).into())
+    }
+}
+
 trait SizingExt {
     fn byte_size(&self) -> usize;
 }
@@ -252,3 +499,199 @@ impl SizingExt for Sizing {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyBlob, TinyOrdMap, TinyOrdSet, TinyString, TinyVec};
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Header {
+        version: u16,
+        flags: u8,
+        nonce: i64,
+    }
+    impl StrictSerialize for Header {}
+
+    #[test]
+    fn encode_value() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Header>().compile().unwrap();
+        let header = Header {
+            version: 2,
+            flags: 0x80,
+            nonce: -1,
+        };
+        let val = ston!(version 2u16, flags 0x80u8, nonce -1i64);
+
+        let mut data = vec![];
+        lib.encode_value(&val, &tn!("Header"), &mut data).unwrap();
+        assert_eq!(data, header.to_strict_serialized::<0xFF>().unwrap().release());
+
+        let mut data = vec![];
+        let wrong = ston!(version 2u16, flags 0x80u8);
+        assert!(matches!(
+            lib.encode_value(&wrong, &tn!("Header"), &mut data),
+            Err(EncodeError::Mismatch(_))
+        ));
+        assert!(data.is_empty());
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Frame {
+        header: Option<u32>,
+        name: TinyString,
+        payload: TinyBlob,
+        tags: TinyVec<TinyString>,
+        index: TinyOrdMap<u8, i16>,
+    }
+    impl StrictSerialize for Frame {}
+
+    #[test]
+    fn encoded_len() {
+        let lib = LibBuilder::with("TestLib", None)
+            .transpile::<Header>()
+            .transpile::<Frame>()
+            .compile()
+            .unwrap();
+        let val = ston!(version 2u16, flags 0x80u8, nonce -1i64);
+        assert_eq!(lib.encoded_len(&val, &tn!("Header")).unwrap(), 11);
+
+        let frame = Frame {
+            header: Some(7),
+            name: tiny_s!("frame"),
+            payload: tiny_blob!(1, 2, 3),
+            tags: tiny_vec![tiny_s!("a"), tiny_s!("bc"), tiny_s!("")],
+            index: tiny_bmap!(1 => -1, 2 => 2),
+        };
+        let data = frame.to_strict_serialized::<0xFFFF>().unwrap().release();
+        let val = StrictVal::struc([
+            ("header", StrictVal::some(7u32)),
+            ("name", StrictVal::str("frame")),
+            ("payload", StrictVal::bytes([1, 2, 3])),
+            (
+                "tags",
+                StrictVal::list([StrictVal::str("a"), StrictVal::str("bc"), StrictVal::str("")]),
+            ),
+            ("index", StrictVal::map([(1u8, -1i16), (2u8, 2i16)])),
+        ]);
+        assert_eq!(lib.encoded_len(&val, &tn!("Frame")).unwrap(), data.len());
+
+        let mut buf = vec![];
+        lib.encode_value(&val, &tn!("Frame"), &mut buf).unwrap();
+        assert_eq!(buf, data);
+
+        let wrong = ston!(version 2u16, flags 0x80u8);
+        assert!(matches!(lib.encoded_len(&wrong, &tn!("Header")), Err(EncodeError::Mismatch(_))));
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Points {
+        coords: [u16; 3],
+        marks: [Mark; 2],
+    }
+    impl StrictSerialize for Points {}
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode)]
+    #[strict_type(lib = "TestLib")]
+    struct Mark {
+        id: u8,
+        weight: u32,
+    }
+    impl encoding::DefaultBasedStrictDumb for Mark {}
+
+    #[test]
+    fn encoded_len_array() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Points>().compile().unwrap();
+        let points = Points {
+            coords: [1, 2, 3],
+            marks: [Mark { id: 4, weight: 5 }, Mark::default()],
+        };
+        let data = points.to_strict_serialized::<0xFF>().unwrap().release();
+        let val = StrictVal::struc([
+            ("coords", StrictVal::list([1u16, 2, 3])),
+            ("marks", StrictVal::list([ston!(id 4u8, weight 5u32), ston!(id 0u8, weight 0u32)])),
+        ]);
+        assert_eq!(lib.encoded_len(&val, &tn!("Points")).unwrap(), data.len());
+        assert_eq!(data.len(), 3 * 2 + 2 * 5);
+
+        // value typified with a type system which doesn't know the type
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+        let typed = sys.typify(val, "TestLib.Points").unwrap();
+        let other = LibBuilder::with("TestLib", None).transpile::<Header>().compile().unwrap();
+        let other = SymbolicSys::from_lib(&other).unwrap();
+        assert!(matches!(
+            other.as_types().strict_value_len(&typed),
+            Err(EncodeError::Unmatched(id)) if id == typed.orig.id
+        ));
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        flag: Flag,
+        tags: TinyOrdSet<u16>,
+        index: TinyOrdMap<u8, TinyString>,
+    }
+
+    impl StrictSerialize for Record {}
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Flag {
+        #[default]
+        #[strict_type(dumb)]
+        Off = 0,
+        On = 1,
+    }
+
+    #[test]
+    fn schema_eq() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let root = tn!("Record");
+        let val = StrictVal::struc([
+            ("flag", StrictVal::enumer("on")),
+            ("tags", StrictVal::set([0x0100u16, 2u16])),
+            ("index", StrictVal::map([(2u8, StrictVal::str("b")), (1u8, StrictVal::str("a"))])),
+        ]);
+        let same = StrictVal::struc([
+            ("flag", StrictVal::enumer(1u8)),
+            ("tags", StrictVal::set([2u16, 0x0100u16])),
+            ("index", StrictVal::map([(1u8, StrictVal::str("a")), (2u8, StrictVal::str("b"))])),
+        ]);
+        let other = StrictVal::struc([
+            ("flag", StrictVal::enumer(1u8)),
+            ("tags", StrictVal::set([2u16, 0x0100u16])),
+            ("index", StrictVal::map([(1u8, StrictVal::str("b")), (2u8, StrictVal::str("a"))])),
+        ]);
+        assert_ne!(val, same);
+        assert!(val.schema_eq(&same, &lib, &root));
+        assert!(!val.schema_eq(&other, &lib, &root));
+        assert!(!val.schema_eq(&ston!(flag 1u8), &lib, &root));
+        assert_eq!(val.schema_hash(&lib, &root).unwrap(), same.schema_hash(&lib, &root).unwrap());
+        assert_ne!(val.schema_hash(&lib, &root).unwrap(), other.schema_hash(&lib, &root).unwrap());
+
+        let record = Record {
+            flag: Flag::On,
+            tags: tiny_bset!(2, 0x0100),
+            index: tiny_bmap!(1 => tiny_s!("a"), 2 => tiny_s!("b")),
+        };
+        let data = record.to_strict_serialized::<0xFF>().unwrap().release();
+        assert_eq!(data.len(), lib.encoded_len(&val, &root).unwrap());
+        assert_eq!(lib.canonical_encoding(&val, &root).unwrap().len(), data.len());
+    }
+}
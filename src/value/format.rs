@@ -0,0 +1,103 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Helpers shared by the type-guided conversions of strict values into self-describing data
+//! formats: CBOR, TOML and JSON.
+
+use encoding::Sizing;
+
+use crate::typesys::UnknownType;
+use crate::{SemId, Ty, TypeRef, TypeSystem};
+
+/// Returns a type from a type system which is known to be complete.
+pub(super) fn get(sys: &TypeSystem, sem_id: SemId) -> &Ty<SemId> {
+    sys.get(sem_id).expect("type system built from a library is complete")
+}
+
+/// Information on how a strict value type is represented in self-describing data formats.
+pub(super) enum Repr<'ty> {
+    Text(Option<SemId>, Option<SemId>, Sizing),
+    Bytes(Sizing),
+    Other(&'ty Ty<SemId>),
+}
+
+pub(super) fn repr<'ty>(
+    sys: &'ty TypeSystem,
+    ty: &'ty Ty<SemId>,
+) -> Result<Repr<'ty>, UnknownType> {
+    Ok(match ty {
+        Ty::UnicodeChar => Repr::Text(None, None, Sizing::ONE),
+        Ty::List(id, sizing) if id.is_unicode_char() => Repr::Text(None, None, *sizing),
+        Ty::List(id, sizing) if get(sys, *id).is_char_enum() => {
+            Repr::Text(None, Some(*id), *sizing)
+        }
+        Ty::Tuple(fields) if sys.is_rstring(fields)? => {
+            let (rest, sizing) = sys.rstring_sizing(fields)?.expect("checked above");
+            Repr::Text(Some(fields[0]), Some(rest), sizing)
+        }
+        Ty::List(id, sizing) if id.is_byte() => Repr::Bytes(*sizing),
+        Ty::Array(id, len) if id.is_byte() => Repr::Bytes(Sizing::fixed(*len as u64)),
+        ty => Repr::Other(ty),
+    })
+}
+
+/// Checks that the string contains only characters allowed by the character enums `first` (for
+/// the first character) and `rest`; `None` means any Unicode character.
+pub(super) fn check_chars(
+    sys: &TypeSystem,
+    s: &str,
+    first: Option<SemId>,
+    rest: Option<SemId>,
+) -> Result<bool, UnknownType> {
+    let allowed = |id: Option<SemId>, c: char| -> Result<bool, UnknownType> {
+        let Some(id) = id else {
+            return Ok(true);
+        };
+        match get(sys, id) {
+            Ty::Enum(variants) => Ok(c.is_ascii() && variants.has_tag(c as u8)),
+            _ => Ok(false),
+        }
+    };
+    for (pos, c) in s.chars().enumerate() {
+        let id = if pos == 0 && first.is_some() { first } else { rest };
+        if !allowed(id, c)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Checks whether the number of items or characters `len` fits the `sizing`.
+pub(super) fn fits(sizing: Sizing, len: usize) -> bool {
+    let len = len as u64;
+    len >= sizing.min && len <= sizing.max
+}
+
+/// Returns the range of values of an integer with `bits` bits.
+pub(super) fn int_range(bits: u32, signed: bool) -> (i128, i128) {
+    if signed {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1i128 << bits) - 1)
+    }
+}
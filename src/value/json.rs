@@ -0,0 +1,562 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Type-guided conversion between strict values and JSON, intended for logging and debugging.
+//!
+//! Unlike [`StrictVal`] serialization with `serde`, the conversion uses the type library to
+//! produce a compact representation, which is converted back into exactly the same strict value.
+//! Integers which don't fit into 53 bits are represented as strings, such that they are not
+//! corrupted by JSON parsers using floating-point numbers.
+//!
+//! | Strict type                     | JSON representation                                |
+//! |---------------------------------|----------------------------------------------------|
+//! | unit                            | `null`                                             |
+//! | integers up to 64 bits          | number; values beyond ±(2^53 - 1) as string         |
+//! | ASCII, restricted and Unicode strings, Unicode char | string                         |
+//! | byte strings and byte arrays    | hex string                                         |
+//! | enums                           | string with the variant name                       |
+//! | unions, including optional values | object with a single key naming the variant      |
+//! | newtypes                        | representation of the wrapped type                 |
+//! | tuples, arrays, lists and sets  | array                                              |
+//! | structures                      | object                                             |
+//! | maps                            | array of key-value arrays                          |
+
+use amplify::hex::{FromHex, ToHex};
+use encoding::{NumCls, Primitive, TypeName, VariantName};
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+
+use super::format::{check_chars, fits, get, int_range, repr, Repr};
+use crate::typesys::{LibSystemError, TypeFqn, UnknownType};
+use crate::typify::TypeSpec;
+use crate::value::{Blob, EnumTag, Path, Step, StrictNum};
+use crate::{SemId, StrictVal, SymbolicSys, Ty, TypeLib, TypeSystem};
+
+/// Largest integer which is exactly represented by JSON parsers using `f64` numbers.
+const MAX_SAFE_INT: i128 = (1 << 53) - 1;
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum JsonError {
+    #[display(inner)]
+    #[from]
+    Library(LibSystemError),
+
+    /// unknown type `{0}`.
+    TypeAbsent(TypeSpec),
+
+    #[display(inner)]
+    #[from]
+    UnknownType(UnknownType),
+
+    /// value{path} {val:?} doesn't match type `{ty}`.
+    ValueMismatch {
+        path: Path,
+        val: StrictVal,
+        ty: Ty<SemId>,
+    },
+
+    /// JSON value{path} `{found}` doesn't match type `{ty}`.
+    JsonMismatch {
+        path: Path,
+        found: String,
+        ty: Ty<SemId>,
+    },
+
+    /// integer {value} at value{path} is out of range for {prim} ({min}..={max}).
+    OutOfRange {
+        path: Path,
+        value: i128,
+        prim: Primitive,
+        min: i128,
+        max: i128,
+    },
+
+    /// JSON object at value{path} misses required field `{field}`.
+    MissingField { path: Path, field: String },
+
+    /// JSON object at value{path} contains field `{field}` not defined by the type.
+    UnknownField { path: Path, field: String },
+
+    /// {0} is not yet supported by the JSON conversion.
+    NotImplemented(String),
+}
+
+impl TypeLib {
+    /// Converts strict value of the library type `root` into JSON.
+    ///
+    /// The library must not have dependencies; use [`SymbolicSys::value_to_json`] for libraries
+    /// which have them.
+    pub fn value_to_json(&self, val: &StrictVal, root: &TypeName) -> Result<Value, JsonError> {
+        let sys = SymbolicSys::from_lib(self)?;
+        sys.value_to_json(TypeFqn::with(self.name.clone(), root.clone()), val)
+    }
+
+    /// Converts JSON produced by [`TypeLib::value_to_json`] back into a strict value of the
+    /// library type `root`.
+    ///
+    /// The library must not have dependencies; use [`SymbolicSys::value_from_json`] for libraries
+    /// which have them.
+    pub fn value_from_json(&self, json: &Value, root: &TypeName) -> Result<StrictVal, JsonError> {
+        let sys = SymbolicSys::from_lib(self)?;
+        sys.value_from_json(TypeFqn::with(self.name.clone(), root.clone()), json)
+    }
+}
+
+impl SymbolicSys {
+    /// Converts strict value of type `spec` into JSON.
+    pub fn value_to_json(
+        &self,
+        spec: impl Into<TypeSpec>,
+        val: &StrictVal,
+    ) -> Result<Value, JsonError> {
+        let sem_id = self.json_type(spec.into())?;
+        let mut conv = Converter {
+            sys: self.as_types(),
+            path: Path::new(),
+        };
+        conv.encode(sem_id, val)
+    }
+
+    /// Converts JSON into a strict value of type `spec`.
+    pub fn value_from_json(
+        &self,
+        spec: impl Into<TypeSpec>,
+        json: &Value,
+    ) -> Result<StrictVal, JsonError> {
+        let sem_id = self.json_type(spec.into())?;
+        let mut conv = Converter {
+            sys: self.as_types(),
+            path: Path::new(),
+        };
+        conv.decode(sem_id, json)
+    }
+
+    fn json_type(&self, spec: TypeSpec) -> Result<SemId, JsonError> {
+        self.to_sem_id(spec.clone())
+            .filter(|sem_id| self.as_types().get(*sem_id).is_some())
+            .ok_or(JsonError::TypeAbsent(spec))
+    }
+}
+
+/// Returns bit size and signedness of an integer primitive supported by the JSON conversion.
+fn int_info(prim: Primitive) -> Result<(u32, bool), JsonError> {
+    let bits = prim.byte_size() as u32 * 8;
+    match prim.info().ty {
+        NumCls::Unsigned if bits <= 64 => Ok((bits, false)),
+        NumCls::Signed if bits <= 64 => Ok((bits, true)),
+        _ => Err(JsonError::NotImplemented(format!("JSON conversion of {prim}"))),
+    }
+}
+
+struct Converter<'sys> {
+    sys: &'sys TypeSystem,
+    path: Path,
+}
+
+impl Converter<'_> {
+    fn at<T>(
+        &mut self,
+        step: Step,
+        f: impl FnOnce(&mut Self) -> Result<T, JsonError>,
+    ) -> Result<T, JsonError> {
+        self.path.push(step).ok();
+        let res = f(self);
+        self.path.pop();
+        res
+    }
+
+    fn check_range(&self, prim: Primitive, value: i128) -> Result<(), JsonError> {
+        let (bits, signed) = int_info(prim)?;
+        let (min, max) = int_range(bits, signed);
+        if value < min || value > max {
+            return Err(JsonError::OutOfRange {
+                path: self.path.clone(),
+                value,
+                prim,
+                min,
+                max,
+            });
+        }
+        Ok(())
+    }
+
+    fn encode(&mut self, sem_id: SemId, val: &StrictVal) -> Result<Value, JsonError> {
+        let sys = self.sys;
+        let ty = get(sys, sem_id);
+        let mismatch = |path: &Path| JsonError::ValueMismatch {
+            path: path.clone(),
+            val: val.clone(),
+            ty: ty.clone(),
+        };
+
+        Ok(match (repr(sys, ty)?, val) {
+            (Repr::Text(first, rest, sizing), StrictVal::String(s)) => {
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(sys, s, first, rest)? {
+                    return Err(mismatch(&self.path));
+                }
+                Value::String(s.clone())
+            }
+            (Repr::Bytes(sizing), StrictVal::Bytes(Blob(data))) if fits(sizing, data.len()) => {
+                Value::String(data.to_hex())
+            }
+            (Repr::Other(Ty::Primitive(Primitive::UNIT)), StrictVal::Unit) => Value::Null,
+            (Repr::Other(Ty::Primitive(prim)), StrictVal::Number(num)) => {
+                let value = match *num {
+                    StrictNum::Uint(n) => n as i128,
+                    StrictNum::Int(n) => n as i128,
+                    _ => return Err(mismatch(&self.path)),
+                };
+                self.check_range(*prim, value)?;
+                if value.abs() > MAX_SAFE_INT {
+                    Value::String(value.to_string())
+                } else {
+                    Value::from(value as i64)
+                }
+            }
+            (Repr::Other(Ty::Enum(variants)), StrictVal::Enum(tag)) => {
+                let name = match tag {
+                    EnumTag::Name(name) => variants.by_name(name).map(|v| &v.name),
+                    EnumTag::Ord(tag) => variants.name_by_tag(*tag),
+                };
+                let name = name.ok_or_else(|| mismatch(&self.path))?;
+                Value::String(name.to_string())
+            }
+            (Repr::Other(Ty::Union(variants)), StrictVal::Union(tag, inner)) => {
+                let variant = match tag {
+                    EnumTag::Name(name) => variants.by_name(name),
+                    EnumTag::Ord(tag) => variants.by_tag(*tag),
+                };
+                let (variant, ty) = variant.ok_or_else(|| mismatch(&self.path))?;
+                let mut object = Map::new();
+                object.insert(variant.name.to_string(), self.encode(*ty, inner)?);
+                Value::Object(object)
+            }
+            (Repr::Other(Ty::Tuple(fields)), StrictVal::Tuple(items))
+                if fields.len() == 1 && items.len() == 1 =>
+            {
+                self.encode(fields[0], &items[0])?
+            }
+            (Repr::Other(Ty::Tuple(fields)), StrictVal::Tuple(items))
+                if fields.len() == items.len() =>
+            {
+                let mut array = Vec::with_capacity(items.len());
+                for (no, (ty, item)) in fields.iter().zip(items).enumerate() {
+                    array.push(self.at(Step::UnnamedField(no as u8), |c| c.encode(*ty, item))?);
+                }
+                Value::Array(array)
+            }
+            (Repr::Other(Ty::Struct(fields)), StrictVal::Struct(items))
+                if fields.len() == items.len()
+                    && fields.iter().zip(items.keys()).all(|(field, name)| &field.name == name) =>
+            {
+                let mut object = Map::new();
+                for (field, item) in fields.iter().zip(items.values()) {
+                    let value = self
+                        .at(Step::NamedField(field.name.clone()), |c| c.encode(field.ty, item))?;
+                    object.insert(field.name.to_string(), value);
+                }
+                Value::Object(object)
+            }
+            (Repr::Other(Ty::Array(ty, len)), StrictVal::List(items))
+                if items.len() == *len as usize =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::List(ty, sizing)), StrictVal::List(items))
+            | (Repr::Other(Ty::Set(ty, sizing)), StrictVal::Set(items))
+                if fits(*sizing, items.len()) =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), StrictVal::Map(items))
+                if fits(*sizing, items.len()) =>
+            {
+                let mut array = Vec::with_capacity(items.len());
+                for (no, (key, item)) in items.iter().enumerate() {
+                    let pair = self.at(Step::Index(no as u32), |c| {
+                        Ok(vec![c.encode(*key_ty, key)?, c.encode(*ty, item)?])
+                    })?;
+                    array.push(Value::Array(pair));
+                }
+                Value::Array(array)
+            }
+            _ => return Err(mismatch(&self.path)),
+        })
+    }
+
+    fn encode_items(&mut self, ty: SemId, items: &[StrictVal]) -> Result<Value, JsonError> {
+        let mut array = Vec::with_capacity(items.len());
+        for (no, item) in items.iter().enumerate() {
+            array.push(self.at(Step::Index(no as u32), |c| c.encode(ty, item))?);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn decode(&mut self, sem_id: SemId, value: &Value) -> Result<StrictVal, JsonError> {
+        let sys = self.sys;
+        let ty = get(sys, sem_id);
+        let mismatch = |path: &Path| JsonError::JsonMismatch {
+            path: path.clone(),
+            found: value.to_string(),
+            ty: ty.clone(),
+        };
+
+        Ok(match (repr(sys, ty)?, value) {
+            (Repr::Text(first, rest, sizing), Value::String(s)) => {
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(sys, s, first, rest)? {
+                    return Err(mismatch(&self.path));
+                }
+                StrictVal::String(s.clone())
+            }
+            (Repr::Bytes(sizing), Value::String(s)) => match Blob::from_hex(s) {
+                Ok(data) if fits(sizing, data.len()) => StrictVal::Bytes(data),
+                _ => return Err(mismatch(&self.path)),
+            },
+            (Repr::Other(Ty::Primitive(Primitive::UNIT)), Value::Null) => StrictVal::Unit,
+            (Repr::Other(Ty::Primitive(prim)), Value::Number(_) | Value::String(_)) => {
+                let value = match value {
+                    Value::Number(n) => n
+                        .as_i64()
+                        .map(i128::from)
+                        .or_else(|| n.as_u64().map(i128::from))
+                        .ok_or_else(|| mismatch(&self.path))?,
+                    Value::String(s) => s.parse().map_err(|_| mismatch(&self.path))?,
+                    _ => unreachable!(),
+                };
+                self.check_range(*prim, value)?;
+                if prim.info().ty == NumCls::Signed {
+                    StrictVal::num(value as i64)
+                } else {
+                    StrictVal::num(value as u64)
+                }
+            }
+            (Repr::Other(Ty::Enum(variants)), Value::String(s)) => {
+                let variant = VariantName::try_from(s.clone())
+                    .ok()
+                    .and_then(|name| variants.by_name(&name))
+                    .ok_or_else(|| mismatch(&self.path))?;
+                StrictVal::enumer(variant.name.clone())
+            }
+            (Repr::Other(Ty::Union(variants)), Value::Object(object)) if object.len() == 1 => {
+                let (name, inner) = object.iter().next().expect("one item");
+                let (variant, ty) = VariantName::try_from(name.clone())
+                    .ok()
+                    .and_then(|name| variants.by_name(&name))
+                    .ok_or_else(|| mismatch(&self.path))?;
+                StrictVal::union(variant.name.clone(), self.decode(*ty, inner)?)
+            }
+            (Repr::Other(Ty::Tuple(fields)), value) if fields.len() == 1 => {
+                StrictVal::Tuple(vec![self.decode(fields[0], value)?])
+            }
+            (Repr::Other(Ty::Tuple(fields)), Value::Array(array))
+                if fields.len() == array.len() =>
+            {
+                let mut items = Vec::with_capacity(array.len());
+                for (no, (ty, item)) in fields.iter().zip(array).enumerate() {
+                    items.push(self.at(Step::UnnamedField(no as u8), |c| c.decode(*ty, item))?);
+                }
+                StrictVal::Tuple(items)
+            }
+            (Repr::Other(Ty::Struct(fields)), Value::Object(object)) => {
+                if let Some(name) =
+                    object.keys().find(|name| !fields.iter().any(|f| f.name.as_str() == *name))
+                {
+                    return Err(JsonError::UnknownField {
+                        path: self.path.clone(),
+                        field: name.clone(),
+                    });
+                }
+                let mut items = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let Some(value) = object.get(field.name.as_str()) else {
+                        return Err(JsonError::MissingField {
+                            path: self.path.clone(),
+                            field: field.name.to_string(),
+                        });
+                    };
+                    let val = self
+                        .at(Step::NamedField(field.name.clone()), |c| c.decode(field.ty, value))?;
+                    items.insert(field.name.clone(), val);
+                }
+                StrictVal::Struct(items)
+            }
+            (Repr::Other(Ty::Array(ty, len)), Value::Array(array))
+                if array.len() == *len as usize =>
+            {
+                StrictVal::List(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::List(ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                StrictVal::List(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::Set(ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                StrictVal::Set(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                let mut items = Vec::with_capacity(array.len());
+                for (no, pair) in array.iter().enumerate() {
+                    let pair = self.at(Step::Index(no as u32), |c| match pair {
+                        Value::Array(pair) if pair.len() == 2 => {
+                            Ok((c.decode(*key_ty, &pair[0])?, c.decode(*ty, &pair[1])?))
+                        }
+                        _ => Err(mismatch(&c.path)),
+                    })?;
+                    items.push(pair);
+                }
+                StrictVal::Map(items)
+            }
+            _ => return Err(mismatch(&self.path)),
+        })
+    }
+
+    fn decode_items(&mut self, ty: SemId, array: &[Value]) -> Result<Vec<StrictVal>, JsonError> {
+        let mut items = Vec::with_capacity(array.len());
+        for (no, item) in array.iter().enumerate() {
+            items.push(self.at(Step::Index(no as u32), |c| c.decode(ty, item))?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyBlob, TinyOrdMap, TinyString, TinyVec};
+    use encoding::{StrictDeserialize, StrictSerialize};
+    use serde_json::json;
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Kind {
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = order)]
+    enum Source {
+        #[strict_type(dumb)]
+        Default,
+        Path(TinyString),
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Record {
+        kind: Kind,
+        small: i8,
+        safe: u64,
+        big: u64,
+        negative: i64,
+        data: TinyBlob,
+        id: [u8; 4],
+        opt: Option<u16>,
+        source: Source,
+        pair: (u8, TinyString),
+        list: TinyVec<i32>,
+        map: TinyOrdMap<u8, i16>,
+    }
+    impl StrictSerialize for Record {}
+    impl StrictDeserialize for Record {}
+
+    #[test]
+    fn json_roundtrip() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Record>().compile().unwrap();
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+
+        let record = Record {
+            kind: Kind::Second,
+            small: -20,
+            safe: MAX_SAFE_INT as u64,
+            big: u64::MAX,
+            negative: i64::MIN,
+            data: TinyBlob::try_from(vec![0xCA, 0xFE]).unwrap(),
+            id: [0xFF; 4],
+            opt: Some(500),
+            source: Source::Path(TinyString::try_from(s!("/etc")).unwrap()),
+            pair: (1, TinyString::try_from(s!("ä")).unwrap()),
+            list: tiny_vec![-1, 24],
+            map: tiny_bmap! { 2 => -300, 10 => 300 },
+        };
+        let data = record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Record", &data).unwrap().val;
+
+        let json = lib.value_to_json(&val, &tn!("Record")).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "kind": "second",
+                "small": -20,
+                "safe": 9007199254740991u64,
+                "big": "18446744073709551615",
+                "negative": "-9223372036854775808",
+                "data": "cafe",
+                "id": "ffffffff",
+                "opt": { "some": 500 },
+                "source": { "path": "/etc" },
+                "pair": [1, "ä"],
+                "list": [-1, 24],
+                "map": [[2, -300], [10, 300]],
+            })
+        );
+        assert_eq!(lib.value_from_json(&json, &tn!("Record")).unwrap(), val);
+    }
+
+    #[test]
+    fn json_errors() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Kind>().compile().unwrap();
+        assert_eq!(
+            lib.value_from_json(&json!("second"), &tn!("Kind")).unwrap(),
+            StrictVal::enumer(vname!("second"))
+        );
+        assert!(matches!(
+            lib.value_from_json(&json!("third"), &tn!("Kind")),
+            Err(JsonError::JsonMismatch { .. })
+        ));
+        assert!(matches!(
+            lib.value_to_json(&StrictVal::num(1u8), &tn!("Kind")),
+            Err(JsonError::ValueMismatch { .. })
+        ));
+        assert!(matches!(
+            lib.value_to_json(&StrictVal::Unit, &tn!("Absent")),
+            Err(JsonError::TypeAbsent(_))
+        ));
+    }
+}
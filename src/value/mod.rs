@@ -26,6 +26,13 @@
 //! - [STON][ston]: strict type object notation, a JSON-like representation of strict types;
 //! - [`decode`]: conversion between strict encoding and strict values;
 //! - [`typify`]: checks of strict values against strict type schema;
+//! - [`cbor`]: type-guided conversion between strict values and CBOR encoding;
+//! - [`toml`][mod@toml]: type-guided conversion between strict values and TOML documents;
+//! - [`json`]: type-guided conversion between strict values and JSON;
+//! - [`check`]: validation of strict values against type library definitions;
+//! - [`coerce`]: migration of strict values to newer versions of their types;
+//! - [`arbitrary`]: generation of arbitrary values of a type for property-based testing;
+//! - [`builder`]: construction of values field by field, validated against their type;
 //! - [`convert`]: conversion between strict values and other text representations (JSON, YAML,
 //!   TOML, etc).
 
@@ -35,15 +42,36 @@ pub mod path;
 pub mod ston;
 pub mod typify;
 pub mod decode;
+pub mod cbor;
+pub mod check;
+pub mod coerce;
+#[cfg(feature = "value-gen")]
+pub mod arbitrary;
+pub mod builder;
 #[cfg(feature = "serde")]
 pub mod convert;
+#[cfg(feature = "serde")]
+pub mod toml;
+#[cfg(feature = "serde")]
+pub mod json;
+mod format;
 mod encode;
 
-pub use path::{KeyStep, Path, PathError, Step};
+#[cfg(feature = "value-gen")]
+pub use arbitrary::ArbitraryError;
+pub use builder::{BuildError, StrictValBuilder};
+pub use check::{FieldViolation, ViolationReason};
+pub use coerce::CoerceError;
+pub use encode::EncodeError;
+#[cfg(feature = "serde")]
+pub use json::JsonError;
+pub use path::{KeyStep, Path, PathError, SelectorError, Step};
+#[cfg(feature = "serde")]
+pub use toml::TomlError;
 pub use val::{Blob, EnumTag, StrictNum, StrictVal};
 
 #[cfg(test)]
-mod test_helpers {
+pub(crate) mod test_helpers {
     use amplify::confinement::{Confined, NonEmptyString};
     use encoding::{Ident, StrictDeserialize, StrictSerialize};
 
@@ -196,3 +196,329 @@ impl StrictVal {
         }
     }
 }
+
+/// Errors selecting values with [`StrictVal::select`].
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SelectorError {
+    /// invalid selector syntax at position {pos}: `{rest}`.
+    Syntax { pos: usize, rest: String },
+
+    /// selector segment `{segment}` at position {pos} can't be applied to a {shape} value.
+    ShapeMismatch {
+        pos: usize,
+        segment: String,
+        shape: &'static str,
+    },
+
+    /// struct doesn't have a field named `{field}`, requested by the selector at position {pos}.
+    UnknownField { pos: usize, field: String },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+    Variant(String),
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Field(name) => write!(f, ".{name}"),
+            Segment::Index(index) => write!(f, "[{index}]"),
+            Segment::Wildcard => f.write_str("[*]"),
+            Segment::Variant(name) => write!(f, ":{name}"),
+        }
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<Vec<(usize, Segment)>, SelectorError> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut segments = vec![];
+    let mut pos = if selector.starts_with('$') { 1 } else { 0 };
+    while pos < selector.len() {
+        let rest = &selector[pos..];
+        let error = || SelectorError::Syntax {
+            pos,
+            rest: rest.to_owned(),
+        };
+        let (segment, len) = if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']').ok_or_else(error)?;
+            let segment = match &inner[..end] {
+                "*" => Segment::Wildcard,
+                index => Segment::Index(index.parse().map_err(|_| error())?),
+            };
+            (segment, end + 2)
+        } else if rest.starts_with(".*") {
+            (Segment::Wildcard, 2)
+        } else if let Some(name) = rest.strip_prefix(['.', ':']) {
+            let len = name.find(|c| !is_name(c)).unwrap_or(name.len());
+            if len == 0 {
+                return Err(error());
+            }
+            let name = name[..len].to_owned();
+            let segment =
+                if rest.starts_with('.') { Segment::Field(name) } else { Segment::Variant(name) };
+            (segment, len + 1)
+        } else {
+            return Err(error());
+        };
+        segments.push((pos, segment));
+        pos += len;
+    }
+    Ok(segments)
+}
+
+impl StrictVal {
+    /// Name of the value kind, used in the error messages.
+    fn shape(&self) -> &'static str {
+        match self {
+            StrictVal::Unit => "unit",
+            StrictVal::Number(_) => "number",
+            StrictVal::String(_) => "string",
+            StrictVal::Bytes(_) => "bytes",
+            StrictVal::Struct(_) => "struct",
+            StrictVal::Enum(_) => "enum",
+            StrictVal::Union(_, _) => "union",
+            StrictVal::List(_) => "list",
+            StrictVal::Set(_) => "set",
+            StrictVal::Tuple(_) => "tuple",
+            StrictVal::Map(_) => "map",
+        }
+    }
+
+    /// Selects nested values matching the `selector` expression.
+    ///
+    /// The selector is a sequence of segments, optionally preceded by the `$` root symbol:
+    /// - `.name` selects a struct field, and `.0` &ndash; a tuple field;
+    /// - `[n]` selects an item of a list, set or tuple;
+    /// - `[*]` or `.*` selects all items of a list, set or tuple, all struct fields or all map
+    ///   values;
+    /// - `:name` keeps only the enum values with the `name` variant and the data of the union
+    ///   values with this variant.
+    ///
+    /// For instance, `$.inputs[*].prevout.txid` selects transaction ids of all inputs.
+    ///
+    /// When a segment doesn't fit the value kind, like a field applied to a list, or a struct
+    /// misses a requested field, the selector doesn't match the value type and an error is
+    /// returned. Data-dependent misses, like indexes outside of a collection or values of other
+    /// variants, just don't produce matches.
+    ///
+    /// Newtype wrappers, which are represented as single-field tuples, are transparent to all
+    /// segments except `.0`, so the selectors work the same way on the values produced by the
+    /// strict decoder.
+    pub fn select(&self, selector: &str) -> Result<Vec<&StrictVal>, SelectorError> {
+        let mut selected = vec![self];
+        for (pos, segment) in parse_selector(selector)? {
+            let mut next = vec![];
+            for val in selected {
+                let val = match (&segment, val) {
+                    (Segment::Field(no), StrictVal::Tuple(_)) if no == "0" => val,
+                    _ => val.skip_wrapper(),
+                };
+                let mismatch = || SelectorError::ShapeMismatch {
+                    pos,
+                    segment: segment.to_string(),
+                    shape: val.shape(),
+                };
+                match (&segment, val) {
+                    (Segment::Field(name), StrictVal::Struct(fields)) => {
+                        let field = fields.iter().find(|(field, _)| field.as_str() == name);
+                        let (_, val) = field.ok_or_else(|| SelectorError::UnknownField {
+                            pos,
+                            field: name.clone(),
+                        })?;
+                        next.push(val);
+                    }
+                    (Segment::Field(no), StrictVal::Tuple(fields)) => {
+                        let no = no.parse::<usize>().map_err(|_| mismatch())?;
+                        next.extend(fields.get(no));
+                    }
+                    (
+                        Segment::Index(index),
+                        StrictVal::List(items) | StrictVal::Set(items) | StrictVal::Tuple(items),
+                    ) => next.extend(items.get(*index)),
+                    (
+                        Segment::Wildcard,
+                        StrictVal::List(items) | StrictVal::Set(items) | StrictVal::Tuple(items),
+                    ) => next.extend(items),
+                    (Segment::Wildcard, StrictVal::Struct(fields)) => next.extend(fields.values()),
+                    (Segment::Wildcard, StrictVal::Map(items)) => {
+                        next.extend(items.iter().map(|(_, val)| val))
+                    }
+                    (Segment::Variant(name), StrictVal::Enum(tag)) => {
+                        if &tag.to_string() == name {
+                            next.push(val);
+                        }
+                    }
+                    (Segment::Variant(name), StrictVal::Union(tag, inner)) => {
+                        if &tag.to_string() == name {
+                            next.push(inner.as_ref());
+                        }
+                    }
+                    _ => return Err(mismatch()),
+                }
+            }
+            selected = next;
+        }
+        Ok(selected)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::TinyVec;
+    use encoding::StrictSerialize;
+
+    use super::*;
+    use crate::{LibBuilder, SystemBuilder};
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Outpoint {
+        txid: [u8; 4],
+        vout: u32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Input(Outpoint);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Inputs(TinyVec<Input>);
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Tx {
+        version: u32,
+        inputs: Inputs,
+    }
+    impl StrictSerialize for Tx {}
+
+    fn tx() -> StrictVal {
+        let input = |txid: u8, witness: StrictVal| {
+            StrictVal::struc([
+                (
+                    "prevout",
+                    StrictVal::struc([
+                        ("txid", StrictVal::bytes([txid; 4])),
+                        ("vout", StrictVal::num(0u32)),
+                    ]),
+                ),
+                ("witness", witness),
+            ])
+        };
+        StrictVal::struc([
+            ("version", StrictVal::num(2u32)),
+            (
+                "inputs",
+                StrictVal::list([
+                    input(1, StrictVal::none()),
+                    input(2, StrictVal::some(StrictVal::bytes([0xAA]))),
+                ]),
+            ),
+            ("kind", StrictVal::enumer("segwit")),
+        ])
+    }
+
+    #[test]
+    fn select() {
+        let tx = tx();
+        assert_eq!(tx.select("").unwrap(), vec![&tx]);
+        assert_eq!(tx.select("$.version").unwrap(), vec![&StrictVal::num(2u32)]);
+        assert_eq!(tx.select("$.inputs[*].prevout.txid").unwrap(), vec![
+            &StrictVal::bytes([1; 4]),
+            &StrictVal::bytes([2; 4])
+        ]);
+        assert_eq!(tx.select(".inputs[1].prevout.txid").unwrap(), vec![&StrictVal::bytes([2; 4])]);
+        assert!(tx.select("$.inputs[2]").unwrap().is_empty());
+        assert_eq!(tx.select("$.inputs[*].witness:some").unwrap(), vec![&StrictVal::bytes([0xAA])]);
+        assert_eq!(tx.select("$.kind:segwit").unwrap().len(), 1);
+        assert!(tx.select("$.kind:legacy").unwrap().is_empty());
+        assert_eq!(tx.select("$.inputs[0].prevout.*").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn select_decoded() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Tx>().compile().unwrap();
+        let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+        let input = |txid: u8| {
+            Input(Outpoint {
+                txid: [txid; 4],
+                vout: 1,
+            })
+        };
+        let tx = Tx {
+            version: 2,
+            inputs: Inputs(tiny_vec![input(1), input(2)]),
+        };
+        let data = tx.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Tx", &data).unwrap().val;
+
+        assert_eq!(val.select("$.inputs[*].txid").unwrap(), vec![
+            &StrictVal::bytes([1; 4]),
+            &StrictVal::bytes([2; 4])
+        ]);
+        assert_eq!(val.select("$.inputs[1].vout").unwrap(), vec![&StrictVal::num(1u32)]);
+        assert_eq!(val.select("$.inputs.0[0].0.txid").unwrap(), vec![&StrictVal::bytes([1; 4])]);
+        assert_eq!(
+            val.select("$.inputs[0].prevout"),
+            Err(SelectorError::UnknownField {
+                pos: 11,
+                field: s!("prevout"),
+            })
+        );
+    }
+
+    #[test]
+    fn select_errors() {
+        let tx = tx();
+        assert_eq!(
+            tx.select("$.inputs.prevout"),
+            Err(SelectorError::ShapeMismatch {
+                pos: 8,
+                segment: s!(".prevout"),
+                shape: "list",
+            })
+        );
+        assert_eq!(
+            tx.select("$.inputs[*].prev"),
+            Err(SelectorError::UnknownField {
+                pos: 11,
+                field: s!("prev"),
+            })
+        );
+        assert_eq!(
+            tx.select("$.version[*]"),
+            Err(SelectorError::ShapeMismatch {
+                pos: 9,
+                segment: s!("[*]"),
+                shape: "number",
+            })
+        );
+        assert_eq!(
+            tx.select("$.inputs[x]"),
+            Err(SelectorError::Syntax {
+                pos: 8,
+                rest: s!("[x]"),
+            })
+        );
+        assert_eq!(
+            tx.select("$.inputs/"),
+            Err(SelectorError::Syntax {
+                pos: 8,
+                rest: s!("/"),
+            })
+        );
+        assert!(matches!(tx.select("$."), Err(SelectorError::Syntax { pos: 1, .. })));
+    }
+}
@@ -0,0 +1,685 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Type-guided conversion between strict values and TOML, intended for human-editable
+//! configuration files.
+//!
+//! TOML is less strict than strict types, thus the type library is used to disambiguate the
+//! representation and to coerce TOML integers to the declared width. A TOML document is always a
+//! table, thus the root type must be represented as a table (structure, union, unit or a map).
+//!
+//! | Strict type                     | TOML representation                                |
+//! |---------------------------------|----------------------------------------------------|
+//! | unit                            | empty table                                        |
+//! | integers up to 64 bits          | integer; unsigned values above `i64::MAX` as string |
+//! | ASCII, restricted and Unicode strings, Unicode char | string                         |
+//! | byte strings and byte arrays    | hex string                                         |
+//! | boolean (`false`/`true` enum)   | boolean                                            |
+//! | enums                           | string with the variant name                       |
+//! | unions                          | table with a single key naming the variant         |
+//! | newtypes                        | representation of the wrapped type                 |
+//! | tuples, arrays, lists and sets  | array                                              |
+//! | structures                      | table; fields with `none` optional values are omitted |
+//! | maps with string, integer or enum keys | table                                     |
+//! | other maps                      | array of key-value arrays                          |
+
+use ::toml::{Table, Value};
+use amplify::hex::{FromHex, ToHex};
+use encoding::{NumCls, Primitive, TypeName, VariantName};
+use indexmap::IndexMap;
+
+use super::format::{check_chars, fits, get, int_range, repr, Repr};
+use crate::ast::EnumVariants;
+use crate::typesys::{LibSystemError, TypeFqn, UnknownType};
+use crate::typify::TypeSpec;
+use crate::value::{Blob, EnumTag, Path, Step, StrictNum};
+use crate::{SemId, StrictVal, SymbolicSys, Ty, TypeLib, TypeSystem};
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum TomlError {
+    #[display(inner)]
+    #[from]
+    Library(LibSystemError),
+
+    /// unknown type `{0}`.
+    TypeAbsent(TypeSpec),
+
+    #[display(inner)]
+    #[from]
+    UnknownType(UnknownType),
+
+    /// invalid TOML document: {0}
+    Syntax(String),
+
+    /// type `{0}` can't be represented as a TOML document, which must be a table.
+    NoTable(Ty<SemId>),
+
+    /// value{path} {val:?} doesn't match type `{ty}`.
+    ValueMismatch {
+        path: Path,
+        val: StrictVal,
+        ty: Ty<SemId>,
+    },
+
+    /// TOML value{path} `{found}` doesn't match type `{ty}`.
+    TomlMismatch {
+        path: Path,
+        found: String,
+        ty: Ty<SemId>,
+    },
+
+    /// integer {value} at value{path} is out of range for {prim} ({min}..={max}).
+    OutOfRange {
+        path: Path,
+        value: i128,
+        prim: Primitive,
+        min: i128,
+        max: i128,
+    },
+
+    /// TOML table at value{path} misses required field `{field}`.
+    MissingField { path: Path, field: String },
+
+    /// TOML table at value{path} contains field `{field}` not defined by the type.
+    UnknownField { path: Path, field: String },
+
+    /// {0} is not yet supported by the TOML conversion.
+    NotImplemented(String),
+}
+
+impl TypeLib {
+    /// Converts strict value of the library type `root` into a TOML document.
+    ///
+    /// The library must not have dependencies; use [`SymbolicSys::to_toml`] for libraries which
+    /// have them.
+    pub fn to_toml(&self, val: &StrictVal, root: &TypeName) -> Result<String, TomlError> {
+        let sys = SymbolicSys::from_lib(self)?;
+        sys.to_toml(TypeFqn::with(self.name.clone(), root.clone()), val)
+    }
+
+    /// Parses TOML document into a strict value of the library type `root`.
+    ///
+    /// The library must not have dependencies; use [`SymbolicSys::from_toml`] for libraries which
+    /// have them.
+    pub fn from_toml(&self, s: &str, root: &TypeName) -> Result<StrictVal, TomlError> {
+        let sys = SymbolicSys::from_lib(self)?;
+        sys.from_toml(TypeFqn::with(self.name.clone(), root.clone()), s)
+    }
+}
+
+impl SymbolicSys {
+    /// Converts strict value of type `spec` into a TOML document.
+    pub fn to_toml(&self, spec: impl Into<TypeSpec>, val: &StrictVal) -> Result<String, TomlError> {
+        let sem_id = self.toml_type(spec.into())?;
+        let mut conv = Converter {
+            sys: self.as_types(),
+            path: Path::new(),
+        };
+        match conv.encode(sem_id, val)? {
+            Value::Table(table) => Ok(table.to_string()),
+            _ => Err(TomlError::NoTable(get(self.as_types(), sem_id).clone())),
+        }
+    }
+
+    /// Parses TOML document into a strict value of type `spec`.
+    pub fn from_toml(&self, spec: impl Into<TypeSpec>, s: &str) -> Result<StrictVal, TomlError> {
+        let sem_id = self.toml_type(spec.into())?;
+        let table = s.parse::<Table>().map_err(|err| TomlError::Syntax(err.to_string()))?;
+        let mut conv = Converter {
+            sys: self.as_types(),
+            path: Path::new(),
+        };
+        conv.decode(sem_id, &Value::Table(table))
+    }
+
+    fn toml_type(&self, spec: TypeSpec) -> Result<SemId, TomlError> {
+        self.to_sem_id(spec.clone())
+            .filter(|sem_id| self.as_types().get(*sem_id).is_some())
+            .ok_or(TomlError::TypeAbsent(spec))
+    }
+}
+
+/// Detects enums which represent booleans, such as `Std.Bool`.
+fn is_bool(variants: &EnumVariants) -> bool {
+    variants.len() == 2
+        && variants.tag_by_name(&vname!("false")) == Some(0)
+        && variants.tag_by_name(&vname!("true")) == Some(1)
+}
+
+/// Returns bit size and signedness of an integer primitive supported by the TOML conversion.
+fn int_info(prim: Primitive) -> Result<(u32, bool), TomlError> {
+    let bits = prim.byte_size() as u32 * 8;
+    match prim.info().ty {
+        NumCls::Unsigned if bits <= 64 => Ok((bits, false)),
+        NumCls::Signed if bits <= 64 => Ok((bits, true)),
+        _ => Err(TomlError::NotImplemented(format!("TOML conversion of {prim}"))),
+    }
+}
+
+/// Checks whether map keys of the given type can be represented as TOML table keys.
+fn is_table_key(sys: &TypeSystem, ty: &Ty<SemId>) -> Result<bool, UnknownType> {
+    Ok(match repr(sys, ty)? {
+        Repr::Text(..) => true,
+        Repr::Other(Ty::Primitive(prim)) => int_info(*prim).is_ok(),
+        Repr::Other(Ty::Enum(_)) => true,
+        _ => false,
+    })
+}
+
+struct Converter<'sys> {
+    sys: &'sys TypeSystem,
+    path: Path,
+}
+
+impl Converter<'_> {
+    fn at<T>(
+        &mut self,
+        step: Step,
+        f: impl FnOnce(&mut Self) -> Result<T, TomlError>,
+    ) -> Result<T, TomlError> {
+        self.path.push(step).ok();
+        let res = f(self);
+        self.path.pop();
+        res
+    }
+
+    fn check_range(&self, prim: Primitive, value: i128) -> Result<(), TomlError> {
+        let (bits, signed) = int_info(prim)?;
+        let (min, max) = int_range(bits, signed);
+        if value < min || value > max {
+            return Err(TomlError::OutOfRange {
+                path: self.path.clone(),
+                value,
+                prim,
+                min,
+                max,
+            });
+        }
+        Ok(())
+    }
+
+    fn encode(&mut self, sem_id: SemId, val: &StrictVal) -> Result<Value, TomlError> {
+        let sys = self.sys;
+        let ty = get(sys, sem_id);
+        let mismatch = |path: &Path| TomlError::ValueMismatch {
+            path: path.clone(),
+            val: val.clone(),
+            ty: ty.clone(),
+        };
+
+        Ok(match (repr(sys, ty)?, val) {
+            (Repr::Text(first, rest, sizing), StrictVal::String(s)) => {
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(sys, s, first, rest)? {
+                    return Err(mismatch(&self.path));
+                }
+                Value::String(s.clone())
+            }
+            (Repr::Bytes(sizing), StrictVal::Bytes(Blob(data))) if fits(sizing, data.len()) => {
+                Value::String(data.to_hex())
+            }
+            (Repr::Other(Ty::Primitive(Primitive::UNIT)), StrictVal::Unit) => {
+                Value::Table(Table::new())
+            }
+            (Repr::Other(Ty::Primitive(prim)), StrictVal::Number(num)) => {
+                let value = match *num {
+                    StrictNum::Uint(n) => n as i128,
+                    StrictNum::Int(n) => n as i128,
+                    _ => return Err(mismatch(&self.path)),
+                };
+                self.check_range(*prim, value)?;
+                match i64::try_from(value) {
+                    Ok(n) => Value::Integer(n),
+                    Err(_) => Value::String(value.to_string()),
+                }
+            }
+            (Repr::Other(Ty::Enum(variants)), StrictVal::Enum(tag)) => {
+                let name = match tag {
+                    EnumTag::Name(name) => variants.by_name(name).map(|v| &v.name),
+                    EnumTag::Ord(tag) => variants.name_by_tag(*tag),
+                };
+                let name = name.ok_or_else(|| mismatch(&self.path))?;
+                if is_bool(variants) {
+                    Value::Boolean(name.as_str() == "true")
+                } else {
+                    Value::String(name.to_string())
+                }
+            }
+            (Repr::Other(Ty::Union(variants)), StrictVal::Union(tag, inner)) => {
+                let variant = match tag {
+                    EnumTag::Name(name) => variants.by_name(name),
+                    EnumTag::Ord(tag) => variants.by_tag(*tag),
+                };
+                let (variant, ty) = variant.ok_or_else(|| mismatch(&self.path))?;
+                let mut table = Table::new();
+                table.insert(variant.name.to_string(), self.encode(*ty, inner)?);
+                Value::Table(table)
+            }
+            (Repr::Other(Ty::Tuple(fields)), StrictVal::Tuple(items))
+                if fields.len() == 1 && items.len() == 1 =>
+            {
+                self.encode(fields[0], &items[0])?
+            }
+            (Repr::Other(Ty::Tuple(fields)), StrictVal::Tuple(items))
+                if fields.len() == items.len() =>
+            {
+                let mut array = Vec::with_capacity(items.len());
+                for (no, (ty, item)) in fields.iter().zip(items).enumerate() {
+                    array.push(self.at(Step::UnnamedField(no as u8), |c| c.encode(*ty, item))?);
+                }
+                Value::Array(array)
+            }
+            (Repr::Other(Ty::Struct(fields)), StrictVal::Struct(items))
+                if fields.len() == items.len()
+                    && fields.iter().zip(items.keys()).all(|(field, name)| &field.name == name) =>
+            {
+                let mut table = Table::new();
+                for (field, item) in fields.iter().zip(items.values()) {
+                    if get(sys, field.ty).is_option() && as_option(item) == Some(None) {
+                        continue;
+                    }
+                    let value = self.at(Step::NamedField(field.name.clone()), |c| {
+                        c.encode_field(field.ty, item)
+                    })?;
+                    table.insert(field.name.to_string(), value);
+                }
+                Value::Table(table)
+            }
+            (Repr::Other(Ty::Array(ty, len)), StrictVal::List(items))
+                if items.len() == *len as usize =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::List(ty, sizing)), StrictVal::List(items))
+            | (Repr::Other(Ty::Set(ty, sizing)), StrictVal::Set(items))
+                if fits(*sizing, items.len()) =>
+            {
+                self.encode_items(*ty, items)?
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), StrictVal::Map(items))
+                if fits(*sizing, items.len()) =>
+            {
+                if is_table_key(sys, get(sys, *key_ty))? {
+                    let mut table = Table::new();
+                    for (no, (key, item)) in items.iter().enumerate() {
+                        let key =
+                            match self.at(Step::Index(no as u32), |c| c.encode(*key_ty, key))? {
+                                Value::String(s) => s,
+                                Value::Integer(n) => n.to_string(),
+                                Value::Boolean(b) => b.to_string(),
+                                _ => unreachable!("table keys are strings or integers"),
+                            };
+                        let value = self.at(Step::Index(no as u32), |c| c.encode(*ty, item))?;
+                        table.insert(key, value);
+                    }
+                    Value::Table(table)
+                } else {
+                    let mut array = Vec::with_capacity(items.len());
+                    for (no, (key, item)) in items.iter().enumerate() {
+                        let pair = self.at(Step::Index(no as u32), |c| {
+                            Ok(vec![c.encode(*key_ty, key)?, c.encode(*ty, item)?])
+                        })?;
+                        array.push(Value::Array(pair));
+                    }
+                    Value::Array(array)
+                }
+            }
+            _ => return Err(mismatch(&self.path)),
+        })
+    }
+
+    /// Converts value of a structure field, representing present optional values inline.
+    fn encode_field(&mut self, sem_id: SemId, val: &StrictVal) -> Result<Value, TomlError> {
+        match (get(self.sys, sem_id).as_some(), as_option(val)) {
+            (Some(some), Some(Some(inner))) => self.encode(*some, inner),
+            _ => self.encode(sem_id, val),
+        }
+    }
+
+    fn encode_items(&mut self, ty: SemId, items: &[StrictVal]) -> Result<Value, TomlError> {
+        let mut array = Vec::with_capacity(items.len());
+        for (no, item) in items.iter().enumerate() {
+            array.push(self.at(Step::Index(no as u32), |c| c.encode(ty, item))?);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn decode(&mut self, sem_id: SemId, value: &Value) -> Result<StrictVal, TomlError> {
+        let sys = self.sys;
+        let ty = get(sys, sem_id);
+        let mismatch = |path: &Path| TomlError::TomlMismatch {
+            path: path.clone(),
+            found: value.to_string(),
+            ty: ty.clone(),
+        };
+
+        Ok(match (repr(sys, ty)?, value) {
+            (Repr::Text(first, rest, sizing), Value::String(s)) => {
+                let len = if ty == &Ty::UnicodeChar { s.chars().count() } else { s.len() };
+                if !fits(sizing, len) || !check_chars(sys, s, first, rest)? {
+                    return Err(mismatch(&self.path));
+                }
+                StrictVal::String(s.clone())
+            }
+            (Repr::Bytes(sizing), Value::String(s)) => match Blob::from_hex(s) {
+                Ok(data) if fits(sizing, data.len()) => StrictVal::Bytes(data),
+                _ => return Err(mismatch(&self.path)),
+            },
+            (Repr::Other(Ty::Primitive(Primitive::UNIT)), Value::Table(table))
+                if table.is_empty() =>
+            {
+                StrictVal::Unit
+            }
+            (Repr::Other(Ty::Primitive(prim)), Value::Integer(_) | Value::String(_)) => {
+                let value = match value {
+                    Value::Integer(n) => *n as i128,
+                    Value::String(s) => s.parse().map_err(|_| mismatch(&self.path))?,
+                    _ => unreachable!(),
+                };
+                self.check_range(*prim, value)?;
+                if prim.info().ty == NumCls::Signed {
+                    StrictVal::num(value as i64)
+                } else {
+                    StrictVal::num(value as u64)
+                }
+            }
+            (Repr::Other(Ty::Enum(variants)), Value::Boolean(b)) if is_bool(variants) => {
+                StrictVal::enumer(vname!(if *b { "true" } else { "false" }))
+            }
+            (Repr::Other(Ty::Enum(variants)), Value::String(s)) => {
+                let variant = VariantName::try_from(s.clone())
+                    .ok()
+                    .and_then(|name| variants.by_name(&name))
+                    .ok_or_else(|| mismatch(&self.path))?;
+                StrictVal::enumer(variant.name.clone())
+            }
+            (Repr::Other(Ty::Union(variants)), Value::Table(table)) if table.len() == 1 => {
+                let (name, inner) = table.iter().next().expect("one item");
+                let (variant, ty) = VariantName::try_from(name.clone())
+                    .ok()
+                    .and_then(|name| variants.by_name(&name))
+                    .ok_or_else(|| mismatch(&self.path))?;
+                StrictVal::union(variant.name.clone(), self.decode(*ty, inner)?)
+            }
+            (Repr::Other(Ty::Tuple(fields)), value) if fields.len() == 1 => {
+                StrictVal::Tuple(vec![self.decode(fields[0], value)?])
+            }
+            (Repr::Other(Ty::Tuple(fields)), Value::Array(array))
+                if fields.len() == array.len() =>
+            {
+                let mut items = Vec::with_capacity(array.len());
+                for (no, (ty, item)) in fields.iter().zip(array).enumerate() {
+                    items.push(self.at(Step::UnnamedField(no as u8), |c| c.decode(*ty, item))?);
+                }
+                StrictVal::Tuple(items)
+            }
+            (Repr::Other(Ty::Struct(fields)), Value::Table(table)) => {
+                if let Some(name) =
+                    table.keys().find(|name| !fields.iter().any(|f| f.name.as_str() == *name))
+                {
+                    return Err(TomlError::UnknownField {
+                        path: self.path.clone(),
+                        field: name.clone(),
+                    });
+                }
+                let mut items = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let field_ty = get(sys, field.ty);
+                    let val = match (table.get(field.name.as_str()), field_ty.as_some()) {
+                        (None, Some(_)) => StrictVal::none(),
+                        (None, None) => {
+                            return Err(TomlError::MissingField {
+                                path: self.path.clone(),
+                                field: field.name.to_string(),
+                            })
+                        }
+                        (Some(value), some) => {
+                            self.at(Step::NamedField(field.name.clone()), |c| match some {
+                                Some(some) => Ok(StrictVal::some(c.decode(*some, value)?)),
+                                None => c.decode(field.ty, value),
+                            })?
+                        }
+                    };
+                    items.insert(field.name.clone(), val);
+                }
+                StrictVal::Struct(items)
+            }
+            (Repr::Other(Ty::Array(ty, len)), Value::Array(array))
+                if array.len() == *len as usize =>
+            {
+                StrictVal::List(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::List(ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                StrictVal::List(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::Set(ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                StrictVal::Set(self.decode_items(*ty, array)?)
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), Value::Table(table))
+                if fits(*sizing, table.len()) && is_table_key(sys, get(sys, *key_ty))? =>
+            {
+                let key_repr = repr(sys, get(sys, *key_ty))?;
+                let mut items = Vec::with_capacity(table.len());
+                for (no, (key, item)) in table.iter().enumerate() {
+                    let key = match key_repr {
+                        Repr::Text(..) => Value::String(key.clone()),
+                        Repr::Other(Ty::Enum(variants)) if is_bool(variants) => {
+                            key.parse().map(Value::Boolean).unwrap_or(Value::String(key.clone()))
+                        }
+                        Repr::Other(Ty::Enum(_)) => Value::String(key.clone()),
+                        _ => key.parse().map(Value::Integer).unwrap_or(Value::String(key.clone())),
+                    };
+                    let (key, item) = self.at(Step::Index(no as u32), |c| {
+                        Ok((c.decode(*key_ty, &key)?, c.decode(*ty, item)?))
+                    })?;
+                    // TOML tables are sorted by the key strings, while strict maps with integer
+                    // and enum keys are sorted by their numeric value
+                    let ord = match (&key_repr, &key) {
+                        (Repr::Other(Ty::Enum(variants)), StrictVal::Enum(EnumTag::Name(name))) => {
+                            variants.tag_by_name(name).unwrap_or_default() as i128
+                        }
+                        (_, StrictVal::Number(StrictNum::Uint(n))) => *n as i128,
+                        (_, StrictVal::Number(StrictNum::Int(n))) => *n as i128,
+                        _ => no as i128,
+                    };
+                    items.push((ord, (key, item)));
+                }
+                items.sort_by_key(|(ord, _)| *ord);
+                StrictVal::Map(items.into_iter().map(|(_, pair)| pair).collect())
+            }
+            (Repr::Other(Ty::Map(key_ty, ty, sizing)), Value::Array(array))
+                if fits(*sizing, array.len()) =>
+            {
+                let mut items = Vec::with_capacity(array.len());
+                for (no, pair) in array.iter().enumerate() {
+                    let pair = self.at(Step::Index(no as u32), |c| match pair {
+                        Value::Array(pair) if pair.len() == 2 => {
+                            Ok((c.decode(*key_ty, &pair[0])?, c.decode(*ty, &pair[1])?))
+                        }
+                        _ => Err(mismatch(&c.path)),
+                    })?;
+                    items.push(pair);
+                }
+                StrictVal::Map(items)
+            }
+            _ => return Err(mismatch(&self.path)),
+        })
+    }
+
+    fn decode_items(&mut self, ty: SemId, array: &[Value]) -> Result<Vec<StrictVal>, TomlError> {
+        let mut items = Vec::with_capacity(array.len());
+        for (no, item) in array.iter().enumerate() {
+            items.push(self.at(Step::Index(no as u32), |c| c.decode(ty, item))?);
+        }
+        Ok(items)
+    }
+}
+
+/// Returns `None` if the value is not an optional value; otherwise returns its content, if any.
+fn as_option(val: &StrictVal) -> Option<Option<&StrictVal>> {
+    let StrictVal::Union(tag, content) = val else {
+        return None;
+    };
+    match tag {
+        EnumTag::Name(name) if name.as_str() == "none" => Some(None),
+        EnumTag::Ord(0) => Some(None),
+        EnumTag::Name(name) if name.as_str() == "some" => Some(Some(content.as_ref())),
+        EnumTag::Ord(1) => Some(Some(content.as_ref())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(dead_code)]
+
+    use amplify::confinement::{TinyBlob, TinyOrdMap, TinyString, TinyVec};
+    use encoding::{StrictDeserialize, StrictSerialize};
+
+    use super::*;
+    use crate::LibBuilder;
+
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Kind {
+        #[strict_type(dumb)]
+        First = 0,
+        Second = 1,
+    }
+
+    // `bool` comes from the `Std` library, which would be a dependency
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Switch {
+        #[strict_type(dumb)]
+        False = 0,
+        True = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib", tags = order)]
+    enum Source {
+        #[strict_type(dumb)]
+        Default,
+        Path(TinyString),
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "TestLib")]
+    struct Config {
+        kind: Kind,
+        enabled: Switch,
+        small: i8,
+        big: u64,
+        name: TinyString,
+        data: TinyBlob,
+        id: [u8; 4],
+        opt: Option<u16>,
+        absent: Option<u16>,
+        source: Source,
+        list: TinyVec<i32>,
+        map: TinyOrdMap<u8, i16>,
+    }
+    impl StrictSerialize for Config {}
+    impl StrictDeserialize for Config {}
+
+    #[test]
+    fn toml_roundtrip() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Config>().compile().unwrap();
+        let sys = SymbolicSys::from_lib(&lib).unwrap();
+
+        let config = Config {
+            kind: Kind::Second,
+            enabled: Switch::True,
+            small: -20,
+            big: u64::MAX,
+            name: TinyString::try_from(s!("name")).unwrap(),
+            data: TinyBlob::try_from(vec![0xCA, 0xFE]).unwrap(),
+            id: [0xFF; 4],
+            opt: Some(500),
+            absent: None,
+            source: Source::Path(TinyString::try_from(s!("/etc")).unwrap()),
+            list: tiny_vec![-1, 24],
+            map: tiny_bmap! { 2 => -300, 10 => 300 },
+        };
+        let data = config.to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+        let val = sys.strict_deserialize_type("TestLib.Config", &data).unwrap().val;
+
+        let toml = lib.to_toml(&val, &tn!("Config")).unwrap();
+        assert!(toml.contains("kind = \"second\"\n"));
+        assert!(toml.contains("enabled = true\n"));
+        assert!(toml.contains("big = \"18446744073709551615\"\n"));
+        assert!(toml.contains("data = \"cafe\"\n"));
+        assert!(toml.contains("opt = 500\n"));
+        assert!(!toml.contains("absent"));
+        let decoded = lib.from_toml(&toml, &tn!("Config")).unwrap();
+        assert_eq!(decoded, val);
+        assert_eq!(lib.to_toml(&decoded, &tn!("Config")).unwrap(), toml);
+    }
+
+    #[test]
+    fn toml_coercion() {
+        let lib = LibBuilder::with("TestLib", None).transpile::<Config>().compile().unwrap();
+        let toml = r#"
+            kind = "first"
+            enabled = false
+            small = 300
+            big = 1
+            name = "name"
+            data = ""
+            id = "00000000"
+            source = { default = {} }
+            list = []
+            map = {}
+        "#;
+        let err = lib.from_toml(toml, &tn!("Config")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "integer 300 at value.small is out of range for I8 (-128..=127)."
+        );
+
+        let val = lib.from_toml(&toml.replace("300", "-128"), &tn!("Config")).unwrap();
+        assert_eq!(val.unwrap_struct("small"), &StrictVal::num(-128i64));
+        assert_eq!(val.unwrap_struct("absent"), &StrictVal::none());
+
+        assert!(matches!(
+            lib.from_toml(&toml.replace("small = 300", "small = 1\nextra = 1"), &tn!("Config")),
+            Err(TomlError::UnknownField { .. })
+        ));
+        assert!(matches!(
+            lib.from_toml(&toml.replace("small = 300", ""), &tn!("Config")),
+            Err(TomlError::MissingField { .. })
+        ));
+    }
+}
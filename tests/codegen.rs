@@ -0,0 +1,121 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Checks that Rust source generated from a type library compiles into types with the same
+//! semantic ids. The generated source is kept in `codegen/record.rs` and must be updated once
+//! the code generation changes.
+
+#[macro_use]
+extern crate amplify;
+#[macro_use]
+extern crate strict_encoding;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use amplify::confinement::{Confined, TinyString, TinyVec};
+use strict_encoding::{StrictDeserialize, StrictDumb, StrictSerialize};
+use strict_types::typelib::CodegenConfig;
+use strict_types::LibBuilder;
+
+const LIB: &str = "Codegen";
+
+mod generated {
+    include!("codegen/record.rs");
+
+    impl strict_encoding::StrictSerialize for Record {}
+    impl strict_encoding::StrictDeserialize for Record {}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB)]
+struct Record {
+    #[strict_type(rename = "type")]
+    kind: u16,
+    #[strict_type(dumb = { Confined::from_iter_checked([0, 1]) })]
+    tags: Confined<BTreeSet<i16>, 2, 8>,
+    #[strict_type(dumb = { Confined::from_iter_checked([(0, 0), (1, 0), (2, 0)]) })]
+    limits: Confined<BTreeMap<u8, u64>, 3, 10>,
+    #[strict_type(dumb = { Confined::from_checked(s!("?")) })]
+    name: Confined<String, 1, 16>,
+    fee: Option<u64>,
+    choice: Choice,
+    level: Level,
+}
+impl StrictSerialize for Record {}
+impl StrictDeserialize for Record {}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB, tags = order, dumb = Self::Pair(strict_dumb!(), strict_dumb!()))]
+enum Choice {
+    Pair(u8, TinyVec<u16>),
+    Named { value: u32, kind: u8 },
+    Nothing,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB, tags = repr, into_u8, try_from_u8)]
+#[repr(u8)]
+enum Level {
+    #[strict_type(dumb)]
+    Low = 1,
+    High = 2,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB)]
+struct Names {
+    #[strict_type(dumb = { Confined::from_iter_checked([tiny_s!("a"), tiny_s!("b")]) })]
+    names: Confined<BTreeSet<TinyString>, 2, 4>,
+}
+
+#[test]
+fn generated_source() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Record>().compile().unwrap();
+    let src = lib.to_rust_source(&CodegenConfig::default());
+    assert_eq!(src, include_str!("codegen/record.rs"), "generated source must be updated");
+
+    let generated =
+        LibBuilder::with(libname!(LIB), None).transpile::<generated::Record>().compile().unwrap();
+    assert_eq!(generated.id(), lib.id());
+
+    let data = Record::strict_dumb().to_strict_serialized::<{ u16::MAX as usize }>().unwrap();
+    let record =
+        generated::Record::from_strict_serialized::<{ u16::MAX as usize }>(data.clone()).unwrap();
+    assert_eq!(record.to_strict_serialized::<{ u16::MAX as usize }>().unwrap(), data);
+    let dumb = generated::Record::strict_dumb();
+    assert_eq!(dumb.to_strict_serialized::<{ u16::MAX as usize }>().unwrap(), data);
+}
+
+#[test]
+fn missing_dumb_value() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Names>().compile().unwrap();
+    let src = lib.to_rust_source(&CodegenConfig::default());
+    assert!(src.contains(
+        "#[strict_type(dumb = { compile_error!(\"provide a dumb value for a collection of at \
+         least 2 unique items\") })]\n    pub names:"
+    ));
+}
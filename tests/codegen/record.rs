@@ -0,0 +1,45 @@
+// Generated from strict type library stl:D_MLG8oT-oeq_wJ7-2SfhvPR-JQZz0Hm-n61hwx1-cLAHP5E#master-wisdom-vision.
+
+use amplify::confinement::{Confined, ConfinedOrdMap, ConfinedOrdSet, ConfinedString, TinyVec};
+use strict_encoding::{StrictDecode, StrictDumb, StrictEncode, StrictType, strict_dumb};
+
+pub const LIB_NAME_CODEGEN: &str = "Codegen";
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_CODEGEN, tags = custom, dumb = Self::Pair(strict_dumb!(), strict_dumb!()))]
+pub enum Choice {
+    #[strict_type(tag = 0)]
+    Pair(u8, TinyVec<u16>),
+    #[strict_type(tag = 1)]
+    Named { value: u32, kind: u8 },
+    #[strict_type(tag = 2)]
+    Nothing,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_CODEGEN, tags = repr, into_u8, try_from_u8)]
+#[repr(u8)]
+pub enum Level {
+    #[strict_type(dumb)]
+    Low = 1,
+    High = 2,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_CODEGEN)]
+pub struct Record {
+    pub type_: u16,
+    #[strict_type(dumb = { Confined::from_iter_checked(0..=1) })]
+    pub tags: ConfinedOrdSet<i16, 2, 8>,
+    #[strict_type(dumb = { Confined::from_iter_checked((0..=2).map(|key| (key, strict_dumb!()))) })]
+    pub limits: ConfinedOrdMap<u8, u64, 3, 10>,
+    #[strict_type(dumb = { Confined::from_checked("?".to_owned()) })]
+    pub name: ConfinedString<1, 16>,
+    pub fee: Option<u64>,
+    pub choice: Choice,
+    pub level: Level,
+}
+
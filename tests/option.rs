@@ -0,0 +1,147 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+#[macro_use]
+extern crate strict_encoding;
+
+use strict_encoding::bitcoin_types::ScriptBuf;
+use strict_encoding::StrictSerialize;
+use strict_types::stl::bitcoin_stl;
+use strict_types::typesys::{NestedCase, TypeFqn};
+use strict_types::{CompileError, LibBuilder, LibRef, SymbolicSys, SystemBuilder, Ty};
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = "Test")]
+struct MaybeByte(Option<u8>);
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = "Test")]
+struct Payment {
+    script: Option<ScriptBuf>,
+    nested: Option<MaybeByte>,
+}
+
+impl StrictSerialize for Payment {}
+
+fn sys() -> SymbolicSys {
+    let bitcoin = bitcoin_stl();
+    let lib = LibBuilder::with(libname!("Test"), None)
+        .with_dependency(&bitcoin)
+        .transpile::<Payment>()
+        .compile()
+        .unwrap();
+    SystemBuilder::new().import(lib).unwrap().import(bitcoin).unwrap().finalize().unwrap()
+}
+
+#[test]
+fn transpile() {
+    let lib = LibBuilder::with(libname!("Test"), None)
+        .with_dependency(&bitcoin_stl())
+        .transpile::<Payment>()
+        .compile()
+        .unwrap();
+    let Ty::Struct(fields) = &lib.types[&tn!("Payment")] else {
+        panic!("`Payment` must be a structure");
+    };
+    for field in fields.iter() {
+        let LibRef::Inline(ty) = &field.ty else {
+            panic!("optional field must be an inline type");
+        };
+        let Ty::Union(variants) = ty.as_ref() else {
+            panic!("optional field must be a union");
+        };
+        let variants = variants.keys().map(|v| (v.tag, v.name.as_str())).collect::<Vec<_>>();
+        assert_eq!(variants, vec![(0, "none"), (1, "some")]);
+        assert!(ty.is_option());
+    }
+}
+
+#[test]
+fn transpile_nested() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Test")]
+    struct Nested {
+        nested: Option<Option<u8>>,
+    }
+
+    // unnamed types can't be nested that deep, so the inner option must be a named type
+    let err = LibBuilder::with(libname!("Test"), None).transpile::<Nested>().compile().unwrap_err();
+    assert!(matches!(err, CompileError::NestedInline(name, ..) if name == tn!("Nested")));
+}
+
+#[test]
+fn layout() {
+    let sys = sys();
+    let tree = sys.type_tree("Test.Payment").unwrap();
+    let nested = tree.iter().map(|info| info.nested.to_vec()).collect::<Vec<_>>();
+    // the script is an optional byte string wrapped into a few newtypes
+    assert_eq!(nested[1].first(), Some(&NestedCase::Option));
+    assert_eq!(nested[1].last(), Some(&NestedCase::ByteStr));
+    // each optional level is kept, so the tags of both levels are present in the layout
+    let options = nested[2].iter().filter(|case| **case == NestedCase::Option).count();
+    assert_eq!(options, 2);
+    assert!(nested[2].contains(&NestedCase::NewType(Some(TypeFqn::from("Test.MaybeByte")))));
+
+    // `None` for both fields takes just the tag bytes
+    let (min, _) = tree.to_layout().size_range();
+    assert_eq!(min, 2);
+}
+
+#[test]
+fn nested_option() {
+    let sys = sys();
+    let layout = sys.type_tree("Test.Payment").unwrap().to_layout();
+
+    let cases =
+        [(None, vec![0u8, 0]), (Some(None), vec![0, 1, 0]), (Some(Some(5)), vec![0, 1, 1, 5])];
+    let mut values = vec![];
+    for (nested, data) in cases {
+        let payment = Payment {
+            script: None,
+            nested: nested.map(MaybeByte),
+        };
+        let encoded = payment.to_strict_serialized::<0xFF>().unwrap().release();
+        assert_eq!(encoded, data);
+        layout.validate_reader(&mut data.as_slice()).unwrap();
+
+        let val = sys.strict_deserialize_type("Test.Payment", &data).unwrap();
+        let reencoded = sys.as_types().strict_serialize_value::<0xFF>(&val).unwrap();
+        assert_eq!(reencoded.release(), data);
+        values.push(val.unbox());
+    }
+    assert_ne!(values[0], values[1]);
+    assert_ne!(values[1], values[2]);
+
+    assert!(layout.validate_reader(&mut &[0u8, 2][..]).is_err());
+    assert!(layout.validate_reader(&mut &[0u8, 1, 2][..]).is_err());
+
+    let script = Payment {
+        script: Some(ScriptBuf::from_bytes(vec![0x51])),
+        nested: None,
+    };
+    let data = script.to_strict_serialized::<0xFF>().unwrap().release();
+    layout.validate_reader(&mut data.as_slice()).unwrap();
+}
@@ -25,8 +25,9 @@
 extern crate strict_encoding;
 
 use armor::AsciiArmor;
-use strict_encoding::STRICT_TYPES_LIB;
+use strict_encoding::{DeserializeError, STRICT_TYPES_LIB};
 use strict_types::stl::std_stl;
+use strict_types::typelib::{ArmorError, Base64Error};
 use strict_types::typesys::SystemBuilder;
 use strict_types::{LibBuilder, SymbolicSys, TypeLib};
 
@@ -60,6 +61,55 @@ fn library() {
     assert_eq!(lib, lib2);
 }
 
+#[test]
+fn armored() {
+    let lib = lib();
+    let s = lib.to_armored();
+    assert!(s.starts_with("-----BEGIN STRICT TYPE LIB-----\nId: stl:"));
+    assert_eq!(TypeLib::from_armored(&s).unwrap(), lib);
+
+    let std = std_stl();
+    let other = std.to_armored();
+    let id_line = |s: &str| s.lines().nth(1).unwrap().to_owned();
+    let wrong_id = s.replace(&id_line(&s), &id_line(&other));
+    assert!(matches!(TypeLib::from_armored(&wrong_id), Err(ArmorError::IdMismatch { .. })));
+
+    let checksum = s.lines().find(|line| line.starts_with("Check-SHA256:")).unwrap();
+    let other_checksum = other.lines().find(|line| line.starts_with("Check-SHA256:")).unwrap();
+    let wrong_checksum = s.replace(checksum, other_checksum);
+    assert!(matches!(TypeLib::from_armored(&wrong_checksum), Err(ArmorError::ChecksumMismatch)));
+
+    let no_checksum = s.replace(&format!("{checksum}\n"), "");
+    assert!(matches!(TypeLib::from_armored(&no_checksum), Err(ArmorError::NoChecksum)));
+}
+
+#[test]
+fn binary_file() {
+    let lib = lib();
+    let path = std::env::temp_dir().join(format!("strict-types-{}.stl", std::process::id()));
+    lib.serialize_to_file(&path).unwrap();
+    let data = std::fs::read(&path).unwrap();
+    assert_eq!(TypeLib::deserialize_from_file(&path).unwrap(), lib);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(TypeLib::from_bytes(&data).unwrap(), lib);
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert_eq!(TypeLib::from_bytes(&trailing), Err(DeserializeError::DataNotEntirelyConsumed));
+    assert!(TypeLib::from_bytes(&data[..data.len() - 1]).is_err());
+}
+
+#[test]
+fn base64() {
+    let lib = lib();
+    let s = lib.to_base64();
+    assert!(s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(TypeLib::from_base64(&s).unwrap(), lib);
+
+    assert!(matches!(TypeLib::from_base64(&format!("{s}=")), Err(Base64Error::Base64(_))));
+    assert!(matches!(TypeLib::from_base64(&s[..s.len() - 4]), Err(Base64Error::Decode(_))));
+}
+
 #[test]
 fn symbols() {
     let sys = sys();
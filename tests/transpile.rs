@@ -0,0 +1,1050 @@
+// Strict encoding schema library, implementing validation and parsing of strict encoded data
+// against a schema.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@ubideco.org>
+//
+// Copyright (C) 2022-2025 Laboratories for Ubiquitous Deterministic Computing (UBIDECO),
+//                         Institute for Distributed and Cognitive Systems (InDCS), Switzerland.
+// Copyright (C) 2022-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+#[macro_use]
+extern crate strict_types;
+
+use std::cell::Cell;
+use std::io;
+use std::marker::PhantomData;
+
+use amplify::confinement::{Confined, NonEmptyVec, TinyOrdMap, TinyVec};
+use strict_encoding::stl::AlphaLodash;
+use strict_encoding::{
+    Ident, Sizing, StrictDecode, StrictDeserialize, StrictDumb, StrictEncode, StrictEnum,
+    StrictProduct, StrictSerialize, StrictSum, StrictTuple, StrictType, TypeName, TypedWrite,
+    VariantError, LIB_NAME_STD,
+};
+use strict_types::stl::{std_stl, strict_types_stl};
+use strict_types::typelib::{common_types, RenameError, TranspileErrors};
+use strict_types::{
+    CompileError, LibBuilder, LibRef, Padding, SemId, StrictVal, SystemBuilder, TranspileError,
+    TranspileRef, Ty,
+};
+
+const LIB: &str = "Test";
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct Wrapper<T: StrictDumb + StrictEncode + StrictDecode>(T);
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct Pair {
+    pub first: Wrapper<u8>,
+    pub second: Wrapper<u16>,
+}
+
+#[test]
+fn transpile_named() {
+    let lib = LibBuilder::with(libname!(LIB), None)
+        .transpile_named::<Wrapper<u8>>(tn!("WrapperU8"))
+        .transpile_named::<Wrapper<u16>>(tn!("WrapperU16"))
+        .transpile_named::<Wrapper<Wrapper<u8>>>(tn!("Double"))
+        .compile_symbols()
+        .unwrap();
+
+    assert_eq!(lib.types().len(), 4);
+    assert_eq!(lib.types().get(&tn!("WrapperU8")).unwrap().to_string(), "U8");
+    assert_eq!(lib.types().get(&tn!("WrapperU16")).unwrap().to_string(), "U16");
+
+    // nested type keeps the name derived by `StrictType`
+    let Some(Ty::Tuple(fields)) = lib.types().get(&tn!("Double")) else {
+        panic!("`Double` must be a newtype");
+    };
+    let Some(TranspileRef::Named(inner)) = fields.ty_by_pos(0) else {
+        panic!("`Double` must wrap a named type");
+    };
+    assert_ne!(inner, &tn!("WrapperU8"));
+    assert_eq!(lib.types().get(inner).unwrap().to_string(), "U8");
+}
+
+/// Generic type with the same strict name for all its instantiations.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Tagged<T>(T);
+
+impl<T: StrictDumb> StrictDumb for Tagged<T> {
+    fn strict_dumb() -> Self { Tagged(T::strict_dumb()) }
+}
+impl<T> StrictType for Tagged<T> {
+    const STRICT_LIB_NAME: &'static str = LIB;
+    fn strict_name() -> Option<TypeName> { Some(tn!("Tagged")) }
+}
+impl<T: StrictDumb> StrictProduct for Tagged<T> {}
+impl<T: StrictDumb> StrictTuple for Tagged<T> {
+    const FIELD_COUNT: u8 = 1;
+}
+impl<T: StrictEncode + StrictDumb> StrictEncode for Tagged<T> {
+    fn strict_encode<W: TypedWrite>(&self, writer: W) -> io::Result<W> {
+        writer.write_newtype::<Self>(&self.0)
+    }
+}
+
+#[test]
+fn transpile_generic_instances() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Tags {
+        first: Tagged<u8>,
+        second: Tagged<u16>,
+        third: Tagged<u8>,
+        nested: Tagged<Tagged<u8>>,
+    }
+
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Tags>().compile_symbols().unwrap();
+    let types = lib.types();
+    assert_eq!(types.len(), 4);
+    assert_eq!(types.get(&tn!("Tagged")).unwrap().to_string(), "U8");
+    assert_eq!(types.get(&tn!("TaggedU16")).unwrap().to_string(), "U16");
+    assert_eq!(types.get(&tn!("TaggedTagged")).unwrap().to_string(), "Tagged");
+    let Some(Ty::Struct(fields)) = types.get(&tn!("Tags")) else {
+        panic!("`Tags` must be a structure");
+    };
+    let refs = fields.iter().map(|field| field.ty.to_string()).collect::<Vec<_>>();
+    assert_eq!(refs, ["Tagged", "TaggedU16", "Tagged", "TaggedTagged"]);
+
+    // explicit names take precedence over the derived ones
+    let lib = LibBuilder::with(libname!(LIB), None)
+        .transpile_named::<Tagged<u16>>(tn!("Short"))
+        .transpile::<Tags>()
+        .compile_symbols()
+        .unwrap();
+    let types = lib.types();
+    assert_eq!(types.len(), 5);
+    assert_eq!(types.get(&tn!("Short")).unwrap().to_string(), "U16");
+    assert_eq!(types.get(&tn!("TaggedU16")).unwrap().to_string(), "U16");
+
+    // the derived name is taken by other type
+    let err = LibBuilder::with(libname!(LIB), None)
+        .transpile_named::<u32>(tn!("TaggedU16"))
+        .transpile::<Tags>()
+        .compile_symbols()
+        .unwrap_err();
+    assert_eq!(err, TranspileError::GenericCollision(tn!("Tagged")));
+}
+
+#[test]
+fn transpile_named_alias() {
+    let lib = LibBuilder::with(libname!(LIB), None)
+        .transpile_named::<u64>(tn!("Sats"))
+        .compile()
+        .unwrap();
+    assert_eq!(lib.types.get(&tn!("Sats")), Some(&Ty::U64));
+    assert_ne!(lib.type_id(&tn!("Sats")), Some(Ty::<SemId>::U64.sem_id_unnamed()));
+
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    let layout = sys.type_tree("Test.Sats").unwrap().to_layout();
+    assert_eq!(layout.size_range(), (8, Some(8)));
+    let trace = layout.explain(&0x0102u64.to_le_bytes()).unwrap();
+    assert_eq!(trace[0].info.ty, Ty::U64);
+    assert_eq!(trace[0].info.fqn.as_ref().unwrap().to_string(), "Test.Sats");
+}
+
+#[test]
+fn rename_type() {
+    let original =
+        LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile_symbols().unwrap();
+    let first_field = |lib: &strict_types::SymbolicLib| {
+        let Some(Ty::Struct(fields)) = lib.types().get(&tn!("Pair")) else {
+            panic!("`Pair` must be a struct");
+        };
+        let Some(TranspileRef::Named(name)) = fields.ty_by_name(&fname!("first")) else {
+            panic!("`Pair.first` must be a named type");
+        };
+        name.clone()
+    };
+    let wrapper = first_field(&original);
+
+    let mut lib = original.clone();
+    lib.rename_type(&wrapper, tn!("First")).unwrap();
+    assert_eq!(first_field(&lib), tn!("First"));
+    assert!(!lib.types().contains_key(&wrapper));
+    assert_eq!(lib.types().get(&tn!("First")), original.types().get(&wrapper));
+
+    assert_eq!(
+        lib.rename_type(&tn!("First"), tn!("Pair")),
+        Err(RenameError::DuplicateName(tn!("Pair")))
+    );
+    assert_eq!(lib.rename_type(&wrapper, tn!("Other")), Err(RenameError::UnknownType(wrapper)));
+
+    assert_ne!(lib.compile().unwrap().id(), original.compile().unwrap().id());
+}
+
+#[test]
+fn rename_compiled_type() {
+    let original = LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile().unwrap();
+    let wrapper = tn!("Wrapperu8");
+    let old_id = original.type_id(&wrapper).unwrap();
+    let old_pair = original.type_id(&tn!("Pair")).unwrap();
+
+    let lib = original.clone().rename_type(&wrapper, tn!("First")).unwrap();
+    assert!(lib.type_id(&wrapper).is_none());
+    assert_ne!(lib.type_id(&tn!("First")).unwrap(), old_id);
+    assert_ne!(lib.type_id(&tn!("Pair")).unwrap(), old_pair);
+    assert_ne!(lib.id(), original.id());
+    assert_eq!(lib.clone().rename_type(&tn!("First"), wrapper.clone()).unwrap(), original);
+
+    let sys = SystemBuilder::new().import(lib.clone()).unwrap().finalize().unwrap();
+    assert!(sys.type_tree("Test.First").is_some());
+
+    assert_eq!(
+        lib.clone().rename_type(&tn!("First"), tn!("Pair")),
+        Err(RenameError::DuplicateName(tn!("Pair")))
+    );
+    assert_eq!(lib.rename_type(&wrapper, tn!("Other")), Err(RenameError::UnknownType(wrapper)));
+
+    let mut broken = original;
+    broken.types.remove(&tn!("Wrapperu16")).unwrap();
+    assert!(matches!(
+        broken.rename_type(&tn!("Pair"), tn!("Other")),
+        Err(RenameError::Inconsistent(_))
+    ));
+}
+
+#[test]
+fn find_references() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Registry {
+        main: Pair,
+        pairs: TinyVec<Pair>,
+        index: TinyOrdMap<u8, (Pair, u8)>,
+    }
+
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Registry>().compile().unwrap();
+    let refs = lib
+        .find_references_to(&tn!("Pair"))
+        .unwrap()
+        .into_iter()
+        .map(|(name, path)| (name.to_string(), path.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(refs, [
+        ("Registry".to_owned(), "main".to_owned()),
+        ("Registry".to_owned(), "pairs.item".to_owned()),
+        ("Registry".to_owned(), "index.value.0".to_owned()),
+    ]);
+
+    let refs = lib.find_references_to(&tn!("Wrapperu8")).unwrap();
+    assert_eq!(refs.len(), 1);
+    assert_eq!(refs[0].0, tn!("Pair"));
+    assert_eq!(refs[0].1.to_string(), "first");
+
+    assert!(lib.find_references_to(&tn!("Registry")).unwrap().is_empty());
+    assert!(lib.find_references_to(&tn!("Absent")).unwrap().is_empty());
+
+    let mut broken = lib;
+    broken.types.remove(&tn!("Pair")).unwrap();
+    assert!(broken.find_references_to(&tn!("Wrapperu8")).is_err());
+}
+
+#[test]
+fn common_types_groups() {
+    let pair = LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile().unwrap();
+    let wrapper = LibBuilder::with(libname!(LIB), None)
+        .transpile::<Wrapper<u16>>()
+        .transpile::<Wrapper<u32>>()
+        .compile()
+        .unwrap();
+
+    let common = common_types(&[&pair, &wrapper]);
+    assert_eq!(common.len(), 1);
+    let (id, group) = common.first_key_value().unwrap();
+    assert_eq!(*id, pair.type_id(&tn!("Wrapperu16")).unwrap());
+    assert_eq!(group, &[(libname!(LIB), tn!("Wrapperu16")), (libname!(LIB), tn!("Wrapperu16"))]);
+
+    assert!(common_types(&[&pair]).is_empty());
+    assert!(common_types(&[]).is_empty());
+}
+
+// `StrictDumb` derive requires all fields, including the skipped ones, to implement it
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB)]
+pub struct Cached {
+    pub value: u16,
+    #[strict_type(skip)]
+    pub cache: Cell<Option<u32>>,
+    #[strict_type(skip)]
+    pub marker: PhantomData<u8>,
+}
+impl StrictDumb for Cached {
+    fn strict_dumb() -> Self { Cached::default() }
+}
+impl StrictSerialize for Cached {}
+impl StrictDeserialize for Cached {}
+
+#[test]
+fn transpile_skipped() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Cached>().compile().unwrap();
+    let Some(Ty::Struct(fields)) = lib.types.get(&tn!("Cached")) else {
+        panic!("`Cached` must be a struct");
+    };
+    assert_eq!(fields.len(), 1);
+    assert!(fields.ty_by_name(&fname!("value")).is_some());
+
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    let layout = sys.type_tree("Test.Cached").unwrap().to_layout();
+    assert_eq!(layout.size_range(), (2, Some(2)));
+    let offsets = layout.field_offsets().unwrap();
+    assert_eq!(offsets.len(), 1);
+    let (path, location) = offsets.into_iter().next().unwrap();
+    assert_eq!(path.to_string(), "value");
+    assert_eq!(location, (0, 2));
+
+    let cached = Cached {
+        value: 0x0102,
+        cache: Cell::new(Some(7)),
+        marker: PhantomData,
+    };
+    let data = cached.to_strict_serialized::<16>().unwrap();
+    assert_eq!(data.as_slice(), &[0x02, 0x01]);
+    let val = sys.strict_deserialize_type("Test.Cached", &data).unwrap().unbox();
+    assert_eq!(val, StrictVal::struc([("value", StrictVal::num(0x0102u16))]));
+    let decoded = Cached::from_strict_serialized::<16>(data).unwrap();
+    assert_eq!(decoded.value, 0x0102);
+    assert_eq!(decoded.cache.get(), None);
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct Wide(u8, u16, u32, u64, i8, i16, i32, (u8, (u16, u8)));
+
+impl StrictSerialize for Wide {}
+
+#[test]
+fn transpile_wide_tuple() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Wide>().compile().unwrap();
+    let Some(Ty::Tuple(fields)) = lib.types.get(&tn!("Wide")) else {
+        panic!("`Wide` must be a tuple");
+    };
+    assert_eq!(fields.len(), 8);
+
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    let layout = sys.type_tree("Test.Wide").unwrap().to_layout();
+    assert_eq!(layout.size_range(), (26, Some(26)));
+    let offsets = layout.field_offsets().unwrap();
+    let paths = offsets.keys().map(|path| path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, ["0", "1", "2", "3", "4", "5", "6", "7.0", "7.1.0", "7.1.1"]);
+    assert_eq!(offsets.values().last(), Some(&(25, 1)));
+
+    let wide = Wide(1, 2, 3, 4, 5, 6, 7, (8, (9, 10)));
+    let data = wide.to_strict_serialized::<64>().unwrap();
+    layout.validate_reader(&mut data.as_slice()).unwrap();
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct CHeader {
+    pub kind: u8,
+    pub reserved: Padding<3>,
+    pub len: u32,
+    pub flags: u16,
+    pub tail: Padding<2>,
+}
+
+impl StrictSerialize for CHeader {}
+impl StrictDeserialize for CHeader {}
+
+#[test]
+fn transpile_padding() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<CHeader>().compile().unwrap();
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    let layout = sys.type_tree("Test.CHeader").unwrap().to_layout();
+    assert_eq!(layout.size_range(), (12, Some(12)));
+    let offsets = layout.field_offsets().unwrap();
+    assert_eq!(offsets.iter().find(|(path, _)| path.to_string() == "len").unwrap().1, &(4, 4));
+    assert_eq!(offsets.iter().find(|(path, _)| path.to_string() == "tail").unwrap().1, &(10, 2));
+
+    let header = CHeader {
+        kind: 1,
+        reserved: Padding,
+        len: 0x0504_0302,
+        flags: 0x0706,
+        tail: Padding,
+    };
+    let data = header.to_strict_serialized::<16>().unwrap();
+    assert_eq!(data.as_slice(), &[1, 0, 0, 0, 2, 3, 4, 5, 6, 7, 0, 0]);
+    layout.validate_reader(&mut data.as_slice()).unwrap();
+
+    let dirty = [1u8, 0xAA, 0xBB, 0xCC, 2, 3, 4, 5, 6, 7, 0xDD, 0xEE];
+    let decoded =
+        CHeader::from_strict_serialized::<16>(Confined::from_checked(dirty.to_vec())).unwrap();
+    assert_eq!(decoded, header);
+}
+
+#[test]
+fn transpile_bulk() {
+    let chained = LibBuilder::with(libname!(LIB), None)
+        .transpile::<Pair>()
+        .transpile::<Wrapper<u32>>()
+        .compile()
+        .unwrap();
+
+    let builder = LibBuilder::with(libname!(LIB), None);
+    let lib = transpile_all!(builder, [Pair, Wrapper<u32>]).compile().unwrap();
+    assert_eq!(lib, chained);
+
+    let registrars: Vec<&dyn Fn(LibBuilder) -> LibBuilder> =
+        vec![&|b| b.transpile::<Pair>(), &|b| b.transpile::<Wrapper<u32>>()];
+    let lib = LibBuilder::with(libname!(LIB), None).transpile_dyn(&registrars).compile().unwrap();
+    assert_eq!(lib, chained);
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct WideFields {
+    #[strict_type(rename = "_0")]
+    _0: u8,
+    #[strict_type(rename = "_1")]
+    _1: u16,
+    #[strict_type(rename = "_2")]
+    _2: u32,
+    #[strict_type(rename = "_3")]
+    _3: u64,
+    #[strict_type(rename = "_4")]
+    _4: i8,
+    #[strict_type(rename = "_5")]
+    _5: i16,
+    #[strict_type(rename = "_6")]
+    _6: i32,
+    #[strict_type(rename = "_7")]
+    _7: (u8, (u16, u8)),
+}
+
+impl StrictSerialize for WideFields {}
+
+#[test]
+fn transpile_tuple_as_struct() {
+    let lib = LibBuilder::with(libname!(LIB), None)
+        .transpile::<Wide>()
+        .transpile::<WideFields>()
+        .compile()
+        .unwrap();
+    let Some(Ty::Struct(fields)) = lib.types.get(&tn!("WideFields")) else {
+        panic!("`WideFields` must be a structure");
+    };
+    let names = fields.iter().map(|field| field.name.to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["_0", "_1", "_2", "_3", "_4", "_5", "_6", "_7"]);
+
+    let sys = SystemBuilder::new().import(lib).unwrap().finalize().unwrap();
+    let tuple = sys.type_tree("Test.Wide").unwrap().to_layout();
+    let fields = sys.type_tree("Test.WideFields").unwrap().to_layout();
+    assert_eq!(tuple.size_range(), fields.size_range());
+    let tuple_offsets = tuple.field_offsets().unwrap();
+    let field_offsets = fields.field_offsets().unwrap();
+    let paths = field_offsets.keys().map(|path| path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, ["_0", "_1", "_2", "_3", "_4", "_5", "_6", "_7.0", "_7.1.0", "_7.1.1"]);
+    assert!(tuple_offsets.values().eq(field_offsets.values()));
+
+    let data = Wide(1, 2, 3, 4, 5, 6, 7, (8, (9, 10))).to_strict_serialized::<64>().unwrap();
+    let wide = WideFields {
+        _0: 1,
+        _1: 2,
+        _2: 3,
+        _3: 4,
+        _4: 5,
+        _5: 6,
+        _6: 7,
+        _7: (8, (9, 10)),
+    };
+    assert_eq!(wide.to_strict_serialized::<64>().unwrap(), data);
+    fields.validate_reader(&mut data.as_slice()).unwrap();
+    assert_ne!(sys.resolve("Test.Wide"), sys.resolve("Test.WideFields"));
+}
+
+#[test]
+fn extend() {
+    let mut lib =
+        LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile_symbols().unwrap();
+    let count = lib.types().len();
+    // all types are already present
+    lib.extend::<Wrapper<u8>>().unwrap();
+    assert_eq!(lib.types().len(), count);
+
+    lib.extend::<Cached>().unwrap();
+    assert_eq!(lib.types().len(), count + 1);
+    let expected = LibBuilder::with(libname!(LIB), None)
+        .transpile::<Pair>()
+        .transpile::<Cached>()
+        .compile()
+        .unwrap();
+    assert_eq!(lib.clone().compile().unwrap(), expected);
+
+    assert_eq!(
+        lib.extend_types([(tn!("Pair"), Ty::U8)]),
+        Err(TranspileError::DuplicateName(tn!("Pair")))
+    );
+    let err = lib
+        .extend_types([(tn!("Other"), Ty::List(TranspileRef::Named(tn!("Unknown")), Sizing::U8))])
+        .unwrap_err();
+    assert!(matches!(err, TranspileError::UnknownType { .. }));
+    assert_eq!(lib.types().len(), count + 1);
+}
+
+#[test]
+fn known_types() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Named {
+        ident: Ident,
+        wrapped: Wrapper<u8>,
+    }
+
+    let builder = LibBuilder::with(libname!(LIB), None).with_dependency(&strict_types_stl());
+    assert_eq!(builder.known_types().count(), 0);
+
+    let builder = builder.transpile::<Named>();
+    let mut known = builder.known_types().cloned().collect::<Vec<_>>();
+    known.sort();
+    assert_eq!(known, vec![tn!("Named"), tn!("Wrapperu8")]);
+    assert!(builder.contains(&tn!("Named")));
+    assert!(builder.contains(&tn!("Wrapperu8")));
+    // types provided by dependencies are not a part of the library
+    assert!(!builder.contains(&tn!("Ident")));
+}
+
+#[test]
+fn transpile_value() {
+    let plain = LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile().unwrap();
+    let value = Pair {
+        first: Wrapper(0xA5),
+        second: Wrapper(0x0102),
+    };
+    let (lib, constants) = LibBuilder::with(libname!(LIB), None)
+        .transpile_value(Ident::from("Magic"), value.clone())
+        .compile_with_constants()
+        .unwrap();
+
+    assert_eq!(lib, plain);
+    assert!(constants.is_for(&lib));
+    assert_eq!(constants.constant("Magic"), Some(&[0xA5, 0x02, 0x01][..]));
+    assert_eq!(constants.constant("Other"), None);
+
+    let err = LibBuilder::with(libname!(LIB), None)
+        .transpile_value(Ident::from("Magic"), value.clone())
+        .transpile_value(Ident::from("Magic"), value)
+        .compile_symbols()
+        .unwrap_err();
+    assert_eq!(err, TranspileError::DuplicateConstant(Ident::from("Magic")));
+
+    let err = LibBuilder::with(libname!(LIB), None)
+        .transpile_value(Ident::from("Magic"), Pair {
+            first: Wrapper(0xA5),
+            second: Wrapper(0x0102),
+        })
+        .compile()
+        .unwrap_err();
+    assert_eq!(err, CompileError::ConstantsDiscarded);
+}
+
+#[test]
+fn transpile_named_duplicate() {
+    let err = LibBuilder::with(libname!(LIB), None)
+        .transpile::<Pair>()
+        .transpile_named::<Wrapper<u16>>(tn!("Pair"))
+        .compile_symbols()
+        .unwrap_err();
+    assert_eq!(err, TranspileError::DuplicateName(tn!("Pair")));
+}
+
+#[test]
+fn duplicate_field() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Renamed {
+        #[strict_type(rename = "value")]
+        first: u8,
+        #[strict_type(rename = "value")]
+        second: u16,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB, tags = order)]
+    enum Payload {
+        #[strict_type(dumb)]
+        Empty,
+        Data {
+            #[strict_type(rename = "value")]
+            first: u8,
+            #[strict_type(rename = "value")]
+            second: u16,
+        },
+    }
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Renamed>().compile_symbols();
+    assert_eq!(err.unwrap_err(), TranspileError::DuplicateField {
+        ty: "Renamed".to_owned(),
+        field: fname!("value")
+    });
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Payload>().try_compile_symbols();
+    let TranspileError::Multiple(errors) = err.unwrap_err() else {
+        panic!("all errors must be reported");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0], TranspileError::DuplicateField {
+        ty: "<unnamed>".to_owned(),
+        field: fname!("value")
+    });
+}
+
+#[test]
+fn multiple_errors() {
+    let errors = TranspileErrors::from(
+        NonEmptyVec::try_from(vec![
+            TranspileError::DuplicateName(tn!("Pair")),
+            TranspileError::UnknownLib(libname!("Absent")),
+        ])
+        .unwrap(),
+    );
+    let err = TranspileError::Multiple(errors);
+    assert_eq!(
+        err.to_string(),
+        "multiple errors found in the library:\n- type with name `Pair` is already present in the \
+         library.\n- unknown library `Absent` absent from dependencies."
+    );
+    assert_eq!(CompileError::from(err), CompileError::DuplicateName(tn!("Pair")));
+}
+
+#[test]
+#[allow(unreachable_patterns)] // derived decoding matches on variant names
+fn duplicate_variant() {
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB, tags = repr, into_u8, try_from_u8)]
+    #[repr(u8)]
+    enum Kind {
+        #[strict_type(rename = "same", dumb)]
+        First = 0,
+        #[strict_type(rename = "same")]
+        Second = 1,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB, tags = order)]
+    enum Either {
+        #[strict_type(rename = "same", dumb)]
+        First,
+        #[strict_type(rename = "same")]
+        Second(u8),
+    }
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Kind>().compile_symbols();
+    assert_eq!(err.unwrap_err(), TranspileError::DuplicateVariant {
+        ty: "Kind".to_owned(),
+        variant: vname!("same")
+    });
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Either>().compile();
+    assert_eq!(err.unwrap_err(), CompileError::DuplicateVariant {
+        ty: "Either".to_owned(),
+        variant: vname!("same")
+    });
+}
+
+#[test]
+fn rename_keeps_ids() {
+    mod before {
+        use super::*;
+
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = LIB, tags = repr, into_u8, try_from_u8)]
+        #[repr(u8)]
+        pub enum Kind {
+            #[strict_type(dumb)]
+            Plain = 0,
+            Special = 1,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = LIB)]
+        pub struct Record {
+            pub amount: u64,
+            pub kind: Kind,
+        }
+    }
+
+    mod after {
+        use super::*;
+
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = LIB, tags = repr, into_u8, try_from_u8, rename = "Kind")]
+        #[repr(u8)]
+        pub enum Category {
+            #[strict_type(dumb)]
+            Plain = 0,
+            #[strict_type(rename = "special")]
+            Extraordinary = 1,
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+        #[strict_type(lib = LIB, rename = "Record")]
+        pub struct Entry {
+            #[strict_type(rename = "amount")]
+            pub value: u64,
+            #[strict_type(rename = "kind")]
+            pub category: Category,
+        }
+    }
+
+    let before =
+        LibBuilder::with(libname!(LIB), None).transpile::<before::Record>().compile().unwrap();
+    let after =
+        LibBuilder::with(libname!(LIB), None).transpile::<after::Entry>().compile().unwrap();
+    assert_eq!(after.type_id(&tn!("Record")), before.type_id(&tn!("Record")));
+    assert_eq!(after.type_id(&tn!("Kind")), before.type_id(&tn!("Kind")));
+    assert_eq!(after.type_id(&tn!("Entry")), None);
+    assert_eq!(after.id(), before.id());
+}
+
+#[test]
+fn uninhabited_type() {
+    // strict type derives can't be used for enums without variants, which have no dumb value
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    struct Never;
+    impl StrictDumb for Never {
+        fn strict_dumb() -> Self { Never }
+    }
+    impl StrictType for Never {
+        const STRICT_LIB_NAME: &'static str = LIB;
+    }
+    impl StrictSum for Never {
+        const ALL_VARIANTS: &'static [(u8, &'static str)] = &[];
+        fn variant_name(&self) -> &'static str { "never" }
+    }
+    impl StrictEnum for Never {}
+    impl TryFrom<u8> for Never {
+        type Error = VariantError<u8>;
+        fn try_from(tag: u8) -> Result<Self, Self::Error> { Err(VariantError::with::<Never>(tag)) }
+    }
+    impl From<Never> for u8 {
+        fn from(_: Never) -> Self { 0 }
+    }
+    impl StrictEncode for Never {
+        fn strict_encode<W: TypedWrite>(&self, writer: W) -> io::Result<W> {
+            writer.write_enum(*self)
+        }
+    }
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Never>().compile_symbols();
+    assert_eq!(err.unwrap_err(), TranspileError::UninhabitedType {
+        name: "Never".to_owned()
+    });
+}
+
+#[test]
+fn empty_struct() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Marker {}
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Tagged {
+        marker: Marker,
+        value: u8,
+    }
+
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Tagged>().compile().unwrap();
+    assert_eq!(lib.types.get(&tn!("Marker")), Some(&Ty::UNIT));
+
+    let err = LibBuilder::with(libname!(LIB), None)
+        .deny_empty_structs()
+        .transpile::<Tagged>()
+        .compile_symbols();
+    assert_eq!(err.unwrap_err(), TranspileError::InContext {
+        context: [tn!("Tagged"), tn!("Marker")].into_iter().collect(),
+        error: Box::new(TranspileError::EmptyStruct(tn!("Marker"))),
+    });
+}
+
+#[test]
+fn error_context() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Inner {
+        #[strict_type(rename = "value")]
+        first: u8,
+        #[strict_type(rename = "value")]
+        second: u16,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Middle(Inner);
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Outer {
+        middle: Middle,
+    }
+
+    let root = TranspileError::DuplicateField {
+        ty: "Inner".to_owned(),
+        field: fname!("value"),
+    };
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Outer>().compile_symbols();
+    let err = err.unwrap_err();
+    assert_eq!(err.root(), &root);
+    assert_eq!(
+        err.to_string(),
+        "while transpiling Outer -> Middle -> Inner: type `Inner` has several fields named \
+         `value`."
+    );
+    // the inner error is a part of the message, thus it is not reported as the error source
+    assert!(std::error::Error::source(&err).is_none());
+
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Middle>().compile();
+    let CompileError::InContext { context, error } = err.unwrap_err() else {
+        panic!("error without context")
+    };
+    assert_eq!(context.as_slice(), &[tn!("Middle"), tn!("Inner")]);
+    assert_eq!(*error, CompileError::DuplicateField {
+        ty: "Inner".to_owned(),
+        field: fname!("value")
+    });
+}
+
+#[test]
+fn try_compile_symbols() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB_NAME_STD)]
+    struct Fake(());
+
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct FakeUser(Fake);
+
+    let builder = || {
+        LibBuilder::with(libname!(LIB), [std_stl().to_dependency_types()])
+            .transpile::<Pair>()
+            .transpile::<FakeUser>()
+            .transpile_named::<Wrapper<u16>>(tn!("Pair"))
+    };
+
+    let err = builder().compile_symbols().unwrap_err();
+    assert_eq!(err, TranspileError::DuplicateName(tn!("Pair")));
+
+    let TranspileError::Multiple(errors) = builder().try_compile_symbols().unwrap_err() else {
+        panic!("all errors must be reported");
+    };
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0], TranspileError::DuplicateName(tn!("Pair")));
+    assert!(matches!(&errors[1], TranspileError::DependencyMissesType(lib, _, name)
+        if lib == &libname!(LIB_NAME_STD) && name == &tn!("Fake")));
+}
+
+#[test]
+fn with_dependency() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Named {
+        ident: Ident,
+        first: AlphaLodash,
+    }
+
+    let std = std_stl();
+    let st = strict_types_stl();
+
+    // `Std` types are known transitively from `StrictTypes`
+    let lib = LibBuilder::with(libname!(LIB), None)
+        .with_dependency(&st)
+        .transpile::<Named>()
+        .compile()
+        .unwrap();
+    assert_eq!(lib.dependencies.len(), 2);
+
+    // shared `Std` types are merged
+    let merged = LibBuilder::with(libname!(LIB), None)
+        .with_dependency(&std)
+        .with_dependency(&st)
+        .transpile::<Named>()
+        .compile()
+        .unwrap();
+    assert_eq!(merged, lib);
+
+    let mut fake = st.clone();
+    let std_types = fake.extern_types.get_mut(&libname!(LIB_NAME_STD)).unwrap();
+    let (&id, _) = std_types.iter().find(|(_, name)| *name == &tn!("AlphaLodash")).unwrap();
+    std_types.remove(&id).unwrap();
+    std_types.insert(SemId::from([0u8; 32]), tn!("AlphaLodash")).unwrap();
+    let err = LibBuilder::with(libname!(LIB), None)
+        .with_dependency(&std)
+        .with_dependency(&fake)
+        .transpile::<Named>()
+        .compile_symbols()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TranspileError::DependencyTypeMismatch(
+            libname!(LIB_NAME_STD),
+            tn!("AlphaLodash"),
+            id,
+            SemId::from([0u8; 32])
+        )
+    );
+}
+
+#[test]
+fn external_refs() {
+    #[derive(Clone, Eq, PartialEq, Debug)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = LIB)]
+    struct Names {
+        first: AlphaLodash,
+        rest: TinyVec<AlphaLodash>,
+        pair: Pair,
+    }
+
+    let lib = LibBuilder::with(libname!(LIB), [std_stl().to_dependency_types()])
+        .transpile::<Names>()
+        .compile_symbols()
+        .unwrap();
+    let refs = lib.external_refs();
+    assert_eq!(refs.len(), 1);
+    let sym = refs.first().unwrap();
+    assert_eq!(sym.to_string(), "Std.AlphaLodash");
+    assert_eq!(sym.lib_id, std_stl().id());
+
+    let local =
+        LibBuilder::with(libname!(LIB), None).transpile::<Pair>().compile_symbols().unwrap();
+    assert!(local.external_refs().is_empty());
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct Boxed {
+    pub inner: Box<Pair>,
+    pub plain: Pair,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+#[strict_type(lib = LIB)]
+pub struct Tree {
+    pub value: u8,
+    pub children: TinyVec<Tree>,
+}
+
+#[test]
+fn transpile_recursive() {
+    let lib = LibBuilder::with(libname!(LIB), None).transpile::<Boxed>().compile().unwrap();
+    let pair = lib.types.get(&tn!("Pair")).unwrap().sem_id_named(&tn!("Pair"));
+    let Ty::Struct(fields) = lib.types.get(&tn!("Boxed")).unwrap() else {
+        panic!("not a struct")
+    };
+    assert!(fields.iter().all(|field| field.ty == LibRef::Named(pair)));
+
+    let symbolic =
+        LibBuilder::with(libname!(LIB), None).transpile::<Tree>().compile_symbols().unwrap();
+    assert!(symbolic
+        .to_string()
+        .contains("data Tree              : value U8, children [Tree ^ ..0xff]"));
+    let err = LibBuilder::with(libname!(LIB), None).transpile::<Tree>().compile().unwrap_err();
+    assert_eq!(err, CompileError::RecursiveType(tn!("Tree")));
+}
+
+#[test]
+fn ambiguous_dependency() {
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Dep")]
+    struct Item {
+        value: u8,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Dep")]
+    struct Extra(u16);
+
+    #[derive(Clone, Eq, PartialEq, Debug, Default)]
+    #[derive(StrictType, StrictEncode, StrictDecode, StrictDumb)]
+    #[strict_type(lib = "Up")]
+    struct Up(Item);
+
+    let v1 = LibBuilder::with(libname!("Dep"), None).transpile::<Item>().compile().unwrap();
+    let v2 = LibBuilder::with(libname!("Dep"), None)
+        .transpile::<Item>()
+        .transpile::<Extra>()
+        .compile()
+        .unwrap();
+    let up = LibBuilder::with(libname!("Up"), None)
+        .with_dependency(&v1)
+        .transpile::<Up>()
+        .compile()
+        .unwrap();
+    let mut ids = vec![v1.id(), v2.id()];
+    ids.sort();
+    let expected = TranspileError::AmbiguousDependency {
+        name: libname!("Dep"),
+        ids,
+    };
+
+    let err = LibBuilder::with(libname!(LIB), [v1.to_dependency_types(), v2.to_dependency_types()])
+        .transpile::<Pair>()
+        .compile_symbols()
+        .unwrap_err();
+    assert_eq!(err, expected);
+
+    // the other version comes transitively from `Up`
+    let err = LibBuilder::with(libname!(LIB), None)
+        .with_dependency(&up)
+        .with_dependency(&v2)
+        .transpile::<Pair>()
+        .compile()
+        .unwrap_err();
+    assert_eq!(err, CompileError::from(expected));
+
+    // the same library may be provided several times
+    LibBuilder::with(libname!(LIB), None)
+        .with_dependency(&up)
+        .with_dependency(&v1)
+        .transpile::<Pair>()
+        .compile()
+        .unwrap();
+}